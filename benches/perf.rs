@@ -0,0 +1,127 @@
+// Benchmarks for the hot paths that grow with the number of tasks:
+// sorting, search indexing, Stats computation and drawing a frame, plus typing into a huge description.
+// Run with `cargo bench`; the datasets come from the same generator as `tdui --generate-fake`.
+
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use ratatui::{Terminal, backend::TestBackend};
+use std::hint::black_box;
+use std::path::PathBuf;
+use tdui::app::{App, Tab};
+use tdui::cli::Args;
+use tdui::clock::Clock;
+use tdui::config::WorkflowConfig;
+use tdui::fake;
+use tdui::models::Todo;
+use tdui::search::SearchIndex;
+use tdui::stats::Stats;
+use tdui::storage::{ArchiveTotals, FileStorage};
+use tdui::text_buffer::TextBuffer;
+
+const SIZES: [usize; 2] = [1_000, 10_000];
+
+fn dataset(count: usize) -> Vec<Todo> {
+    fake::generate(count, 1, Clock::default().today())
+}
+
+/// A throwaway directory so benchmarks never touch the real todo file
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("tdui-bench").join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("create bench directory");
+    dir
+}
+
+fn bench_sort(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sort_by_due");
+    for size in SIZES {
+        let todos = dataset(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &todos, |b, todos| {
+            b.iter_batched(|| todos.clone(), |mut todos| Todo::sort_by_due(&mut todos), BatchSize::LargeInput);
+        });
+    }
+    group.finish();
+}
+
+fn bench_search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("search");
+    for size in SIZES {
+        let todos = dataset(size);
+        let index_path = scratch_dir(&format!("search-{}", size)).join("search_index.json");
+
+        group.bench_with_input(BenchmarkId::new("cold_sync", size), &todos, |b, todos| {
+            b.iter_batched(
+                || SearchIndex::open(index_path.clone()),
+                |mut index| index.sync(todos, &[]),
+                BatchSize::LargeInput,
+            );
+        });
+
+        let mut index = SearchIndex::open(index_path.clone());
+        index.sync(&todos, &[]);
+        group.bench_with_input(BenchmarkId::new("query", size), &index, |b, index| {
+            b.iter(|| index.search(black_box("review bud")));
+        });
+    }
+    group.finish();
+}
+
+fn bench_stats(c: &mut Criterion) {
+    let mut group = c.benchmark_group("stats");
+    let clock = Clock::default();
+    let workflow = WorkflowConfig::default();
+    for size in SIZES {
+        let todos = dataset(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &todos, |b, todos| {
+            b.iter(|| Stats::compute(todos, &[], ArchiveTotals::default(), &[], &workflow, &clock));
+        });
+    }
+    group.finish();
+}
+
+fn bench_render(c: &mut Criterion) {
+    let mut group = c.benchmark_group("render");
+    for size in SIZES {
+        // App reads its files from under HOME, so point HOME at a seeded scratch directory
+        let home = scratch_dir(&format!("render-{}", size));
+        let mut storage = FileStorage::open(home.join(".local/share/tdui/todos.json"));
+        storage.append(dataset(size));
+        storage.flush().expect("write bench todos");
+        // SAFETY: benchmarks are single-threaded while the environment is changed
+        unsafe { std::env::set_var("HOME", &home) };
+
+        let mut app = App::new(&Args::default());
+        let mut terminal = Terminal::new(TestBackend::new(160, 48)).expect("test terminal");
+
+        group.bench_function(BenchmarkId::new("tasks_tab", size), |b| {
+            b.iter(|| terminal.draw(|frame| tdui::ui::render(frame, &app)).map(|_| ()));
+        });
+
+        app.selected_tab = Tab::Stats;
+        app.refresh_stats();
+        group.bench_function(BenchmarkId::new("stats_tab", size), |b| {
+            b.iter(|| terminal.draw(|frame| tdui::ui::render(frame, &app)).map(|_| ()));
+        });
+    }
+    group.finish();
+}
+
+fn bench_long_description(c: &mut Criterion) {
+    // A pasted log: what a keystroke in the editor costs shouldn't depend on how long it is
+    let log: String = (0..20_000)
+        .map(|i| format!("12:00:{:05} INFO worker-{} wrote a batch of records to the output sink\n", i, i % 8))
+        .collect();
+    let mut text = TextBuffer::new(&log);
+    let width = 76;
+    let first = text.rows(width).saturating_sub(20);
+    c.bench_function("long_description/type", |b| {
+        b.iter(|| {
+            text.push('x');
+            black_box(text.cursor(width));
+            black_box(text.rows_in_view(width, first, 20));
+            text.pop();
+        });
+    });
+}
+
+criterion_group!(benches, bench_sort, bench_search, bench_stats, bench_render, bench_long_description);
+criterion_main!(benches);
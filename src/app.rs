@@ -5,13 +5,47 @@
 // - Input mode (normal, insert, etc.)
 // - Application state machine
 
-use crate::models::Todo;
-use crate::storage::FileStorage;
-use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use crate::batch::BatchEdit;
+use crate::board::{Board, Step};
+use crate::cli::Args;
+use crate::clock::Clock;
+use crate::config::{Config, ConfirmConfig, DueGroup, PopupSize, SortMode, StatsSource, expand_home};
+use crate::doctor;
+use crate::drop_folder::DropFolder;
+use crate::duplicate;
+use crate::error::TduiError;
+use crate::ics::CalendarFeeds;
+use crate::keymap::Keymap;
+use crate::mcp::{self, PendingCall, ToolCall};
+use crate::models::{Blackout, Habit, HabitFrequency, ParentCompletion, Priority, Project, Recurrence, ReminderOffset, TimeEntry, Todo};
+use crate::notify::{self, Notifier, Reminder};
+use crate::planner::{self, Capacity, PlannedTask};
+use crate::quick_add;
+use crate::read_later;
+use crate::search::{self, SearchHit, SearchIndex, TaskSource};
+use crate::server::{self, Server};
+use crate::session::{SessionStart, SessionSummary};
+use crate::slack;
+use crate::stats::{HISTORY_DAYS, SourceStats, Stats, StatsKey};
+use crate::storage::{self, Archive, ArchiveTotals, BlackoutStorage, DamagedFile, FileStorage, GitRepo, HabitStorage, NoteStorage, ProjectStorage, TaskVersion};
+use crate::theme::{Theme, ThemeKind};
+use crate::template;
+use crate::text_buffer::TextBuffer;
+use crate::transfer::{self, Destination};
+use crate::triage::{self, TriageAction, TriageItem};
+use crate::urgency::{Scorer, Urgency};
+use crate::usage::UsageLog;
+use crate::vault::VaultSync;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Position, Rect};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 use std::io::Stdout;
-use chrono::{Local, NaiveDate, Datelike};
+use std::time::Instant;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Datelike, Utc};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum InputMode {
@@ -19,8 +53,39 @@ pub enum InputMode {
     EditingTitle,
     EditingDescription,
     EditingDate,
+    EditingTime,
+    EditingContext,
+    EditingReminders,
+    EditingRecurrence,
+    EditingEstimate,
     DonePanel,
     DeletePanel,
+    Search,
+    ContextPanel,
+    WorkspacePanel,
+    PlanPanel,
+    HabitPanel,
+    WaitingOn,
+    ProjectPanel,
+    BlackoutPanel,
+    InlineTask,
+    JumpToId,
+    AddUrl,
+    JumpHints,
+    EditingField,
+    EditingNote,
+    Focus,
+    TriagePanel,
+    HistoryPanel,
+    AttentionPanel,
+    ParentDonePanel,
+    EmptyTrashPanel,
+    ToolCallPanel,
+    BatchEdit,
+    TransferPanel,
+    DiscardPanel,
+    DuplicatePanel,
+    Recovery,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -30,26 +95,54 @@ pub enum Panel {
     Task,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Tab {
     Tasks,
     Stats,
+    Habits,
+    /// The open tasks in a column per workflow status
+    Board,
+    /// One month as a large grid, with the tasks due each day
+    Month,
+    /// The year's days shaded by how many tasks are due on each
+    Year,
+    /// Completed tasks, most recently done first, to reopen or correct when they were done
+    Done,
+    /// A task list bound to a filter, indexing into `App::workspaces`
+    Workspace(usize),
 }
 
 impl Tab {
-    pub fn next(&self) -> Self {
-        match self {
-            Tab::Tasks => Tab::Stats,
-            Tab::Stats => Tab::Tasks,
-        }
+    /// The tabs config can list, by the kind name used there
+    const BUILT_IN: [(&'static str, Tab); 7] = [
+        ("tasks", Tab::Tasks),
+        ("stats", Tab::Stats),
+        ("habits", Tab::Habits),
+        ("board", Tab::Board),
+        ("month", Tab::Month),
+        ("year", Tab::Year),
+        ("done", Tab::Done),
+    ];
+
+    pub fn from_kind(kind: &str) -> Option<Tab> {
+        Self::BUILT_IN.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(kind.trim()))
+            .map(|&(_, tab)| tab)
     }
 
-    pub fn previous(&self) -> Self {
-        match self {
-            Tab::Tasks => Tab::Stats,
-            Tab::Stats => Tab::Tasks,
+    /// Every kind name config can use, as "tasks, stats, … or done"
+    fn kind_names() -> String {
+        let names: Vec<&str> = Self::BUILT_IN.iter().map(|&(name, _)| name).collect();
+        match names.split_last() {
+            Some((last, rest)) if !rest.is_empty() => format!("{} or {}", rest.join(", "), last),
+            _ => names.concat(),
         }
     }
+
+    /// Whether the tab shows the task list, calendar and details panels
+    pub fn shows_tasks(&self) -> bool {
+        matches!(self, Tab::Tasks | Tab::Workspace(_))
+    }
 }
 
 impl Panel {
@@ -62,6 +155,292 @@ impl Panel {
     }
 }
 
+/// Where a jump hint label takes the selection
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JumpTarget {
+    /// Index into `todos`
+    Task(usize),
+    Day(NaiveDate),
+}
+
+/// Something on screen the mouse can pick a task up from or drop it on, recorded by the UI as it
+/// draws
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MouseTarget {
+    /// A task's row in the list, by index into `todos`
+    ListRow(usize),
+    /// A task on the board, by index into `todos`
+    BoardCard(usize),
+    /// A board column, by index into the workflow's open statuses
+    BoardColumn(usize),
+}
+
+/// A task being dragged with the mouse
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Drag {
+    pub id: usize,
+    /// Where it would land if let go now; None until the mouse moves off where it started
+    pub over: Option<MouseTarget>,
+}
+
+/// Longest completion streak the greeting counts back
+const STREAK_DAYS: i64 = 366;
+
+/// Letters jump hint labels are made of, easiest to reach first
+const HINT_LETTERS: &[u8] = b"asdfghjklqwertyuiopzxcvbnm";
+/// How often the task files are checked for changes saved by another tdui, a sync or an editor
+const FILES_CHECK_SECS: u64 = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListFilter {
+    All,
+    Today,
+    Backlog,
+    /// Tasks waiting on someone else
+    Waiting,
+    /// Tasks parked for some day, which no other filter shows
+    Someday,
+    Context(String),
+    /// Tasks in one project
+    Project(String),
+    /// Tasks due on one day, opened from the Month tab
+    Day(NaiveDate),
+}
+
+impl ListFilter {
+    pub fn matches(&self, todo: &Todo, today: NaiveDate) -> bool {
+        match self {
+            ListFilter::All => true,
+            // Overdue tasks count as today's work
+            ListFilter::Today => todo.due_date.is_some_and(|d| d <= today),
+            ListFilter::Backlog => todo.due_date.is_none_or(|d| d > today),
+            ListFilter::Waiting => todo.is_waiting(),
+            ListFilter::Someday => todo.someday,
+            ListFilter::Context(context) => todo.context.as_ref() == Some(context),
+            ListFilter::Project(project) => todo.project.as_ref() == Some(project),
+            ListFilter::Day(date) => todo.due_date == Some(*date),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            ListFilter::All => "All".to_string(),
+            ListFilter::Today => "Today".to_string(),
+            ListFilter::Backlog => "Backlog".to_string(),
+            ListFilter::Waiting => "Waiting".to_string(),
+            ListFilter::Someday => "Someday".to_string(),
+            ListFilter::Context(context) => context.clone(),
+            ListFilter::Project(project) => format!("Project: {}", project),
+            ListFilter::Day(date) => date.format("%a %Y-%m-%d").to_string(),
+        }
+    }
+}
+
+/// Section of the grouped task list, in display order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DueBucket {
+    Overdue,
+    Today,
+    Tomorrow,
+    ThisWeek,
+    Later,
+    NoDate,
+    /// One of the groups set in config, by position
+    Custom(usize),
+}
+
+impl DueBucket {
+    pub fn of(todo: &Todo, today: NaiveDate) -> Self {
+        let Some(due_date) = todo.due_date else {
+            return DueBucket::NoDate;
+        };
+        // The week runs Monday to Sunday
+        let end_of_week = today + chrono::Duration::days(6 - today.weekday().num_days_from_monday() as i64);
+        match (due_date - today).num_days() {
+            days if days < 0 => DueBucket::Overdue,
+            0 => DueBucket::Today,
+            1 => DueBucket::Tomorrow,
+            _ if due_date <= end_of_week => DueBucket::ThisWeek,
+            _ => DueBucket::Later,
+        }
+    }
+
+    /// The first of `groups` the task falls in, or the usual buckets when none are configured;
+    /// dated tasks past every group go under Later, undated ones without a group under No date
+    pub fn in_groups(todo: &Todo, today: NaiveDate, groups: &[DueGroup]) -> Self {
+        if groups.is_empty() {
+            return Self::of(todo, today);
+        }
+        let position = match todo.due_date {
+            Some(due_date) => {
+                let days = (due_date - today).num_days();
+                groups.iter().position(|group| !group.undated && group.until_days.is_none_or(|until| days <= until))
+            }
+            None => groups.iter().position(|group| group.undated),
+        };
+        match (position, todo.due_date) {
+            (Some(position), _) => DueBucket::Custom(position),
+            (None, Some(_)) => DueBucket::Later,
+            (None, None) => DueBucket::NoDate,
+        }
+    }
+
+    pub fn label<'a>(&self, groups: &'a [DueGroup]) -> &'a str {
+        match self {
+            DueBucket::Overdue => "Overdue",
+            DueBucket::Today => "Today",
+            DueBucket::Tomorrow => "Tomorrow",
+            DueBucket::ThisWeek => "This week",
+            DueBucket::Later => "Later",
+            DueBucket::NoDate => "No date",
+            DueBucket::Custom(position) => groups.get(*position).map_or("?", |group| group.name.as_str()),
+        }
+    }
+}
+
+/// Where the user was in a tab, restored when they come back to it
+#[derive(Debug, Clone)]
+pub struct TabState {
+    pub selected_id: Option<usize>,
+    pub focused_panel: Panel,
+}
+
+impl Default for TabState {
+    fn default() -> Self {
+        Self { selected_id: None, focused_panel: Panel::List }
+    }
+}
+
+/// An extra Tasks-style tab that only lists tasks matching its filter
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub filter: ListFilter,
+    pub state: TabState,
+}
+
+/// Two filtered lists shown side by side, each remembering its own selection
+#[derive(Debug, Clone)]
+pub struct SplitView {
+    pub filters: [ListFilter; 2],
+    pub selected_ids: [Option<usize>; 2],
+    pub active_pane: usize,
+}
+
+/// The field being edited in the project settings popup
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProjectField {
+    Color,
+    Tags,
+    ArchiveDays,
+    OwnFile,
+}
+
+impl ProjectField {
+    pub fn next(&self) -> Self {
+        match self {
+            ProjectField::Color => ProjectField::Tags,
+            ProjectField::Tags => ProjectField::ArchiveDays,
+            ProjectField::ArchiveDays => ProjectField::OwnFile,
+            ProjectField::OwnFile => ProjectField::Color,
+        }
+    }
+}
+
+/// The field being typed into in the blackout popup
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlackoutField {
+    Start,
+    End,
+    Label,
+}
+
+impl BlackoutField {
+    pub fn next(&self) -> Self {
+        match self {
+            BlackoutField::Start => BlackoutField::End,
+            BlackoutField::End => BlackoutField::Label,
+            BlackoutField::Label => BlackoutField::Start,
+        }
+    }
+}
+
+/// The field being typed into in the batch-edit popup
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BatchField {
+    Tags,
+    Priority,
+    Project,
+}
+
+impl BatchField {
+    pub fn next(&self) -> Self {
+        match self {
+            BatchField::Tags => BatchField::Priority,
+            BatchField::Priority => BatchField::Project,
+            BatchField::Project => BatchField::Tags,
+        }
+    }
+}
+
+/// The field of the Task details panel being edited in place
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DetailField {
+    Title,
+    DueDate,
+    Tags,
+    Priority,
+}
+
+impl DetailField {
+    pub fn next(&self) -> Self {
+        match self {
+            DetailField::Title => DetailField::DueDate,
+            DetailField::DueDate => DetailField::Tags,
+            DetailField::Tags => DetailField::Priority,
+            DetailField::Priority => DetailField::Title,
+        }
+    }
+
+    pub fn previous(&self) -> Self {
+        match self {
+            DetailField::Title => DetailField::Priority,
+            DetailField::DueDate => DetailField::Title,
+            DetailField::Tags => DetailField::DueDate,
+            DetailField::Priority => DetailField::Tags,
+        }
+    }
+}
+
+/// A running focus session: only its task is shown, with a countdown
+#[derive(Debug, Clone)]
+pub struct FocusSession {
+    pub todo_id: usize,
+    pub started_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    /// Non-urgent notices (reminders, feed errors) held back until the session ends
+    pub held_back: Vec<String>,
+}
+
+impl FocusSession {
+    pub fn remaining(&self) -> Duration {
+        (self.ends_at - Utc::now()).max(Duration::zero())
+    }
+}
+
+/// Everything on screen that can change without a key press; a frame is only drawn again when
+/// this differs from the last one's, or an event came in
+#[derive(Debug, Clone, PartialEq)]
+struct ViewKey {
+    notice: Option<String>,
+    revision: u64,
+    habits_revision: u64,
+    calendar_revision: u64,
+    /// The minute (for the clock in the footer and the greeting) and the seconds left in a focus
+    /// session's countdown; None while the terminal is in the background, so they don't tick there
+    ticks: Option<(i64, Option<i64>)>,
+    save_status: &'static str,
+}
+
 pub struct App {
     pub should_quit: bool,
     pub current_date: NaiveDate,
@@ -73,33 +452,285 @@ pub struct App {
     pub show_delete_panel: bool,
     pub delete_panel_yes_selected: bool,
     pub deleting_todo_id: Option<usize>,
+    pub show_discard_panel: bool,
+    pub discard_panel_yes_selected: bool,
+    /// Editor mode to go back to if the discard is cancelled
+    discard_return_mode: InputMode,
+    /// Task editor fields as they were when it opened, to tell whether anything changed
+    task_editor_original: [String; 8],
+    pub show_duplicate_panel: bool,
+    pub duplicate_panel_yes_selected: bool,
+    /// The open task the one being added looks like
+    pub duplicate_of: Option<usize>,
+    /// Add the task being saved even if it looks like an open one
+    adding_duplicate: bool,
+    /// The "Don't ask again" checkbox in the open confirmation popup
+    pub dont_ask_again: bool,
     pub input_mode: InputMode,
     pub focused_panel: Panel,
+    pub zoomed: bool,
+    pub split_view: Option<SplitView>,
     pub selected_tab: Tab,
+    pub workspaces: Vec<Workspace>,
+    tasks_tab_state: TabState,
+    pub show_workspace_panel: bool,
+    pub show_plan_panel: bool,
+    pub selected_habit_index: usize,
+    /// Position in the Done tab's list
+    pub selected_done_index: usize,
+    pub show_habit_panel: bool,
+    pub show_waiting_panel: bool,
+    pub show_project_panel: bool,
+    /// The project whose settings are open, and its settings as typed
+    pub project_panel_name: String,
+    pub project_field: ProjectField,
+    pub project_color_input: String,
+    pub project_tags_input: String,
+    pub project_archive_input: String,
+    pub project_own_file: bool,
+    pub show_blackout_panel: bool,
+    /// The blackout being changed, None when adding one; then its dates and label as typed
+    pub editing_blackout: Option<Blackout>,
+    pub blackout_field: BlackoutField,
+    pub blackout_start_input: String,
+    pub blackout_end_input: String,
+    pub blackout_label_input: String,
+    /// The one-line title input opened with `o` below the list selection
+    pub show_inline_task: bool,
+    pub inline_task_title: String,
+    /// Digits typed so far to jump to a list number, or the task ID typed after ':'
+    pub jump_input: String,
+    /// The first letter of a jump hint, once typed
+    pub jump_hint_input: String,
+    /// The link typed or pasted after 'U', to save as a task to read later
+    pub url_input: String,
+    /// Page titles being fetched for tasks added from links
+    title_fetches: Vec<Receiver<(usize, anyhow::Result<String>)>>,
+    /// Slack posts still being sent, each giving back what to say once it's gone
+    slack_posts: Vec<Receiver<anyhow::Result<String>>>,
+    /// Reminders being sent to the configured notification backends, each giving back any failure
+    notification_sends: Vec<Receiver<anyhow::Result<()>>>,
+    /// Who the selected task is waiting on, as typed in the popup
+    pub waiting_on_input: String,
+    pub new_habit_name: String,
+    pub new_habit_frequency: HabitFrequency,
+    pub deleting_habit_id: Option<usize>,
+    pub show_note_panel: bool,
+    /// The day whose note is open in the editor
+    pub editing_note_date: Option<NaiveDate>,
+    pub note_text: TextBuffer,
+    pub note_scroll: u16,
+    pub focus: Option<FocusSession>,
+    /// The task list at startup, to summarize the session on quit
+    session_start: SessionStart,
+    /// Whether to show the summary on quit (config, unless --no-summary)
+    summary_on_exit: bool,
+    /// Set once quitting, while the summary screen is up
+    pub exit_summary: Option<SessionSummary>,
+    /// IDs of the tasks marked for a batch edit
+    pub marked: BTreeSet<usize>,
+    pub show_batch_panel: bool,
+    pub batch_field: BatchField,
+    pub batch_tags: String,
+    pub batch_priority: String,
+    pub batch_project: String,
+    /// Moving or copying the marked (or selected) tasks to another project or data folder
+    pub show_transfer_panel: bool,
+    pub transfer_copy: bool,
+    /// Typed to narrow down the destinations, or to name a new project
+    pub transfer_input: String,
+    pub selected_transfer_index: usize,
+    /// Damaged task files found at startup, waiting for the user to keep what was recovered or quit
+    pub show_recovery_panel: bool,
+    pub show_triage_panel: bool,
+    pub triage: Vec<TriageItem>,
+    pub selected_triage_index: usize,
+    pub show_history_panel: bool,
+    /// The selected task's committed versions where its description changed, newest first
+    pub history: Vec<TaskVersion>,
+    pub selected_history_index: usize,
+    pub show_attention_panel: bool,
+    /// IDs of the escalated tasks listed in the "needs attention" popup
+    pub attention: Vec<usize>,
+    pub selected_attention_index: usize,
+    pub show_parent_done_panel: bool,
+    pub show_empty_trash_panel: bool,
+    /// An assistant's change waiting for a yes or no, the first of `tool_calls`
+    pub show_tool_call_panel: bool,
+    /// The task whose subtasks were all just finished, asked about in the popup
+    pub parent_done_id: Option<usize>,
+    pub plan: Vec<PlannedTask>,
+    pub selected_plan_index: usize,
+    pub workspace_choices: Vec<ListFilter>,
+    pub selected_workspace_index: usize,
     pub selected_todo_index: Option<usize>,
+    pub collapsed_buckets: HashSet<DueBucket>,
+    pub done_today: Vec<Todo>,
+    /// The day Done today was last worked out for, to notice midnight passing
+    current_day: NaiveDate,
+    pub done_today_expanded: bool,
+    pub done_today_selected: bool,
     pub selected_calendar_date: Option<NaiveDate>,
     pub task_description_scroll: u16,
+    /// Rows the details' description took once wrapped, and how many fit, when last drawn
+    pub description_rows: Cell<(u16, u16)>,
+    /// How the selected task's description was laid out when last drawn, kept for the next frame
+    pub description_layout: RefCell<Option<crate::ui::DescriptionLayout>>,
+    /// Which `#<id>` link in the selected task's description Enter follows
+    pub selected_link: usize,
     pub edit_description_scroll: u16,
+    /// Width and height (label included) of the open multi-line editor, description or note, when last drawn
+    pub editor_size: Cell<(u16, u16)>,
+    /// Percent of the terminal's width and height the task editor took when last drawn
+    pub task_editor_percent: Cell<(u16, u16)>,
     pub editing_todo_id: Option<usize>,
     pub new_task_title: String,
-    pub new_task_description: String,
+    pub new_task_description: TextBuffer,
     pub new_task_due_date: Option<NaiveDate>,
     pub date_input_buffer: String,
+    /// The task's due time as typed, e.g. "14:30"; blank for none
+    pub new_task_time: String,
+    pub show_search_panel: bool,
+    pub search_query: String,
+    pub search_results: Vec<SearchHit>,
+    pub selected_search_index: Option<usize>,
+    pub new_task_context: String,
+    pub new_task_reminders: String,
+    /// The task's repeat rule as typed, in RRULE form
+    pub new_task_recurrence: String,
+    /// The details panel field being edited in place, and its text as typed
+    pub editing_field: Option<DetailField>,
+    pub field_input: String,
+    /// The task's estimate as typed, e.g. "1h30m"
+    pub new_task_estimate: String,
+    /// The task a new task is added under, when adding a subtask
+    pub new_task_parent: Option<usize>,
+    /// Message shown in the footer until the next key press (reminders, input errors)
+    pub notice: Option<String>,
+    pub show_context_panel: bool,
+    pub context_choices: Vec<String>,
+    pub selected_context_index: usize,
+    pub config: Config,
+    pub theme: Theme,
+    pub clock: Clock,
     storage: FileStorage,
+    search_index: SearchIndex,
+    search_index_dirty: bool,
+    /// Whether the last write of todos.json failed, so the failure is only announced once
+    save_failing: bool,
+    archive: Archive,
+    /// Every archived task, kept for search so the month files are only read when they change
+    archived_todos: Vec<Todo>,
+    /// Checkbox sync with an Obsidian vault, when one is configured, and when it last ran
+    vault: Option<VaultSync>,
+    vault_synced_at: Instant,
+    /// The folder whose dropped transcripts become tasks, when one is configured, and when it was last looked in
+    drop_folder: Option<DropFolder>,
+    drop_folder_checked_at: Instant,
+    /// When the task files were last checked for changes saved elsewhere
+    files_checked_at: Instant,
+    /// What the last frame showed, or None when the next one has to be drawn regardless
+    drawn: Option<ViewKey>,
+    /// Whether the terminal has focus (as far as its focus events say) and when a key or click last came in
+    focused: bool,
+    last_input: Instant,
+    /// Days with a task done that's since been archived, for the greeting's streak
+    archived_done_days: BTreeSet<NaiveDate>,
+    habit_storage: HabitStorage,
+    note_storage: NoteStorage,
+    project_storage: ProjectStorage,
+    blackout_storage: BlackoutStorage,
+    stats: Option<(StatsKey, Stats)>,
+    notifier: Notifier,
+    pub calendar_feeds: CalendarFeeds,
+    /// The web dashboard, when `server.listen` is set
+    server: Option<Server>,
+    /// The storage revision, day and context the dashboard's JSON was last published for
+    server_published: Option<(u64, NaiveDate, Option<String>)>,
+    /// Changes assistants asked for through the tool server, oldest first, each waiting for a yes or no
+    tool_calls: VecDeque<PendingCall>,
+    /// The data directory's repo when `git.auto_commit` is on
+    git: Option<GitRepo>,
+    pub keymap: Keymap,
+    /// Entries of the "keys" config that couldn't be used, shown on a screen at startup
+    pub keymap_problems: Vec<String>,
+    pub show_keymap_problems: bool,
+    /// Counts of the actions used, kept only when `usage_metrics` is on
+    usage: Option<UsageLog>,
+    /// Show the Usage view on the Stats tab instead of the task stats
+    pub show_usage: bool,
+    /// Show estimated against actual effort on the Stats tab instead of the task stats
+    pub show_estimates: bool,
+    /// Stats tab merges every context here and each of `stats.sources`, instead of the active context
+    pub stats_all_sources: bool,
+    /// Where tasks and board columns were last drawn, when the mouse is on
+    pub mouse_targets: RefCell<Vec<(Rect, MouseTarget)>>,
+    pub drag: Option<Drag>,
 }
 
 impl App {
-    pub fn new() -> Self {
-        let storage = FileStorage::new(FileStorage::get_default_path());
-        let all_todos = storage.load_todos().unwrap_or_else(|_| Vec::new());
-        // Filter out completed and deleted todos
-        let todos: Vec<Todo> = all_todos.into_iter().filter(|t| !t.completed && !t.deleted).collect();
-        let selected_todo_index = if todos.is_empty() { None } else { Some(0) };
+    pub fn new(args: &Args) -> Self {
+        let mut storage = FileStorage::open(FileStorage::get_default_path()).with_background_writer().with_journal();
+        // A config.json that doesn't parse is left as it is for the user to fix, and the defaults used meanwhile
+        let (config, config_error) = match Config::read(Config::get_default_path()) {
+            Ok(config) => (config, None),
+            Err(err) => (Config::load(Config::get_default_path()), Some(format!("{}, using the defaults", err))),
+        };
+        let mut theme = if args.no_color || Theme::no_color_requested() {
+            Theme::from_kind(ThemeKind::Monochrome)
+        } else {
+            Theme::from_kind(config.theme)
+        };
+        if config.ascii.unwrap_or_else(|| !Theme::unicode_supported()) {
+            theme = theme.with_ascii();
+        }
+
+        // A bad timezone name falls back to system time, with a notice so it doesn't go unnoticed
+        let (clock, clock_error) = match Clock::from_config(config.timezone.as_deref()) {
+            Ok(clock) => (clock, None),
+            Err(err) => (Clock::default(), Some(format!("{}, using system time", err))),
+        };
+
+        // Subscribed calendars show their cached events now and refresh in the background
+        let calendar_feeds = CalendarFeeds::start(&config.calendars, &clock);
+
+        // The dashboard is off unless there's an address to listen on; it hears of each change to the tasks
+        let (server, server_error) = match config.server.listen.as_deref().map(|listen| Server::start(listen, &config.server, storage.subscribe())) {
+            Some(Ok(server)) => (Some(server), None),
+            Some(Err(err)) => (None, Some(format!("Web dashboard is off: {}", err))),
+            None => (None, None),
+        };
+
+        // Git problems (no git installed, say) leave storage working without history
+        let (git, git_error) = match config.git.auto_commit.then(|| GitRepo::open(GitRepo::get_default_path())) {
+            Some(Ok(repo)) => (Some(repo), None),
+            Some(Err(err)) => (None, Some(format!("Git history is off: {}", err))),
+            None => (None, None),
+        };
+
+        let unknown_tabs: Vec<&str> = config.tabs.iter()
+            .map(|tab| tab.kind.as_str())
+            .filter(|&kind| Tab::from_kind(kind).is_none())
+            .collect();
+        let tab_error = (!unknown_tabs.is_empty()).then(|| format!(
+            "Unknown tab{} in config: {} (tabs can be {})",
+            if unknown_tabs.len() == 1 { "" } else { "s" },
+            unknown_tabs.join(", "),
+            Tab::kind_names(),
+        ));
+
+        // The first thing the quick health check finds, unless something more pressing is shown
+        let health_warning = doctor::quick(&config, &storage.file_paths()).into_iter()
+            .max_by_key(|finding| finding.severity)
+            .map(|finding| format!("{} (run tdui doctor for more)", finding.message));
+
+        let (keymap, keymap_problems) = Keymap::from_config(&config.keys);
+        let usage = config.usage_metrics.then(|| UsageLog::open(UsageLog::get_default_path()));
 
         let mut app = Self {
             should_quit: false,
-            current_date: Local::now().date_naive(),
-            todos,
+            current_date: clock.today(),
+            todos: Vec::new(),
             show_new_task_panel: false,
             show_done_panel: false,
             done_panel_yes_selected: true,
@@ -107,273 +738,3108 @@ impl App {
             show_delete_panel: false,
             delete_panel_yes_selected: true,
             deleting_todo_id: None,
+            show_discard_panel: false,
+            discard_panel_yes_selected: true,
+            discard_return_mode: InputMode::Normal,
+            show_duplicate_panel: false,
+            duplicate_panel_yes_selected: true,
+            duplicate_of: None,
+            adding_duplicate: false,
+            task_editor_original: Default::default(),
+            dont_ask_again: false,
             input_mode: InputMode::Normal,
             focused_panel: Panel::List,
+            zoomed: false,
+            split_view: None,
             selected_tab: Tab::Tasks,
-            selected_todo_index,
+            workspaces: Vec::new(),
+            tasks_tab_state: TabState::default(),
+            show_workspace_panel: false,
+            show_plan_panel: false,
+            selected_habit_index: 0,
+            selected_done_index: 0,
+            show_habit_panel: false,
+            show_waiting_panel: false,
+            show_project_panel: false,
+            project_panel_name: String::new(),
+            project_field: ProjectField::Color,
+            project_color_input: String::new(),
+            project_tags_input: String::new(),
+            project_archive_input: String::new(),
+            project_own_file: false,
+            show_blackout_panel: false,
+            editing_blackout: None,
+            blackout_field: BlackoutField::Start,
+            blackout_start_input: String::new(),
+            blackout_end_input: String::new(),
+            blackout_label_input: String::new(),
+            show_inline_task: false,
+            inline_task_title: String::new(),
+            jump_input: String::new(),
+            url_input: String::new(),
+            title_fetches: Vec::new(),
+            slack_posts: Vec::new(),
+            notification_sends: Vec::new(),
+            jump_hint_input: String::new(),
+            waiting_on_input: String::new(),
+            new_habit_name: String::new(),
+            new_habit_frequency: HabitFrequency::Daily,
+            deleting_habit_id: None,
+            show_note_panel: false,
+            editing_note_date: None,
+            note_text: TextBuffer::default(),
+            note_scroll: 0,
+            focus: None,
+            session_start: SessionStart::capture(&[]),
+            summary_on_exit: config.summary.on_exit && !args.no_summary,
+            exit_summary: None,
+            marked: BTreeSet::new(),
+            show_batch_panel: false,
+            batch_field: BatchField::Tags,
+            batch_tags: String::new(),
+            batch_priority: String::new(),
+            batch_project: String::new(),
+            show_transfer_panel: false,
+            transfer_copy: false,
+            transfer_input: String::new(),
+            selected_transfer_index: 0,
+            show_recovery_panel: false,
+            show_triage_panel: false,
+            triage: Vec::new(),
+            selected_triage_index: 0,
+            show_history_panel: false,
+            history: Vec::new(),
+            selected_history_index: 0,
+            show_attention_panel: false,
+            attention: Vec::new(),
+            selected_attention_index: 0,
+            show_parent_done_panel: false,
+            show_empty_trash_panel: false,
+            show_tool_call_panel: false,
+            parent_done_id: None,
+            plan: Vec::new(),
+            selected_plan_index: 0,
+            workspace_choices: Vec::new(),
+            selected_workspace_index: 0,
+            selected_todo_index: None,
+            collapsed_buckets: HashSet::new(),
+            done_today: Vec::new(),
+            current_day: clock.today(),
+            done_today_expanded: false,
+            done_today_selected: false,
             selected_calendar_date: None,
             task_description_scroll: 0,
+            description_rows: Cell::new((0, 0)),
+            description_layout: RefCell::new(None),
+            selected_link: 0,
             edit_description_scroll: 0,
+            editor_size: Cell::new((0, 0)),
+            task_editor_percent: Cell::new((60, 70)),
             editing_todo_id: None,
             new_task_title: String::new(),
-            new_task_description: String::new(),
+            new_task_description: TextBuffer::default(),
             new_task_due_date: None,
             date_input_buffer: String::new(),
+            new_task_time: String::new(),
+            show_search_panel: false,
+            search_query: String::new(),
+            search_results: Vec::new(),
+            selected_search_index: None,
+            new_task_context: String::new(),
+            new_task_reminders: String::new(),
+            new_task_recurrence: String::new(),
+            editing_field: None,
+            field_input: String::new(),
+            new_task_estimate: String::new(),
+            new_task_parent: None,
+            notice: config_error.or(clock_error).or(git_error).or(server_error).or(tab_error).or(health_warning),
+            show_context_panel: false,
+            context_choices: Vec::new(),
+            selected_context_index: 0,
+            vault: VaultSync::from_config(&config.vault),
+            drop_folder: DropFolder::from_config(&config.drop_folder),
+            config,
+            theme,
+            clock,
             storage,
+            search_index: SearchIndex::open(SearchIndex::get_default_path()),
+            search_index_dirty: false,
+            save_failing: false,
+            archive: Archive::open(Archive::get_default_path()),
+            archived_done_days: BTreeSet::new(),
+            archived_todos: Vec::new(),
+            vault_synced_at: Instant::now(),
+            drop_folder_checked_at: Instant::now(),
+            files_checked_at: Instant::now(),
+            drawn: None,
+            focused: true,
+            last_input: Instant::now(),
+            habit_storage: HabitStorage::open(HabitStorage::get_default_path()),
+            note_storage: NoteStorage::open(NoteStorage::get_default_path()),
+            project_storage: ProjectStorage::open(ProjectStorage::get_default_path()),
+            blackout_storage: BlackoutStorage::open(BlackoutStorage::get_default_path()),
+            stats: None,
+            notifier: Notifier::new(clock),
+            calendar_feeds,
+            server,
+            server_published: None,
+            tool_calls: VecDeque::new(),
+            git,
+            keymap,
+            show_keymap_problems: !keymap_problems.is_empty(),
+            keymap_problems,
+            usage,
+            show_usage: false,
+            show_estimates: false,
+            stats_all_sources: false,
+            mouse_targets: RefCell::new(Vec::new()),
+            drag: None,
         };
 
-        app.sort_todos();
+        app.selected_tab = app.tabs()[0];
+        app.archived_todos = app.archive.load_all();
+        // Damaged files come first; archiving and triage would change tasks the user may not keep
+        let damaged = !app.storage.damaged_files().is_empty();
+        if !damaged {
+            app.purge_old_trash();
+            app.archive_old_tasks();
+            app.sync_vault();
+            app.import_dropped_files();
+        }
+        app.reload_todos();
+        app.refresh_search_index();
+        app.session_start = SessionStart::capture(app.all_todos());
+        if damaged {
+            app.show_recovery_panel = true;
+            app.input_mode = InputMode::Recovery;
+        } else {
+            app.open_triage_panel();
+        }
+        app.nag_waiting();
         app
     }
 
+    /// Reload the active task list from storage, keeping only tasks in the active context
+    fn reload_todos(&mut self) {
+        let context = self.config.active_context.clone();
+        self.refresh_done_today();
+        // Filter out completed and deleted todos
+        self.todos = self.all_todos()
+            .iter()
+            .filter(|t| !t.completed && !t.deleted && t.in_context(context.as_deref()))
+            .cloned()
+            .collect();
+        self.sort_todos();
+        self.selected_todo_index = if self.todos.is_empty() { None } else { Some(0) };
+        self.done_today_selected = false;
+        self.task_description_scroll = 0;
+        self.selected_link = 0;
+    }
+
     pub fn next_panel(&mut self) {
+        // With a split view, Tab visits the second list before moving on to the calendar
+        if self.focused_panel == Panel::List
+            && self.split_view.as_ref().is_some_and(|split| split.active_pane == 0)
+        {
+            self.switch_split_pane(1);
+            return;
+        }
+
         self.focused_panel = self.focused_panel.next();
+        if self.focused_panel == Panel::List && self.split_view.is_some() {
+            self.switch_split_pane(0);
+        }
 
         // Initialize calendar selection to today when switching to calendar panel
         if self.focused_panel == Panel::Calendar && self.selected_calendar_date.is_none() {
-            self.selected_calendar_date = Some(Local::now().date_naive());
+            self.selected_calendar_date = Some(self.clock.today());
         }
     }
 
-    pub fn next_tab(&mut self) {
-        self.selected_tab = self.selected_tab.next();
+    pub fn toggle_zoom(&mut self) {
+        self.zoomed = !self.zoomed;
     }
 
-    pub fn previous_tab(&mut self) {
-        self.selected_tab = self.selected_tab.previous();
+    pub fn toggle_split_view(&mut self) {
+        if self.split_view.is_some() {
+            self.split_view = None;
+        } else {
+            self.split_view = Some(SplitView {
+                filters: [ListFilter::Today, ListFilter::Backlog],
+                selected_ids: [None, None],
+                active_pane: 0,
+            });
+            self.focused_panel = Panel::List;
+            self.sync_list_selection();
+        }
     }
 
-    fn sort_todos(&mut self) {
-        self.todos.sort_by(|a, b| {
-            // First sort by due date (ascending, None comes last)
-            match (a.due_date, b.due_date) {
-                (Some(date_a), Some(date_b)) => {
-                    // Both have due dates, compare them
-                    match date_a.cmp(&date_b) {
-                        std::cmp::Ordering::Equal => {
-                            // If due dates are equal, sort by created date
-                            a.created_at.cmp(&b.created_at)
-                        }
-                        other => other,
-                    }
-                }
-                (Some(_), None) => std::cmp::Ordering::Less,  // Tasks with due dates come first
-                (None, Some(_)) => std::cmp::Ordering::Greater, // Tasks without due dates come last
-                (None, None) => a.created_at.cmp(&b.created_at), // Both have no due date, sort by created
-            }
-        });
+    fn switch_split_pane(&mut self, pane: usize) {
+        if let Some(split) = &mut self.split_view {
+            split.active_pane = pane;
+            let selected_id = split.selected_ids[pane];
+            self.selected_todo_index = selected_id.and_then(|id| self.todos.iter().position(|t| t.id == id));
+            self.task_description_scroll = 0;
+            self.selected_link = 0;
+        }
+        self.sync_list_selection();
     }
 
-    pub fn select_previous_todo(&mut self) {
-        if self.todos.is_empty() {
+    /// Keep the selection inside the visible list; with a split view each pane keeps its own,
+    /// and the focused pane drives `selected_todo_index`
+    pub fn sync_list_selection(&mut self) {
+        if self.selected_tab == Tab::Board {
+            let board = self.board();
+            self.selected_todo_index = self.selected_todo_index
+                .filter(|&i| board.position_of(i).is_some())
+                .or(board.first());
+            return;
+        }
+        if self.done_today_selected && self.shows_done_today() {
             self.selected_todo_index = None;
             return;
         }
+        self.done_today_selected = false;
 
-        self.selected_todo_index = Some(match self.selected_todo_index {
-            Some(i) if i > 0 => i - 1,
-            Some(_) => self.todos.len() - 1,
-            None => 0,
-        });
-
-        // Reset scroll when changing tasks
-        self.task_description_scroll = 0;
-    }
-
-    pub fn select_next_todo(&mut self) {
-        if self.todos.is_empty() {
-            self.selected_todo_index = None;
+        let Some(mut split) = self.split_view.clone() else {
+            let visible = self.visible_todo_indices();
+            self.selected_todo_index = self.selected_todo_index
+                .filter(|i| visible.contains(i))
+                .or(visible.first().copied());
             return;
+        };
+
+        for pane in 0..2 {
+            let visible = self.selectable_todo_indices(&split.filters[pane]);
+            if pane == split.active_pane {
+                let selected = self.selected_todo_index
+                    .filter(|i| visible.contains(i))
+                    .or(visible.first().copied());
+                self.selected_todo_index = selected;
+                split.selected_ids[pane] = selected.map(|i| self.todos[i].id);
+            } else {
+                let still_visible = split.selected_ids[pane]
+                    .is_some_and(|id| visible.iter().any(|&i| self.todos[i].id == id));
+                if !still_visible {
+                    split.selected_ids[pane] = visible.first().map(|&i| self.todos[i].id);
+                }
+            }
         }
 
-        self.selected_todo_index = Some(match self.selected_todo_index {
-            Some(i) if i < self.todos.len() - 1 => i + 1,
-            Some(_) => 0,
-            None => 0,
-        });
+        self.split_view = Some(split);
+    }
 
-        // Reset scroll when changing tasks
-        self.task_description_scroll = 0;
+    /// The built-in filters followed by every context, then every project, in use
+    fn filter_choices(&self) -> Vec<ListFilter> {
+        let mut choices = vec![ListFilter::All, ListFilter::Today, ListFilter::Backlog, ListFilter::Waiting, ListFilter::Someday];
+        let mut contexts: Vec<String> = self.todos.iter().filter_map(|t| t.context.clone()).collect();
+        contexts.sort();
+        contexts.dedup();
+        choices.extend(contexts.into_iter().map(ListFilter::Context));
+        let mut projects: Vec<String> = self.todos.iter().filter_map(|t| t.project.clone()).collect();
+        projects.sort();
+        projects.dedup();
+        choices.extend(projects.into_iter().map(ListFilter::Project));
+        choices
     }
 
-    pub fn select_next_day(&mut self) {
-        if let Some(date) = self.selected_calendar_date {
-            self.selected_calendar_date = Some(date + chrono::Duration::days(1));
-            self.update_calendar_view();
-        } else {
-            self.selected_calendar_date = Some(Local::now().date_naive());
+    pub fn cycle_split_filter(&mut self) {
+        let choices = self.filter_choices();
+        if let Some(split) = &mut self.split_view {
+            let pane = split.active_pane;
+            let current = choices.iter().position(|f| *f == split.filters[pane]).unwrap_or(0);
+            split.filters[pane] = choices[(current + 1) % choices.len()].clone();
         }
+        self.sync_list_selection();
     }
 
-    pub fn select_previous_day(&mut self) {
-        if let Some(date) = self.selected_calendar_date {
-            self.selected_calendar_date = Some(date - chrono::Duration::days(1));
-            self.update_calendar_view();
-        } else {
-            self.selected_calendar_date = Some(Local::now().date_naive());
+    /// Change the selected task so it matches the other pane's filter, and follow it there
+    pub fn move_selected_to_other_pane(&mut self) {
+        let Some(split) = self.split_view.clone() else {
+            return;
+        };
+        let Some(index) = self.selected_todo_index else {
+            return;
+        };
+
+        let other_pane = 1 - split.active_pane;
+        let today = self.clock.today();
+        let todo = &mut self.todos[index];
+        match &split.filters[other_pane] {
+            ListFilter::All if !todo.someday => return,
+            ListFilter::All | ListFilter::Someday => {}
+            ListFilter::Today => {
+                if todo.due_date.is_none_or(|d| d > today) {
+                    todo.due_date = Some(today);
+                }
+            }
+            ListFilter::Backlog => {
+                if todo.due_date.is_some_and(|d| d <= today) {
+                    todo.due_date = None;
+                }
+            }
+            ListFilter::Waiting => {
+                todo.set_waiting(true);
+                let waiting_status = &self.config.waiting.status;
+                if self.config.workflow.open_statuses().iter().any(|status| status.name == *waiting_status) {
+                    todo.set_status(Some(waiting_status.clone()));
+                }
+            }
+            ListFilter::Context(context) => todo.context = Some(context.clone()),
+            ListFilter::Project(project) => todo.project = Some(project.clone()),
+            ListFilter::Day(date) => todo.due_date = Some(*date),
         }
-    }
+        // Only the Someday list shows someday tasks
+        todo.someday = split.filters[other_pane] == ListFilter::Someday;
+        let task_id = todo.id;
 
-    pub fn select_day_above(&mut self) {
-        if let Some(date) = self.selected_calendar_date {
-            self.selected_calendar_date = Some(date - chrono::Duration::days(7));
-            self.update_calendar_view();
-        } else {
-            self.selected_calendar_date = Some(Local::now().date_naive());
+        self.sort_todos();
+        self.persist_todo(task_id);
+        if let Some(split) = &mut self.split_view {
+            split.active_pane = other_pane;
         }
+        self.selected_todo_index = self.todos.iter().position(|t| t.id == task_id);
+        self.sync_list_selection();
     }
 
-    pub fn select_day_below(&mut self) {
-        if let Some(date) = self.selected_calendar_date {
-            self.selected_calendar_date = Some(date + chrono::Duration::days(7));
-            self.update_calendar_view();
-        } else {
-            self.selected_calendar_date = Some(Local::now().date_naive());
+    /// Every open tab in display order: Tasks, Stats, then the workspaces
+    /// The tabs listed in config, in their order, then the open workspaces; Tasks if config lists none it knows
+    pub fn tabs(&self) -> Vec<Tab> {
+        let mut tabs: Vec<Tab> = Vec::new();
+        for tab in self.config.tabs.iter().filter_map(|tab| Tab::from_kind(&tab.kind)) {
+            if !tabs.contains(&tab) {
+                tabs.push(tab);
+            }
+        }
+        if tabs.is_empty() {
+            tabs.push(Tab::Tasks);
         }
+        tabs.extend((0..self.workspaces.len()).map(Tab::Workspace));
+        tabs
     }
 
-    fn update_calendar_view(&mut self) {
-        // Check if selected date is outside the visible range and shift the view if needed
-        if let Some(selected) = self.selected_calendar_date {
-            let current_year = self.current_date.year();
-            let current_month = self.current_date.month();
+    pub fn tab_title(&self, tab: Tab) -> String {
+        let name = match tab {
+            Tab::Tasks => "Tasks",
+            Tab::Stats => "Stats",
+            Tab::Habits => "Habits",
+            Tab::Board => "Board",
+            Tab::Month => "Month",
+            Tab::Year => "Year",
+            Tab::Done => "Done",
+            Tab::Workspace(i) => return self.workspaces[i].filter.label(),
+        };
+        // Config can rename a built-in tab
+        self.config.tabs.iter()
+            .find(|entry| Tab::from_kind(&entry.kind) == Some(tab))
+            .and_then(|entry| entry.title.clone())
+            .unwrap_or_else(|| name.to_string())
+    }
 
-            let selected_year = selected.year();
-            let selected_month = selected.month();
+    pub fn next_tab(&mut self) {
+        let tabs = self.tabs();
+        let current = tabs.iter().position(|&t| t == self.selected_tab).unwrap_or(0);
+        self.switch_tab(tabs[(current + 1) % tabs.len()]);
+    }
 
-            // Calculate the first month of the visible range (previous month)
-            let (prev_year, prev_month) = if current_month == 1 {
-                (current_year - 1, 12)
-            } else {
-                (current_year, current_month - 1)
-            };
+    pub fn previous_tab(&mut self) {
+        let tabs = self.tabs();
+        let current = tabs.iter().position(|&t| t == self.selected_tab).unwrap_or(0);
+        self.switch_tab(tabs[(current + tabs.len() - 1) % tabs.len()]);
+    }
 
-            // Calculate the last month of the visible range (next month)
-            let (next_year, next_month) = if current_month == 12 {
-                (current_year + 1, 1)
-            } else {
-                (current_year, current_month + 1)
-            };
+    /// Move to another tab, remembering the selection of the one being left
+    fn switch_tab(&mut self, tab: Tab) {
+        let state = TabState {
+            selected_id: self.selected_todo_index.map(|i| self.todos[i].id),
+            focused_panel: self.focused_panel.clone(),
+        };
+        match self.selected_tab {
+            Tab::Tasks => self.tasks_tab_state = state,
+            Tab::Workspace(i) => self.workspaces[i].state = state,
+            Tab::Stats | Tab::Habits | Tab::Board | Tab::Month | Tab::Year | Tab::Done => {}
+        }
 
-            // Check if selected date is before the visible range
-            if selected_year < prev_year || (selected_year == prev_year && selected_month < prev_month) {
-                // Shift view backward by one month
-                self.current_date = if current_month == 1 {
-                    NaiveDate::from_ymd_opt(current_year - 1, 12, 1).unwrap()
-                } else {
-                    NaiveDate::from_ymd_opt(current_year, current_month - 1, 1).unwrap()
-                };
-            }
-            // Check if selected date is after the visible range
-            else if selected_year > next_year || (selected_year == next_year && selected_month > next_month) {
-                // Shift view forward by one month
-                self.current_date = if current_month == 12 {
-                    NaiveDate::from_ymd_opt(current_year + 1, 1, 1).unwrap()
-                } else {
-                    NaiveDate::from_ymd_opt(current_year, current_month + 1, 1).unwrap()
-                };
+        self.selected_tab = tab;
+        let restored = match tab {
+            Tab::Tasks => Some(self.tasks_tab_state.clone()),
+            Tab::Workspace(i) => Some(self.workspaces[i].state.clone()),
+            Tab::Stats | Tab::Habits | Tab::Done => None,
+            // The month and year share the calendar's selected day
+            Tab::Month | Tab::Year => {
+                self.selected_calendar_date.get_or_insert(self.clock.today());
+                None
             }
+            // The board shares the task selection, and its arrows only move it while the list has focus
+            Tab::Board => Some(TabState { selected_id: self.selected_todo_index.map(|i| self.todos[i].id), focused_panel: Panel::List }),
+        };
+        if let Some(state) = restored {
+            self.selected_todo_index = state.selected_id
+                .and_then(|id| self.todos.iter().position(|t| t.id == id));
+            self.focused_panel = state.focused_panel;
+            self.done_today_selected = false;
+            self.task_description_scroll = 0;
+            self.selected_link = 0;
+            self.sync_list_selection();
         }
     }
 
-    pub fn reset_calendar_to_today(&mut self) {
-        let today = Local::now().date_naive();
-        self.current_date = today;
-        self.selected_calendar_date = Some(today);
+    /// The filter of the current workspace tab, if one is selected
+    fn workspace_filter(&self) -> Option<&ListFilter> {
+        match self.selected_tab {
+            Tab::Workspace(i) => self.workspaces.get(i).map(|w| &w.filter),
+            _ => None,
+        }
     }
 
-    pub fn scroll_description_up(&mut self) {
-        if self.task_description_scroll > 0 {
-            self.task_description_scroll -= 1;
+    /// The project new tasks go in: the current workspace's, if it's bound to one
+    fn workspace_project(&self) -> Option<String> {
+        match self.workspace_filter() {
+            Some(ListFilter::Project(project)) => Some(project.clone()),
+            _ => None,
         }
     }
 
-    pub fn scroll_description_down(&mut self) {
-        self.task_description_scroll += 1;
+    /// What the views are filtered to: the workspace's filter, or else the active context
+    pub fn filter_label(&self) -> String {
+        match (self.workspace_filter(), &self.config.active_context) {
+            (Some(filter), _) => filter.label(),
+            (None, Some(context)) => context.clone(),
+            (None, None) => "All contexts".to_string(),
+        }
+    }
+
+    /// Whether the latest changes are on disk, and committed when git history is on
+    pub fn save_status(&self) -> &'static str {
+        match &self.git {
+            _ if self.storage.is_dirty() => "unsaved",
+            Some(git) if git.has_pending() => "not committed",
+            Some(_) => "committed",
+            None => "saved",
+        }
     }
 
-    pub fn scroll_edit_description_up(&mut self) {
-        self.edit_description_scroll = self.edit_description_scroll.saturating_sub(3);
+    pub fn open_workspace_panel(&mut self) {
+        self.workspace_choices = self.filter_choices();
+        self.selected_workspace_index = 0;
+        self.show_workspace_panel = true;
+        self.input_mode = InputMode::WorkspacePanel;
     }
 
-    pub fn scroll_edit_description_down(&mut self) {
-        self.edit_description_scroll = self.edit_description_scroll.saturating_add(3);
+    pub fn close_workspace_panel(&mut self) {
+        self.show_workspace_panel = false;
+        self.workspace_choices.clear();
+        self.selected_workspace_index = 0;
+        self.input_mode = InputMode::Normal;
     }
 
-    pub fn auto_scroll_to_cursor(&mut self) {
-        // Auto-scroll the description view to keep the cursor visible
-        // Estimate visible lines: panel is 70% height, description gets Min(10) lines
-        // With typical terminal height, we can see about 10-15 lines
-        let visible_lines = 10u16;
+    pub fn select_previous_workspace_choice(&mut self) {
+        let count = self.workspace_choices.len();
+        self.selected_workspace_index = (self.selected_workspace_index + count - 1) % count;
+    }
 
-        let line_count = self.new_task_description.split('\n').count() as u16;
+    pub fn select_next_workspace_choice(&mut self) {
+        let count = self.workspace_choices.len();
+        self.selected_workspace_index = (self.selected_workspace_index + 1) % count;
+    }
 
-        // If content exceeds visible area, scroll to show the bottom
-        if line_count > visible_lines {
-            // Keep cursor near bottom with 1 line padding
-            self.edit_description_scroll = (line_count - visible_lines + 1).max(0);
-        } else {
-            // Content fits, no scroll needed
-            self.edit_description_scroll = 0;
+    /// Propose due dates for the undated and overdue tasks in the active context, for review before applying
+    pub fn open_plan_panel(&mut self) {
+        self.plan = planner::plan(&self.todos, self.clock.today(), self.capacity(), |date| self.is_working_day(date));
+        if self.plan.is_empty() {
+            self.notice = Some("Nothing to plan: no undated or overdue tasks".to_string());
+            return;
         }
+        self.selected_plan_index = 0;
+        self.show_plan_panel = true;
+        self.input_mode = InputMode::PlanPanel;
     }
 
-    pub fn get_all_todos(&self) -> Vec<Todo> {
-        self.storage.load_todos().unwrap_or_else(|_| Vec::new())
+    pub fn close_plan_panel(&mut self) {
+        self.show_plan_panel = false;
+        self.plan.clear();
+        self.selected_plan_index = 0;
+        self.input_mode = InputMode::Normal;
     }
 
-    pub fn open_new_task_panel(&mut self) {
-        self.open_new_task_panel_with_date(None);
+    pub fn select_previous_plan_item(&mut self) {
+        let count = self.plan.len();
+        self.selected_plan_index = (self.selected_plan_index + count - 1) % count;
     }
 
-    pub fn open_new_task_panel_with_date(&mut self, due_date: Option<NaiveDate>) {
-        self.show_new_task_panel = true;
-        self.input_mode = InputMode::EditingTitle;
-        self.editing_todo_id = None;
-        self.new_task_title.clear();
-        self.new_task_description.clear();
-        self.new_task_due_date = due_date;
-        self.date_input_buffer = due_date
-            .map(|d| d.format("%Y-%m-%d").to_string())
-            .unwrap_or_else(|| String::new());
-        self.edit_description_scroll = 0;
+    pub fn select_next_plan_item(&mut self) {
+        let count = self.plan.len();
+        self.selected_plan_index = (self.selected_plan_index + 1) % count;
     }
 
-    pub fn open_edit_task_panel(&mut self) {
-        if let Some(index) = self.selected_todo_index {
-            if let Some(todo) = self.todos.get(index) {
-                self.show_new_task_panel = true;
-                self.input_mode = InputMode::EditingTitle;
-                self.editing_todo_id = Some(todo.id);
-                self.new_task_title = todo.title.clone();
-                self.new_task_description = todo.description.clone();
-                self.new_task_due_date = todo.due_date;
-                self.date_input_buffer = todo.due_date
-                    .map(|d| d.format("%Y-%m-%d").to_string())
-                    .unwrap_or_else(|| String::new());
-                self.edit_description_scroll = 0;
+    /// Move the selected proposal to the next (or previous) working day, never before today
+    pub fn shift_planned_task(&mut self, days: i64) {
+        let today = self.clock.today();
+        let Some(item) = self.plan.get(self.selected_plan_index) else {
+            return;
+        };
+        let mut date = item.to.unwrap_or(today);
+        for _ in 0..366 {
+            date += chrono::Duration::days(days);
+            if date < today {
+                return;
+            }
+            if self.is_working_day(date) {
+                break;
             }
         }
+        let item = &mut self.plan[self.selected_plan_index];
+        item.to = Some(date);
+        item.include = true;
     }
 
-    pub fn close_new_task_panel(&mut self) {
-        self.show_new_task_panel = false;
-        self.input_mode = InputMode::Normal;
-        self.editing_todo_id = None;
-        self.new_task_title.clear();
-        self.new_task_description.clear();
-        self.new_task_due_date = None;
+    /// Leave the selected task out of the plan, or put it back
+    pub fn toggle_planned_task(&mut self) {
+        if let Some(item) = self.plan.get_mut(self.selected_plan_index) {
+            item.include = !item.include && item.to.is_some();
+        }
+    }
+
+    /// Write the accepted due dates
+    pub fn apply_plan(&mut self) {
+        let accepted: Vec<(usize, NaiveDate)> = self.plan.iter()
+            .filter(|item| item.include)
+            .filter_map(|item| item.to.map(|to| (item.id, to)))
+            .collect();
+        let selected_id = self.selected_todo_index.and_then(|index| self.todos.get(index)).map(|t| t.id);
+
+        for (id, due_date) in &accepted {
+            if let Some(todo) = self.todos.iter_mut().find(|t| t.id == *id) {
+                todo.due_date = Some(*due_date);
+            }
+            self.persist_todo(*id);
+        }
+        self.sort_todos();
+        self.selected_todo_index = selected_id.and_then(|id| self.todos.iter().position(|t| t.id == id));
+        self.close_plan_panel();
+        self.notice = Some(format!("Scheduled {} task{}", accepted.len(), if accepted.len() == 1 { "" } else { "s" }));
+    }
+
+    /// Mark the selected task for a batch edit, or unmark it
+    pub fn toggle_mark_selected(&mut self) {
+        if let Some(todo) = self.selected_todo_index.and_then(|index| self.todos.get(index))
+            && !self.marked.remove(&todo.id)
+        {
+            self.marked.insert(todo.id);
+        }
+    }
+
+    /// Marked tasks still in the list, in list order
+    pub fn marked_todos(&self) -> Vec<&Todo> {
+        self.todos.iter().filter(|t| self.marked.contains(&t.id)).collect()
+    }
+
+    pub fn open_batch_panel(&mut self) {
+        if self.marked_todos().is_empty() {
+            self.notice = Some("Mark tasks with space first, then B to edit them together".to_string());
+            return;
+        }
+        self.show_batch_panel = true;
+        self.input_mode = InputMode::BatchEdit;
+        self.batch_field = BatchField::Tags;
+    }
+
+    pub fn close_batch_panel(&mut self) {
+        self.show_batch_panel = false;
+        self.input_mode = InputMode::Normal;
+        self.batch_tags.clear();
+        self.batch_priority.clear();
+        self.batch_project.clear();
+    }
+
+    /// The edit described by the batch-edit fields so far
+    pub fn batch_edit(&self) -> anyhow::Result<BatchEdit> {
+        let mut edit = BatchEdit::parse(&self.batch_tags, &self.batch_priority, &self.batch_project)?;
+        // Putting tasks in a project also gives them its default tags
+        if let Some(Some(name)) = &edit.project
+            && let Some(project) = self.project_storage.get(name)
+        {
+            for tag in &project.default_tags {
+                if !edit.add_tags.contains(tag) && !edit.remove_tags.contains(tag) {
+                    edit.add_tags.push(tag.clone());
+                }
+            }
+        }
+        Ok(edit)
+    }
+
+    fn batch_input(&mut self) -> &mut String {
+        match self.batch_field {
+            BatchField::Tags => &mut self.batch_tags,
+            BatchField::Priority => &mut self.batch_priority,
+            BatchField::Project => &mut self.batch_project,
+        }
+    }
+
+    /// Apply the batch edit to every marked task, then clear the marks
+    pub fn apply_batch_edit(&mut self) {
+        let edit = match self.batch_edit() {
+            Ok(edit) => edit,
+            Err(err) => {
+                // Leave the popup open so the input can be fixed
+                self.notice = Some(err.to_string());
+                return;
+            }
+        };
+        let ids: Vec<usize> = self.marked_todos().iter()
+            .filter(|todo| !edit.changes(todo).is_empty())
+            .map(|todo| todo.id)
+            .collect();
+        for id in &ids {
+            if let Some(todo) = self.todos.iter_mut().find(|t| t.id == *id) {
+                edit.apply(todo);
+            }
+            self.persist_todo(*id);
+        }
+        self.marked.clear();
+        self.close_batch_panel();
+        self.notice = Some(format!("Updated {} task{}", ids.len(), if ids.len() == 1 { "" } else { "s" }));
+    }
+
+    /// The marked tasks, or the selected one when none are marked
+    pub fn marked_or_selected(&self) -> Vec<&Todo> {
+        let marked = self.marked_todos();
+        if !marked.is_empty() {
+            return marked;
+        }
+        self.selected_todo_index.and_then(|index| self.todos.get(index)).into_iter().collect()
+    }
+
+    pub fn open_transfer_panel(&mut self) {
+        if self.marked_or_selected().is_empty() {
+            return;
+        }
+        self.transfer_copy = false;
+        self.transfer_input.clear();
+        self.selected_transfer_index = 0;
+        self.show_transfer_panel = true;
+        self.input_mode = InputMode::TransferPanel;
+    }
+
+    pub fn close_transfer_panel(&mut self) {
+        self.show_transfer_panel = false;
+        self.transfer_input.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Where the tasks can go, narrowed down by what's typed: projects in use or with settings, a
+    /// new one named as typed unless one already has that name, no project, then the other data folders
+    pub fn transfer_choices(&self) -> Vec<Destination> {
+        let typed = self.transfer_input.trim();
+        let matches = |name: &str| name.to_lowercase().contains(&typed.to_lowercase());
+        let mut projects: Vec<String> = self.all_todos().iter()
+            .filter_map(|todo| todo.project.clone())
+            .chain(self.project_storage.projects().iter().map(|project| project.name.clone()))
+            .collect();
+        projects.sort_by_key(|name| name.to_lowercase());
+        projects.dedup();
+
+        let new_project = !typed.is_empty() && !projects.iter().any(|name| name.eq_ignore_ascii_case(typed));
+        let mut choices: Vec<Destination> = projects.into_iter()
+            .filter(|name| matches(name))
+            .map(|name| Destination::Project(Some(name)))
+            .collect();
+        if new_project {
+            choices.push(Destination::NewProject(typed.to_string()));
+        }
+        if typed.is_empty() {
+            choices.push(Destination::Project(None));
+        }
+        choices.extend(self.config.stats.sources.iter().enumerate()
+            .filter(|(_, folder)| matches(&folder.name))
+            .map(|(index, _)| Destination::Folder(index)));
+        choices
+    }
+
+    /// Move or copy the tasks to the chosen destination, then clear the marks
+    pub fn apply_transfer(&mut self) {
+        let Some(destination) = self.transfer_choices().get(self.selected_transfer_index).cloned() else {
+            return;
+        };
+        let picked: Vec<Todo> = self.marked_or_selected().into_iter().cloned().collect();
+        let verb = if self.transfer_copy { "Copied" } else { "Moved" };
+        let plural = |count: usize| if count == 1 { "" } else { "s" };
+        let project = match &destination {
+            Destination::Project(project) => project.clone(),
+            Destination::NewProject(name) => Some(name.clone()),
+            Destination::Folder(index) => {
+                let folder = self.config.stats.sources[*index].clone();
+                self.transfer_to_folder(&picked, &folder);
+                return;
+            }
+        };
+
+        // Like a batch edit, putting tasks in a project also gives them its default tags
+        let default_tags = project.as_deref()
+            .and_then(|name| self.project_storage.get(name))
+            .map(|settings| settings.default_tags.clone())
+            .unwrap_or_default();
+        let into_project = |todo: &mut Todo| {
+            todo.project = project.clone();
+            for tag in &default_tags {
+                if !todo.tags.contains(tag) {
+                    todo.tags.push(tag.clone());
+                }
+            }
+        };
+        let ids: Vec<usize> = if self.transfer_copy {
+            let copies: Vec<Todo> = picked.iter().map(transfer::fresh_copy).collect();
+            let mut copies = transfer::renumber(&copies, self.next_todo_id());
+            copies.iter_mut().for_each(into_project);
+            let ids = copies.iter().map(|todo| todo.id).collect();
+            self.todos.extend(copies);
+            ids
+        } else {
+            for todo in self.todos.iter_mut().filter(|todo| picked.iter().any(|p| p.id == todo.id)) {
+                into_project(todo);
+            }
+            picked.iter().map(|todo| todo.id).collect()
+        };
+        for id in &ids {
+            self.persist_todo(*id);
+        }
+        self.sort_todos();
+        if let Some(&first) = ids.first() {
+            self.selected_todo_index = self.todos.iter().position(|t| t.id == first);
+        }
+        self.sync_list_selection();
+        self.marked.clear();
+        self.close_transfer_panel();
+        let to = project.map_or_else(|| "out of their project".to_string(), |name| format!("to {}", name));
+        self.notice = Some(format!("{} {} task{} {}", verb, ids.len(), plural(ids.len()), to));
+    }
+
+    /// Add the tasks and their subtasks to another data folder, taking them out of this one when moving
+    fn transfer_to_folder(&mut self, picked: &[Todo], folder: &StatsSource) {
+        let mut tasks = transfer::with_subtasks(picked, self.all_todos());
+        if self.transfer_copy {
+            tasks = tasks.iter().map(transfer::fresh_copy).collect();
+        }
+        let count = match transfer::add_to_folder(&expand_home(&folder.path), &tasks) {
+            Ok(count) => count,
+            Err(err) => {
+                // Leave the popup open to pick somewhere else
+                self.notice = Some(format!("Couldn't add the tasks to {}: {}", folder.name, err));
+                return;
+            }
+        };
+        if !self.transfer_copy {
+            let ids: HashSet<usize> = tasks.iter().map(|todo| todo.id).collect();
+            self.storage.remove_where(|todo| ids.contains(&todo.id));
+            self.todos.retain(|todo| !ids.contains(&todo.id));
+            self.record_change(format!("move {} task{} to {}", count, if count == 1 { "" } else { "s" }, folder.name));
+            self.refresh_search_index();
+            self.selected_todo_index = self.selected_todo_index.map(|index| index.min(self.todos.len().saturating_sub(1)));
+            if self.todos.is_empty() {
+                self.selected_todo_index = None;
+            }
+            self.sync_list_selection();
+        }
+        self.marked.clear();
+        self.close_transfer_panel();
+        let verb = if self.transfer_copy { "Copied" } else { "Moved" };
+        self.notice = Some(format!("{} {} task{} to {}", verb, count, if count == 1 { "" } else { "s" }, folder.name));
+    }
+
+    /// Ask about open tasks that have gone untouched for too long (done once, at startup)
+    pub fn damaged_files(&self) -> &[DamagedFile] {
+        self.storage.damaged_files()
+    }
+
+    /// Back up the damaged files and save the recovered tasks over them
+    fn keep_recovered_tasks(&mut self) {
+        let recovered: usize = self.storage.damaged_files().iter().map(|file| file.recovered).sum();
+        match self.storage.keep_recovered() {
+            Ok(()) => {
+                self.show_recovery_panel = false;
+                self.input_mode = InputMode::Normal;
+                self.notice = Some(format!(
+                    "Kept {} recovered task{}; the damaged originals were backed up next to them",
+                    recovered,
+                    if recovered == 1 { "" } else { "s" },
+                ));
+                self.open_triage_panel();
+            }
+            Err(err) => self.notice = Some(format!("Couldn't back up the damaged file: {}", err)),
+        }
+    }
+
+    /// Ask about stale tasks and someday tasks due a look, then show the escalated ones once that's done
+    fn open_triage_panel(&mut self) {
+        let (stale_after, someday_after) = (self.config.triage.stale_after_days, self.config.triage.someday_after_days);
+        if stale_after > 0 {
+            self.triage = triage::stale(&self.todos, stale_after, &self.clock);
+        }
+        if someday_after > 0 {
+            self.triage.extend(triage::someday(&self.todos, someday_after, &self.clock));
+        }
+        if self.triage.is_empty() {
+            self.open_attention_panel();
+        } else {
+            self.selected_triage_index = 0;
+            self.show_triage_panel = true;
+            self.input_mode = InputMode::TriagePanel;
+        }
+    }
+
+    /// Leave every task as it is for now; they'll be asked about again next startup
+    pub fn close_triage_panel(&mut self) {
+        self.show_triage_panel = false;
+        self.triage.clear();
+        self.selected_triage_index = 0;
+        self.input_mode = InputMode::Normal;
+        self.open_attention_panel();
+    }
+
+    /// Show how the selected task's description changed, from the git history of the data
+    pub fn open_history_panel(&mut self) {
+        let Some(id) = self.selected_todo_index.and_then(|index| self.todos.get(index)).map(|todo| todo.id) else {
+            return;
+        };
+        // Commit what's pending so the latest edit is in the history too
+        self.flush_changes(true);
+        let Some(git) = &self.git else {
+            self.notice = Some("Git history is off: set \"git\": { \"auto_commit\": true } in config".to_string());
+            return;
+        };
+        let versions = match git.task_versions(id) {
+            Ok(versions) => versions,
+            Err(err) => {
+                self.notice = Some(format!("Couldn't read the history: {}", err));
+                return;
+            }
+        };
+        // Keep the version that introduced each description, oldest to newest, then flip it
+        let mut history: Vec<TaskVersion> = Vec::new();
+        for version in versions.into_iter().rev() {
+            if history.last().is_none_or(|last| last.todo.description != version.todo.description) {
+                history.push(version);
+            }
+        }
+        history.reverse();
+        if history.is_empty() {
+            self.notice = Some(format!("Task #{} has no committed history yet", id));
+            return;
+        }
+        self.history = history;
+        self.selected_history_index = 0;
+        self.show_history_panel = true;
+        self.input_mode = InputMode::HistoryPanel;
+    }
+
+    pub fn close_history_panel(&mut self) {
+        self.show_history_panel = false;
+        self.history.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Whether an open task is overdue by more than the escalation threshold
+    pub fn is_escalated(&self, todo: &Todo) -> bool {
+        escalated(todo, self.config.escalation.overdue_days, self.clock.today())
+    }
+
+    /// List the escalated tasks, if there are any
+    fn open_attention_panel(&mut self) {
+        self.attention = self.todos.iter()
+            .filter(|todo| self.is_escalated(todo))
+            .map(|todo| todo.id)
+            .collect();
+        if !self.attention.is_empty() {
+            self.selected_attention_index = 0;
+            self.show_attention_panel = true;
+            self.input_mode = InputMode::AttentionPanel;
+        }
+    }
+
+    pub fn close_attention_panel(&mut self) {
+        self.show_attention_panel = false;
+        self.attention.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn select_previous_attention_item(&mut self) {
+        let count = self.attention.len().max(1);
+        self.selected_attention_index = (self.selected_attention_index + count - 1) % count;
+    }
+
+    pub fn select_next_attention_item(&mut self) {
+        let count = self.attention.len().max(1);
+        self.selected_attention_index = (self.selected_attention_index + 1) % count;
+    }
+
+    /// Close the popup and select the chosen task in the list
+    pub fn open_attention_item(&mut self) {
+        let id = self.attention.get(self.selected_attention_index).copied();
+        self.close_attention_panel();
+        if let Some(id) = id {
+            self.select_task_by_id(id);
+        }
+    }
+
+    /// Complete or reschedule (to the next working day with room) the chosen task and drop it from
+    /// the popup
+    pub fn resolve_attention_item(&mut self, complete: bool) {
+        let Some(&id) = self.attention.get(self.selected_attention_index) else {
+            return;
+        };
+        if complete {
+            self.completing_todo_id = Some(id);
+            self.mark_task_complete();
+        } else {
+            let date = self.next_day_with_room(self.clock.today());
+            if let Some(todo) = self.todos.iter_mut().find(|t| t.id == id) {
+                todo.due_date = Some(date);
+            }
+            self.persist_todo(id);
+            self.sort_todos();
+        }
+        self.attention.retain(|&other| other != id);
+        if self.attention.is_empty() {
+            self.close_attention_panel();
+        } else {
+            self.selected_attention_index = self.selected_attention_index.min(self.attention.len() - 1);
+            self.input_mode = InputMode::AttentionPanel;
+        }
+    }
+
+    pub fn select_previous_triage_item(&mut self) {
+        let count = self.triage.len();
+        self.selected_triage_index = (self.selected_triage_index + count - 1) % count;
+    }
+
+    pub fn select_next_triage_item(&mut self) {
+        let count = self.triage.len();
+        self.selected_triage_index = (self.selected_triage_index + 1) % count;
+    }
+
+    pub fn set_triage_action(&mut self, action: TriageAction) {
+        if let Some(item) = self.triage.get_mut(self.selected_triage_index) {
+            // Only someday tasks have anywhere to be made active from
+            if action != TriageAction::Activate || item.someday {
+                item.action = action;
+            }
+        }
+    }
+
+    /// Reschedule the selected task to the next (or previous) working day, never today or earlier
+    pub fn shift_triage_date(&mut self, days: i64) {
+        let today = self.clock.today();
+        let Some(item) = self.triage.get(self.selected_triage_index) else {
+            return;
+        };
+        // The first press picks the first working day after today with room for it
+        let (mut date, days) = match item.action {
+            TriageAction::Reschedule(date) => (date, days),
+            _ => {
+                let date = self.next_day_with_room(today);
+                self.set_triage_action(TriageAction::Reschedule(date));
+                return;
+            }
+        };
+        for _ in 0..366 {
+            date += chrono::Duration::days(days);
+            if date <= today {
+                return;
+            }
+            if self.is_working_day(date) {
+                break;
+            }
+        }
+        self.set_triage_action(TriageAction::Reschedule(date));
+    }
+
+    /// Apply the chosen action to every task in the review
+    pub fn apply_triage(&mut self) {
+        let items = std::mem::take(&mut self.triage);
+        let selected_id = self.selected_todo_index.and_then(|index| self.todos.get(index)).map(|t| t.id);
+
+        for item in &items {
+            match item.action {
+                TriageAction::Keep => self.persist_todo(item.id),
+                TriageAction::Activate => {
+                    if let Some(todo) = self.todos.iter_mut().find(|t| t.id == item.id) {
+                        todo.someday = false;
+                    }
+                    self.persist_todo(item.id);
+                }
+                TriageAction::Reschedule(date) => {
+                    if let Some(todo) = self.todos.iter_mut().find(|t| t.id == item.id) {
+                        todo.due_date = Some(date);
+                        todo.someday = false;
+                    }
+                    self.persist_todo(item.id);
+                }
+                TriageAction::Delete => {
+                    self.record_change(format!("delete: {}", item.title));
+                    self.storage.update(item.id, |todo| todo.mark_deleted());
+                    self.todos.retain(|t| t.id != item.id);
+                }
+            }
+        }
+        self.refresh_search_index();
+        self.sort_todos();
+        self.selected_todo_index = selected_id.and_then(|id| self.todos.iter().position(|t| t.id == id))
+            .or(if self.todos.is_empty() { None } else { Some(0) });
+
+        let count = |wanted: fn(&TriageAction) -> bool| items.iter().filter(|item| wanted(&item.action)).count();
+        self.notice = Some(format!(
+            "Triaged {} tasks: {} kept, {} made active, {} rescheduled, {} deleted",
+            items.len(),
+            count(|action| *action == TriageAction::Keep),
+            count(|action| *action == TriageAction::Activate),
+            count(|action| matches!(action, TriageAction::Reschedule(_))),
+            count(|action| *action == TriageAction::Delete),
+        ));
+        self.close_triage_panel();
+    }
+
+    pub fn habits(&self) -> &[Habit] {
+        self.habit_storage.habits()
+    }
+
+    pub fn select_previous_habit(&mut self) {
+        let count = self.habits().len().max(1);
+        self.selected_habit_index = (self.selected_habit_index + count - 1) % count;
+    }
+
+    pub fn select_next_habit(&mut self) {
+        let count = self.habits().len().max(1);
+        self.selected_habit_index = (self.selected_habit_index + 1) % count;
+    }
+
+    /// Check the selected habit off for today (or this week), or undo that
+    pub fn toggle_selected_habit(&mut self) {
+        let Some(habit) = self.habits().get(self.selected_habit_index) else {
+            return;
+        };
+        let today = self.clock.today();
+        let verb = if habit.is_done(today) { "uncheck habit" } else { "check off habit" };
+        let (id, change) = (habit.id, format!("{}: {}", verb, habit.name));
+        self.record_change(change);
+        if let Err(err) = self.habit_storage.update(id, |habit| habit.toggle(today)) {
+            self.notice = Some(format!("Couldn't save habits: {}", err));
+        }
+    }
+
+    pub fn open_habit_panel(&mut self) {
+        self.new_habit_name.clear();
+        self.new_habit_frequency = HabitFrequency::Daily;
+        self.show_habit_panel = true;
+        self.input_mode = InputMode::HabitPanel;
+    }
+
+    pub fn close_habit_panel(&mut self) {
+        self.show_habit_panel = false;
+        self.new_habit_name.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn save_new_habit(&mut self) {
+        let name = self.new_habit_name.trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        let id = self.habits().iter().map(|h| h.id).max().unwrap_or(0) + 1;
+        self.record_change(format!("add habit: {}", name));
+        let habit = Habit::new(id, name, self.new_habit_frequency, self.clock.today());
+        if let Err(err) = self.habit_storage.add(habit) {
+            self.notice = Some(format!("Couldn't save habits: {}", err));
+        }
+        self.selected_habit_index = self.habits().len().saturating_sub(1);
+        self.close_habit_panel();
+    }
+
+    pub fn open_delete_habit_panel(&mut self) {
+        if let Some(habit) = self.habits().get(self.selected_habit_index) {
+            self.deleting_habit_id = Some(habit.id);
+            if !self.config.confirm.delete {
+                self.delete_habit();
+                return;
+            }
+            self.show_delete_panel = true;
+            self.delete_panel_yes_selected = true;
+            self.dont_ask_again = false;
+            self.input_mode = InputMode::DeletePanel;
+        }
+    }
+
+    fn delete_habit(&mut self) {
+        if let Some(habit) = self.deleting_habit_id.and_then(|id| self.habits().iter().find(|h| h.id == id)) {
+            let change = format!("delete habit: {}", habit.name);
+            self.record_change(change);
+        }
+        if let Some(id) = self.deleting_habit_id
+            && let Err(err) = self.habit_storage.remove(id)
+        {
+            self.notice = Some(format!("Couldn't save habits: {}", err));
+        }
+        self.selected_habit_index = self.selected_habit_index.min(self.habits().len().saturating_sub(1));
+        self.close_delete_panel();
+    }
+
+    /// Keys specific to the Habits tab; returns false for keys it shares with the other tabs
+    /// Press picks up the task under the mouse (selecting it), dragging shows where it would land
+    /// and letting go drops it there; the wheel moves the selection
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) {
+        if self.input_mode != InputMode::Normal {
+            return;
+        }
+        let position = Position::new(mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let picked = self.mouse_target_at(position, |target| !matches!(target, MouseTarget::BoardColumn(_)));
+                if let Some(MouseTarget::ListRow(index) | MouseTarget::BoardCard(index)) = picked {
+                    self.selected_todo_index = Some(index);
+                    self.focused_panel = Panel::List;
+                    self.done_today_selected = false;
+                    self.drag = Some(Drag { id: self.todos[index].id, over: None });
+                }
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                let on_board = self.selected_tab == Tab::Board;
+                let over = self.mouse_target_at(position, |target| match target {
+                    MouseTarget::BoardColumn(_) => on_board,
+                    MouseTarget::ListRow(_) => !on_board,
+                    MouseTarget::BoardCard(_) => false,
+                });
+                if let Some(drag) = &mut self.drag {
+                    drag.over = over;
+                }
+            }
+            MouseEventKind::Up(MouseButton::Left) => {
+                if let Some(Drag { id, over: Some(target) }) = self.drag.take() {
+                    self.drop_task(id, target);
+                }
+            }
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown if self.selected_tab == Tab::Board => {
+                let step = if mouse.kind == MouseEventKind::ScrollUp { Step::Up } else { Step::Down };
+                if let Some(index) = self.selected_todo_index.and_then(|index| self.board().step(index, step)) {
+                    self.selected_todo_index = Some(index);
+                }
+            }
+            MouseEventKind::ScrollUp if self.selected_tab.shows_tasks() => self.select_previous_todo(),
+            MouseEventKind::ScrollDown if self.selected_tab.shows_tasks() => self.select_next_todo(),
+            _ => {}
+        }
+    }
+
+    /// The last drawn target at `position` that `wanted` accepts
+    fn mouse_target_at(&self, position: Position, wanted: impl Fn(&MouseTarget) -> bool) -> Option<MouseTarget> {
+        self.mouse_targets.borrow().iter()
+            .rev()
+            .find(|(area, target)| area.contains(position) && wanted(target))
+            .map(|&(_, target)| target)
+    }
+
+    /// Drop the dragged task `id` on a board column (moving it to that status) or on a task in
+    /// the list (taking that task's due date and its place among the tasks due that day)
+    fn drop_task(&mut self, id: usize, target: MouseTarget) {
+        let Some(index) = self.todos.iter().position(|t| t.id == id) else {
+            return;
+        };
+        match target {
+            MouseTarget::BoardColumn(status) if self.config.workflow.index_of(&self.todos[index]) != status => {
+                self.set_status(index, status);
+            }
+            MouseTarget::ListRow(target) if target != index => {
+                let due_date = self.todos[target].due_date;
+                let target_id = self.todos[target].id;
+                let mut same_day: Vec<usize> = self.todos.iter()
+                    .filter(|t| t.due_date == due_date && t.id != id)
+                    .map(|t| t.id)
+                    .collect();
+                // Dragged down it goes after the task it's dropped on, dragged up before it
+                let at = same_day.iter().position(|&other| other == target_id).unwrap_or(0);
+                same_day.insert(if index < target { at + 1 } else { at }, id);
+                for (rank, other) in same_day.into_iter().enumerate() {
+                    let Some(todo) = self.todos.iter_mut().find(|t| t.id == other) else {
+                        continue;
+                    };
+                    if todo.rank != Some(rank as u32) || todo.due_date != due_date {
+                        todo.rank = Some(rank as u32);
+                        todo.due_date = due_date;
+                        self.persist_todo(other);
+                    }
+                }
+                self.sort_todos();
+                self.selected_todo_index = self.todos.iter().position(|t| t.id == id);
+            }
+            _ => {}
+        }
+    }
+
+    /// The board's layout of the open tasks, with the swimlanes its tab entry asks for
+    pub fn board(&self) -> Board {
+        let swimlanes = self.config.tabs.iter()
+            .find(|tab| Tab::from_kind(&tab.kind) == Some(Tab::Board))
+            .map(|tab| tab.lanes)
+            .unwrap_or_default();
+        Board::new(&self.todos, &self.config.workflow, swimlanes)
+    }
+
+    /// Arrows move around the board's grid; other keys act on the selected task as in the list
+    fn handle_board_key(&mut self, key: crossterm::event::KeyEvent) -> bool {
+        let step = match key.code {
+            KeyCode::Up => Step::Up,
+            KeyCode::Down => Step::Down,
+            KeyCode::Left if !key.modifiers.contains(KeyModifiers::SHIFT) => Step::Left,
+            KeyCode::Right if !key.modifiers.contains(KeyModifiers::SHIFT) => Step::Right,
+            // There are no other panels to move to
+            KeyCode::Tab => return true,
+            _ => return false,
+        };
+        if let Some(index) = self.selected_todo_index.and_then(|index| self.board().step(index, step)) {
+            self.selected_todo_index = Some(index);
+        }
+        true
+    }
+
+    /// Arrows move the selected day, PgUp/PgDn a month at a time; Enter lists the day's tasks
+    /// from the Month tab and shows the day in the calendar from the Year tab
+    fn handle_day_key(&mut self, key: crossterm::event::KeyEvent) -> bool {
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        match key.code {
+            KeyCode::Left if !shift => self.select_previous_day(),
+            KeyCode::Right if !shift => self.select_next_day(),
+            KeyCode::Up => self.select_day_above(),
+            KeyCode::Down => self.select_day_below(),
+            KeyCode::PageUp => self.step_selected_month(false),
+            KeyCode::PageDown => self.step_selected_month(true),
+            KeyCode::Enter if self.selected_tab == Tab::Year => self.show_in_calendar(),
+            KeyCode::Enter => self.open_day_list(),
+            KeyCode::Char('+') => self.open_new_task_panel_with_date(self.selected_calendar_date),
+            KeyCode::Char('q' | 'c' | 'w' | '/') | KeyCode::Esc | KeyCode::Left | KeyCode::Right => return false,
+            _ => {}
+        }
+        true
+    }
+
+    /// Move the selected day to the same day of the next or previous month (or its last day)
+    fn step_selected_month(&mut self, forward: bool) {
+        let date = self.selected_calendar_date.unwrap_or_else(|| self.clock.today());
+        let months = chrono::Months::new(1);
+        let stepped = if forward { date.checked_add_months(months) } else { date.checked_sub_months(months) };
+        if let Some(stepped) = stepped {
+            self.selected_calendar_date = Some(stepped);
+            self.update_calendar_view();
+        }
+    }
+
+    /// Switch to the task tab with the calendar focused on the selected day
+    fn show_in_calendar(&mut self) {
+        let tab = self.tabs().into_iter().find(Tab::shows_tasks).unwrap_or(Tab::Tasks);
+        self.switch_tab(tab);
+        self.focused_panel = Panel::Calendar;
+        self.update_calendar_view();
+    }
+
+    /// How many tasks are due on each day, leaving out someday tasks
+    pub fn due_counts(&self) -> HashMap<NaiveDate, usize> {
+        let mut counts = HashMap::new();
+        for date in self.todos.iter().filter(|todo| !todo.someday).filter_map(|todo| todo.due_date) {
+            *counts.entry(date).or_default() += 1;
+        }
+        counts
+    }
+
+    /// Open the tasks due on the selected day in a workspace tab, reusing one already open
+    fn open_day_list(&mut self) {
+        let filter = ListFilter::Day(self.selected_calendar_date.unwrap_or_else(|| self.clock.today()));
+        let index = match self.workspaces.iter().position(|w| w.filter == filter) {
+            Some(index) => index,
+            None => {
+                self.workspaces.push(Workspace { filter, state: TabState::default() });
+                self.workspaces.len() - 1
+            }
+        };
+        self.switch_tab(Tab::Workspace(index));
+    }
+
+    fn handle_habit_key(&mut self, key: crossterm::event::KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Up => self.select_previous_habit(),
+            KeyCode::Down => self.select_next_habit(),
+            KeyCode::Char(' ') | KeyCode::Enter => self.toggle_selected_habit(),
+            KeyCode::Char('+') => self.open_habit_panel(),
+            KeyCode::Char('-') => self.open_delete_habit_panel(),
+            KeyCode::Char('q' | 'c' | 'w') | KeyCode::Esc | KeyCode::Left | KeyCode::Right => return false,
+            _ => {}
+        }
+        true
+    }
+
+    /// Keys on the Done tab; false for ones the main screen handles
+    fn handle_done_key(&mut self, key: crossterm::event::KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Up => self.selected_done_index = self.selected_done_index.saturating_sub(1),
+            KeyCode::Down => self.selected_done_index = (self.selected_done_index + 1).min(self.done_tasks().len().saturating_sub(1)),
+            KeyCode::Char('r') => self.reopen_selected_done(),
+            KeyCode::Char('<') => self.shift_completion(-1),
+            KeyCode::Char('>') => self.shift_completion(1),
+            KeyCode::Char('q' | 'c' | 'w') | KeyCode::Esc | KeyCode::Left | KeyCode::Right => return false,
+            _ => {}
+        }
+        true
+    }
+
+    /// Completed tasks in the active context, most recently done first
+    pub fn done_tasks(&self) -> Vec<&Todo> {
+        let context = self.config.active_context.as_deref();
+        let mut done: Vec<&Todo> = self.all_todos().iter()
+            .filter(|t| t.completed && !t.deleted && t.in_context(context))
+            .collect();
+        done.sort_by_key(|t| std::cmp::Reverse(t.completed_at));
+        done
+    }
+
+    /// Reopen the task selected on the Done tab, as 'd' would on the list
+    fn reopen_selected_done(&mut self) {
+        let Some(id) = self.done_tasks().get(self.selected_done_index).map(|t| t.id) else {
+            return;
+        };
+        self.completing_todo_id = Some(id);
+        self.mark_task_complete();
+        self.notice = Some(format!("Reopened \"{}\"", self.stored_title(id)));
+        self.selected_done_index = self.selected_done_index.min(self.done_tasks().len().saturating_sub(1));
+    }
+
+    /// Move when the task selected on the Done tab was done by `days`, for one marked done a day
+    /// after it was finished; never to before it was created or to later than now
+    fn shift_completion(&mut self, days: i64) {
+        let Some((id, created_at, completed_at)) = self.done_tasks().get(self.selected_done_index)
+            .and_then(|t| t.completed_at.map(|at| (t.id, t.created_at, at)))
+        else {
+            return;
+        };
+        let at = completed_at + Duration::days(days);
+        if at < created_at {
+            self.notice = Some("It can't have been done before it was added".to_string());
+            return;
+        }
+        if at > Utc::now() {
+            return;
+        }
+        let date = self.clock.date_of(at).format("%Y-%m-%d");
+        self.record_change(format!("done on {}: {}", date, self.stored_title(id)));
+        self.storage.update(id, |todo| todo.completed_at = Some(at));
+        self.refresh_done_today();
+        // The list is ordered by when tasks were done, so follow the task to its new place
+        self.selected_done_index = self.done_tasks().iter().position(|t| t.id == id).unwrap_or(0);
+    }
+
+    /// Open a workspace tab for the chosen filter and switch to it
+    pub fn open_selected_workspace(&mut self) {
+        if let Some(filter) = self.workspace_choices.get(self.selected_workspace_index).cloned() {
+            self.workspaces.push(Workspace { filter, state: TabState::default() });
+            self.switch_tab(Tab::Workspace(self.workspaces.len() - 1));
+        }
+        self.close_workspace_panel();
+    }
+
+    /// Close the current workspace tab and fall back to the tab before it
+    pub fn close_current_workspace(&mut self) {
+        let Tab::Workspace(index) = self.selected_tab else {
+            return;
+        };
+        self.previous_tab();
+        self.workspaces.remove(index);
+        // Later workspaces shift down by one
+        if let Tab::Workspace(i) = self.selected_tab
+            && i > index
+        {
+            self.selected_tab = Tab::Workspace(i - 1);
+        }
+    }
+
+    fn sort_todos(&mut self) {
+        Todo::sort_by_due(&mut self.todos);
+        // Most urgent first, tasks scoring the same staying in due order
+        if self.config.sort == SortMode::Urgency {
+            let scorer = Scorer::new(&self.config.urgency, self.storage.todos(), &self.clock);
+            let scores: HashMap<usize, f64> = self.todos.iter().map(|todo| (todo.id, scorer.score(todo).total)).collect();
+            self.todos.sort_by(|a, b| scores[&b.id].total_cmp(&scores[&a.id]));
+        }
+        // Escalated tasks stay pinned to the top whatever the order below them
+        let (after_days, today) = (self.config.escalation.overdue_days, self.clock.today());
+        self.todos.sort_by_key(|todo| !escalated(todo, after_days, today));
+        if self.config.tree_view {
+            self.todos = tree_order(std::mem::take(&mut self.todos));
+        }
+    }
+
+    /// Sort the list again after its order changed, keeping the same task selected
+    fn resort_keeping_selection(&mut self) {
+        let selected = self.selected_todo_index.and_then(|index| self.todos.get(index)).map(|todo| todo.id);
+        self.sort_todos();
+        self.selected_todo_index = selected.and_then(|id| self.todos.iter().position(|t| t.id == id)).or(self.selected_todo_index);
+    }
+
+    /// Indices into `todos` that match a list filter (and the current workspace's filter); someday
+    /// tasks only match when one of the two is the Someday filter
+    pub fn filtered_todo_indices(&self, filter: &ListFilter) -> Vec<usize> {
+        let today = self.clock.today();
+        let workspace_filter = self.workspace_filter();
+        let someday = *filter == ListFilter::Someday || workspace_filter == Some(&ListFilter::Someday);
+        self.todos.iter()
+            .enumerate()
+            .filter(|(_, todo)| todo.someday == someday)
+            .filter(|(_, todo)| filter.matches(todo, today))
+            .filter(|(_, todo)| workspace_filter.is_none_or(|f| f.matches(todo, today)))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Like `filtered_todo_indices`, minus tasks hidden in a collapsed group or a folded task
+    fn selectable_todo_indices(&self, filter: &ListFilter) -> Vec<usize> {
+        let indices = self.filtered_todo_indices(filter);
+        let folded_away = self.folded_away(&indices);
+        indices.into_iter()
+            .filter(|&i| !self.is_hidden(&self.todos[i]) && !folded_away.contains(&self.todos[i].id))
+            .collect()
+    }
+
+    /// IDs of the tasks at `indices` (in tree order) that sit under a folded task, in the tree
+    pub fn folded_away(&self, indices: &[usize]) -> HashSet<usize> {
+        let mut hidden = HashSet::new();
+        if !self.config.tree_view {
+            return hidden;
+        }
+        for &i in indices {
+            let todo = &self.todos[i];
+            if todo.parent.is_some_and(|parent| self.config.tree_folded.contains(&parent) || hidden.contains(&parent)) {
+                hidden.insert(todo.id);
+            }
+        }
+        hidden
+    }
+
+    /// Indices into `todos` the cursor can move through in the focused list
+    pub fn visible_todo_indices(&self) -> Vec<usize> {
+        match &self.split_view {
+            Some(split) => self.selectable_todo_indices(&split.filters[split.active_pane]),
+            None => self.selectable_todo_indices(&ListFilter::All),
+        }
+    }
+
+    /// True when the task sits in a collapsed group of the grouped list
+    pub fn is_hidden(&self, todo: &Todo) -> bool {
+        self.config.group_by_due && self.collapsed_buckets.contains(&self.due_bucket(todo))
+    }
+
+    /// The section of the grouped list a task goes under
+    pub fn due_bucket(&self, todo: &Todo) -> DueBucket {
+        DueBucket::in_groups(todo, self.clock.today(), &self.config.due_groups)
+    }
+
+    pub fn toggle_grouping(&mut self) {
+        self.config.group_by_due = !self.config.group_by_due;
+        // Groups go by due date, so they'd pull subtasks away from their parents
+        if self.config.group_by_due && self.config.tree_view {
+            self.config.tree_view = false;
+            self.resort_keeping_selection();
+        }
+        let _ = self.config.save();
+    }
+
+    /// Switch the list between due date and urgency order
+    pub fn toggle_sort(&mut self) {
+        self.config.sort = match self.config.sort {
+            SortMode::Due => SortMode::Urgency,
+            SortMode::Urgency => SortMode::Due,
+        };
+        self.resort_keeping_selection();
+        if let Err(err) = self.config.save() {
+            self.notice = Some(format!("Couldn't save config: {}", err));
+        }
+    }
+
+    /// A task's urgency score, with subtasks and parents looked up among every stored task
+    pub fn urgency_of(&self, todo: &Todo) -> Urgency {
+        Scorer::new(&self.config.urgency, self.all_todos(), &self.clock).score(todo)
+    }
+
+    /// Switch between the flat list and the tree of tasks and their subtasks
+    pub fn toggle_tree_view(&mut self) {
+        self.config.tree_view = !self.config.tree_view;
+        if self.config.tree_view {
+            self.config.group_by_due = false;
+        }
+        self.resort_keeping_selection();
+        let _ = self.config.save();
+    }
+
+    /// Whether Tab and Shift+Tab act on the tree rather than move between panels
+    fn editing_tree(&self) -> bool {
+        self.config.tree_view && self.focused_panel == Panel::List && self.selected_tab.shows_tasks()
+    }
+
+    /// How deep each of the tasks at `indices` (in tree order) sits under parents also among them,
+    /// by task ID; a subtask whose parent isn't listed starts again at the top level
+    pub fn tree_depths(&self, indices: &[usize]) -> HashMap<usize, usize> {
+        let mut depths = HashMap::new();
+        for &i in indices {
+            let todo = &self.todos[i];
+            let depth = todo.parent.and_then(|parent| depths.get(&parent)).map_or(0, |depth| depth + 1);
+            depths.insert(todo.id, depth);
+        }
+        depths
+    }
+
+    /// Make the selected task a subtask of the one above it at the same level (its due date, project
+    /// and own subtasks stay as they are)
+    pub fn demote_selected(&mut self) {
+        let Some(index) = self.selected_todo_index else {
+            return;
+        };
+        let visible = self.visible_todo_indices();
+        let Some(position) = visible.iter().position(|&i| i == index) else {
+            return;
+        };
+        let depths = self.tree_depths(&visible);
+        let depth = |i: usize| depths[&self.todos[i].id];
+        // Deeper tasks above belong to an earlier sibling; a shallower one means there's no sibling
+        let sibling = visible[..position].iter().rev()
+            .find(|&&i| depth(i) <= depth(index))
+            .filter(|&&i| depth(i) == depth(index))
+            .map(|&i| (self.todos[i].id, self.todos[i].title.clone()));
+        let Some((parent_id, parent_title)) = sibling else {
+            self.notice = Some("There's no task above at the same level to put this one under".to_string());
+            return;
+        };
+        // Unfold the new parent, or the task would vanish under it
+        if self.config.tree_folded.remove(&parent_id) {
+            let _ = self.config.save();
+        }
+        self.set_parent_of_selected(Some(parent_id));
+        self.notice = Some(format!("Now a subtask of \"{}\"", parent_title));
+    }
+
+    /// Move the selected subtask up a level, out from under its parent (a subtask of a top-level
+    /// task becomes a top-level task itself)
+    pub fn promote_selected(&mut self) {
+        let Some(parent_id) = self.selected_todo_index.and_then(|index| self.todos.get(index)).and_then(|todo| todo.parent) else {
+            self.notice = Some("That's already a top-level task".to_string());
+            return;
+        };
+        let grandparent = self.all_todos().iter().find(|t| t.id == parent_id).and_then(|t| t.parent);
+        self.set_parent_of_selected(grandparent);
+        self.notice = Some(match grandparent.and_then(|id| self.all_todos().iter().find(|t| t.id == id)) {
+            Some(grandparent) => format!("Now a subtask of \"{}\"", grandparent.title),
+            None => "Now a top-level task".to_string(),
+        });
+    }
+
+    /// Left in the tree: fold the selected task's subtasks away, or if there's nothing to fold, go
+    /// to its parent. Right: unfold them, or go to the first of them (`fold` is false)
+    pub fn fold_selected(&mut self, fold: bool) {
+        let Some(index) = self.selected_todo_index else {
+            return;
+        };
+        let id = self.todos[index].id;
+        let has_subtasks = self.todos.iter().any(|t| t.parent == Some(id));
+        let folded = self.config.tree_folded.contains(&id);
+        if has_subtasks && fold != folded {
+            if fold {
+                self.config.tree_folded.insert(id);
+            } else {
+                self.config.tree_folded.remove(&id);
+            }
+            // Tasks since done or deleted don't need remembering
+            let open: HashSet<usize> = self.todos.iter().map(|t| t.id).collect();
+            self.config.tree_folded.retain(|id| open.contains(id));
+            if let Err(err) = self.config.save() {
+                self.notice = Some(format!("Couldn't save config: {}", err));
+            }
+            return;
+        }
+        let visible = self.visible_todo_indices();
+        let target = if fold {
+            let parent = self.todos[index].parent;
+            visible.iter().find(|&&i| Some(self.todos[i].id) == parent)
+        } else {
+            visible.iter().find(|&&i| self.todos[i].parent == Some(id))
+        };
+        if let Some(&target) = target {
+            self.selected_todo_index = Some(target);
+            self.task_description_scroll = 0;
+            self.selected_link = 0;
+        }
+    }
+
+    fn set_parent_of_selected(&mut self, parent: Option<usize>) {
+        let Some(index) = self.selected_todo_index else {
+            return;
+        };
+        let id = self.todos[index].id;
+        self.todos[index].parent = parent;
+        self.persist_todo(id);
+        self.resort_keeping_selection();
+    }
+
+    /// Fold the selected task's group down to its header; the cursor falls back to the first visible task
+    pub fn collapse_selected_group(&mut self) {
+        if let Some(index) = self.selected_todo_index {
+            self.collapsed_buckets.insert(self.due_bucket(&self.todos[index]));
+        }
+    }
+
+    pub fn expand_all_groups(&mut self) {
+        self.collapsed_buckets.clear();
+    }
+
+    /// Places the list cursor can rest: each visible task, then the "Done today" header if it's shown
+    fn cursor_stops(&self) -> Vec<Option<usize>> {
+        let mut stops: Vec<Option<usize>> = self.visible_todo_indices().into_iter().map(Some).collect();
+        if self.shows_done_today() {
+            stops.push(None);
+        }
+        stops
+    }
+
+    fn move_cursor(&mut self, forward: bool) {
+        let stops = self.cursor_stops();
+        if stops.is_empty() {
+            self.selected_todo_index = None;
+            return;
+        }
+
+        let current = if self.done_today_selected {
+            Some(stops.len() - 1)
+        } else {
+            self.selected_todo_index.and_then(|i| stops.iter().position(|&s| s == Some(i)))
+        };
+        let new_position = match current {
+            Some(p) if forward => (p + 1) % stops.len(),
+            Some(p) => (p + stops.len() - 1) % stops.len(),
+            None => 0,
+        };
+        self.selected_todo_index = stops[new_position];
+        self.done_today_selected = stops[new_position].is_none();
+
+        // Reset scroll when changing tasks
+        self.task_description_scroll = 0;
+        self.selected_link = 0;
+    }
+
+    pub fn select_previous_todo(&mut self) {
+        self.move_cursor(false);
+    }
+
+    /// Digits jump to that list number as they're typed ("1", "12"), a trailing 'g' ends the
+    /// number and ':' starts a jump by ID; returns true if the key was used
+    fn handle_jump_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char(digit) if digit.is_ascii_digit() => {
+                let visible = self.visible_todo_indices();
+                self.jump_input.push(digit);
+                // A number past the end starts over from this digit, so "3" then "5" later isn't 35
+                let number = match self.jump_input.parse::<usize>() {
+                    Ok(number) if (1..=visible.len()).contains(&number) => number,
+                    _ => {
+                        self.jump_input = digit.to_string();
+                        digit.to_digit(10).unwrap_or(0) as usize
+                    }
+                };
+                if let Some(&index) = number.checked_sub(1).and_then(|i| visible.get(i)) {
+                    self.select_todo_at(index);
+                } else {
+                    self.jump_input.clear();
+                }
+                true
+            }
+            KeyCode::Char('g') if !self.jump_input.is_empty() => {
+                self.jump_input.clear();
+                true
+            }
+            KeyCode::Char(':') => {
+                self.jump_input.clear();
+                self.input_mode = InputMode::JumpToId;
+                true
+            }
+            _ => {
+                self.jump_input.clear();
+                false
+            }
+        }
+    }
+
+    /// Select the task with the typed ID, wherever it is in the list
+    pub fn jump_to_id(&mut self) {
+        match self.jump_input.trim_start_matches('#').parse::<usize>() {
+            Ok(id) => self.select_task_by_id(id),
+            Err(_) => self.notice = Some(format!("No open task with ID {}", self.jump_input)),
+        }
+        self.close_jump_to_id();
+    }
+
+    /// Select the task with `id` in the list, or say why it isn't there
+    fn select_task_by_id(&mut self, id: usize) {
+        if let Some(index) = self.todos.iter().position(|t| t.id == id) {
+            if self.visible_todo_indices().contains(&index) {
+                self.select_todo_at(index);
+            } else {
+                self.notice = Some(format!("Task {} is hidden by the current filter or a collapsed group", id));
+            }
+            return;
+        }
+        self.notice = Some(match self.all_todos().iter().find(|t| t.id == id) {
+            Some(todo) if todo.deleted => format!("Task {} was deleted", id),
+            Some(todo) if todo.completed => format!("Task {} is done: {}", id, todo.title),
+            Some(todo) => format!("Task {} is in another context: {}", id, todo.title),
+            None => format!("No open task with ID {}", id),
+        });
+    }
+
+    /// The `#<id>` links in the selected task's description
+    pub fn selected_task_links(&self) -> Vec<(std::ops::Range<usize>, usize)> {
+        self.selected_todo_index
+            .and_then(|index| self.todos.get(index))
+            .map(|todo| Todo::task_links(&todo.description))
+            .unwrap_or_default()
+    }
+
+    /// Move the link Enter follows to the next (or previous) one in the description
+    fn step_selected_link(&mut self, forward: bool) {
+        let count = self.selected_task_links().len();
+        if count > 0 {
+            self.selected_link = if forward { (self.selected_link + 1) % count } else { (self.selected_link + count - 1) % count };
+        }
+    }
+
+    /// Jump to the task the selected `#<id>` link points at
+    fn follow_selected_link(&mut self) {
+        if let Some(&(_, id)) = self.selected_task_links().get(self.selected_link) {
+            self.select_task_by_id(id);
+        }
+    }
+
+    /// Bumped by every change to the stored tasks, for caching what's worked out from them
+    pub fn tasks_revision(&self) -> u64 {
+        self.storage.revision()
+    }
+
+    /// Tasks whose description links to `id`, for the details panel
+    pub fn back_references(&self, id: usize) -> Vec<&Todo> {
+        self.all_todos().iter()
+            .filter(|todo| !todo.deleted && todo.id != id && todo.linked_ids().contains(&id))
+            .collect()
+    }
+
+    pub fn close_jump_to_id(&mut self) {
+        self.jump_input.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn open_add_url(&mut self) {
+        self.url_input.clear();
+        self.input_mode = InputMode::AddUrl;
+    }
+
+    pub fn close_add_url(&mut self) {
+        self.url_input.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Add the typed link as a task titled with the link itself, and fetch the page's title for it
+    pub fn save_url_task(&mut self) {
+        let url = match read_later::check_url(&self.url_input) {
+            Ok(url) => url,
+            Err(err) => {
+                self.notice = Some(err.to_string());
+                return;
+            }
+        };
+        let id = self.next_todo_id();
+        let mut todo = Todo::new(id, url.clone(), String::new(), None);
+        todo.url = Some(url.clone());
+        // Same context as the full popup would start with
+        todo.context = match self.workspace_filter() {
+            Some(ListFilter::Context(context)) => Some(context.clone()),
+            _ => self.config.active_context.clone(),
+        };
+        todo.project = self.workspace_project();
+        self.todos.push(todo);
+        self.sort_todos();
+        self.persist_todo(id);
+        self.selected_todo_index = self.todos.iter().position(|t| t.id == id);
+        self.title_fetches.push(read_later::fetch_title_in_background(id, url));
+        self.close_add_url();
+    }
+
+    /// Retitle tasks added from links once their page titles arrive, unless they've been renamed since
+    fn check_title_fetches(&mut self) {
+        let mut done = Vec::new();
+        self.title_fetches.retain(|fetch| match fetch.try_recv() {
+            Ok(result) => {
+                done.push(result);
+                false
+            }
+            Err(err) => err == TryRecvError::Empty,
+        });
+        for (id, result) in done {
+            let Some(todo) = self.todos.iter_mut().find(|t| t.id == id) else {
+                continue;
+            };
+            match result {
+                Ok(title) if todo.url.as_ref() == Some(&todo.title) => {
+                    todo.title = title;
+                    self.sort_todos();
+                    self.persist_todo(id);
+                }
+                Ok(_) => {}
+                Err(err) => self.notice = Some(format!("Couldn't read the page title for task {}: {}", id, err)),
+            }
+        }
+    }
+
+    /// Post the selected task to its project's Slack channel (or the default one)
+    pub fn post_selected_to_slack(&mut self) {
+        let Some(todo) = self.selected_todo_index.and_then(|index| self.todos.get(index)) else {
+            return;
+        };
+        match slack::webhook_for(&self.config.slack, todo.project.as_deref()) {
+            Ok(webhook) => {
+                let done = format!("Posted \"{}\" to Slack", todo.title);
+                self.slack_posts.push(slack::post_in_background(webhook, slack::task_message(todo), done));
+                self.notice = Some("Posting to Slack...".to_string());
+            }
+            Err(err) => self.notice = Some(format!("Can't post to Slack: {}", err)),
+        }
+    }
+
+    /// Post what's overdue, due and done today to the default Slack channel
+    pub fn post_plan_to_slack(&mut self) {
+        match slack::webhook_for(&self.config.slack, None) {
+            Ok(webhook) => {
+                let text = slack::plan_message(&self.todos, self.clock.today(), &self.clock);
+                self.slack_posts.push(slack::post_in_background(webhook, text, "Posted today's plan to Slack".to_string()));
+                self.notice = Some("Posting to Slack...".to_string());
+            }
+            Err(err) => self.notice = Some(format!("Can't post to Slack: {}", err)),
+        }
+    }
+
+    fn check_slack_posts(&mut self) {
+        let mut done = Vec::new();
+        self.slack_posts.retain(|post| match post.try_recv() {
+            Ok(result) => {
+                done.push(result);
+                false
+            }
+            Err(err) => err == TryRecvError::Empty,
+        });
+        for result in done {
+            self.notice = Some(match result {
+                Ok(message) => message,
+                Err(err) => format!("Couldn't post to Slack: {}", err),
+            });
+        }
+    }
+
+    /// Two-letter labels for every task in the focused list, then every day on the calendar
+    pub fn jump_hints(&self) -> Vec<(String, JumpTarget)> {
+        let first_day = crate::ui::get_previous_month(self.current_date);
+        let end = crate::ui::get_next_month(crate::ui::get_next_month(self.current_date));
+        let days = first_day.iter_days().take_while(|&date| date < end).map(JumpTarget::Day);
+        let targets = self.visible_todo_indices().into_iter().map(JumpTarget::Task).chain(days);
+
+        let letters = HINT_LETTERS.len();
+        targets.take(letters * letters)
+            .enumerate()
+            .map(|(i, target)| {
+                let label = [HINT_LETTERS[i / letters], HINT_LETTERS[i % letters]];
+                (String::from_utf8_lossy(&label).into_owned(), target)
+            })
+            .collect()
+    }
+
+    pub fn open_jump_hints(&mut self) {
+        self.jump_hint_input.clear();
+        self.input_mode = InputMode::JumpHints;
+    }
+
+    pub fn close_jump_hints(&mut self) {
+        self.jump_hint_input.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Narrow the hints to those starting with the typed letter, then jump on the second
+    fn type_jump_hint(&mut self, letter: char) {
+        self.jump_hint_input.push(letter.to_ascii_lowercase());
+        let hints = self.jump_hints();
+        if !hints.iter().any(|(label, _)| label.starts_with(&self.jump_hint_input)) {
+            self.close_jump_hints();
+            return;
+        }
+        let Some(&(_, target)) = hints.iter().find(|(label, _)| *label == self.jump_hint_input) else {
+            return;
+        };
+        match target {
+            JumpTarget::Task(index) => {
+                self.focused_panel = Panel::List;
+                self.select_todo_at(index);
+            }
+            JumpTarget::Day(date) => {
+                self.focused_panel = Panel::Calendar;
+                self.selected_calendar_date = Some(date);
+                self.update_calendar_view();
+            }
+        }
+        self.close_jump_hints();
+    }
+
+    fn select_todo_at(&mut self, index: usize) {
+        self.selected_todo_index = Some(index);
+        self.done_today_selected = false;
+        self.task_description_scroll = 0;
+        self.selected_link = 0;
+    }
+
+    pub fn select_next_todo(&mut self) {
+        self.move_cursor(true);
+    }
+
+    /// Tasks completed today (within the current workspace's filter), newest first
+    pub fn done_today_todos(&self) -> Vec<&Todo> {
+        let today = self.clock.today();
+        let workspace_filter = self.workspace_filter();
+        self.done_today.iter()
+            .filter(|todo| workspace_filter.is_none_or(|f| f.matches(todo, today)))
+            .collect()
+    }
+
+    /// The "Done today" section only appears under a single, unsplit list
+    pub fn shows_done_today(&self) -> bool {
+        self.split_view.is_none() && !self.done_today_todos().is_empty()
+    }
+
+    pub fn toggle_done_today(&mut self) {
+        self.done_today_expanded = !self.done_today_expanded;
+    }
+
+    fn refresh_done_today(&mut self) {
+        let today = self.clock.today();
+        let context = self.config.active_context.as_deref();
+        self.done_today = self.storage.todos().iter()
+            .filter(|t| t.completed && !t.deleted && t.in_context(context))
+            .filter(|t| t.completed_at.is_some_and(|at| self.clock.date_of(at) == today))
+            .cloned()
+            .collect();
+        self.done_today.sort_by_key(|t| std::cmp::Reverse(t.completed_at));
+    }
+
+    pub fn select_next_day(&mut self) {
+        if let Some(date) = self.selected_calendar_date {
+            self.selected_calendar_date = Some(date + chrono::Duration::days(1));
+            self.update_calendar_view();
+        } else {
+            self.selected_calendar_date = Some(self.clock.today());
+        }
+    }
+
+    pub fn select_previous_day(&mut self) {
+        if let Some(date) = self.selected_calendar_date {
+            self.selected_calendar_date = Some(date - chrono::Duration::days(1));
+            self.update_calendar_view();
+        } else {
+            self.selected_calendar_date = Some(self.clock.today());
+        }
+    }
+
+    pub fn select_day_above(&mut self) {
+        if let Some(date) = self.selected_calendar_date {
+            self.selected_calendar_date = Some(date - chrono::Duration::days(7));
+            self.update_calendar_view();
+        } else {
+            self.selected_calendar_date = Some(self.clock.today());
+        }
+    }
+
+    pub fn select_day_below(&mut self) {
+        if let Some(date) = self.selected_calendar_date {
+            self.selected_calendar_date = Some(date + chrono::Duration::days(7));
+            self.update_calendar_view();
+        } else {
+            self.selected_calendar_date = Some(self.clock.today());
+        }
+    }
+
+    fn update_calendar_view(&mut self) {
+        // Check if selected date is outside the visible range and shift the view if needed
+        if let Some(selected) = self.selected_calendar_date {
+            self.current_date = calendar_view_for(self.current_date, selected);
+        }
+    }
+
+    /// Start a focus session on the selected task, hiding everything else until it ends
+    pub fn start_focus_session(&mut self) {
+        if let Some(todo) = self.selected_todo_index.and_then(|index| self.todos.get(index)) {
+            let started_at = Utc::now();
+            self.focus = Some(FocusSession {
+                todo_id: todo.id,
+                started_at,
+                ends_at: started_at + Duration::minutes(self.config.focus.minutes as i64),
+                held_back: Vec::new(),
+            });
+            self.input_mode = InputMode::Focus;
+        }
+    }
+
+    /// Stop the focus session (early or on time), logging the time spent to the task
+    pub fn end_focus_session(&mut self) {
+        let Some(session) = self.focus.take() else {
+            return;
+        };
+        self.input_mode = InputMode::Normal;
+
+        // Sessions cut short within the first minute aren't worth logging
+        let entry = TimeEntry { started_at: session.started_at, ended_at: Utc::now().min(session.ends_at) };
+        let mut summary = if entry.duration() >= Duration::minutes(1)
+            && let Some(todo) = self.todos.iter_mut().find(|t| t.id == session.todo_id)
+        {
+            todo.time_entries.push(entry);
+            let summary = format!("Focused {} on \"{}\"", TimeEntry::format_duration(entry.duration()), todo.title);
+            self.persist_todo(session.todo_id);
+            summary
+        } else {
+            "Focus session stopped".to_string()
+        };
+        if let Some(latest) = session.held_back.last() {
+            summary = match session.held_back.len() {
+                1 => format!("{} - held back: {}", summary, latest),
+                n => format!("{} - held back {}: {}", summary, n, latest),
+            };
+        }
+        self.notice = Some(summary);
+    }
+
+    /// The task the focus session is on
+    pub fn focused_todo(&self) -> Option<&Todo> {
+        let session = self.focus.as_ref()?;
+        self.todos.iter().find(|t| t.id == session.todo_id)
+    }
+
+    /// The daily note written for `date`, if any
+    pub fn note_on(&self, date: NaiveDate) -> Option<&str> {
+        self.note_storage.get(date)
+    }
+
+    /// Days that have a note
+    pub fn note_dates(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        self.note_storage.notes().keys().copied()
+    }
+
+    /// Open the note editor for the day selected in the calendar
+    pub fn open_note_panel(&mut self) {
+        let date = self.selected_calendar_date.unwrap_or_else(|| self.clock.today());
+        self.show_note_panel = true;
+        self.input_mode = InputMode::EditingNote;
+        self.editing_note_date = Some(date);
+        self.note_text = TextBuffer::new(self.note_storage.get(date).unwrap_or_default());
+        scroll_to_end(&self.note_text, &mut self.note_scroll, self.editor_size.get());
+    }
+
+    pub fn close_note_panel(&mut self) {
+        self.show_note_panel = false;
+        self.input_mode = InputMode::Normal;
+        self.editing_note_date = None;
+        self.note_text.clear();
+        self.note_scroll = 0;
+    }
+
+    pub fn save_note(&mut self) {
+        if let Some(date) = self.editing_note_date {
+            self.record_change(format!("note: {}", date.format("%Y-%m-%d")));
+            if let Err(err) = self.note_storage.set(date, self.note_text.to_string()) {
+                self.notice = Some(format!("Couldn't save notes: {}", err));
+            }
+        }
+        self.close_note_panel();
+    }
+
+    /// Whether `date` is a working day: a configured weekday that isn't a holiday (listed in
+    /// config, or an event in one of the holiday calendars) or blacked out
+    pub fn is_working_day(&self, date: NaiveDate) -> bool {
+        let workdays = &self.config.workdays;
+        workdays.days.contains(&date.weekday())
+            && !workdays.holidays.contains(&date)
+            && self.blackout_on(date).is_none()
+            && !self.calendar_feeds.feeds()
+                .filter(|(name, _)| workdays.holiday_calendars.iter().any(|holiday| holiday == name))
+                .any(|(_, events)| events.iter().any(|event| event.covers(date)))
+    }
+
+    /// The blackout `date` falls in, if any
+    pub fn blackout_on(&self, date: NaiveDate) -> Option<&Blackout> {
+        self.blackout_storage.covering(date)
+    }
+
+    /// Open the blackout popup for the day selected in the calendar: the blackout it's in, or a
+    /// new one starting that day
+    pub fn open_blackout_panel(&mut self) {
+        let date = self.selected_calendar_date.unwrap_or_else(|| self.clock.today());
+        self.editing_blackout = self.blackout_on(date).cloned();
+        let (start, end, label) = match &self.editing_blackout {
+            Some(blackout) => (blackout.start, blackout.end, blackout.label.clone()),
+            None => (date, date, String::new()),
+        };
+        self.blackout_start_input = start.format("%Y-%m-%d").to_string();
+        self.blackout_end_input = end.format("%Y-%m-%d").to_string();
+        self.blackout_label_input = label;
+        self.blackout_field = BlackoutField::End;
+        self.show_blackout_panel = true;
+        self.input_mode = InputMode::BlackoutPanel;
+    }
+
+    pub fn close_blackout_panel(&mut self) {
+        self.show_blackout_panel = false;
+        self.editing_blackout = None;
+        self.blackout_start_input.clear();
+        self.blackout_end_input.clear();
+        self.blackout_label_input.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    fn blackout_input(&mut self) -> &mut String {
+        match self.blackout_field {
+            BlackoutField::Start => &mut self.blackout_start_input,
+            BlackoutField::End => &mut self.blackout_end_input,
+            BlackoutField::Label => &mut self.blackout_label_input,
+        }
+    }
+
+    /// Save the typed blackout; bad dates leave the popup open to fix them
+    pub fn save_blackout_panel(&mut self) {
+        let today = self.clock.today();
+        let mut dates = Vec::new();
+        for (field, input) in [(BlackoutField::Start, &self.blackout_start_input), (BlackoutField::End, &self.blackout_end_input)] {
+            match quick_add::parse_due_date(input.trim(), today) {
+                Some(date) => dates.push(date),
+                None => {
+                    self.notice = Some(format!("Can't read \"{}\" as a date (try 2026-10-20, friday or in 3 days)", input.trim()));
+                    self.blackout_field = field;
+                    return;
+                }
+            }
+        }
+        if dates[1] < dates[0] {
+            self.notice = Some("A blackout can't end before it starts".to_string());
+            self.blackout_field = BlackoutField::End;
+            return;
+        }
+        let blackout = Blackout { start: dates[0], end: dates[1], label: self.blackout_label_input.trim().to_string() };
+        self.record_change(format!("blackout: {}", blackout.describe()));
+        match self.blackout_storage.upsert(self.editing_blackout.as_ref(), blackout.clone()) {
+            Ok(()) => self.notice = Some(format!("Away {}", blackout.describe())),
+            Err(err) => self.notice = Some(format!("Couldn't save blackouts: {}", err)),
+        }
+        self.close_blackout_panel();
+    }
+
+    /// Take the blackout being changed off the calendar
+    pub fn remove_blackout(&mut self) {
+        if let Some(blackout) = self.editing_blackout.clone() {
+            self.record_change(format!("remove blackout: {}", blackout.describe()));
+            match self.blackout_storage.remove(&blackout) {
+                Ok(()) => self.notice = Some(format!("Removed {}", blackout.describe())),
+                Err(err) => self.notice = Some(format!("Couldn't save blackouts: {}", err)),
+            }
+        }
+        self.close_blackout_panel();
+    }
+
+    /// Move the selected task one workflow status on (or back); stepping past the last open status
+    /// marks it done, as `d` does
+    pub fn step_selected_status(&mut self, forward: bool) {
+        let Some(index) = self.selected_todo_index else {
+            return;
+        };
+        let Some(todo) = self.todos.get(index) else {
+            return;
+        };
+        let current = self.config.workflow.index_of(todo);
+        let next = if forward {
+            if current + 1 == self.config.workflow.open_statuses().len() {
+                self.open_done_panel();
+                return;
+            }
+            current + 1
+        } else {
+            if current == 0 {
+                return;
+            }
+            current - 1
+        };
+        self.set_status(index, next);
+    }
+
+    /// Put the task at `index` in the `status`th open workflow status
+    fn set_status(&mut self, index: usize, status: usize) {
+        let open = self.config.workflow.open_statuses();
+        // The first status is the default, so it isn't stored
+        let name = (status > 0).then(|| open[status].name.clone());
+        let waiting = open[status].name == self.config.waiting.status;
+        let id = self.todos[index].id;
+        self.todos[index].set_status(name);
+        self.todos[index].set_waiting(waiting);
+        self.persist_todo(id);
+    }
+
+    /// The configured color of the named project, if it has one
+    pub fn project_color(&self, name: Option<&str>) -> Option<&str> {
+        self.project_storage.color_of(name)
+    }
+
+    /// Open the settings of the selected task's project
+    pub fn open_project_panel(&mut self) {
+        let Some(todo) = self.selected_todo_index.and_then(|index| self.todos.get(index)) else {
+            return;
+        };
+        let Some(name) = todo.project.clone() else {
+            self.notice = Some("The selected task has no project (mark it and press B to set one)".to_string());
+            return;
+        };
+        let project = self.project_storage.get(&name).cloned().unwrap_or_else(|| Project::new(name.clone()));
+        self.project_color_input = project.color.unwrap_or_default();
+        self.project_tags_input = project.default_tags.join(" ");
+        self.project_archive_input = project.archive_after_days.map_or(String::new(), |days| days.to_string());
+        self.project_own_file = self.storage.is_split(&name);
+        self.project_panel_name = name;
+        self.project_field = ProjectField::Color;
+        self.show_project_panel = true;
+        self.input_mode = InputMode::ProjectPanel;
+    }
+
+    pub fn close_project_panel(&mut self) {
+        self.show_project_panel = false;
+        self.project_panel_name.clear();
+        self.project_color_input.clear();
+        self.project_tags_input.clear();
+        self.project_archive_input.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// The project panel's text field being typed in, if the focused field is one
+    fn project_input(&mut self) -> Option<&mut String> {
+        match self.project_field {
+            ProjectField::Color => Some(&mut self.project_color_input),
+            ProjectField::Tags => Some(&mut self.project_tags_input),
+            ProjectField::ArchiveDays => Some(&mut self.project_archive_input),
+            ProjectField::OwnFile => None,
+        }
+    }
+
+    /// Save the typed settings (blank for none); bad input leaves the popup open to fix it
+    pub fn save_project_panel(&mut self) {
+        let color = Some(self.project_color_input.trim().to_string()).filter(|color| !color.is_empty());
+        if let Some(color) = &color
+            && color.parse::<ratatui::style::Color>().is_err()
+        {
+            self.notice = Some(format!("Unknown color \"{}\" (use a name like cyan or a hex like #ff8800)", color));
+            self.project_field = ProjectField::Color;
+            return;
+        }
+        let archive_after_days = match self.project_archive_input.trim() {
+            "" => None,
+            input => match input.parse::<u32>() {
+                Ok(days) => Some(days),
+                Err(_) => {
+                    self.notice = Some(format!("Archive after \"{}\" isn't a number of days", input));
+                    self.project_field = ProjectField::ArchiveDays;
+                    return;
+                }
+            },
+        };
+        let mut project = self.project_storage.get(&self.project_panel_name)
+            .cloned()
+            .unwrap_or_else(|| Project::new(self.project_panel_name.clone()));
+        project.color = color;
+        project.default_tags = self.project_tags_input.split_whitespace()
+            .filter_map(|word| Todo::normalize_tag(word.trim_start_matches('+')))
+            .collect();
+        project.archive_after_days = archive_after_days;
+        match self.project_storage.upsert(project) {
+            Ok(()) => self.record_change(format!("project: {}", self.project_panel_name)),
+            Err(err) => self.notice = Some(format!("Couldn't save projects: {}", err)),
+        }
+        // Splitting the tasks out (or back in) happens with the next task write
+        self.storage.set_split(&self.project_panel_name, self.project_own_file);
+        self.close_project_panel();
+    }
+
+    /// Ask who the selected task is waiting on
+    pub fn open_waiting_panel(&mut self) {
+        let Some(todo) = self.selected_todo_index.and_then(|index| self.todos.get(index)) else {
+            return;
+        };
+        self.waiting_on_input = todo.delegated_to.clone().unwrap_or_default();
+        self.show_waiting_panel = true;
+        self.input_mode = InputMode::WaitingOn;
+    }
+
+    pub fn close_waiting_panel(&mut self) {
+        self.show_waiting_panel = false;
+        self.waiting_on_input.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Put the selected task in the waiting status, delegated to whoever was typed (blank for nobody in particular)
+    pub fn save_waiting_on(&mut self) {
+        let delegated_to = Some(self.waiting_on_input.trim().to_string()).filter(|name| !name.is_empty());
+        let waiting_status = self.config.waiting.status.clone();
+        let has_status = self.config.workflow.open_statuses().iter().any(|status| status.name == waiting_status);
+        if let Some(index) = self.selected_todo_index
+            && let Some(todo) = self.todos.get_mut(index)
+        {
+            todo.delegated_to = delegated_to;
+            todo.set_waiting(true);
+            if has_status {
+                todo.set_status(Some(waiting_status));
+            }
+            let id = todo.id;
+            self.persist_todo(id);
+        }
+        self.close_waiting_panel();
+    }
+
+    /// Note in the selected waiting task's description that its owner was chased up, restarting the nag clock
+    pub fn ping_selected(&mut self) {
+        let today = self.clock.today();
+        let Some(todo) = self.selected_todo_index.and_then(|index| self.todos.get_mut(index)) else {
+            return;
+        };
+        if !todo.is_waiting() {
+            self.notice = Some("Only waiting tasks can be pinged (W sets who a task is waiting on)".to_string());
+            return;
+        }
+        let who = todo.delegated_to.clone().unwrap_or_else(|| "them".to_string());
+        if !todo.description.is_empty() && !todo.description.ends_with('\n') {
+            todo.description.push('\n');
+        }
+        todo.description.push_str(&format!("{}: pinged {}", today.format("%Y-%m-%d"), who));
+        todo.last_pinged = Some(Utc::now());
+        let (id, title) = (todo.id, todo.title.clone());
+        self.persist_todo(id);
+        self.notice = Some(format!("Pinged {} about \"{}\"", who, title));
+    }
+
+    /// Park the selected task for some day, or bring a someday task back; either way it leaves
+    /// the list it's in, and the cursor moves on to the task that took its place
+    pub fn toggle_someday_selected(&mut self) {
+        let Some(index) = self.selected_todo_index else {
+            return;
+        };
+        let position = self.visible_todo_indices().iter().position(|&i| i == index).unwrap_or(0);
+        let todo = &mut self.todos[index];
+        todo.someday = !todo.someday;
+        let (id, title, someday) = (todo.id, todo.title.clone(), todo.someday);
+        self.persist_todo(id);
+        self.notice = Some(if someday {
+            format!("\"{}\" is parked for someday (w opens a Someday workspace)", title)
+        } else {
+            format!("\"{}\" is back on the active list", title)
+        });
+
+        let visible = self.visible_todo_indices();
+        self.selected_todo_index = visible.get(position.min(visible.len().saturating_sub(1))).copied();
+        self.sync_list_selection();
+    }
+
+    /// Tell the user at startup about waiting tasks nobody has chased up lately
+    fn nag_waiting(&mut self) {
+        let now = Utc::now();
+        let count = self.todos.iter()
+            .filter(|todo| todo.needs_ping(now, self.config.waiting.nag_after_days))
+            .count();
+        if count > 0 && self.notice.is_none() {
+            self.notice = Some(format!(
+                "{} waiting task{} due a ping (P on the task; w > Waiting lists them all)",
+                count, if count == 1 { " is" } else { "s are" },
+            ));
+        }
+    }
+
+    /// Move the selected task's due date `days` later (or earlier if negative), counting from today
+    /// if it has none, stepping over days away; with `skip_when_rescheduling` it keeps going until it
+    /// lands on a working day
+    pub fn reschedule_selected(&mut self, days: i64) {
+        let Some(todo) = self.selected_todo_index.and_then(|index| self.todos.get(index)) else {
+            return;
+        };
+        let id = todo.id;
+        let mut due_date = todo.due_date.unwrap_or_else(|| self.clock.today()) + chrono::Duration::days(days);
+        // Days away are always stepped over; weekends and holidays only if config says so
+        let skip = |date: NaiveDate| match self.config.workdays.skip_when_rescheduling {
+            true => !self.is_working_day(date),
+            false => self.blackout_on(date).is_some(),
+        };
+        // Give up after a year, in case no weekday is configured as working
+        for _ in 0..366 {
+            if !skip(due_date) {
+                break;
+            }
+            due_date += chrono::Duration::days(days.signum());
+        }
+
+        if let Some(todo) = self.todos.iter_mut().find(|t| t.id == id) {
+            todo.due_date = Some(due_date);
+        }
+        self.sort_todos();
+        self.persist_todo(id);
+        self.selected_todo_index = self.todos.iter().position(|t| t.id == id);
+        self.notice = Some(format!("Due {}", due_date.format("%a %Y-%m-%d")));
+    }
+
+    pub fn reset_calendar_to_today(&mut self) {
+        let today = self.clock.today();
+        self.current_date = today;
+        self.selected_calendar_date = Some(today);
+    }
+
+    pub fn scroll_description_up(&mut self) {
+        // From where it's drawn, if the panel has grown since scrolling down
+        let (rows, visible) = self.description_rows.get();
+        self.task_description_scroll = self.task_description_scroll.min(rows.saturating_sub(visible)).saturating_sub(1);
+    }
+
+    /// Scroll down a wrapped row, stopping once the last one is in view
+    pub fn scroll_description_down(&mut self) {
+        let (rows, visible) = self.description_rows.get();
+        if self.task_description_scroll + visible < rows {
+            self.task_description_scroll += 1;
+        }
+    }
+
+    /// Whole days since the task was created
+    pub fn age_days(&self, todo: &Todo) -> i64 {
+        (self.clock.today() - self.clock.date_of(todo.created_at)).num_days().max(0)
+    }
+
+    /// Every stored todo in any state, served from the in-memory copy of the file
+    pub fn all_todos(&self) -> &[Todo] {
+        self.storage.todos()
+    }
+
+    /// All stored todos (any state) that belong to the active context
+    pub fn get_context_todos(&self) -> Vec<Todo> {
+        let context = self.config.active_context.as_deref();
+        self.all_todos()
+            .iter()
+            .filter(|t| t.in_context(context))
+            .cloned()
+            .collect()
+    }
+
+    /// Remove tasks in the trash longer than `purge_deleted_after_days`, saying which went
+    fn purge_old_trash(&mut self) {
+        let Some(days) = self.config.purge_deleted_after_days else {
+            return;
+        };
+        let cutoff = Utc::now() - Duration::days(days as i64);
+        let purged = self.storage.remove_where(|todo| todo.deleted && todo.last_touched() < cutoff);
+        if !purged.is_empty() {
+            self.record_change(format!("purge: {} deleted tasks", purged.len()));
+            self.notice = Some(format!(
+                "Removed {} task{} deleted over {} days ago: {}",
+                purged.len(), if purged.len() == 1 { "" } else { "s" }, days, title_summary(&purged),
+            ));
+        }
+    }
+
+    /// How many deleted tasks are waiting in the trash
+    pub fn trash_count(&self) -> usize {
+        self.all_todos().iter().filter(|todo| todo.deleted).count()
+    }
+
+    pub fn open_empty_trash_panel(&mut self) {
+        if self.trash_count() == 0 {
+            self.notice = Some("The trash is empty".to_string());
+            return;
+        }
+        self.show_empty_trash_panel = true;
+        self.input_mode = InputMode::EmptyTrashPanel;
+    }
+
+    pub fn close_empty_trash_panel(&mut self) {
+        self.show_empty_trash_panel = false;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Remove every deleted task for good
+    pub fn empty_trash(&mut self) {
+        let purged = self.storage.remove_where(|todo| todo.deleted);
+        self.close_empty_trash_panel();
+        if purged.is_empty() {
+            return;
+        }
+        self.record_change(format!("purge: {} deleted tasks", purged.len()));
+        self.refresh_search_index();
+        self.notice = Some(format!("Removed {} deleted task{} for good", purged.len(), if purged.len() == 1 { "" } else { "s" }));
+    }
+
+    /// Move closed tasks older than `archive_after_days` (or, with `archive_at_day_end`, closed
+    /// before today) out of todos.json
+    fn archive_old_tasks(&mut self) {
+        let today = self.clock.today();
+        let default_cutoff = if self.config.archive_at_day_end {
+            Some(today)
+        } else {
+            self.config.archive_after_days.map(|days| today - chrono::Duration::days(days as i64))
+        };
+        // A project's own archive policy wins over the configured one
+        let projects = &self.project_storage;
+        let cutoff = |todo: &Todo| {
+            match todo.project.as_deref().and_then(|name| projects.get(name)?.archive_after_days) {
+                Some(days) => Some(today - chrono::Duration::days(days as i64)),
+                None => default_cutoff,
+            }
+        };
+        match self.archive.archive_closed(&mut self.storage, cutoff, &self.clock) {
+            Ok(0) => {}
+            Ok(count) => {
+                self.record_change(format!("archive: {} closed tasks", count));
+                self.archived_todos = self.archive.load_all();
+            }
+            Err(err) => self.notice = Some(format!("Archiving failed: {}", err)),
+        }
+        if self.config.greeting {
+            let archived = self.archive.load_range(today - Duration::days(STREAK_DAYS), today);
+            self.archived_done_days = archived.iter()
+                .filter(|todo| todo.completed && !todo.deleted)
+                .filter_map(|todo| todo.completed_at)
+                .map(|at| self.clock.date_of(at))
+                .collect();
+        }
+    }
+
+    /// Days in a row, back from today, with a task done; today only breaks the run once it's over
+    pub fn completion_streak(&self) -> usize {
+        let today = self.clock.today();
+        let mut done_days = self.archived_done_days.clone();
+        done_days.extend(self.all_todos().iter()
+            .filter(|todo| todo.completed && !todo.deleted)
+            .filter_map(|todo| todo.completed_at)
+            .map(|at| self.clock.date_of(at)));
+        let start = if done_days.contains(&today) { today } else { today - Duration::days(1) };
+        std::iter::successors(Some(start), |day| day.pred_opt())
+            .take(STREAK_DAYS as usize)
+            .take_while(|day| done_days.contains(day))
+            .count()
+    }
+
+    /// At midnight, yesterday's completions leave Done today (and go to the archive if configured)
+    fn check_day_boundary(&mut self) {
+        let today = self.clock.today();
+        if today == self.current_day {
+            return;
+        }
+        self.current_day = today;
+        self.archive_old_tasks();
+        self.refresh_done_today();
+        self.refresh_search_index();
+    }
+
+    /// Recompute the Stats tab's numbers if tasks, the day or the context changed since last time
+    pub fn refresh_stats(&mut self) {
+        let key = StatsKey {
+            revision: self.storage.revision(),
+            habits_revision: self.habit_storage.revision(),
+            today: self.clock.today(),
+            context: self.config.active_context.clone(),
+            all_sources: self.stats_all_sources,
+        };
+        if self.stats.as_ref().is_some_and(|(cached, _)| *cached == key) {
+            return;
+        }
+        if key.all_sources {
+            let stats = self.all_sources_stats();
+            self.stats = Some((key, stats));
+            return;
+        }
+        // Only the archive months the charts reach are read
+        let context = self.config.active_context.as_deref();
+        let start = key.today - chrono::Duration::days(HISTORY_DAYS);
+        let archived: Vec<Todo> = self.archive.load_range(start, key.today)
+            .into_iter()
+            .filter(|t| t.in_context(context))
+            .collect();
+        let totals = self.archive.totals(context);
+        let stats = Stats::compute(&self.get_context_todos(), &archived, totals, self.habit_storage.habits(), &self.config.workflow, &self.clock);
+        self.stats = Some((key, stats));
+    }
+
+    /// Stats over every task here, whatever its context, and in each configured source folder, with
+    /// each folder's share kept apart for coloring
+    fn all_sources_stats(&mut self) -> Stats {
+        let today = self.clock.today();
+        let start = today - chrono::Duration::days(HISTORY_DAYS);
+        let mut sources = vec![(
+            "Local".to_string(),
+            None,
+            self.all_todos().to_vec(),
+            self.archive.load_range(start, today),
+            self.archive.totals(None),
+        )];
+        let mut missing = Vec::new();
+        for source in &self.config.stats.sources {
+            let dir = expand_home(&source.path);
+            if !dir.join("todos.json").exists() {
+                missing.push(source.name.as_str());
+            }
+            // Only read: nothing here is ever flushed back
+            let storage = FileStorage::open(dir.join("todos.json"));
+            let archive = Archive::open(dir.join("archive"));
+            sources.push((
+                source.name.clone(),
+                source.color.clone(),
+                storage.todos().to_vec(),
+                archive.load_range(start, today),
+                archive.totals(None),
+            ));
+        }
+        if !missing.is_empty() {
+            self.notice = Some(format!("No todos.json found for stats source {}", missing.join(", ")));
+        }
+
+        let live: Vec<Todo> = sources.iter().flat_map(|(_, _, live, _, _)| live.iter().cloned()).collect();
+        let archived: Vec<Todo> = sources.iter().flat_map(|(_, _, _, archived, _)| archived.iter().cloned()).collect();
+        let totals = sources.iter().fold(ArchiveTotals::default(), |sum, (_, _, _, _, totals)| ArchiveTotals {
+            completed: sum.completed + totals.completed,
+            deleted: sum.deleted + totals.deleted,
+        });
+        let mut stats = Stats::compute(&live, &archived, totals, self.habit_storage.habits(), &self.config.workflow, &self.clock);
+        stats.sources = sources.into_iter()
+            .map(|(name, color, live, archived, _)| SourceStats::compute(name, color, &live, &archived, &self.clock))
+            .collect();
+        stats
+    }
+
+    /// Cached Stats tab numbers, filled in by `refresh_stats`
+    pub fn stats(&self) -> Option<&Stats> {
+        self.stats.as_ref().map(|(_, stats)| stats)
+    }
+
+    pub fn usage(&self) -> Option<&UsageLog> {
+        self.usage.as_ref()
+    }
+
+    pub fn open_context_panel(&mut self) {
+        // Offer every context used by an open task, plus the active one
+        let mut contexts: Vec<String> = self.all_todos()
+            .iter()
+            .filter(|t| !t.completed && !t.deleted)
+            .filter_map(|t| t.context.clone())
+            .chain(self.config.active_context.clone())
+            .collect();
+        contexts.sort();
+        contexts.dedup();
+
+        self.selected_context_index = self.config.active_context.as_ref()
+            .and_then(|active| contexts.iter().position(|c| c == active))
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        self.context_choices = contexts;
+        self.show_context_panel = true;
+        self.input_mode = InputMode::ContextPanel;
+    }
+
+    pub fn close_context_panel(&mut self) {
+        self.show_context_panel = false;
+        self.context_choices.clear();
+        self.selected_context_index = 0;
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn select_previous_context(&mut self) {
+        // Index 0 is "All contexts", followed by each context choice
+        let count = self.context_choices.len() + 1;
+        self.selected_context_index = (self.selected_context_index + count - 1) % count;
+    }
+
+    pub fn select_next_context(&mut self) {
+        let count = self.context_choices.len() + 1;
+        self.selected_context_index = (self.selected_context_index + 1) % count;
+    }
+
+    pub fn apply_selected_context(&mut self) {
+        self.config.active_context = match self.selected_context_index {
+            0 => None,
+            i => self.context_choices.get(i - 1).cloned(),
+        };
+        let _ = self.config.save();
+        self.reload_todos();
+        self.close_context_panel();
+    }
+
+    pub fn open_new_task_panel(&mut self) {
+        // A day's list adds tasks due that day
+        let due_date = match self.workspace_filter() {
+            Some(ListFilter::Day(date)) => Some(*date),
+            _ => None,
+        };
+        self.open_new_task_panel_with_date(due_date);
+    }
+
+    /// Other tasks due on `date`, timed ones first, for spotting deadlines piling up on one day
+    pub fn tasks_due_on(&self, date: NaiveDate) -> Vec<&Todo> {
+        let mut due: Vec<&Todo> = self.todos.iter()
+            .filter(|todo| todo.due_date == Some(date) && !todo.someday && Some(todo.id) != self.editing_todo_id)
+            .collect();
+        due.sort_by_key(|todo| (todo.due_time.is_none(), todo.due_time));
+        due
+    }
+
+    /// How full each day is: the open tasks due on it (someday ones aside), or their estimated
+    /// hours when a day's capacity is set in hours
+    pub fn day_loads(&self) -> HashMap<NaiveDate, f64> {
+        let capacity = self.capacity();
+        let mut loads = HashMap::new();
+        for todo in self.todos.iter().filter(|t| !t.completed && !t.deleted && !t.someday) {
+            if let Some(due_date) = todo.due_date {
+                *loads.entry(due_date).or_insert(0.0) += capacity.load(todo);
+            }
+        }
+        loads
+    }
+
+    /// How much a day holds, in tasks or hours
+    pub fn day_capacity(&self) -> f64 {
+        self.capacity().limit()
+    }
+
+    fn capacity(&self) -> Capacity {
+        Capacity::from_config(&self.config.planning)
+    }
+
+    /// Days with more due on them than a day holds
+    pub fn over_capacity_days(&self) -> HashSet<NaiveDate> {
+        let capacity = self.day_capacity();
+        self.day_loads().into_iter().filter(|(_, load)| *load > capacity).map(|(date, _)| date).collect()
+    }
+
+    /// A day's load against its capacity: "3/5" tasks, or "4.5/6h"
+    pub fn describe_load(&self, load: f64) -> String {
+        let unit = if self.config.planning.daily_hours.is_some() { "h" } else { "" };
+        let load = format!("{:.1}", load);
+        format!("{}/{}{}", load.trim_end_matches(".0"), self.day_capacity(), unit)
+    }
+
+    /// The first working day after `after` with room left for another task; the first working
+    /// day after it if none has room within a year
+    pub fn next_day_with_room(&self, after: NaiveDate) -> NaiveDate {
+        let loads = self.day_loads();
+        let capacity = self.day_capacity();
+        let mut first_working = None;
+        let mut date = after;
+        for _ in 0..366 {
+            date += Duration::days(1);
+            if !self.is_working_day(date) {
+                continue;
+            }
+            if loads.get(&date).copied().unwrap_or(0.0) < capacity {
+                return date;
+            }
+            first_working.get_or_insert(date);
+        }
+        first_working.unwrap_or(date)
+    }
+
+    pub fn open_new_task_panel_with_date(&mut self, due_date: Option<NaiveDate>) {
+        self.show_new_task_panel = true;
+        self.input_mode = InputMode::EditingTitle;
+        self.editing_todo_id = None;
+        self.new_task_title.clear();
+        self.new_task_description.clear();
+        self.new_task_due_date = due_date;
+        self.date_input_buffer = due_date
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        self.new_task_time.clear();
+        // New tasks start in the workspace's context, or else the active one
+        self.new_task_context = match self.workspace_filter() {
+            Some(ListFilter::Context(context)) => context.clone(),
+            _ => self.config.active_context.clone().unwrap_or_default(),
+        };
+        self.new_task_reminders.clear();
+        self.new_task_recurrence.clear();
+        self.new_task_estimate.clear();
+        self.new_task_parent = None;
+        self.edit_description_scroll = 0;
+        self.task_editor_original = self.task_editor_fields();
+    }
+
+    /// Edit one field of the selected task right in the details panel, starting with `field`
+    pub fn start_field_edit(&mut self, field: DetailField) {
+        let Some(todo) = self.selected_todo_index.and_then(|index| self.todos.get(index)) else {
+            return;
+        };
+        self.field_input = match field {
+            DetailField::Title => todo.title.clone(),
+            DetailField::DueDate => todo.due_date.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default(),
+            DetailField::Tags => todo.tags.join(" "),
+            DetailField::Priority => todo.priority.map(|p| p.label().to_lowercase()).unwrap_or_default(),
+        };
+        self.editing_field = Some(field);
+        self.input_mode = InputMode::EditingField;
+    }
+
+    pub fn cancel_field_edit(&mut self) {
+        self.editing_field = None;
+        self.field_input.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Save the field being edited in place, then edit `next` or finish; on a value that can't
+    /// be used, say why and keep editing
+    pub fn save_field_edit(&mut self, next: Option<DetailField>) {
+        let (Some(field), Some(index)) = (self.editing_field, self.selected_todo_index) else {
+            return;
+        };
+        let input = self.field_input.trim().to_string();
+        let today = self.clock.today();
+        let todo = &mut self.todos[index];
+        match field {
+            DetailField::Title if input.is_empty() => {
+                self.notice = Some("A task needs a title".to_string());
+                return;
+            }
+            DetailField::Title => todo.title = input,
+            DetailField::DueDate => {
+                todo.due_date = match input.as_str() {
+                    "" | "none" => None,
+                    _ => match quick_add::parse_due_date(&input, today) {
+                        Some(date) => Some(date),
+                        None => {
+                            self.notice = Some(format!("Can't read \"{}\" as a date (try 2026-10-20, friday or in 3 days)", input));
+                            return;
+                        }
+                    },
+                };
+                if todo.due_date.is_none() {
+                    todo.due_time = None;
+                }
+            }
+            DetailField::Tags => {
+                todo.tags.clear();
+                for tag in input.split_whitespace().filter_map(Todo::normalize_tag) {
+                    if !todo.tags.contains(&tag) {
+                        todo.tags.push(tag);
+                    }
+                }
+            }
+            DetailField::Priority => {
+                todo.priority = match input.as_str() {
+                    "" | "none" => None,
+                    _ => match Priority::parse(&input) {
+                        Some(priority) => Some(priority),
+                        None => {
+                            self.notice = Some(format!("Unknown priority \"{}\" (use high, medium, low or none)", input));
+                            return;
+                        }
+                    },
+                };
+            }
+        }
+        let id = todo.id;
+        self.sort_todos();
+        self.persist_todo(id);
+        self.selected_todo_index = self.todos.iter().position(|t| t.id == id);
+        match next {
+            Some(field) => self.start_field_edit(field),
+            None => self.cancel_field_edit(),
+        }
+    }
+
+    /// Add a subtask under the selected task, starting in its context
+    pub fn open_new_subtask_panel(&mut self) {
+        let Some(parent) = self.selected_todo_index.and_then(|index| self.todos.get(index)) else {
+            return;
+        };
+        let (id, context) = (parent.id, parent.context.clone());
+        self.open_new_task_panel();
+        self.new_task_parent = Some(id);
+        if let Some(context) = context {
+            self.new_task_context = context;
+        }
+        self.task_editor_original = self.task_editor_fields();
+    }
+
+    pub fn open_edit_task_panel(&mut self) {
+        if let Some(index) = self.selected_todo_index
+            && let Some(todo) = self.todos.get(index)
+        {
+            self.show_new_task_panel = true;
+            self.input_mode = InputMode::EditingTitle;
+            self.editing_todo_id = Some(todo.id);
+            self.new_task_title = todo.title.clone();
+            self.new_task_description = TextBuffer::new(&todo.description);
+            self.new_task_due_date = todo.due_date;
+            self.date_input_buffer = todo.due_date
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .unwrap_or_default();
+            self.new_task_time = todo.due_time.map(|time| time.format("%H:%M").to_string()).unwrap_or_default();
+            self.new_task_context = todo.context.clone().unwrap_or_default();
+            self.new_task_reminders = match &todo.reminders {
+                Some(offsets) if offsets.is_empty() => "none".to_string(),
+                Some(offsets) => ReminderOffset::format_list(offsets),
+                None => String::new(),
+            };
+            self.new_task_recurrence = todo.recurrence.as_ref().map(Recurrence::to_string).unwrap_or_default();
+            self.new_task_estimate = todo.estimate().map(TimeEntry::format_duration).unwrap_or_default();
+            self.edit_description_scroll = 0;
+            self.task_editor_original = self.task_editor_fields();
+        }
+    }
+
+    pub fn close_new_task_panel(&mut self) {
+        self.show_new_task_panel = false;
+        self.input_mode = InputMode::Normal;
+        self.editing_todo_id = None;
+        self.new_task_title.clear();
+        self.new_task_description.clear();
+        self.new_task_due_date = None;
         self.date_input_buffer.clear();
+        self.new_task_time.clear();
+        self.new_task_context.clear();
+        self.new_task_reminders.clear();
+        self.new_task_recurrence.clear();
+        self.new_task_estimate.clear();
+        self.new_task_parent = None;
+        self.adding_duplicate = false;
+    }
+
+    /// The task editor's fields as typed so far
+    fn task_editor_fields(&self) -> [String; 8] {
+        [
+            self.new_task_title.clone(),
+            self.new_task_description.to_string(),
+            self.date_input_buffer.clone(),
+            self.new_task_time.clone(),
+            self.new_task_context.clone(),
+            self.new_task_reminders.clone(),
+            self.new_task_recurrence.clone(),
+            self.new_task_estimate.clone(),
+        ]
+    }
+
+    /// Esc in the task editor: close it, first asking whether to discard any changes if configured to
+    pub fn cancel_new_task_panel(&mut self) {
+        if self.config.confirm.discard_edits && self.task_editor_fields() != self.task_editor_original {
+            self.open_discard_panel();
+        } else {
+            self.close_new_task_panel();
+        }
+    }
+
+    /// Esc in the note editor, like `cancel_new_task_panel`
+    pub fn cancel_note_panel(&mut self) {
+        let saved = self.editing_note_date.and_then(|date| self.note_storage.get(date)).unwrap_or_default();
+        if self.config.confirm.discard_edits && self.note_text.to_string() != saved {
+            self.open_discard_panel();
+        } else {
+            self.close_note_panel();
+        }
+    }
+
+    fn open_discard_panel(&mut self) {
+        self.show_discard_panel = true;
+        self.discard_panel_yes_selected = true;
+        self.dont_ask_again = false;
+        self.discard_return_mode = self.input_mode.clone();
+        self.input_mode = InputMode::DiscardPanel;
+    }
+
+    /// Close the discard popup, going back to the editor
+    pub fn close_discard_panel(&mut self) {
+        self.show_discard_panel = false;
+        self.input_mode = self.discard_return_mode.clone();
+    }
+
+    /// Throw away the editor's changes and close it
+    pub fn discard_edits(&mut self) {
+        self.close_discard_panel();
+        if self.show_note_panel {
+            self.close_note_panel();
+        } else {
+            self.close_new_task_panel();
+        }
+    }
+
+    fn open_duplicate_panel(&mut self, existing_id: usize) {
+        self.show_duplicate_panel = true;
+        self.duplicate_panel_yes_selected = true;
+        self.duplicate_of = Some(existing_id);
+        self.dont_ask_again = false;
+        self.discard_return_mode = self.input_mode.clone();
+        self.input_mode = InputMode::DuplicatePanel;
+    }
+
+    /// Close the duplicate question, going back to the editor
+    pub fn close_duplicate_panel(&mut self) {
+        self.show_duplicate_panel = false;
+        self.duplicate_of = None;
+        self.input_mode = self.discard_return_mode.clone();
+    }
+
+    /// Drop the new task and show the open one it looked like instead
+    pub fn open_duplicate(&mut self) {
+        let existing = self.duplicate_of;
+        self.close_duplicate_panel();
+        self.close_new_task_panel();
+        if let Some(id) = existing {
+            self.select_task_by_id(id);
+            if self.selected_todo_index.and_then(|index| self.todos.get(index)).is_some_and(|todo| todo.id == id) {
+                self.open_edit_task_panel();
+            }
+        }
+    }
+
+    /// Save the new task as typed, even though it looks like an open one
+    pub fn add_duplicate(&mut self) {
+        self.close_duplicate_panel();
+        self.adding_duplicate = true;
+        self.save_new_task();
+    }
+
+    /// Grow the task editor by `step` percent of the terminal each way (shrink it when negative),
+    /// starting from the size it's drawn at, and keep the size in config for next time
+    pub fn resize_task_editor(&mut self, step: i16) {
+        let (width, height) = self.task_editor_percent.get();
+        let resize = |percent: u16| (percent as i16 + step).clamp(20, 100) as u16;
+        self.config.editor_size = Some(PopupSize { width: resize(width), height: resize(height) });
+        if let Err(err) = self.config.save() {
+            self.notice = Some(format!("Couldn't save config: {}", err));
+        }
+    }
+
+    /// Turn a confirmation off in config (and save it) if "Don't ask again" was ticked
+    fn apply_dont_ask_again(&mut self, change: impl FnOnce(&mut ConfirmConfig)) {
+        if self.dont_ask_again {
+            change(&mut self.config.confirm);
+            if let Err(err) = self.config.save() {
+                self.notice = Some(format!("Couldn't save config: {}", err));
+            }
+        }
     }
 
     pub fn open_done_panel(&mut self) {
-        if let Some(index) = self.selected_todo_index {
-            if let Some(todo) = self.todos.get(index) {
-                self.show_done_panel = true;
-                self.completing_todo_id = Some(todo.id);
-                self.done_panel_yes_selected = true;
-                self.input_mode = InputMode::DonePanel;
+        if let Some(index) = self.selected_todo_index
+            && let Some(todo) = self.todos.get(index)
+        {
+            self.completing_todo_id = Some(todo.id);
+            if !self.config.confirm.done {
+                self.mark_task_complete();
+                return;
             }
+            self.show_done_panel = true;
+            self.done_panel_yes_selected = true;
+            self.dont_ask_again = false;
+            self.input_mode = InputMode::DonePanel;
         }
     }
 
@@ -389,102 +3855,530 @@ impl App {
     }
 
     pub fn mark_task_complete(&mut self) {
+        let mut finished = None;
         if let Some(completing_id) = self.completing_todo_id {
-            // Load all todos (including completed ones)
-            let mut all_todos = self.storage.load_todos().unwrap_or_else(|_| Vec::new());
+            let reopening = self.all_todos().iter().any(|t| t.id == completing_id && t.completed);
+            finished = Some((completing_id, reopening));
+            let verb = if reopening { "reopen" } else { "complete" };
+            self.record_change(format!("{}: {}", verb, self.stored_title(completing_id)));
 
-            // Find and mark the task as complete
-            if let Some(todo) = all_todos.iter_mut().find(|t| t.id == completing_id) {
-                todo.toggle_completed();
-            }
+            // Mark the stored task as complete
+            self.storage.update(completing_id, |todo| todo.toggle_completed());
 
-            // Save all todos (including the newly completed one)
-            let _ = self.storage.save_todos(&all_todos);
+            // A repeating task comes back as its next occurrence
+            let next = self.all_todos().iter()
+                .find(|t| t.id == completing_id)
+                .filter(|_| !reopening)
+                .and_then(|todo| todo.next_occurrence(self.next_todo_id(), self.clock.today()));
+            if let Some(next) = next {
+                let due = next.due_date.map(|d| d.format("%a %Y-%m-%d").to_string()).unwrap_or_default();
+                self.notice = Some(format!("Repeats: #{} is due {}", next.id, due));
+                self.record_change(storage::describe_change(None, &next));
+                self.storage.upsert(next.clone());
+                if next.in_context(self.config.active_context.as_deref()) {
+                    self.todos.push(next);
+                    self.sort_todos();
+                }
+            }
+            self.refresh_search_index();
+            self.refresh_done_today();
 
-            // Remove the completed task from the current display list
+            // Remove the completed task from the current display list, or put a reopened one back
             self.todos.retain(|t| t.id != completing_id);
+            if reopening
+                && let Some(todo) = self.all_todos().iter().find(|t| t.id == completing_id)
+                && todo.in_context(self.config.active_context.as_deref())
+            {
+                self.todos.push(todo.clone());
+                self.sort_todos();
+            }
 
             // Adjust selected index if needed
             if self.todos.is_empty() {
                 self.selected_todo_index = None;
-            } else if let Some(index) = self.selected_todo_index {
-                if index >= self.todos.len() {
-                    self.selected_todo_index = Some(self.todos.len() - 1);
-                }
+            } else if let Some(index) = self.selected_todo_index
+                && index >= self.todos.len()
+            {
+                self.selected_todo_index = Some(self.todos.len() - 1);
             }
         }
         self.close_done_panel();
+        if let Some((id, reopened)) = finished {
+            self.update_parent_of(id, reopened);
+        }
+    }
+
+    /// After a subtask is done or reopened, follow its parent's choice: once every subtask is
+    /// done, mark the parent done too (or ask to); when one is reopened, reopen the parent
+    fn update_parent_of(&mut self, id: usize, reopened: bool) {
+        let Some(parent_id) = self.all_todos().iter().find(|t| t.id == id).and_then(|t| t.parent) else {
+            return;
+        };
+        let Some(parent) = self.all_todos().iter().find(|t| t.id == parent_id && !t.deleted) else {
+            return;
+        };
+        let mode = parent.parent_completion.unwrap_or(self.config.subtasks.complete_parent);
+        // Nothing to do unless the parent is done and a subtask reopened, or the other way round
+        if mode == ParentCompletion::Off || parent.completed != reopened {
+            return;
+        }
+        let all_done = self.all_todos().iter()
+            .filter(|t| t.parent == Some(parent_id) && !t.deleted)
+            .all(|t| t.completed);
+        if !reopened && !all_done {
+            return;
+        }
+
+        let title = parent.title.clone();
+        if reopened || mode == ParentCompletion::Auto {
+            self.completing_todo_id = Some(parent_id);
+            self.mark_task_complete();
+            self.notice = Some(if reopened {
+                format!("Reopened \"{}\" as it has an open subtask again", title)
+            } else {
+                format!("Done \"{}\" too, as every subtask is", title)
+            });
+        } else {
+            self.parent_done_id = Some(parent_id);
+            self.show_parent_done_panel = true;
+            self.input_mode = InputMode::ParentDonePanel;
+        }
+    }
+
+    /// Answer the "every subtask is done" popup, optionally remembering the answer for that task
+    pub fn answer_parent_done(&mut self, complete: bool, remember: Option<ParentCompletion>) {
+        let Some(parent_id) = self.parent_done_id.take() else {
+            return;
+        };
+        self.show_parent_done_panel = false;
+        self.input_mode = InputMode::Normal;
+        if let Some(mode) = remember {
+            if let Some(todo) = self.todos.iter_mut().find(|t| t.id == parent_id) {
+                todo.parent_completion = Some(mode);
+            }
+            self.storage.update(parent_id, |todo| todo.parent_completion = Some(mode));
+        }
+        if complete {
+            self.completing_todo_id = Some(parent_id);
+            self.mark_task_complete();
+        }
+    }
+
+    /// How many of the task's subtasks are done, out of how many; None if it has none
+    pub fn subtask_progress(&self, id: usize) -> Option<(usize, usize)> {
+        let subtasks: Vec<&Todo> = self.all_todos().iter()
+            .filter(|t| t.parent == Some(id) && !t.deleted)
+            .collect();
+        if subtasks.is_empty() {
+            return None;
+        }
+        Some((subtasks.iter().filter(|t| t.completed).count(), subtasks.len()))
     }
 
     pub fn open_delete_panel(&mut self) {
-        if let Some(index) = self.selected_todo_index {
-            if let Some(todo) = self.todos.get(index) {
-                self.show_delete_panel = true;
-                self.deleting_todo_id = Some(todo.id);
-                self.delete_panel_yes_selected = true;
-                self.input_mode = InputMode::DeletePanel;
+        if let Some(index) = self.selected_todo_index
+            && let Some(todo) = self.todos.get(index)
+        {
+            self.deleting_todo_id = Some(todo.id);
+            if !self.config.confirm.delete {
+                self.mark_task_deleted();
+                return;
+            }
+            self.show_delete_panel = true;
+            self.delete_panel_yes_selected = true;
+            self.dont_ask_again = false;
+            self.input_mode = InputMode::DeletePanel;
+        }
+    }
+
+    pub fn close_delete_panel(&mut self) {
+        self.show_delete_panel = false;
+        self.deleting_todo_id = None;
+        self.deleting_habit_id = None;
+        self.delete_panel_yes_selected = true;
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn toggle_delete_button(&mut self) {
+        self.delete_panel_yes_selected = !self.delete_panel_yes_selected;
+    }
+
+    pub fn mark_task_deleted(&mut self) {
+        if let Some(deleting_id) = self.deleting_todo_id {
+            self.record_change(format!("delete: {}", self.stored_title(deleting_id)));
+
+            // Mark the stored task as deleted
+            self.storage.update(deleting_id, |todo| todo.mark_deleted());
+            self.refresh_search_index();
+
+            // Remove the deleted task from the current display list
+            self.todos.retain(|t| t.id != deleting_id);
+
+            // Adjust selected index if needed
+            if self.todos.is_empty() {
+                self.selected_todo_index = None;
+            } else if let Some(index) = self.selected_todo_index
+                && index >= self.todos.len()
+            {
+                self.selected_todo_index = Some(self.todos.len() - 1);
             }
         }
+        self.close_delete_panel();
+    }
+
+    pub fn open_search_panel(&mut self) {
+        self.show_search_panel = true;
+        self.input_mode = InputMode::Search;
+        self.search_query.clear();
+        self.search_results.clear();
+        self.selected_search_index = None;
+    }
+
+    pub fn close_search_panel(&mut self) {
+        self.show_search_panel = false;
+        self.input_mode = InputMode::Normal;
+        self.search_query.clear();
+        self.search_results.clear();
+        self.selected_search_index = None;
+    }
+
+    pub fn update_search_results(&mut self) {
+        self.search_results = self.search_index.search(&self.search_query);
+        // Matching notes go after the active tasks
+        let notes_at = self.search_results.iter()
+            .position(|hit| hit.source != TaskSource::Active)
+            .unwrap_or(self.search_results.len());
+        let notes = search::search_notes(self.note_storage.notes(), &self.search_query);
+        self.search_results.splice(notes_at..notes_at, notes);
+        self.selected_search_index = if self.search_results.is_empty() { None } else { Some(0) };
+    }
+
+    pub fn select_previous_search_result(&mut self) {
+        if let Some(i) = self.selected_search_index {
+            self.selected_search_index = Some(if i > 0 { i - 1 } else { self.search_results.len() - 1 });
+        }
+    }
+
+    pub fn select_next_search_result(&mut self) {
+        if let Some(i) = self.selected_search_index {
+            self.selected_search_index = Some(if i < self.search_results.len() - 1 { i + 1 } else { 0 });
+        }
+    }
+
+    pub fn open_selected_search_result(&mut self) {
+        // A note opens on its day in the calendar, with the note shown below it
+        if let Some(hit) = self.selected_search_index.and_then(|i| self.search_results.get(i))
+            && hit.source == TaskSource::Note
+            && let Some(date) = hit.due_date
+        {
+            self.selected_calendar_date = Some(date);
+            self.current_date = date;
+            self.focused_panel = Panel::Calendar;
+            self.close_search_panel();
+            return;
+        }
+
+        // Someday tasks open in a Someday workspace, the only list that shows them
+        if self.selected_search_index.and_then(|i| self.search_results.get(i)).is_some_and(|hit| hit.source == TaskSource::Someday) {
+            let index = match self.workspaces.iter().position(|w| w.filter == ListFilter::Someday) {
+                Some(index) => index,
+                None => {
+                    self.workspaces.push(Workspace { filter: ListFilter::Someday, state: TabState::default() });
+                    self.workspaces.len() - 1
+                }
+            };
+            self.switch_tab(Tab::Workspace(index));
+        }
+
+        // Only open tasks are in the lists; completed and deleted hits are informational
+        if let Some(hit) = self.selected_search_index.and_then(|i| self.search_results.get(i))
+            && matches!(hit.source, TaskSource::Active | TaskSource::Someday)
+            && let Some(position) = self.todos.iter().position(|t| t.id == hit.id)
+        {
+            self.selected_todo_index = Some(position);
+            self.focused_panel = Panel::List;
+            self.task_description_scroll = 0;
+            self.selected_link = 0;
+            self.close_search_panel();
+        }
+    }
+
+    /// Bring the search index up to date in memory; it's written to disk with the next flush
+    fn refresh_search_index(&mut self) {
+        if self.search_index.sync(self.storage.todos(), &self.archived_todos) {
+            self.search_index_dirty = true;
+        }
+    }
+
+    /// Copy an edited task from the active list back into storage
+    fn persist_todo(&mut self, id: usize) {
+        if let Some(todo) = self.todos.iter_mut().find(|t| t.id == id) {
+            todo.touch();
+            let todo = todo.clone();
+            let before = self.storage.todos().iter().find(|t| t.id == id);
+            let change = storage::describe_change(before, &todo);
+            self.storage.upsert(todo);
+            self.record_change(change);
+            self.refresh_search_index();
+        }
+    }
+
+    /// Describe a change for the next git commit, when git history is on
+    fn record_change(&mut self, message: String) {
+        if let Some(git) = &mut self.git {
+            git.record(message);
+        }
+    }
+
+    /// Title of a stored task, for change descriptions
+    fn stored_title(&self, id: usize) -> String {
+        self.all_todos().iter().find(|t| t.id == id).map(|t| t.title.clone()).unwrap_or_default()
+    }
+
+    /// Commit local changes, pull and push the data repo, then reload whatever the pull brought in
+    pub fn sync_git(&mut self) {
+        self.flush_changes(true);
+        let Some(git) = &self.git else {
+            self.notice = Some("Git history is off: set \"git\": { \"auto_commit\": true } in config".to_string());
+            return;
+        };
+        if let Err(err) = git.sync() {
+            self.notice = Some(format!("Sync failed: {}", err));
+            return;
+        }
+        // Read again in place rather than reopened, so the dashboard's subscription keeps getting changes
+        if let Err(err) = self.storage.reload() {
+            self.notice = Some(format!("Synced, but couldn't read the pulled tasks: {}", err));
+            return;
+        }
+        self.archive = Archive::open(Archive::get_default_path());
+        self.habit_storage = HabitStorage::open(HabitStorage::get_default_path());
+        self.note_storage = NoteStorage::open(NoteStorage::get_default_path());
+        self.project_storage = ProjectStorage::open(ProjectStorage::get_default_path());
+        self.blackout_storage = BlackoutStorage::open(BlackoutStorage::get_default_path());
+        self.stats = None;
+        self.show_changes_elsewhere();
+        self.notice = Some("Synced with the remote".to_string());
     }
 
-    pub fn close_delete_panel(&mut self) {
-        self.show_delete_panel = false;
-        self.deleting_todo_id = None;
-        self.delete_panel_yes_selected = true;
-        self.input_mode = InputMode::Normal;
+    /// Catch up on what happened while the terminal was in the background: files another tdui, a
+    /// sync or an editor changed, and the day moving on
+    fn on_focus_gained(&mut self) {
+        self.check_day_boundary();
+        // An open editor or panel holds on to the task it was opened on; the next focus picks changes up
+        if self.input_mode != InputMode::Normal {
+            return;
+        }
+        // Saving takes in what was saved elsewhere first, so this only writes what changed here
+        self.flush_changes(true);
+        self.check_task_files();
+        // These are written as soon as they change, so there's nothing unsaved to lose
+        self.habit_storage = HabitStorage::open(HabitStorage::get_default_path());
+        self.note_storage = NoteStorage::open(NoteStorage::get_default_path());
+        self.project_storage = ProjectStorage::open(ProjectStorage::get_default_path());
+        self.blackout_storage = BlackoutStorage::open(BlackoutStorage::get_default_path());
+        self.stats = None;
     }
 
-    pub fn toggle_delete_button(&mut self) {
-        self.delete_panel_yes_selected = !self.delete_panel_yes_selected;
+    /// Read the task files again if something else saved them; with unsaved changes here, the
+    /// next save takes theirs in instead
+    fn check_task_files(&mut self) {
+        self.files_checked_at = Instant::now();
+        match self.storage.reload() {
+            Ok(false) => {}
+            Ok(true) => self.show_changes_elsewhere(),
+            Err(err) => self.notice = Some(format!("Couldn't read tasks changed elsewhere: {}", err)),
+        }
     }
 
-    pub fn mark_task_deleted(&mut self) {
-        if let Some(deleting_id) = self.deleting_todo_id {
-            // Load all todos (including completed and deleted ones)
-            let mut all_todos = self.storage.load_todos().unwrap_or_else(|_| Vec::new());
+    /// Rebuild the list around tasks taken in from the files, keeping the same task selected
+    fn show_changes_elsewhere(&mut self) {
+        let selected = self.selected_todo_index.and_then(|index| self.todos.get(index)).map(|todo| todo.id);
+        self.reload_todos();
+        self.selected_todo_index = selected.and_then(|id| self.todos.iter().position(|t| t.id == id)).or(self.selected_todo_index);
+        self.refresh_search_index();
+    }
 
-            // Find and mark the task as deleted
-            if let Some(todo) = all_todos.iter_mut().find(|t| t.id == deleting_id) {
-                todo.mark_deleted();
+    /// Bring in what changed in the vault's checkboxes and write back what changed here
+    fn sync_vault(&mut self) {
+        self.vault_synced_at = Instant::now();
+        let Some(vault) = &self.vault else {
+            return;
+        };
+        let next_id = self.next_todo_id();
+        match vault.sync(&mut self.storage, next_id, self.config.active_context.as_deref(), &self.clock) {
+            Ok(report) if report.is_empty() => {}
+            Ok(report) => {
+                self.record_change(format!("vault sync: {}", report.describe()));
+                let selected = self.selected_todo_index.and_then(|index| self.todos.get(index)).map(|todo| todo.id);
+                self.reload_todos();
+                self.selected_todo_index = selected.and_then(|id| self.todos.iter().position(|t| t.id == id)).or(self.selected_todo_index);
+                self.refresh_search_index();
+                self.notice = Some(format!("Vault synced: {}", report.describe()));
             }
+            Err(err) => self.notice = Some(format!("Vault sync failed: {}", err)),
+        }
+    }
 
-            // Save all todos (including the newly deleted one)
-            let _ = self.storage.save_todos(&all_todos);
-
-            // Remove the deleted task from the current display list
-            self.todos.retain(|t| t.id != deleting_id);
+    /// Add a task for each transcript dropped into the drop folder since the last look
+    fn import_dropped_files(&mut self) {
+        self.drop_folder_checked_at = Instant::now();
+        let Some(folder) = &self.drop_folder else {
+            return;
+        };
+        let (dropped, errors) = folder.take();
+        let mut titles = Vec::new();
+        for file in dropped {
+            let id = self.next_todo_id();
+            let mut todo = Todo::new(id, file.title.clone(), file.description, None);
+            todo.context = self.config.active_context.clone();
+            self.todos.push(todo);
+            self.persist_todo(id);
+            titles.push(file.title);
+        }
+        if !titles.is_empty() {
+            let selected = self.selected_todo_index.and_then(|index| self.todos.get(index)).map(|todo| todo.id);
+            self.sort_todos();
+            self.selected_todo_index = selected.and_then(|id| self.todos.iter().position(|t| t.id == id)).or(self.selected_todo_index);
+            self.notice = Some(match titles.as_slice() {
+                [title] => format!("Added \"{}\" from the drop folder", title),
+                _ => format!("Added {} tasks from the drop folder", titles.len()),
+            });
+        } else if let Some(error) = errors.first() {
+            self.notice = Some(format!("Drop folder: {}", error));
+        }
+    }
 
-            // Adjust selected index if needed
-            if self.todos.is_empty() {
-                self.selected_todo_index = None;
-            } else if let Some(index) = self.selected_todo_index {
-                if index >= self.todos.len() {
-                    self.selected_todo_index = Some(self.todos.len() - 1);
-                }
+    /// Write pending task and search index changes (right away when `force`, otherwise once they're due)
+    fn flush_changes(&mut self, force: bool) {
+        // Saving happens on the storage's own thread; a write that fails is retried there, and how
+        // it went shows up here on a later tick
+        let saved = if force { self.storage.flush() } else { self.storage.flush_if_due() };
+        match saved {
+            // Unsaved changes stay in memory and the write is retried, so say so once rather than every tick
+            Err(_) if self.save_failing => {}
+            Err(TduiError::StorageLocked { path }) => {
+                self.notice = Some(format!("{} is read-only or in use; changes will be saved once it can be written", path.display()));
             }
+            Err(err) => self.notice = Some(format!("Couldn't save tasks: {}", err)),
+            Ok(true) if self.save_failing => self.notice = Some("Tasks saved".to_string()),
+            Ok(_) => {}
+        }
+        self.save_failing = self.storage.is_failing();
+        // An open editor or panel holds on to the task it was opened on, so the list waits for it to close
+        if self.input_mode == InputMode::Normal && self.storage.take_changed_elsewhere() {
+            self.show_changes_elsewhere();
+        }
+        if force && let Some(usage) = &mut self.usage {
+            let _ = usage.save(self.clock.today());
+        }
+        if self.search_index_dirty && (force || !self.storage.is_dirty()) {
+            let _ = self.search_index.save();
+            self.search_index_dirty = false;
+        }
+        // Commit once the described changes are all on disk
+        if !self.storage.is_dirty()
+            && let Some(git) = &mut self.git
+            && git.has_pending()
+            && let Err(err) = git.commit()
+        {
+            self.notice = Some(format!("Git commit failed: {}", err));
         }
-        self.close_delete_panel();
     }
 
     pub fn save_new_task(&mut self) {
         if !self.new_task_title.is_empty() {
+            let context = Todo::normalize_context(&self.new_task_context);
+            // Blank keeps the global defaults, "none" turns reminders off for this task
+            let reminders = match self.new_task_reminders.trim() {
+                "" => None,
+                "none" => Some(Vec::new()),
+                input => match ReminderOffset::parse_list(input) {
+                    Ok(offsets) => Some(offsets),
+                    Err(err) => {
+                        // Leave the panel open so the input can be fixed
+                        self.notice = Some(err.to_string());
+                        self.input_mode = InputMode::EditingReminders;
+                        return;
+                    }
+                },
+            };
+            let recurrence = match self.new_task_recurrence.trim() {
+                "" => None,
+                input => match Recurrence::parse(input) {
+                    Ok(rule) => Some(rule),
+                    Err(err) => {
+                        self.notice = Some(err.to_string());
+                        self.input_mode = InputMode::EditingRecurrence;
+                        return;
+                    }
+                },
+            };
+            // A time only means something with a date to go with it
+            let due_time = match self.new_task_time.trim() {
+                "" => None,
+                input => match NaiveTime::parse_from_str(input, "%H:%M") {
+                    Ok(time) => self.new_task_due_date.map(|_| time),
+                    Err(_) => {
+                        self.notice = Some(format!("'{}' isn't a time: use HH:MM, e.g. 14:30", input));
+                        self.input_mode = InputMode::EditingTime;
+                        return;
+                    }
+                },
+            };
+            let estimate = match self.new_task_estimate.trim() {
+                "" => None,
+                input => match TimeEntry::parse_duration(input) {
+                    Ok(duration) => Some(duration.num_minutes().clamp(0, u32::MAX as i64) as u32),
+                    Err(err) => {
+                        self.notice = Some(err.to_string());
+                        self.input_mode = InputMode::EditingEstimate;
+                        return;
+                    }
+                },
+            };
+            // Quick capture makes it easy to add the same thing twice
+            if self.editing_todo_id.is_none()
+                && self.config.confirm.duplicates
+                && !self.adding_duplicate
+                && let Some(existing) = duplicate::find(&self.new_task_title, self.all_todos())
+            {
+                self.open_duplicate_panel(existing.id);
+                return;
+            }
             let task_id = if let Some(editing_id) = self.editing_todo_id {
                 // Edit existing todo
                 if let Some(todo) = self.todos.iter_mut().find(|t| t.id == editing_id) {
                     todo.title = self.new_task_title.clone();
-                    todo.description = self.new_task_description.clone();
+                    todo.description = self.new_task_description.to_string();
                     todo.due_date = self.new_task_due_date;
+                    todo.due_time = due_time;
+                    todo.context = context.clone();
+                    todo.reminders = reminders;
+                    todo.recurrence = recurrence;
+                    todo.estimate_minutes = estimate;
                 }
                 editing_id
             } else {
-                // Create new todo
-                let new_id = self.todos.iter().map(|t| t.id).max().unwrap_or(0) + 1;
-                let todo = Todo::new(
-                    new_id,
-                    self.new_task_title.clone(),
-                    self.new_task_description.clone(),
-                    self.new_task_due_date,
+                let new_id = self.next_todo_id();
+                let (title, description) = template::resolve(
+                    &self.config.templates,
+                    &self.new_task_title,
+                    &self.new_task_description.to_string(),
+                    self.clock.today(),
                 );
+                let mut todo = Todo::new(new_id, title, description, self.new_task_due_date);
+                todo.due_time = due_time;
+                todo.context = context.clone();
+                todo.reminders = reminders;
+                todo.recurrence = recurrence;
+                todo.estimate_minutes = estimate;
+                // A subtask goes in its parent's project, and any other task in the workspace's
+                todo.parent = self.new_task_parent;
+                todo.project = match self.new_task_parent {
+                    Some(id) => self.all_todos().iter().find(|t| t.id == id).and_then(|parent| parent.project.clone()),
+                    None => self.workspace_project(),
+                };
                 self.todos.push(todo);
                 new_id
             };
@@ -492,47 +4386,470 @@ impl App {
             // Sort todos after adding/editing
             self.sort_todos();
 
-            // Update selected index to point to the edited/added task after sorting
-            self.selected_todo_index = self.todos.iter().position(|t| t.id == task_id);
+            // Persist to storage
+            self.persist_todo(task_id);
+
+            // A task moved out of the active context drops out of the list
+            if !self.todos.iter().all(|t| t.in_context(self.config.active_context.as_deref())) {
+                self.reload_todos();
+            }
 
-            // Persist to file
-            let _ = self.storage.save_todos(&self.todos);
+            // Update selected index to point to the edited/added task after sorting
+            self.selected_todo_index = self.todos.iter().position(|t| t.id == task_id)
+                .or(if self.todos.is_empty() { None } else { Some(0) });
         }
         self.close_new_task_panel();
     }
 
+    /// IDs are unique across completed, deleted and archived tasks too
+    fn next_todo_id(&self) -> usize {
+        self.todos.iter()
+            .map(|t| t.id + 1)
+            .chain([self.storage.next_free_id(&self.archive)])
+            .max()
+            .unwrap_or(1)
+    }
+
+    /// Start typing a new task's title right in the list, below the selection
+    pub fn open_inline_task(&mut self) {
+        self.show_inline_task = true;
+        self.inline_task_title.clear();
+        self.input_mode = InputMode::InlineTask;
+    }
+
+    pub fn close_inline_task(&mut self) {
+        self.show_inline_task = false;
+        self.inline_task_title.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Add the typed title as a task and leave the input open for the next one; a blank title closes it
+    pub fn save_inline_task(&mut self) {
+        let title = self.inline_task_title.trim().to_string();
+        if title.is_empty() {
+            self.close_inline_task();
+            return;
+        }
+        let id = self.next_todo_id();
+        let (title, description) = template::resolve(&self.config.templates, &title, "", self.clock.today());
+        let mut todo = Todo::new(id, title, description, None);
+        // Same context as the full popup would start with
+        todo.context = match self.workspace_filter() {
+            Some(ListFilter::Context(context)) => Some(context.clone()),
+            _ => self.config.active_context.clone(),
+        };
+        todo.project = self.workspace_project();
+        self.todos.push(todo);
+        self.sort_todos();
+        self.persist_todo(id);
+        self.selected_todo_index = self.todos.iter().position(|t| t.id == id);
+        self.inline_task_title.clear();
+    }
+
+    /// Surface any reminders that came due since the last loop iteration
+    fn check_reminders(&mut self) {
+        let mut due = self.notifier.poll(&self.todos, &self.config.reminders, &self.clock);
+        // During a focus session only reminders for tasks due today or earlier get through
+        if let Some(session) = &mut self.focus {
+            let today = self.clock.today();
+            let (urgent, held_back): (Vec<_>, Vec<_>) = due.into_iter().partition(|reminder| reminder.due_date <= today);
+            session.held_back.extend(held_back.iter().map(|reminder| reminder.message()));
+            due = urgent;
+        }
+        if let Some(latest) = due.last() {
+            self.notice = Some(match due.len() {
+                1 => latest.message(),
+                n => format!("{} (+{} more)", latest.message(), n - 1),
+            });
+        }
+
+        let backends = &self.config.notifications.backends;
+        if !backends.is_empty() && !due.is_empty() {
+            let title = match due.len() {
+                1 => "Reminder".to_string(),
+                n => format!("{} reminders", n),
+            };
+            let message = due.iter().map(Reminder::description).collect::<Vec<_>>().join("\n");
+            self.notification_sends.push(notify::send_in_background(backends.clone(), title, message));
+        }
+    }
+
+    /// Hand the web dashboard fresh JSON when the tasks, the day or the context changed
+    fn publish_dashboard(&mut self) {
+        if self.server.is_none() {
+            return;
+        }
+        let key = (self.storage.revision(), self.clock.today(), self.config.active_context.clone());
+        if self.server_published.as_ref() == Some(&key) {
+            return;
+        }
+        self.refresh_stats();
+        let context = self.config.active_context.as_deref();
+        let today = server::today_json(&self.todos, &self.done_today, context, &self.clock);
+        let stats = self.stats().map(|stats| server::stats_json(stats, self.done_today.len(), self.completion_streak()));
+        if let (Some(server), Some(stats)) = (&self.server, stats) {
+            server.publish(today, stats);
+        }
+        self.server_published = Some(key);
+    }
+
+    /// Report reminders that a notification backend couldn't deliver
+    fn check_notification_sends(&mut self) {
+        let mut failures = Vec::new();
+        self.notification_sends.retain(|send| loop {
+            match send.try_recv() {
+                Ok(Err(err)) => failures.push(err),
+                Ok(Ok(())) => {}
+                Err(err) => break err == TryRecvError::Empty,
+            }
+        });
+        if let Some(err) = failures.last() {
+            self.notice = Some(format!("Couldn't send the reminder to {}", err));
+        }
+    }
+
+    /// Answer assistants' reads straight away and line up their changes to ask about, one at a time
+    fn check_tool_calls(&mut self) {
+        let incoming: Vec<PendingCall> = self.server.as_ref().map(|server| server.tool_calls().collect()).unwrap_or_default();
+        for pending in incoming {
+            if pending.call.is_write() {
+                self.tool_calls.push_back(pending);
+                continue;
+            }
+            let result = match &pending.call {
+                ToolCall::ListTasks { query, include_done } => Ok(mcp::list_tasks(self.all_todos(), query.as_deref(), *include_done)),
+                ToolCall::GetTask { id } => self.all_todos().iter()
+                    .find(|t| t.id == *id && !t.deleted)
+                    .map(mcp::task_json)
+                    .ok_or_else(|| format!("No task #{}", id)),
+                _ => continue,
+            };
+            pending.answer(result);
+        }
+        // The assistant has given up on these, so they're no longer asked about
+        while !self.show_tool_call_panel && self.tool_calls.front().is_some_and(PendingCall::expired) {
+            if let Some(pending) = self.tool_calls.pop_front() {
+                pending.answer(Err("Nobody answered the permission prompt in tdui".to_string()));
+            }
+        }
+        // Asking waits until nothing else is being typed or answered
+        if !self.tool_calls.is_empty() && !self.show_tool_call_panel && self.input_mode == InputMode::Normal {
+            self.show_tool_call_panel = true;
+            self.input_mode = InputMode::ToolCallPanel;
+            self.drawn = None;
+        }
+    }
+
+    /// What the assistant asking wants to do, for the popup
+    pub fn tool_call_question(&self) -> Option<String> {
+        self.tool_calls.front().map(|pending| pending.call.describe(self.all_todos()))
+    }
+
+    /// How many more changes are waiting behind the one being asked about
+    pub fn tool_calls_waiting(&self) -> usize {
+        self.tool_calls.len().saturating_sub(1)
+    }
+
+    /// Make the change being asked about, or turn it down
+    pub fn answer_tool_call(&mut self, allow: bool) {
+        self.show_tool_call_panel = false;
+        self.input_mode = InputMode::Normal;
+        let Some(pending) = self.tool_calls.pop_front() else {
+            return;
+        };
+        if !allow {
+            pending.answer(Err("Declined in tdui".to_string()));
+            return;
+        }
+        let result = self.apply_tool_call(&pending.call);
+        if let Err(err) = &result {
+            self.notice = Some(format!("Couldn't do what the assistant asked: {}", err));
+        }
+        pending.answer(result);
+    }
+
+    /// Make an assistant's change, giving back the task as it is afterwards
+    fn apply_tool_call(&mut self, call: &ToolCall) -> Result<serde_json::Value, String> {
+        let id = match call {
+            ToolCall::AddTask(fields) => {
+                let title = fields.title.as_deref().map(str::trim).unwrap_or_default().to_string();
+                if title.is_empty() {
+                    return Err("A task needs a title".to_string());
+                }
+                let id = self.next_todo_id();
+                let mut todo = Todo::new(id, title.clone(), String::new(), None);
+                fields.apply(&mut todo);
+                todo.title = title.clone();
+                todo.context = self.config.active_context.clone();
+                self.todos.push(todo);
+                self.sort_todos();
+                self.persist_todo(id);
+                self.notice = Some(format!("Added \"{}\" for the assistant", title));
+                id
+            }
+            ToolCall::UpdateTask { id, fields } => {
+                let Some(before) = self.all_todos().iter().find(|t| t.id == *id && !t.deleted).cloned() else {
+                    return Err(format!("No task #{}", id));
+                };
+                let mut todo = before.clone();
+                fields.apply(&mut todo);
+                todo.touch();
+                self.record_change(storage::describe_change(Some(&before), &todo));
+                self.storage.upsert(todo);
+                let selected = self.selected_todo_index.and_then(|index| self.todos.get(index)).map(|todo| todo.id);
+                self.reload_todos();
+                self.selected_todo_index = selected.and_then(|id| self.todos.iter().position(|t| t.id == id)).or(self.selected_todo_index);
+                self.refresh_search_index();
+                *id
+            }
+            ToolCall::CompleteTask { id } => {
+                if !self.all_todos().iter().any(|t| t.id == *id && !t.deleted && !t.completed) {
+                    return Err(format!("No open task #{}", id));
+                }
+                self.completing_todo_id = Some(*id);
+                self.mark_task_complete();
+                *id
+            }
+            ToolCall::DeleteTask { id } => {
+                if !self.all_todos().iter().any(|t| t.id == *id && !t.deleted) {
+                    return Err(format!("No task #{}", id));
+                }
+                self.deleting_todo_id = Some(*id);
+                self.mark_task_deleted();
+                *id
+            }
+            // Reads are answered as they come in
+            ToolCall::ListTasks { .. } | ToolCall::GetTask { .. } => return Err("Not a change".to_string()),
+        };
+        Ok(self.all_todos().iter().find(|t| t.id == id).map(mcp::task_json).unwrap_or_default())
+    }
+
     pub fn run(&mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> anyhow::Result<()> {
+        let result = self.event_loop(terminal);
+
+        // Don't lose edits made just before quitting (or before an error)
+        self.sync_vault();
+        self.flush_changes(true);
+        if result.is_ok() && self.summary_on_exit {
+            self.show_exit_summary(terminal)?;
+        }
+        result
+    }
+
+    /// Show what changed this session until any key is pressed
+    fn show_exit_summary(&mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> anyhow::Result<()> {
+        self.exit_summary = Some(self.session_start.summary(self.all_todos(), self.clock.today()));
+        terminal.draw(|frame| crate::ui::render(frame, self))?;
+        loop {
+            match crate::event::read_event(self.poll_interval())? {
+                Some(Event::Key(_)) => return Ok(()),
+                Some(Event::Resize(..)) => {
+                    terminal.draw(|frame| crate::ui::render(frame, self))?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// How long to wait for an event before checking on reminders, saves and the rest again: longer
+    /// in low-power mode while the terminal is in the background or nobody's typing
+    fn poll_interval(&self) -> std::time::Duration {
+        let poll = &self.config.poll;
+        let idle = self.last_input.elapsed().as_secs() >= poll.idle_secs;
+        let interval = if poll.low_power && (!self.focused || idle) { poll.low_power_interval_ms } else { poll.interval_ms };
+        // At least a millisecond, so a 0 doesn't spin
+        std::time::Duration::from_millis(interval.max(1))
+    }
+
+    fn view_key(&self) -> ViewKey {
+        ViewKey {
+            notice: self.notice.clone(),
+            revision: self.storage.revision(),
+            habits_revision: self.habit_storage.revision(),
+            calendar_revision: self.calendar_feeds.revision(),
+            ticks: self.focused.then(|| {
+                (self.clock.now().timestamp() / 60, self.focus.as_ref().map(|session| session.remaining().num_seconds()))
+            }),
+            save_status: self.save_status(),
+        }
+    }
+
+    fn event_loop(&mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> anyhow::Result<()> {
         loop {
-            // Render the UI
-            terminal.draw(|frame| crate::ui::render(frame, self))?;
+            self.check_day_boundary();
+            self.check_reminders();
+            self.check_title_fetches();
+            self.check_slack_posts();
+            self.check_notification_sends();
+            self.check_tool_calls();
+            let interval = self.config.vault.interval_secs;
+            if interval > 0 && self.vault_synced_at.elapsed().as_secs() >= interval {
+                self.sync_vault();
+            }
+            if self.drop_folder_checked_at.elapsed().as_secs() >= self.config.drop_folder.interval_secs.max(1) {
+                self.import_dropped_files();
+            }
+            // Not every terminal reports focus, so changes saved elsewhere are looked for as time passes too
+            if self.input_mode == InputMode::Normal && self.files_checked_at.elapsed().as_secs() >= FILES_CHECK_SECS {
+                self.check_task_files();
+            }
+            if let Some(error) = self.calendar_feeds.poll(&self.clock).pop() {
+                match &mut self.focus {
+                    Some(session) => session.held_back.push(error),
+                    None => self.notice = Some(error),
+                }
+            }
+            if self.focus.as_ref().is_some_and(|session| session.remaining().is_zero()) {
+                self.end_focus_session();
+            }
+            if self.selected_tab == Tab::Stats {
+                self.refresh_stats();
+            }
+            self.publish_dashboard();
+
+            // Render the UI, unless it would look just like the last frame
+            let view = self.view_key();
+            if self.drawn.as_ref() != Some(&view) {
+                terminal.draw(|frame| crate::ui::render(frame, self))?;
+                self.drawn = Some(view);
+            }
 
             // Handle events
-            if event::poll(std::time::Duration::from_millis(100))? {
-                if let Event::Key(key) = event::read()? {
+            match crate::event::read_event(self.poll_interval())? {
+                Some(Event::Key(key)) => {
+                    self.last_input = Instant::now();
                     self.handle_key_event(key);
+                    self.sync_list_selection();
+                    self.drawn = None;
                 }
+                // The pointer moving on its own changes nothing
+                Some(Event::Mouse(mouse)) if mouse.kind == MouseEventKind::Moved => {}
+                Some(Event::Mouse(mouse)) if self.config.mouse => {
+                    self.last_input = Instant::now();
+                    self.handle_mouse_event(mouse);
+                    self.sync_list_selection();
+                    self.drawn = None;
+                }
+                Some(Event::FocusGained) => {
+                    self.focused = true;
+                    self.on_focus_gained();
+                    self.drawn = None;
+                }
+                Some(Event::FocusLost) => self.focused = false,
+                // A resize, or anything else the terminal sends
+                Some(_) => self.drawn = None,
+                None => {}
             }
 
             if self.should_quit {
                 break;
             }
+            self.flush_changes(false);
         }
         Ok(())
     }
 
     fn handle_key_event(&mut self, key: crossterm::event::KeyEvent) {
+        // Any key acknowledges the current notice, or the keybinding problems shown at startup
+        self.notice = None;
+        if self.show_keymap_problems {
+            self.show_keymap_problems = false;
+            return;
+        }
+
+        let in_task_editor = matches!(self.input_mode,
+            InputMode::EditingTitle | InputMode::EditingDescription | InputMode::EditingDate | InputMode::EditingTime
+            | InputMode::EditingContext | InputMode::EditingReminders | InputMode::EditingRecurrence | InputMode::EditingEstimate);
+        if in_task_editor && let Some(step) = editor_resize_step(key) {
+            self.resize_task_editor(step);
+            return;
+        }
+
         match self.input_mode {
             InputMode::Normal => {
+                // Custom keys stand in for the defaults the handlers below know
+                let Some(key) = self.keymap.translate(key) else {
+                    return;
+                };
+                if self.selected_tab == Tab::Habits && self.handle_habit_key(key) {
+                    return;
+                }
+                if self.selected_tab == Tab::Board && self.handle_board_key(key) {
+                    return;
+                }
+                if self.selected_tab == Tab::Done && self.handle_done_key(key) {
+                    return;
+                }
+                if matches!(self.selected_tab, Tab::Month | Tab::Year) && self.handle_day_key(key) {
+                    return;
+                }
+                if self.focused_panel == Panel::List && self.selected_tab.shows_tasks() && self.handle_jump_key(key) {
+                    return;
+                }
+                if let Some(usage) = &mut self.usage
+                    && let Some(action) = Keymap::action_of(&key)
+                {
+                    usage.record(action, self.clock.today());
+                }
                 match key.code {
                     KeyCode::Char('q') => self.should_quit = true,
+                    KeyCode::Char('S') => self.sync_git(),
+                    KeyCode::Char('D') => self.open_empty_trash_panel(),
+                    KeyCode::Char('u') if self.selected_tab == Tab::Stats && self.usage.is_some() => {
+                        self.show_usage = !self.show_usage;
+                        self.show_estimates = false;
+                    }
+                    KeyCode::Char('E') if self.selected_tab == Tab::Stats => {
+                        self.show_estimates = !self.show_estimates;
+                        self.show_usage = false;
+                    }
+                    KeyCode::Char('a') if self.selected_tab == Tab::Stats => {
+                        self.stats_all_sources = !self.stats_all_sources;
+                        self.refresh_stats();
+                    }
                     KeyCode::Char('+') => self.open_new_task_panel(),
+                    KeyCode::Char('/') => self.open_search_panel(),
+                    KeyCode::Char('c') => self.open_context_panel(),
+                    KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.close_current_workspace();
+                    }
+                    KeyCode::Char('w') => self.open_workspace_panel(),
+                    KeyCode::Char('p') if self.selected_tab.shows_tasks() => self.open_plan_panel(),
+                    KeyCode::Char('z') if self.selected_tab.shows_tasks() => self.toggle_zoom(),
+                    KeyCode::Char('v') if self.selected_tab.shows_tasks() => self.toggle_split_view(),
+                    KeyCode::Char('f') if self.focused_panel == Panel::List && self.split_view.is_some() => {
+                        self.cycle_split_filter();
+                    }
+                    KeyCode::Char('m') if self.focused_panel == Panel::List && self.split_view.is_some() => {
+                        self.move_selected_to_other_pane();
+                    }
+                    // In the tree, Tab and Shift+Tab move the selected task a level down or up
+                    KeyCode::Tab if self.editing_tree() => self.demote_selected(),
+                    KeyCode::BackTab if self.editing_tree() => self.promote_selected(),
                     KeyCode::Tab => self.next_panel(),
+                    // With tasks marked, Esc clears the marks instead of quitting
+                    KeyCode::Esc if !self.marked.is_empty() => self.marked.clear(),
+                    // ...and in the tree, it goes back to the flat list
+                    KeyCode::Esc if self.config.tree_view && self.selected_tab.shows_tasks() => self.toggle_tree_view(),
                     KeyCode::Esc => self.should_quit = true,
+                    KeyCode::Char(' ') if self.focused_panel == Panel::List && self.selected_tab.shows_tasks() => {
+                        self.toggle_mark_selected();
+                    }
+                    KeyCode::Char('B') if self.focused_panel == Panel::List && self.selected_tab.shows_tasks() => {
+                        self.open_batch_panel();
+                    }
+                    KeyCode::Char('T') if self.focused_panel == Panel::List && self.selected_tab.shows_tasks() => {
+                        self.open_transfer_panel();
+                    }
                     KeyCode::Left => {
                         if key.modifiers.contains(KeyModifiers::SHIFT) {
                             self.previous_tab();
                         } else if self.focused_panel == Panel::Calendar {
                             self.select_previous_day();
+                        } else if self.focused_panel == Panel::Task {
+                            self.step_selected_link(false);
+                        } else if self.editing_tree() {
+                            self.fold_selected(true);
                         }
                     }
                     KeyCode::Right => {
@@ -540,6 +4857,10 @@ impl App {
                             self.next_tab();
                         } else if self.focused_panel == Panel::Calendar {
                             self.select_next_day();
+                        } else if self.focused_panel == Panel::Task {
+                            self.step_selected_link(true);
+                        } else if self.editing_tree() {
+                            self.fold_selected(false);
                         }
                     }
                     KeyCode::Up => {
@@ -560,131 +4881,547 @@ impl App {
                             self.scroll_description_down();
                         }
                     }
-                    KeyCode::Enter => {
-                        if self.focused_panel == Panel::List && self.selected_todo_index.is_some() {
-                            self.open_edit_task_panel();
-                        } else if self.focused_panel == Panel::Calendar {
-                            self.open_new_task_panel_with_date(self.selected_calendar_date);
-                        }
+                    KeyCode::Enter => {
+                        if self.focused_panel == Panel::List && self.done_today_selected {
+                            self.toggle_done_today();
+                        } else if self.focused_panel == Panel::List && self.selected_todo_index.is_some() {
+                            self.open_edit_task_panel();
+                        } else if self.focused_panel == Panel::Calendar {
+                            self.open_new_task_panel_with_date(self.selected_calendar_date);
+                        } else if self.focused_panel == Panel::Task {
+                            self.follow_selected_link();
+                        }
+                    }
+                    KeyCode::Char('d') if self.focused_panel == Panel::List && self.selected_todo_index.is_some() => {
+                        self.open_done_panel();
+                    }
+                    KeyCode::Char('j') if self.selected_tab.shows_tasks() => self.open_jump_hints(),
+                    KeyCode::Char('o') if self.focused_panel == Panel::List => self.open_inline_task(),
+                    KeyCode::Char('W') if self.focused_panel == Panel::List => self.open_waiting_panel(),
+                    KeyCode::Char('C') if self.focused_panel == Panel::List => self.open_project_panel(),
+                    KeyCode::Char('P') if self.focused_panel == Panel::List => self.ping_selected(),
+                    KeyCode::Char('M') if self.focused_panel == Panel::List => self.toggle_someday_selected(),
+                    KeyCode::Char('A') if self.focused_panel == Panel::List => self.open_new_subtask_panel(),
+                    KeyCode::Char('e') if self.focused_panel == Panel::Task => self.start_field_edit(DetailField::Title),
+                    KeyCode::Char('U') if self.selected_tab.shows_tasks() => self.open_add_url(),
+                    KeyCode::Char('H') if self.selected_tab.shows_tasks() && self.selected_todo_index.is_some() => self.open_history_panel(),
+                    KeyCode::Char('Y') if self.selected_tab.shows_tasks() => self.post_selected_to_slack(),
+                    KeyCode::Char('y') if key.modifiers.contains(KeyModifiers::ALT) && self.selected_tab.shows_tasks() => self.post_plan_to_slack(),
+                    KeyCode::Char('s') if self.focused_panel == Panel::List => {
+                        self.step_selected_status(!key.modifiers.contains(KeyModifiers::ALT));
+                    }
+                    KeyCode::Char('-') if self.focused_panel == Panel::List && self.selected_todo_index.is_some() => {
+                        self.open_delete_panel();
+                    }
+                    KeyCode::Char('g') if self.selected_tab.shows_tasks() => self.toggle_grouping(),
+                    KeyCode::Char('i') if self.selected_tab.shows_tasks() => self.toggle_tree_view(),
+                    KeyCode::Char('O') if self.selected_tab.shows_tasks() => self.toggle_sort(),
+                    KeyCode::Char('x') if self.focused_panel == Panel::List && self.config.group_by_due => {
+                        self.collapse_selected_group();
+                    }
+                    KeyCode::Char('X') if self.config.group_by_due => self.expand_all_groups(),
+                    KeyCode::Char('>') if self.focused_panel == Panel::List => self.reschedule_selected(1),
+                    KeyCode::Char('<') if self.focused_panel == Panel::List => self.reschedule_selected(-1),
+                    KeyCode::Char('F') if self.focused_panel == Panel::List && self.selected_tab.shows_tasks() => {
+                        self.start_focus_session();
+                    }
+                    KeyCode::Char('t') if self.focused_panel == Panel::Calendar => {
+                        self.reset_calendar_to_today();
+                    }
+                    KeyCode::Char('b') if self.focused_panel == Panel::Calendar && self.selected_tab.shows_tasks() => {
+                        self.open_blackout_panel();
+                    }
+                    KeyCode::Char('n') if self.focused_panel == Panel::Calendar && self.selected_tab.shows_tasks() => {
+                        self.open_note_panel();
+                    }
+                    _ => {}
+                }
+            }
+            InputMode::EditingTitle => {
+                match key.code {
+                    KeyCode::Char(c) => {
+                        self.new_task_title.push(c);
+                    }
+                    KeyCode::Backspace => {
+                        self.new_task_title.pop();
+                    }
+                    KeyCode::Tab => {
+                        // Switch to description input
+                        self.input_mode = InputMode::EditingDescription;
+                    }
+                    KeyCode::Enter => {
+                        // Save the task
+                        self.save_new_task();
+                    }
+                    KeyCode::Esc => {
+                        self.cancel_new_task_panel();
+                    }
+                    _ => {}
+                }
+            }
+            InputMode::EditingDescription => {
+                if edit_multiline(&mut self.new_task_description, &mut self.edit_description_scroll, self.editor_size.get(), key) {
+                    return;
+                }
+                match key.code {
+                    KeyCode::Tab => {
+                        // Switch to date input
+                        self.input_mode = InputMode::EditingDate;
+                    }
+                    KeyCode::Enter => {
+                        // Save the task
+                        self.save_new_task();
+                    }
+                    KeyCode::Esc => {
+                        self.cancel_new_task_panel();
+                    }
+                    _ => {}
+                }
+            }
+            InputMode::EditingDate => {
+                match key.code {
+                    KeyCode::Char(c) if c.is_ascii_digit() || c == '-' => {
+                        self.date_input_buffer.push(c);
+                    }
+                    KeyCode::Backspace => {
+                        self.date_input_buffer.pop();
+                    }
+                    KeyCode::Tab => {
+                        // Keep the typed date and switch to time input
+                        if let Ok(date) = NaiveDate::parse_from_str(&self.date_input_buffer, "%Y-%m-%d") {
+                            self.new_task_due_date = Some(date);
+                        }
+                        self.input_mode = InputMode::EditingTime;
+                    }
+                    KeyCode::Enter => {
+                        // Try to parse the date
+                        if let Ok(date) = NaiveDate::parse_from_str(&self.date_input_buffer, "%Y-%m-%d") {
+                            self.new_task_due_date = Some(date);
+                        }
+                        // Save the task
+                        self.save_new_task();
+                    }
+                    KeyCode::Esc => {
+                        self.cancel_new_task_panel();
+                    }
+                    _ => {}
+                }
+            }
+            InputMode::EditingTime => {
+                match key.code {
+                    KeyCode::Char(c) if c.is_ascii_digit() || c == ':' => {
+                        self.new_task_time.push(c);
+                    }
+                    KeyCode::Backspace => {
+                        self.new_task_time.pop();
+                    }
+                    KeyCode::Tab => {
+                        // Switch to context input
+                        self.input_mode = InputMode::EditingContext;
+                    }
+                    KeyCode::Enter => {
+                        // Save the task
+                        self.save_new_task();
+                    }
+                    KeyCode::Esc => {
+                        self.cancel_new_task_panel();
+                    }
+                    _ => {}
+                }
+            }
+            InputMode::EditingContext => {
+                match key.code {
+                    KeyCode::Char(c) if !c.is_whitespace() => {
+                        self.new_task_context.push(c);
+                    }
+                    KeyCode::Backspace => {
+                        self.new_task_context.pop();
                     }
-                    KeyCode::Char('d') => {
-                        if self.focused_panel == Panel::List && self.selected_todo_index.is_some() {
-                            self.open_done_panel();
-                        }
+                    KeyCode::Tab => {
+                        // Switch to reminders input
+                        self.input_mode = InputMode::EditingReminders;
                     }
-                    KeyCode::Char('-') => {
-                        if self.focused_panel == Panel::List && self.selected_todo_index.is_some() {
-                            self.open_delete_panel();
-                        }
+                    KeyCode::Enter => {
+                        // Save the task
+                        self.save_new_task();
                     }
-                    KeyCode::Char('t') => {
-                        if self.focused_panel == Panel::Calendar {
-                            self.reset_calendar_to_today();
-                        }
+                    KeyCode::Esc => {
+                        self.cancel_new_task_panel();
                     }
                     _ => {}
                 }
             }
-            InputMode::EditingTitle => {
+            InputMode::EditingReminders => {
                 match key.code {
                     KeyCode::Char(c) => {
-                        self.new_task_title.push(c);
+                        self.new_task_reminders.push(c);
                     }
                     KeyCode::Backspace => {
-                        self.new_task_title.pop();
+                        self.new_task_reminders.pop();
                     }
                     KeyCode::Tab => {
-                        // Switch to description input
-                        self.input_mode = InputMode::EditingDescription;
+                        // Switch to repeat input
+                        self.input_mode = InputMode::EditingRecurrence;
                     }
                     KeyCode::Enter => {
                         // Save the task
                         self.save_new_task();
                     }
                     KeyCode::Esc => {
-                        self.close_new_task_panel();
+                        self.cancel_new_task_panel();
                     }
                     _ => {}
                 }
             }
-            InputMode::EditingDescription => {
+            InputMode::EditingRecurrence => {
                 match key.code {
                     KeyCode::Char(c) => {
-                        if key.modifiers.contains(KeyModifiers::CONTROL) {
-                            match c {
-                                'u' => {
-                                    // Ctrl+U: Scroll description view up
-                                    self.scroll_edit_description_up();
-                                }
-                                'd' => {
-                                    // Ctrl+D: Scroll description view down
-                                    self.scroll_edit_description_down();
-                                }
-                                _ => {
-                                    self.new_task_description.push(c);
-                                    self.auto_scroll_to_cursor();
-                                }
-                            }
-                        } else {
-                            self.new_task_description.push(c);
-                            self.auto_scroll_to_cursor();
-                        }
+                        self.new_task_recurrence.push(c);
                     }
                     KeyCode::Backspace => {
-                        self.new_task_description.pop();
-                        self.auto_scroll_to_cursor();
-                    }
-                    KeyCode::PageUp => {
-                        // PageUp: Scroll description view up
-                        self.scroll_edit_description_up();
-                    }
-                    KeyCode::PageDown => {
-                        // PageDown: Scroll description view down
-                        self.scroll_edit_description_down();
+                        self.new_task_recurrence.pop();
                     }
                     KeyCode::Tab => {
-                        // Switch to date input
-                        self.input_mode = InputMode::EditingDate;
+                        // Switch to estimate input
+                        self.input_mode = InputMode::EditingEstimate;
                     }
                     KeyCode::Enter => {
-                        if key.modifiers.contains(KeyModifiers::ALT) {
-                            // Alt+Enter: Add newline to description
-                            self.new_task_description.push('\n');
-                            self.auto_scroll_to_cursor();
-                        } else {
-                            // Enter: Save the task
-                            self.save_new_task();
-                        }
+                        // Save the task
+                        self.save_new_task();
                     }
                     KeyCode::Esc => {
-                        self.close_new_task_panel();
+                        self.cancel_new_task_panel();
                     }
                     _ => {}
                 }
             }
-            InputMode::EditingDate => {
+            InputMode::EditingEstimate => {
                 match key.code {
-                    KeyCode::Char(c) if c.is_ascii_digit() || c == '-' => {
-                        self.date_input_buffer.push(c);
+                    KeyCode::Char(c) if c.is_ascii_alphanumeric() || c == ' ' => {
+                        self.new_task_estimate.push(c);
                     }
                     KeyCode::Backspace => {
-                        self.date_input_buffer.pop();
+                        self.new_task_estimate.pop();
                     }
                     KeyCode::Tab => {
                         // Switch back to title input
                         self.input_mode = InputMode::EditingTitle;
                     }
                     KeyCode::Enter => {
-                        // Try to parse the date
-                        if let Ok(date) = NaiveDate::parse_from_str(&self.date_input_buffer, "%Y-%m-%d") {
-                            self.new_task_due_date = Some(date);
-                        }
                         // Save the task
                         self.save_new_task();
                     }
                     KeyCode::Esc => {
-                        self.close_new_task_panel();
+                        self.cancel_new_task_panel();
+                    }
+                    _ => {}
+                }
+            }
+            InputMode::ContextPanel => {
+                match key.code {
+                    KeyCode::Up => {
+                        self.select_previous_context();
+                    }
+                    KeyCode::Down => {
+                        self.select_next_context();
+                    }
+                    KeyCode::Enter => {
+                        self.apply_selected_context();
+                    }
+                    KeyCode::Esc => {
+                        self.close_context_panel();
+                    }
+                    _ => {}
+                }
+            }
+            InputMode::WorkspacePanel => {
+                match key.code {
+                    KeyCode::Up => {
+                        self.select_previous_workspace_choice();
+                    }
+                    KeyCode::Down => {
+                        self.select_next_workspace_choice();
+                    }
+                    KeyCode::Enter => {
+                        self.open_selected_workspace();
+                    }
+                    KeyCode::Esc => {
+                        self.close_workspace_panel();
+                    }
+                    _ => {}
+                }
+            }
+            InputMode::HabitPanel => {
+                match key.code {
+                    KeyCode::Char(c) => self.new_habit_name.push(c),
+                    KeyCode::Backspace => {
+                        self.new_habit_name.pop();
+                    }
+                    KeyCode::Tab => self.new_habit_frequency = self.new_habit_frequency.toggle(),
+                    KeyCode::Enter => self.save_new_habit(),
+                    KeyCode::Esc => self.close_habit_panel(),
+                    _ => {}
+                }
+            }
+            InputMode::JumpHints => {
+                match key.code {
+                    KeyCode::Char(c) if c.is_ascii_alphabetic() => self.type_jump_hint(c),
+                    _ => self.close_jump_hints(),
+                }
+            }
+            InputMode::AddUrl => {
+                match key.code {
+                    KeyCode::Char(c) => self.url_input.push(c),
+                    KeyCode::Backspace => {
+                        self.url_input.pop();
+                    }
+                    KeyCode::Enter => self.save_url_task(),
+                    KeyCode::Esc => self.close_add_url(),
+                    _ => {}
+                }
+            }
+            InputMode::JumpToId => {
+                match key.code {
+                    KeyCode::Char(c) if c.is_ascii_digit() => self.jump_input.push(c),
+                    KeyCode::Backspace => {
+                        self.jump_input.pop();
+                    }
+                    KeyCode::Enter => self.jump_to_id(),
+                    KeyCode::Esc => self.close_jump_to_id(),
+                    _ => {}
+                }
+            }
+            InputMode::InlineTask => {
+                match key.code {
+                    KeyCode::Char(c) => self.inline_task_title.push(c),
+                    KeyCode::Backspace => {
+                        self.inline_task_title.pop();
+                    }
+                    KeyCode::Enter => self.save_inline_task(),
+                    KeyCode::Esc => self.close_inline_task(),
+                    _ => {}
+                }
+            }
+            InputMode::ProjectPanel => {
+                match key.code {
+                    KeyCode::Char(' ') if self.project_field == ProjectField::OwnFile => {
+                        self.project_own_file = !self.project_own_file;
+                    }
+                    KeyCode::Char(c) => {
+                        if let Some(input) = self.project_input() {
+                            input.push(c);
+                        }
+                    }
+                    KeyCode::Backspace => {
+                        if let Some(input) = self.project_input() {
+                            input.pop();
+                        }
+                    }
+                    KeyCode::Tab => self.project_field = self.project_field.next(),
+                    KeyCode::Enter => self.save_project_panel(),
+                    KeyCode::Esc => self.close_project_panel(),
+                    _ => {}
+                }
+            }
+            InputMode::BlackoutPanel => {
+                match key.code {
+                    KeyCode::Char(c) => self.blackout_input().push(c),
+                    KeyCode::Backspace => {
+                        self.blackout_input().pop();
+                    }
+                    KeyCode::Tab => self.blackout_field = self.blackout_field.next(),
+                    KeyCode::Delete => self.remove_blackout(),
+                    KeyCode::Enter => self.save_blackout_panel(),
+                    KeyCode::Esc => self.close_blackout_panel(),
+                    _ => {}
+                }
+            }
+            InputMode::WaitingOn => {
+                match key.code {
+                    KeyCode::Char(c) => self.waiting_on_input.push(c),
+                    KeyCode::Backspace => {
+                        self.waiting_on_input.pop();
+                    }
+                    KeyCode::Enter => self.save_waiting_on(),
+                    KeyCode::Esc => self.close_waiting_panel(),
+                    _ => {}
+                }
+            }
+            InputMode::DiscardPanel => {
+                match key.code {
+                    KeyCode::Tab | KeyCode::Left | KeyCode::Right => {
+                        self.discard_panel_yes_selected = !self.discard_panel_yes_selected;
+                    }
+                    KeyCode::Char(' ') => self.dont_ask_again = !self.dont_ask_again,
+                    KeyCode::Enter => {
+                        if self.discard_panel_yes_selected {
+                            self.apply_dont_ask_again(|confirm| confirm.discard_edits = false);
+                            self.discard_edits();
+                        } else {
+                            self.close_discard_panel();
+                        }
+                    }
+                    KeyCode::Esc => self.close_discard_panel(),
+                    _ => {}
+                }
+            }
+            InputMode::DuplicatePanel => {
+                match key.code {
+                    KeyCode::Tab | KeyCode::Left | KeyCode::Right => {
+                        self.duplicate_panel_yes_selected = !self.duplicate_panel_yes_selected;
+                    }
+                    KeyCode::Char(' ') => self.dont_ask_again = !self.dont_ask_again,
+                    KeyCode::Enter => {
+                        self.apply_dont_ask_again(|confirm| confirm.duplicates = false);
+                        if self.duplicate_panel_yes_selected {
+                            self.open_duplicate();
+                        } else {
+                            self.add_duplicate();
+                        }
+                    }
+                    KeyCode::Esc => self.close_duplicate_panel(),
+                    _ => {}
+                }
+            }
+            InputMode::TransferPanel => {
+                match key.code {
+                    KeyCode::Char(c) => {
+                        self.transfer_input.push(c);
+                        self.selected_transfer_index = 0;
+                    }
+                    KeyCode::Backspace => {
+                        self.transfer_input.pop();
+                        self.selected_transfer_index = 0;
+                    }
+                    KeyCode::Up => self.selected_transfer_index = self.selected_transfer_index.saturating_sub(1),
+                    KeyCode::Down => {
+                        let last = self.transfer_choices().len().saturating_sub(1);
+                        self.selected_transfer_index = (self.selected_transfer_index + 1).min(last);
+                    }
+                    KeyCode::Tab => self.transfer_copy = !self.transfer_copy,
+                    KeyCode::Enter => self.apply_transfer(),
+                    KeyCode::Esc => self.close_transfer_panel(),
+                    _ => {}
+                }
+            }
+            InputMode::BatchEdit => {
+                match key.code {
+                    KeyCode::Char(c) => self.batch_input().push(c),
+                    KeyCode::Backspace => {
+                        self.batch_input().pop();
+                    }
+                    KeyCode::Tab => self.batch_field = self.batch_field.next(),
+                    KeyCode::Enter => self.apply_batch_edit(),
+                    KeyCode::Esc => self.close_batch_panel(),
+                    _ => {}
+                }
+            }
+            InputMode::Recovery => {
+                match key.code {
+                    KeyCode::Enter => self.keep_recovered_tasks(),
+                    // Quitting leaves the damaged files as they are, to be fixed by hand
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        self.summary_on_exit = false;
+                        self.should_quit = true;
+                    }
+                    _ => {}
+                }
+            }
+            InputMode::AttentionPanel => {
+                match key.code {
+                    KeyCode::Up => self.select_previous_attention_item(),
+                    KeyCode::Down => self.select_next_attention_item(),
+                    KeyCode::Char('d') => self.resolve_attention_item(true),
+                    KeyCode::Char('r') => self.resolve_attention_item(false),
+                    KeyCode::Enter => self.open_attention_item(),
+                    KeyCode::Esc => self.close_attention_panel(),
+                    _ => {}
+                }
+            }
+            InputMode::EditingField => {
+                match key.code {
+                    KeyCode::Char(c) => self.field_input.push(c),
+                    KeyCode::Backspace => {
+                        self.field_input.pop();
+                    }
+                    KeyCode::Tab => self.save_field_edit(self.editing_field.map(|field| field.next())),
+                    KeyCode::BackTab => self.save_field_edit(self.editing_field.map(|field| field.previous())),
+                    KeyCode::Enter => self.save_field_edit(None),
+                    KeyCode::Esc => self.cancel_field_edit(),
+                    _ => {}
+                }
+            }
+            InputMode::ToolCallPanel => {
+                match key.code {
+                    KeyCode::Char('y') => self.answer_tool_call(true),
+                    KeyCode::Char('n') | KeyCode::Esc => self.answer_tool_call(false),
+                    _ => {}
+                }
+            }
+            InputMode::EmptyTrashPanel => {
+                match key.code {
+                    KeyCode::Char('y') => self.empty_trash(),
+                    KeyCode::Char('n') | KeyCode::Esc => self.close_empty_trash_panel(),
+                    _ => {}
+                }
+            }
+            InputMode::ParentDonePanel => {
+                match key.code {
+                    KeyCode::Char('y') | KeyCode::Enter => self.answer_parent_done(true, None),
+                    KeyCode::Char('n') | KeyCode::Esc => self.answer_parent_done(false, None),
+                    KeyCode::Char('a') => self.answer_parent_done(true, Some(ParentCompletion::Auto)),
+                    KeyCode::Char('o') => self.answer_parent_done(false, Some(ParentCompletion::Off)),
+                    _ => {}
+                }
+            }
+            InputMode::HistoryPanel => {
+                match key.code {
+                    KeyCode::Up => self.selected_history_index = self.selected_history_index.saturating_sub(1),
+                    KeyCode::Down => {
+                        self.selected_history_index = (self.selected_history_index + 1).min(self.history.len().saturating_sub(1));
                     }
+                    KeyCode::Esc | KeyCode::Char('q') => self.close_history_panel(),
+                    _ => {}
+                }
+            }
+            InputMode::TriagePanel => {
+                match key.code {
+                    KeyCode::Up => self.select_previous_triage_item(),
+                    KeyCode::Down => self.select_next_triage_item(),
+                    KeyCode::Char('k') => self.set_triage_action(TriageAction::Keep),
+                    KeyCode::Char('a') => self.set_triage_action(TriageAction::Activate),
+                    KeyCode::Char('x') | KeyCode::Char('-') => self.set_triage_action(TriageAction::Delete),
+                    KeyCode::Char('r') | KeyCode::Right => self.shift_triage_date(1),
+                    KeyCode::Left => self.shift_triage_date(-1),
+                    KeyCode::Enter => self.apply_triage(),
+                    KeyCode::Esc => self.close_triage_panel(),
+                    _ => {}
+                }
+            }
+            InputMode::Focus => {
+                if matches!(key.code, KeyCode::Esc | KeyCode::Char('q')) {
+                    self.end_focus_session();
+                }
+            }
+            InputMode::EditingNote => {
+                if edit_multiline(&mut self.note_text, &mut self.note_scroll, self.editor_size.get(), key) {
+                    return;
+                }
+                match key.code {
+                    KeyCode::Enter => self.save_note(),
+                    KeyCode::Esc => self.cancel_note_panel(),
+                    _ => {}
+                }
+            }
+            InputMode::PlanPanel => {
+                match key.code {
+                    KeyCode::Up => self.select_previous_plan_item(),
+                    KeyCode::Down => self.select_next_plan_item(),
+                    KeyCode::Left => self.shift_planned_task(-1),
+                    KeyCode::Right => self.shift_planned_task(1),
+                    KeyCode::Char(' ') => self.toggle_planned_task(),
+                    KeyCode::Enter => self.apply_plan(),
+                    KeyCode::Esc => self.close_plan_panel(),
                     _ => {}
                 }
             }
@@ -693,8 +5430,10 @@ impl App {
                     KeyCode::Tab | KeyCode::Left | KeyCode::Right => {
                         self.toggle_done_button();
                     }
+                    KeyCode::Char(' ') => self.dont_ask_again = !self.dont_ask_again,
                     KeyCode::Enter => {
                         if self.done_panel_yes_selected {
+                            self.apply_dont_ask_again(|confirm| confirm.done = false);
                             self.mark_task_complete();
                         } else {
                             self.close_done_panel();
@@ -706,13 +5445,44 @@ impl App {
                     _ => {}
                 }
             }
+            InputMode::Search => {
+                match key.code {
+                    KeyCode::Char(c) => {
+                        self.search_query.push(c);
+                        self.update_search_results();
+                    }
+                    KeyCode::Backspace => {
+                        self.search_query.pop();
+                        self.update_search_results();
+                    }
+                    KeyCode::Up => {
+                        self.select_previous_search_result();
+                    }
+                    KeyCode::Down => {
+                        self.select_next_search_result();
+                    }
+                    KeyCode::Enter => {
+                        self.open_selected_search_result();
+                    }
+                    KeyCode::Esc => {
+                        self.close_search_panel();
+                    }
+                    _ => {}
+                }
+            }
             InputMode::DeletePanel => {
                 match key.code {
                     KeyCode::Tab | KeyCode::Left | KeyCode::Right => {
                         self.toggle_delete_button();
                     }
+                    KeyCode::Char(' ') => self.dont_ask_again = !self.dont_ask_again,
                     KeyCode::Enter => {
                         if self.delete_panel_yes_selected {
+                            self.apply_dont_ask_again(|confirm| confirm.delete = false);
+                        }
+                        if self.delete_panel_yes_selected && self.deleting_habit_id.is_some() {
+                            self.delete_habit();
+                        } else if self.delete_panel_yes_selected {
                             self.mark_task_deleted();
                         } else {
                             self.close_delete_panel();
@@ -727,3 +5497,153 @@ impl App {
         }
     }
 }
+
+/// Whether an open task is overdue by more than `after_days` (0 turns escalation off)
+fn escalated(todo: &Todo, after_days: u32, today: NaiveDate) -> bool {
+    after_days > 0
+        && todo.is_overdue(today)
+        && todo.due_date.is_some_and(|due| (today - due).num_days() > after_days as i64)
+}
+
+/// How much Ctrl++ or Ctrl+- resizes a popup by, in percent. Without the kitty keyboard protocol
+/// most terminals send Ctrl+= as a plain "=" and Ctrl+- as Ctrl+_ (which arrives as Ctrl+7), so
+/// Alt with + or - works too.
+/// `todos` with each task's subtasks right after it (and theirs after them), keeping the order
+/// they're in otherwise; a subtask whose parent isn't among them stays where it is
+fn tree_order(todos: Vec<Todo>) -> Vec<Todo> {
+    let ids: HashSet<usize> = todos.iter().map(|todo| todo.id).collect();
+    let mut children: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut roots = Vec::new();
+    for (i, todo) in todos.iter().enumerate() {
+        match todo.parent.filter(|parent| ids.contains(parent)) {
+            Some(parent) => children.entry(parent).or_default().push(i),
+            None => roots.push(i),
+        }
+    }
+    let mut order = Vec::with_capacity(todos.len());
+    let mut placed = vec![false; todos.len()];
+    let mut stack: Vec<usize> = roots.into_iter().rev().collect();
+    while let Some(i) = stack.pop() {
+        if std::mem::replace(&mut placed[i], true) {
+            continue;
+        }
+        order.push(i);
+        if let Some(subtasks) = children.get(&todos[i].id) {
+            stack.extend(subtasks.iter().rev());
+        }
+    }
+    // Tasks that are each other's parents (from a hand edit) have no way in from the top; keep them
+    order.extend((0..todos.len()).filter(|&i| !placed[i]));
+    let mut todos: Vec<Option<Todo>> = todos.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|i| todos[i].take()).collect()
+}
+
+fn editor_resize_step(key: KeyEvent) -> Option<i16> {
+    const STEP: i16 = 5;
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let alt = key.modifiers.contains(KeyModifiers::ALT);
+    match key.code {
+        KeyCode::Char('+' | '=') if ctrl || alt => Some(STEP),
+        KeyCode::Char('-' | '_') if ctrl || alt => Some(-STEP),
+        KeyCode::Char('7') if ctrl => Some(-STEP),
+        _ => None,
+    }
+}
+
+/// Keys shared by the multi-line editors (task description, daily note): typing, Backspace,
+/// Alt+Enter for a new line and Ctrl+U/D or PgUp/PgDn to scroll. Returns false for any other key.
+fn edit_multiline(text: &mut TextBuffer, scroll: &mut u16, size: (u16, u16), key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            *scroll = scroll.saturating_sub(3);
+        }
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            *scroll = scroll.saturating_add(3).min(max_editor_scroll(text, size));
+        }
+        KeyCode::Char(c) => {
+            text.push(c);
+            scroll_to_end(text, scroll, size);
+        }
+        KeyCode::Backspace => {
+            text.pop();
+            scroll_to_end(text, scroll, size);
+        }
+        KeyCode::PageUp => *scroll = scroll.saturating_sub(3),
+        KeyCode::PageDown => *scroll = scroll.saturating_add(3).min(max_editor_scroll(text, size)),
+        KeyCode::Enter if key.modifiers.contains(KeyModifiers::ALT) => {
+            text.push('\n');
+            scroll_to_end(text, scroll, size);
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// How far a multi-line editor of `size` (width, height) can scroll: to where the row with
+/// the cursor, at the end of `text`, is the last one showing. Counts the rows as wrapped, plus
+/// the label above them
+fn max_editor_scroll(text: &TextBuffer, (width, visible): (u16, u16)) -> u16 {
+    // Before the editor is first drawn its size isn't known; the editors get at least 10 rows
+    let visible = if visible == 0 { 10 } else { visible };
+    let (cursor_row, _) = text.cursor(width);
+    (1 + cursor_row as u16 + 1).saturating_sub(visible)
+}
+
+/// Scroll a multi-line editor so the cursor at the end of `text` stays visible
+fn scroll_to_end(text: &TextBuffer, scroll: &mut u16, size: (u16, u16)) {
+    *scroll = max_editor_scroll(text, size);
+}
+
+/// The calendar's centre month after moving the selection to `selected`: shifted by one month
+/// if `selected` fell outside the three visible months, otherwise `current_date` unchanged
+pub fn calendar_view_for(current_date: NaiveDate, selected: NaiveDate) -> NaiveDate {
+    let current_year = current_date.year();
+    let current_month = current_date.month();
+
+    let selected_year = selected.year();
+    let selected_month = selected.month();
+
+    // Calculate the first month of the visible range (previous month)
+    let (prev_year, prev_month) = if current_month == 1 {
+        (current_year - 1, 12)
+    } else {
+        (current_year, current_month - 1)
+    };
+
+    // Calculate the last month of the visible range (next month)
+    let (next_year, next_month) = if current_month == 12 {
+        (current_year + 1, 1)
+    } else {
+        (current_year, current_month + 1)
+    };
+
+    // Check if selected date is before the visible range
+    if selected_year < prev_year || (selected_year == prev_year && selected_month < prev_month) {
+        // Shift view backward by one month
+        if current_month == 1 {
+            NaiveDate::from_ymd_opt(current_year - 1, 12, 1).unwrap()
+        } else {
+            NaiveDate::from_ymd_opt(current_year, current_month - 1, 1).unwrap()
+        }
+    }
+    // Check if selected date is after the visible range
+    else if selected_year > next_year || (selected_year == next_year && selected_month > next_month) {
+        // Shift view forward by one month
+        if current_month == 12 {
+            NaiveDate::from_ymd_opt(current_year + 1, 1, 1).unwrap()
+        } else {
+            NaiveDate::from_ymd_opt(current_year, current_month + 1, 1).unwrap()
+        }
+    } else {
+        current_date
+    }
+}
+
+/// The first few titles of `todos`, e.g. "Buy milk, Call Bob and 3 more"
+fn title_summary(todos: &[Todo]) -> String {
+    let titles: Vec<&str> = todos.iter().take(3).map(|todo| todo.title.as_str()).collect();
+    match todos.len().saturating_sub(titles.len()) {
+        0 => titles.join(", "),
+        more => format!("{} and {} more", titles.join(", "), more),
+    }
+}
@@ -0,0 +1,87 @@
+// Batch module - One edit of tags, priority and project applied to every marked task at once
+// Parsed from the batch-edit popup's fields, where a blank field leaves that attribute alone,
+// so the popup can preview each task's changes before anything is written.
+
+use crate::models::{Priority, Todo};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BatchEdit {
+    pub add_tags: Vec<String>,
+    pub remove_tags: Vec<String>,
+    /// None leaves the priority alone, Some(None) clears it
+    pub priority: Option<Option<Priority>>,
+    /// None leaves the project alone, Some(None) clears it
+    pub project: Option<Option<String>>,
+}
+
+impl BatchEdit {
+    /// Read the popup fields: tags as "+urgent -later" (a bare name adds), priority as
+    /// high/medium/low or "none", project as a name or "none"
+    pub fn parse(tags: &str, priority: &str, project: &str) -> anyhow::Result<Self> {
+        let mut edit = BatchEdit::default();
+        for word in tags.split_whitespace() {
+            let (remove, name) = match word.strip_prefix('-') {
+                Some(name) => (true, name),
+                None => (false, word.trim_start_matches('+')),
+            };
+            if let Some(tag) = Todo::normalize_tag(name) {
+                if remove {
+                    edit.remove_tags.push(tag);
+                } else {
+                    edit.add_tags.push(tag);
+                }
+            }
+        }
+
+        edit.priority = match priority.trim() {
+            "" => None,
+            "none" => Some(None),
+            input => Some(Some(Priority::parse(input).ok_or_else(|| {
+                anyhow::anyhow!("unknown priority \"{}\" (use high, medium, low or none)", input)
+            })?)),
+        };
+
+        edit.project = match project.trim() {
+            "" => None,
+            "none" => Some(None),
+            name => Some(Some(name.to_string())),
+        };
+        Ok(edit)
+    }
+
+    pub fn apply(&self, todo: &mut Todo) {
+        todo.tags.retain(|tag| !self.remove_tags.contains(tag));
+        for tag in &self.add_tags {
+            if !todo.tags.contains(tag) {
+                todo.tags.push(tag.clone());
+            }
+        }
+        if let Some(priority) = self.priority {
+            todo.priority = priority;
+        }
+        if let Some(project) = &self.project {
+            todo.project = project.clone();
+        }
+    }
+
+    /// What applying the edit would change on `todo`, as "Tags: a -> a, b" lines; empty when nothing would
+    pub fn changes(&self, todo: &Todo) -> Vec<String> {
+        let mut after = todo.clone();
+        self.apply(&mut after);
+
+        let tags = |todo: &Todo| if todo.tags.is_empty() { "-".to_string() } else { todo.tags.join(", ") };
+        let priority = |todo: &Todo| todo.priority.map_or("-", |p| p.label()).to_string();
+        let project = |todo: &Todo| todo.project.clone().unwrap_or_else(|| "-".to_string());
+
+        let mut changes = Vec::new();
+        let mut compare = |field: &str, before: String, after: String| {
+            if before != after {
+                changes.push(format!("{}: {} -> {}", field, before, after));
+            }
+        };
+        compare("Tags", tags(todo), tags(&after));
+        compare("Priority", priority(todo), priority(&after));
+        compare("Project", project(todo), project(&after));
+        changes
+    }
+}
@@ -0,0 +1,132 @@
+// Board module - The Board tab's grid: a column per open workflow status, split into swimlanes
+// Lanes group tasks by priority, project or tag (set on the board's entry in the "tabs" config).
+// Moving down past the last task of a lane carries on into the next lane of the same column.
+
+use crate::config::{Swimlanes, WorkflowConfig};
+use crate::models::{Priority, Todo};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Board {
+    pub swimlanes: Swimlanes,
+    /// Open workflow statuses, in order
+    pub columns: Vec<String>,
+    /// Only lanes holding at least one task, so every board has at least one lane
+    pub lanes: Vec<Lane>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lane {
+    /// What the lane's tasks share, e.g. "High" or a project; None for the tasks with none of it
+    pub title: Option<String>,
+    /// Indices into the task list, one list per column
+    pub cells: Vec<Vec<usize>>,
+}
+
+/// A way to move the selection across the board
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Step {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Board {
+    /// Lay out `todos` (open tasks, in list order); someday tasks stay off the board
+    pub fn new(todos: &[Todo], workflow: &WorkflowConfig, swimlanes: Swimlanes) -> Self {
+        let columns: Vec<String> = workflow.open_statuses().iter().map(|status| status.name.clone()).collect();
+        let lane_of = |todo: &Todo| -> Option<String> {
+            match swimlanes {
+                Swimlanes::None => None,
+                Swimlanes::Priority => todo.priority.map(|priority| priority.label().to_string()),
+                Swimlanes::Project => todo.project.clone(),
+                Swimlanes::Tag => todo.tags.first().cloned(),
+            }
+        };
+
+        // Lanes in a fixed order: priorities high to low, names alphabetically; the rest go last
+        let mut titles: Vec<Option<String>> = match swimlanes {
+            Swimlanes::None => vec![None],
+            Swimlanes::Priority => [Priority::High, Priority::Medium, Priority::Low].iter()
+                .map(|priority| Some(priority.label().to_string()))
+                .collect(),
+            Swimlanes::Project | Swimlanes::Tag => {
+                let mut names: Vec<String> = todos.iter().filter_map(lane_of).collect();
+                names.sort_by_key(|name| name.to_lowercase());
+                names.dedup();
+                names.into_iter().map(Some).collect()
+            }
+        };
+        if swimlanes != Swimlanes::None {
+            titles.push(None);
+        }
+
+        let mut lanes: Vec<Lane> = titles.into_iter()
+            .map(|title| Lane { title, cells: vec![Vec::new(); columns.len()] })
+            .collect();
+        for (index, todo) in todos.iter().enumerate().filter(|(_, todo)| !todo.someday) {
+            let title = lane_of(todo);
+            if let Some(lane) = lanes.iter_mut().find(|lane| lane.title == title) {
+                lane.cells[workflow.index_of(todo)].push(index);
+            }
+        }
+        lanes.retain(|lane| lane.cells.iter().any(|cell| !cell.is_empty()));
+        if lanes.is_empty() {
+            lanes.push(Lane { title: None, cells: vec![Vec::new(); columns.len()] });
+        }
+        Self { swimlanes, columns, lanes }
+    }
+
+    /// Heading of a lane, None when the board has no swimlanes
+    pub fn lane_title<'a>(&self, lane: &'a Lane) -> Option<&'a str> {
+        let rest = match self.swimlanes {
+            Swimlanes::None => return None,
+            Swimlanes::Priority => "No priority",
+            Swimlanes::Project => "No project",
+            Swimlanes::Tag => "No tag",
+        };
+        Some(lane.title.as_deref().unwrap_or(rest))
+    }
+
+    /// The first task on the board, reading lane by lane and column by column
+    pub fn first(&self) -> Option<usize> {
+        self.lanes.iter().flat_map(|lane| lane.cells.iter().flatten()).next().copied()
+    }
+
+    /// (lane, column, row) of the task at `index`, if it's on the board
+    pub fn position_of(&self, index: usize) -> Option<(usize, usize, usize)> {
+        self.lanes.iter().enumerate().find_map(|(lane_index, lane)| {
+            lane.cells.iter().enumerate().find_map(|(column, cell)| {
+                cell.iter().position(|&i| i == index).map(|row| (lane_index, column, row))
+            })
+        })
+    }
+
+    /// The task one step from the one at `index`; None when there's nowhere to go
+    pub fn step(&self, index: usize, step: Step) -> Option<usize> {
+        let (lane, column, row) = self.position_of(index)?;
+        let cell = |lane: usize, column: usize| &self.lanes[lane].cells[column];
+        match step {
+            Step::Down if row + 1 < cell(lane, column).len() => Some(cell(lane, column)[row + 1]),
+            Step::Down => (lane + 1..self.lanes.len())
+                .find_map(|below| cell(below, column).first().copied()),
+            Step::Up if row > 0 => Some(cell(lane, column)[row - 1]),
+            Step::Up => (0..lane).rev()
+                .find_map(|above| cell(above, column).last().copied()),
+            Step::Left | Step::Right => {
+                // Skip columns with nothing in them, landing in the nearest lane that has a task
+                let columns: Vec<usize> = match step {
+                    Step::Left => (0..column).rev().collect(),
+                    _ => (column + 1..self.columns.len()).collect(),
+                };
+                let target = columns.into_iter().find(|&c| self.lanes.iter().any(|lane| !lane.cells[c].is_empty()))?;
+                let mut nearest: Vec<usize> = (0..self.lanes.len()).collect();
+                nearest.sort_by_key(|&other| other.abs_diff(lane));
+                nearest.into_iter()
+                    .map(|other| cell(other, target))
+                    .find(|cell| !cell.is_empty())
+                    .map(|cell| cell[row.min(cell.len() - 1)])
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+// CLI module - Command-line argument parsing
+
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: tdui [OPTIONS]
+       tdui [OPTIONS] <TASK>...   Add a task and exit, e.g. tdui buy milk tomorrow @home
+       tdui add -                 Add a task read from stdin: first line title, the rest description
+       tdui add-url <URL>         Add a task to read a web page later, titled with the page's title
+       tdui report --html <FILE>  Write a static HTML report (open and overdue tasks, charts, streaks)
+       tdui plan --week           Print this week's tasks by day as a Markdown checklist (--html <FILE> for a printable page)
+       tdui digest --week         Print the week's digest (done, overdue, coming up) as email-ready HTML
+                                  (--html <FILE> writes it to a file, --send mails it as set in config)
+       tdui config export <FILE>  Save all settings (config and project settings, no tasks) to one file
+       tdui config import <FILE>  Load settings saved by export, keeping the old config as config.json.bak
+       tdui vault sync            Sync tasks with the checkboxes in the Obsidian vault set in config
+       tdui slack today           Post today's plan to the Slack webhook set in config
+       tdui auth <SERVICE>        Store a token in the OS keyring instead of config: slack [PROJECT], telegram, matrix or smtp
+       tdui doctor                Check the task files, config, write access, backups and sync, with what to do about problems
+       tdui import <FILE>         Add the tasks in a todo.txt, .ics, .csv or Taskwarrior .json file; tasks imported before are merged, not repeated
+                                  (--dry-run lists what would be created, merged and skipped without saving)
+
+Options:
+  --no-color            Use the monochrome theme (also enabled by the NO_COLOR env var)
+  --no-summary          Quit without the session summary screen
+  --validate            Check the task files and config for problems, without changing them, and exit
+  --force               Add the task even if an open one has nearly the same title
+  --generate-fake <N>   Developer tool: add N synthetic tasks to the todo file and exit
+  -h, --help            Print this help";
+
+#[derive(Debug, Clone, Default)]
+pub struct Args {
+    pub no_color: bool,
+    /// Skip the exit summary, whatever the config says
+    pub no_summary: bool,
+    pub help: bool,
+    pub generate_fake: Option<usize>,
+    /// Report problems in the task files and config (`--validate`)
+    pub validate: bool,
+    /// Words of a task to add without opening the UI
+    pub quick_add: Option<String>,
+    /// Read the task to add from stdin (`tdui add -`)
+    pub add_from_stdin: bool,
+    /// `tdui add-url <URL>`
+    pub add_url: Option<String>,
+    /// Write an HTML report (`tdui report --html <FILE>`)
+    pub report: bool,
+    /// Print a plan of the week (`tdui plan --week`)
+    pub plan_week: bool,
+    /// Print or send the week's digest (`tdui digest --week`)
+    pub digest_week: bool,
+    /// `--week`, only meaningful with `plan` and `digest`
+    pub week: bool,
+    /// `--send`, only meaningful with `digest`
+    pub send: bool,
+    /// Where `report`, `plan` and `digest` write HTML
+    pub html: Option<PathBuf>,
+    /// `tdui config export <FILE>`
+    pub config_export: Option<PathBuf>,
+    /// `tdui config import <FILE>`
+    pub config_import: Option<PathBuf>,
+    /// `tdui vault sync`
+    pub vault_sync: bool,
+    /// `tdui slack today`
+    pub slack_today: bool,
+    /// `tdui auth <SERVICE> [PROJECT]`
+    pub auth: Option<(String, Option<String>)>,
+    /// `tdui doctor`
+    pub doctor: bool,
+    /// `tdui import <FILE>`
+    pub import: Option<PathBuf>,
+    /// `--dry-run`, only meaningful with `import`
+    pub dry_run: bool,
+    /// `--force`, only meaningful when adding a task
+    pub force: bool,
+}
+
+impl Args {
+    pub fn parse() -> anyhow::Result<Self> {
+        Self::parse_from(std::env::args().skip(1))
+    }
+
+    pub fn parse_from(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut parsed = Args::default();
+
+        let mut words: Vec<String> = Vec::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--no-color" => parsed.no_color = true,
+                "--no-summary" => parsed.no_summary = true,
+                "--validate" => parsed.validate = true,
+                "-h" | "--help" => parsed.help = true,
+                "--generate-fake" => {
+                    let count = args.next()
+                        .and_then(|value| value.parse().ok())
+                        .ok_or_else(|| anyhow::anyhow!("--generate-fake needs a task count\n\n{}", USAGE))?;
+                    parsed.generate_fake = Some(count);
+                }
+                "--html" => {
+                    let path = args.next()
+                        .ok_or_else(|| anyhow::anyhow!("--html needs an output file\n\n{}", USAGE))?;
+                    parsed.html = Some(PathBuf::from(path));
+                }
+                "--week" => parsed.week = true,
+                "--send" => parsed.send = true,
+                "--dry-run" => parsed.dry_run = true,
+                "--force" => parsed.force = true,
+                // Everything after "--" is task text, even if it starts with a dash
+                "--" => words.extend(args.by_ref()),
+                other if other.starts_with('-') && other != "-" => anyhow::bail!("unknown argument '{}'\n\n{}", other, USAGE),
+                _ => words.push(arg),
+            }
+        }
+        match words.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+            [] => {}
+            ["report"] => parsed.report = true,
+            ["plan"] => parsed.plan_week = true,
+            ["digest"] => parsed.digest_week = true,
+            ["doctor"] => parsed.doctor = true,
+            ["add", "-"] | ["-"] => parsed.add_from_stdin = true,
+            ["add-url", url] => parsed.add_url = Some(url.to_string()),
+            ["add-url", ..] => anyhow::bail!("use tdui add-url <URL>\n\n{}", USAGE),
+            ["config", "export", path] => parsed.config_export = Some(PathBuf::from(path)),
+            ["config", "import", path] => parsed.config_import = Some(PathBuf::from(path)),
+            ["vault", "sync"] => parsed.vault_sync = true,
+            ["slack", "today"] => parsed.slack_today = true,
+            ["auth", service] => parsed.auth = Some((service.to_string(), None)),
+            ["auth", service, project] => parsed.auth = Some((service.to_string(), Some(project.to_string()))),
+            ["auth", ..] => anyhow::bail!("use tdui auth <SERVICE> [PROJECT]\n\n{}", USAGE),
+            ["import", path] => parsed.import = Some(PathBuf::from(path)),
+            ["config", "export" | "import", ..] => anyhow::bail!("use tdui config export <FILE> or tdui config import <FILE>\n\n{}", USAGE),
+            _ => parsed.quick_add = Some(words.join(" ")),
+        }
+        let html_allowed = parsed.report || parsed.plan_week || parsed.digest_week;
+        if (parsed.report && parsed.html.is_none()) || (parsed.html.is_some() && !html_allowed) {
+            anyhow::bail!("use tdui report --html <FILE>\n\n{}", USAGE);
+        }
+        if parsed.dry_run && parsed.import.is_none() {
+            anyhow::bail!("use tdui import <FILE> --dry-run\n\n{}", USAGE);
+        }
+        if parsed.force && parsed.quick_add.is_none() && !parsed.add_from_stdin {
+            anyhow::bail!("use tdui --force <TASK>... or tdui add - --force\n\n{}", USAGE);
+        }
+        if parsed.digest_week {
+            if !parsed.week || (parsed.send && parsed.html.is_some()) {
+                anyhow::bail!("use tdui digest --week [--html <FILE> | --send]\n\n{}", USAGE);
+            }
+        } else if parsed.plan_week != parsed.week {
+            anyhow::bail!("use tdui plan --week\n\n{}", USAGE);
+        }
+        if parsed.send && !parsed.digest_week {
+            anyhow::bail!("use tdui digest --week --send\n\n{}", USAGE);
+        }
+
+        Ok(parsed)
+    }
+}
@@ -0,0 +1,64 @@
+// Clock module - The one place that decides what "now" and "today" mean
+// Timestamps are stored in UTC; every conversion to a calendar day goes through the
+// clock so stats, coloring and reminders agree on which day a moment belongs to.
+
+use crate::config::Config;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Clock {
+    /// Named zone from the config, or None to follow the system's local time
+    zone: Option<Tz>,
+}
+
+impl Clock {
+    /// Build a clock from an IANA zone name like "Europe/Berlin" (None means system local time)
+    pub fn from_config(zone: Option<&str>) -> anyhow::Result<Self> {
+        let zone = match zone {
+            Some(name) => Some(name.parse::<Tz>()
+                .map_err(|_| anyhow::anyhow!("unknown timezone '{}'", name))?),
+            None => None,
+        };
+        Ok(Self { zone })
+    }
+
+    /// The clock for the configured timezone, or system time with a warning on stderr if it's
+    /// unknown; for the command line tools, which have nowhere else to say so
+    pub fn from_config_or_warn(config: &Config) -> Self {
+        Self::from_config(config.timezone.as_deref()).unwrap_or_else(|err| {
+            eprintln!("Warning: {}, using system time", err);
+            Self::default()
+        })
+    }
+
+    /// Convert a stored UTC timestamp to the clock's timezone
+    pub fn local(&self, at: DateTime<Utc>) -> DateTime<FixedOffset> {
+        match self.zone {
+            Some(zone) => at.with_timezone(&zone).fixed_offset(),
+            None => at.with_timezone(&Local).fixed_offset(),
+        }
+    }
+
+    pub fn now(&self) -> DateTime<FixedOffset> {
+        self.local(Utc::now())
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.now().date_naive()
+    }
+
+    /// The calendar day a stored UTC timestamp falls on in the clock's timezone
+    pub fn date_of(&self, at: DateTime<Utc>) -> NaiveDate {
+        self.local(at).date_naive()
+    }
+
+    /// The moment a wall-clock date and time happen (the earlier one if DST repeats it)
+    pub fn at(&self, date: NaiveDate, time: NaiveTime) -> Option<DateTime<FixedOffset>> {
+        let naive = date.and_time(time);
+        match self.zone {
+            Some(zone) => zone.from_local_datetime(&naive).earliest().map(|at| at.fixed_offset()),
+            None => Local.from_local_datetime(&naive).earliest().map(|at| at.fixed_offset()),
+        }
+    }
+}
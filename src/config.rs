@@ -0,0 +1,712 @@
+// Config module - User settings persisted between sessions
+
+use crate::error::{TduiError, WithPath};
+use crate::models::{ParentCompletion, ReminderOffset, Todo};
+use crate::theme::ThemeKind;
+use chrono::{NaiveDate, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Context (e.g. "@home") that filters every view, or None to show all tasks
+    pub active_context: Option<String>,
+    /// Color theme: "default", "high-contrast", "deuteranopia" or "monochrome"
+    pub theme: ThemeKind,
+    /// Replace Braille charts and big-text digits with plain text that screen readers can read
+    pub screen_reader: bool,
+    /// Force ASCII-only rendering on (true) or off (false); unset auto-detects from the locale
+    pub ascii: Option<bool>,
+    /// Group the task list under Overdue / Today / Tomorrow / This week / Later / No date headers
+    pub group_by_due: bool,
+    /// Show subtasks under their parent task, indented, instead of where their due dates put them
+    pub tree_view: bool,
+    /// Tasks whose subtasks are folded away in the tree, by ID
+    pub tree_folded: BTreeSet<usize>,
+    /// Order of the list: "due" (by due date) or "urgency" (by the score `urgency` weighs up)
+    pub sort: SortMode,
+    pub urgency: UrgencyConfig,
+    /// Your own sections for the grouped list, in order, e.g. "This sprint" for the next 14 days;
+    /// empty uses the headers above
+    pub due_groups: Vec<DueGroup>,
+    pub reminders: ReminderConfig,
+    pub notifications: NotificationConfig,
+    /// IANA timezone (e.g. "Europe/Berlin") used to decide which day a task was created,
+    /// completed or due; unset follows the system's local time
+    pub timezone: Option<String>,
+    /// Move completed and deleted tasks this many days old into monthly archive files at startup;
+    /// unset keeps everything in todos.json
+    pub archive_after_days: Option<u32>,
+    /// Archive tasks closed on earlier days as soon as the day is over (at startup, or at midnight
+    /// while running), so Done today starts empty each morning; overrides `archive_after_days`
+    pub archive_at_day_end: bool,
+    /// Remove deleted tasks for good at startup once they've been in the trash this many days;
+    /// unset keeps them until the trash is emptied (or they're archived)
+    pub purge_deleted_after_days: Option<u32>,
+    /// Read-only .ics feeds whose events are shown on the Calendar panel
+    pub calendars: Vec<CalendarFeed>,
+    pub workdays: WorkdayConfig,
+    pub planning: PlanningConfig,
+    pub focus: FocusConfig,
+    pub aging: AgingConfig,
+    pub triage: TriageConfig,
+    pub escalation: EscalationConfig,
+    pub confirm: ConfirmConfig,
+    pub summary: SummaryConfig,
+    pub git: GitConfig,
+    pub vault: VaultConfig,
+    pub drop_folder: DropFolderConfig,
+    pub slack: SlackConfig,
+    pub mail: MailConfig,
+    pub digest: DigestConfig,
+    pub server: ServerConfig,
+    pub stats: StatsConfig,
+    pub workflow: WorkflowConfig,
+    pub waiting: WaitingConfig,
+    pub subtasks: SubtaskConfig,
+    /// Tasks stamped out by typing "/name" as the title
+    pub templates: Vec<TaskTemplate>,
+    /// The tabs shown, in order; leave one out to hide it
+    pub tabs: Vec<TabConfig>,
+    pub footer: FooterConfig,
+    /// Add a line under the tabs greeting the day: the date, what's due today and overdue, and
+    /// how many days in a row a task has been done
+    pub greeting: bool,
+    /// Custom keys for main screen actions, e.g. "done": "x" or "ping": "none"
+    pub keys: BTreeMap<String, String>,
+    /// Count which actions are used each day in usage.json, for the Usage view on the Stats tab;
+    /// off unless turned on, and never sent anywhere
+    pub usage_metrics: bool,
+    /// Click to select tasks and drag them between board columns or to another place in the list;
+    /// off by default, as it takes over the terminal's own text selection
+    pub mouse: bool,
+    /// Size of the task editor in percent of the terminal, e.g. { "width": 60, "height": 70 }, as
+    /// last set with Ctrl++ and Ctrl+- in it; unset fits the editor to the task being edited
+    pub editor_size: Option<PopupSize>,
+    pub poll: PollConfig,
+    #[serde(skip)]
+    file_path: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReminderConfig {
+    /// Offsets used by tasks that don't set their own, e.g. ["1d", "1h"]
+    pub default_offsets: Vec<ReminderOffset>,
+    /// Time of day a task counts as due when it has a due date but no due time
+    pub due_time: NaiveTime,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// Where reminders go besides the footer, as many as wanted, e.g. [{ "kind": "desktop" }, { "kind": "bell" }]
+    pub backends: Vec<BackendConfig>,
+}
+
+/// One place notifications are sent, chosen by its "kind"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum BackendConfig {
+    /// A desktop notification
+    Desktop,
+    /// Ring the terminal's bell
+    Bell,
+    Webhook(WebhookConfig),
+    Command(CommandConfig),
+    Telegram(TelegramConfig),
+    Matrix(MatrixConfig),
+}
+
+/// POSTs {"title", "message", "text"} as JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+}
+
+/// Runs a program of your own, e.g. ["notify-send", "{title}", "{message}"]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandConfig {
+    /// The program and its arguments, in which "{title}" and "{message}" are filled in; no shell is involved
+    pub command: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegramConfig {
+    /// Token from @BotFather, e.g. "123456:ABC-DEF..."; leave it out to use the one stored by
+    /// `tdui auth telegram`
+    #[serde(default)]
+    pub bot_token: Option<String>,
+    /// The chat the bot writes to; message the bot first, then read the ID from its getUpdates
+    pub chat_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatrixConfig {
+    /// e.g. "https://matrix.org"
+    pub homeserver: String,
+    /// The room's ID (not its alias), e.g. "!abcdef:matrix.org"
+    pub room_id: String,
+    /// Access token of the account that posts, which must have joined the room; leave it out to
+    /// use the one stored by `tdui auth matrix`
+    #[serde(default)]
+    pub access_token: Option<String>,
+}
+
+/// A section of the grouped list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DueGroup {
+    pub name: String,
+    /// Takes tasks due at most this many days from today (0 is today, -1 is overdue) that an
+    /// earlier group hasn't; unset takes every dated task left
+    #[serde(default)]
+    pub until_days: Option<i64>,
+    /// Takes the tasks with no due date instead
+    #[serde(default)]
+    pub undated: bool,
+}
+
+/// Which days count as working days, for calendar shading and rescheduling
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkdayConfig {
+    /// Working weekdays, e.g. ["Mon", "Tue", "Wed", "Thu", "Fri"]
+    pub days: Vec<Weekday>,
+    /// Individual days off, e.g. ["2026-12-25"]
+    pub holidays: Vec<NaiveDate>,
+    /// Names of subscribed calendars whose events are all days off (e.g. a public holiday feed)
+    pub holiday_calendars: Vec<String>,
+    /// Make the reschedule keys (> and <) step over weekends and holidays
+    pub skip_when_rescheduling: bool,
+}
+
+impl Default for WorkdayConfig {
+    fn default() -> Self {
+        Self {
+            days: vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri],
+            holidays: Vec::new(),
+            holiday_calendars: Vec::new(),
+            skip_when_rescheduling: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlanningConfig {
+    /// Most tasks "schedule for me" puts on one working day, counting tasks already due then;
+    /// unused when `daily_hours` is set
+    pub daily_capacity: usize,
+    /// Hours of estimated work a day holds, if a day's load is measured in hours rather than
+    /// counted in tasks (a task without an estimate counts as none)
+    pub daily_hours: Option<f64>,
+}
+
+impl Default for PlanningConfig {
+    fn default() -> Self {
+        Self { daily_capacity: 5, daily_hours: None }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FocusConfig {
+    /// Length of a focus session
+    pub minutes: u32,
+}
+
+impl Default for FocusConfig {
+    fn default() -> Self {
+        Self { minutes: 25 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PollConfig {
+    /// Milliseconds between checks for reminders, finished saves and the like while no key is pressed
+    pub interval_ms: u64,
+    /// Check less often while the terminal is in the background or nothing has been pressed for
+    /// `idle_secs`, to save battery
+    pub low_power: bool,
+    /// Milliseconds between checks in low-power mode
+    pub low_power_interval_ms: u64,
+    pub idle_secs: u64,
+}
+
+impl Default for PollConfig {
+    fn default() -> Self {
+        Self { interval_ms: 100, low_power: false, low_power_interval_ms: 1000, idle_secs: 60 }
+    }
+}
+
+/// How the task list is ordered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortMode {
+    #[default]
+    Due,
+    Urgency,
+}
+
+/// Weights of the urgency score's parts, Taskwarrior's by default; each is scaled by how much of
+/// it a task has
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UrgencyConfig {
+    /// A fifth of it for a due date 14 or more days out, rising to all of it a week overdue
+    pub due: f64,
+    pub priority_high: f64,
+    pub priority_medium: f64,
+    pub priority_low: f64,
+    /// Growing to all of it over a year
+    pub age: f64,
+    /// 0.8 of it for one tag, 0.9 for two, all of it for three or more
+    pub tags: f64,
+    /// For an open subtask, as finishing it unblocks its parent
+    pub blocking: f64,
+    /// For a task with open subtasks, as it waits on them (negative, so they come first)
+    pub blocked: f64,
+}
+
+impl Default for UrgencyConfig {
+    fn default() -> Self {
+        Self {
+            due: 12.0,
+            priority_high: 6.0,
+            priority_medium: 3.9,
+            priority_low: 1.8,
+            age: 2.0,
+            tags: 1.0,
+            blocking: 8.0,
+            blocked: -5.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AgingConfig {
+    /// Show how long a task has been open, e.g. "(14d)", once it's this many days old; 0 turns it off
+    pub after_days: u32,
+}
+
+impl Default for AgingConfig {
+    fn default() -> Self {
+        Self { after_days: 14 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TriageConfig {
+    /// At startup, ask about open tasks nobody has edited or reviewed for this many days; 0 turns it off
+    pub stale_after_days: u32,
+    /// Ask about each someday task again once it's gone this many days without a look; 0 turns it off
+    pub someday_after_days: u32,
+}
+
+impl Default for TriageConfig {
+    fn default() -> Self {
+        Self { stale_after_days: 30, someday_after_days: 7 }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EscalationConfig {
+    /// Tasks overdue by more than this many days are pinned to the top of the list, drawn bold and
+    /// blinking, and listed at every startup until rescheduled or done; 0 turns it off
+    pub overdue_days: u32,
+}
+
+/// Which actions ask for confirmation first; "Don't ask again" in a popup turns its one off here
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfirmConfig {
+    pub done: bool,
+    /// Deleting a task or a habit
+    pub delete: bool,
+    /// Closing the task or note editor with unsaved changes
+    pub discard_edits: bool,
+    /// Adding a task whose title is nearly the same as an open one's
+    pub duplicates: bool,
+}
+
+impl Default for ConfirmConfig {
+    fn default() -> Self {
+        Self { done: true, delete: true, discard_edits: false, duplicates: true }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SummaryConfig {
+    /// On quit, show what was completed, added and rescheduled this session until a key is pressed
+    pub on_exit: bool,
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        Self { on_exit: true }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GitConfig {
+    /// Keep ~/.local/share/tdui as a git repo, committing every saved change
+    pub auto_commit: bool,
+}
+
+/// The statuses a task moves through, first to last; the last one means Done
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkflowConfig {
+    pub statuses: Vec<StatusConfig>,
+}
+
+/// One step of the workflow, e.g. "In Progress"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusConfig {
+    pub name: String,
+    /// Color name ("yellow") or hex ("#ffaa00") for the status label; unset uses the text color
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+impl Default for WorkflowConfig {
+    fn default() -> Self {
+        let status = |name: &str, color: Option<&str>| StatusConfig {
+            name: name.to_string(),
+            color: color.map(str::to_string),
+        };
+        Self {
+            statuses: vec![
+                status("Todo", None),
+                status("In Progress", Some("yellow")),
+                status("Waiting", Some("magenta")),
+                status("Done", Some("green")),
+            ],
+        }
+    }
+}
+
+impl WorkflowConfig {
+    /// Where the task is in the workflow: the last status once done, the first if unset or unknown
+    pub fn index_of(&self, todo: &Todo) -> usize {
+        if todo.completed {
+            return self.statuses.len() - 1;
+        }
+        todo.status()
+            .and_then(|name| self.open_statuses().iter().position(|status| status.name == name))
+            .unwrap_or(0)
+    }
+
+    pub fn status_of(&self, todo: &Todo) -> &StatusConfig {
+        &self.statuses[self.index_of(todo)]
+    }
+
+    /// Every status but the last, which is Done
+    pub fn open_statuses(&self) -> &[StatusConfig] {
+        &self.statuses[..self.statuses.len() - 1]
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VaultConfig {
+    /// Obsidian vault (or any folder of Markdown notes) whose checkbox tasks are synced with
+    /// tdui's; unset turns syncing off
+    pub path: Option<PathBuf>,
+    /// Notes to sync, as globs relative to the vault, e.g. "Projects/**/*.md"
+    pub patterns: Vec<String>,
+    /// While tdui runs, sync this often as well as at startup and quit; 0 syncs only then
+    pub interval_secs: u64,
+}
+
+impl Default for VaultConfig {
+    fn default() -> Self {
+        Self { path: None, patterns: vec!["**/*.md".to_string()], interval_secs: 60 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DropFolderConfig {
+    /// Folder whose text and transcript files (.txt, .md, .srt, .vtt) become tasks, e.g. where a
+    /// voice memo app saves its transcripts; unset turns it off
+    pub path: Option<PathBuf>,
+    /// While tdui runs, look for new files this often as well as at startup
+    pub interval_secs: u64,
+}
+
+impl Default for DropFolderConfig {
+    fn default() -> Self {
+        Self { path: None, interval_secs: 10 }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlackConfig {
+    /// Incoming webhook URL that tasks and the day's plan are posted to; unset uses the one stored
+    /// by `tdui auth slack`
+    pub webhook_url: Option<String>,
+    /// Webhooks for tasks in particular projects, by project name, e.g. "Website": "https://hooks.slack.com/...";
+    /// `tdui auth slack <PROJECT>` keeps one in the keyring instead
+    pub projects: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MailConfig {
+    /// A command the message is piped to instead of using SMTP, e.g. ["sendmail", "-t"]
+    pub sendmail: Vec<String>,
+    /// SMTP server to send through, e.g. "smtp.example.com"
+    pub smtp_host: Option<String>,
+    /// 465 is TLS from the start; on any other port the connection is upgraded with STARTTLS
+    /// when the server offers it
+    pub smtp_port: u16,
+    /// Log in as this user, if the server wants a login
+    pub smtp_username: Option<String>,
+    /// Unset uses the one stored by `tdui auth smtp`
+    pub smtp_password: Option<String>,
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        Self { sendmail: Vec::new(), smtp_host: None, smtp_port: 587, smtp_username: None, smtp_password: None }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DigestConfig {
+    /// Who `tdui digest --week --send` mails the digest to, e.g. "me@example.com"
+    pub to: Option<String>,
+    /// Who it's from, e.g. "tdui <me@example.com>"; unset sends it from `to`
+    pub from: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// Address the read-only web dashboard listens on while tdui is open, e.g. "0.0.0.0:8080" to
+    /// reach it from other devices on the LAN; unset keeps it off
+    pub listen: Option<String>,
+    /// Only answer requests carrying this value as "?token=" or an "Authorization: Bearer" header;
+    /// needed to listen on anything but a loopback address, or with `tools` on
+    pub token: Option<String>,
+    /// Also take task tool calls from AI assistants at /mcp (Model Context Protocol over
+    /// JSON-RPC), asking in the TUI before any change
+    pub tools: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatsConfig {
+    /// Other tdui data folders (e.g. a work one kept apart from this one) merged in when the Stats
+    /// tab shows all sources; they are only read, never written
+    pub sources: Vec<StatsSource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsSource {
+    /// Shown in the Stats tab's legend, e.g. "Work"
+    pub name: String,
+    /// The folder holding its todos.json, e.g. "~/work-tdui"
+    pub path: PathBuf,
+    /// Color of its series, e.g. "magenta" or "#ff8800"
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WaitingConfig {
+    /// Workflow status that means waiting on someone else
+    pub status: String,
+    /// Flag waiting tasks nobody has pinged for this many days; 0 turns it off
+    pub nag_after_days: u32,
+}
+
+impl Default for WaitingConfig {
+    fn default() -> Self {
+        Self { status: "Waiting".to_string(), nag_after_days: 3 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubtaskConfig {
+    /// What finishing a task's last open subtask does to it: "ask", "auto" (mark it done too) or
+    /// "off"; a task can choose for itself. Reopening a subtask reopens the task unless "off".
+    pub complete_parent: ParentCompletion,
+}
+
+impl Default for SubtaskConfig {
+    fn default() -> Self {
+        Self { complete_parent: ParentCompletion::Ask }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FooterConfig {
+    /// What the footer line shows, as text with any of {hints} (the key hints), {counts} (open,
+    /// overdue and due today), {clock}, {sync} (whether changes are saved and committed) and
+    /// {filter} (the context or workspace shown)
+    pub left: String,
+    /// The same, kept to the right-hand end of the line
+    pub right: String,
+}
+
+impl Default for FooterConfig {
+    fn default() -> Self {
+        Self { left: "{hints}".to_string(), right: String::new() }
+    }
+}
+
+/// Width and height of a popup, each in percent of the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PopupSize {
+    pub width: u16,
+    pub height: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TabConfig {
+    /// Which built-in tab: "tasks", "stats", "habits" or "board"
+    pub kind: String,
+    /// Shown instead of the tab's usual name
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Board only: split its status columns into rows by "priority", "project" or "tag"
+    #[serde(default)]
+    pub lanes: Swimlanes,
+}
+
+/// What a board's swimlanes group tasks by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Swimlanes {
+    /// One row holding every task
+    #[default]
+    None,
+    Priority,
+    Project,
+    /// By a task's first tag
+    Tag,
+}
+
+impl TabConfig {
+    pub fn new(kind: &str) -> Self {
+        Self { kind: kind.to_string(), title: None, lanes: Swimlanes::None }
+    }
+
+    pub fn defaults() -> Vec<Self> {
+        vec![Self::new("tasks"), Self::new("stats"), Self::new("habits")]
+    }
+}
+
+/// A reusable task; `{{date}}`-style variables in it are filled in when the task is created
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskTemplate {
+    pub name: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// A subscribed calendar: an http(s)/webcal URL or a local .ics file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarFeed {
+    pub name: String,
+    pub url: String,
+}
+
+impl Default for ReminderConfig {
+    fn default() -> Self {
+        Self {
+            default_offsets: vec![ReminderOffset::from_minutes(24 * 60)],
+            due_time: NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn load(file_path: PathBuf) -> Self {
+        // Missing or unreadable config falls back to defaults
+        Self::read(file_path.clone()).unwrap_or_else(|_| Config::default().filled_in(file_path))
+    }
+
+    /// Like `load`, but a config.json that doesn't parse is an error rather than the defaults
+    pub fn read(file_path: PathBuf) -> Result<Self, TduiError> {
+        // A missing file just means the defaults
+        let config = match fs::read_to_string(&file_path) {
+            Ok(contents) => serde_json::from_str::<Config>(&contents).map_err(|err| TduiError::ConfigInvalid {
+                path: file_path.clone(),
+                reason: err.to_string(),
+            })?,
+            Err(_) => Config::default(),
+        };
+        Ok(config.filled_in(file_path))
+    }
+
+    /// Remember where the config lives and fill in settings that can't be left empty
+    fn filled_in(self, file_path: PathBuf) -> Self {
+        let mut config = self;
+        config.file_path = file_path;
+        // A workflow needs somewhere to start and a Done to end in
+        if config.workflow.statuses.len() < 2 {
+            config.workflow = WorkflowConfig::default();
+        }
+        // A missing or empty list means the usual tabs
+        if config.tabs.is_empty() {
+            config.tabs = TabConfig::defaults();
+        }
+        config
+    }
+
+    pub fn save(&self) -> Result<(), TduiError> {
+        if let Some(parent) = self.file_path.parent() {
+            fs::create_dir_all(parent).with_path(parent)?;
+        }
+
+        let json = serde_json::to_string_pretty(self).with_path(&self.file_path)?;
+        fs::write(&self.file_path, json).with_path(&self.file_path)?;
+
+        Ok(())
+    }
+
+    /// Save to `file_path`, and keep saving there
+    pub fn save_as(&mut self, file_path: PathBuf) -> Result<(), TduiError> {
+        self.file_path = file_path;
+        self.save()
+    }
+
+    pub fn get_default_path() -> PathBuf {
+        // Get home directory
+        let home = std::env::var("HOME")
+            .or_else(|_| std::env::var("USERPROFILE"))
+            .unwrap_or_else(|_| ".".to_string());
+
+        // Return path: ~/.config/tdui/config.json
+        PathBuf::from(home)
+            .join(".config")
+            .join("tdui")
+            .join("config.json")
+    }
+}
+
+/// A path from config with a leading "~" taken as the home directory, the usual way to write one
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), std::env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
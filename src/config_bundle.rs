@@ -0,0 +1,58 @@
+// Config bundle module - One portable file with every setting, for `tdui config export/import`
+// Holds config.json (theme, tabs, workflow, templates and the rest) and the project settings,
+// but no tasks, so it can be shared with teammates or copied to a new machine.
+
+use crate::config::Config;
+use crate::models::Project;
+use crate::storage::ProjectStorage;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Bumped if the bundle layout ever changes incompatibly
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct ConfigBundle {
+    tdui_config_bundle: u32,
+    config: Config,
+    #[serde(default)]
+    projects: Vec<Project>,
+}
+
+/// Write the current config and project settings to `path`
+pub fn export(path: &Path) -> anyhow::Result<()> {
+    let bundle = ConfigBundle {
+        tdui_config_bundle: FORMAT_VERSION,
+        config: Config::load(Config::get_default_path()),
+        projects: ProjectStorage::open(ProjectStorage::get_default_path()).projects().to_vec(),
+    };
+    fs::write(path, serde_json::to_string_pretty(&bundle)?)
+        .map_err(|err| anyhow::anyhow!("couldn't write {}: {}", path.display(), err))
+}
+
+/// Replace the config with the one in `path` (keeping the old file as config.json.bak) and add its
+/// project settings; returns how many projects it had. Nothing changes if the file doesn't read.
+pub fn import(path: &Path) -> anyhow::Result<usize> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| anyhow::anyhow!("couldn't read {}: {}", path.display(), err))?;
+    let bundle: ConfigBundle = serde_json::from_str(&contents)
+        .map_err(|err| anyhow::anyhow!("{} isn't a tdui config export: {}", path.display(), err))?;
+    if bundle.tdui_config_bundle > FORMAT_VERSION {
+        anyhow::bail!("{} was exported by a newer tdui", path.display());
+    }
+
+    let config_path = Config::get_default_path();
+    if config_path.exists() {
+        fs::copy(&config_path, config_path.with_extension("json.bak"))?;
+    }
+    let mut config = bundle.config;
+    config.save_as(config_path)?;
+
+    let mut projects = ProjectStorage::open(ProjectStorage::get_default_path());
+    let count = bundle.projects.len();
+    for project in bundle.projects {
+        projects.upsert(project)?;
+    }
+    Ok(count)
+}
@@ -0,0 +1,154 @@
+// Digest module - The week in review, for a Friday self-report: what got done this week, what's
+// overdue and what's due in the week ahead. Used by `tdui digest --week`, which prints it as an
+// HTML page ready to paste into an email (or writes it with --html), or with --send mails it,
+// with a plain-text copy, to the address set in config.
+
+use crate::clock::Clock;
+use crate::config::Config;
+use crate::mail::{self, Message};
+use crate::models::Todo;
+use crate::report::escape;
+use crate::storage::{Archive, FileStorage};
+use chrono::{Duration, NaiveDate, Weekday};
+use std::fmt::Write;
+
+/// How far ahead "coming up" looks, counting today
+const UPCOMING_DAYS: i64 = 7;
+
+/// A task in the digest, with the day it was done (or is due)
+#[derive(Debug, Clone)]
+pub struct DigestTask {
+    pub date: NaiveDate,
+    pub title: String,
+    pub project: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Digest {
+    pub title: String,
+    /// Done since Monday, in the order they were done
+    pub completed: Vec<DigestTask>,
+    /// Open tasks due before today, oldest first
+    pub overdue: Vec<DigestTask>,
+    /// Open tasks due from today through the next week
+    pub upcoming: Vec<DigestTask>,
+}
+
+impl Digest {
+    /// Read the stored and archived tasks and sum up the week containing today
+    pub fn load() -> anyhow::Result<Self> {
+        let config = Config::load(Config::get_default_path());
+        let clock = Clock::from_config_or_warn(&config);
+        let storage = FileStorage::open(FileStorage::get_default_path());
+        storage.load_todos()?;
+        let today = clock.today();
+        let monday = today.week(Weekday::Mon).first_day();
+        // Tasks done earlier in the week may have been archived already
+        let archived = Archive::open(Archive::get_default_path()).load_range(monday, today);
+
+        let context = config.active_context.as_deref();
+        let todos: Vec<Todo> = storage.todos().iter()
+            .chain(archived.iter().filter(|a| !storage.todos().iter().any(|t| t.id == a.id)))
+            .filter(|t| t.in_context(context) && !t.deleted)
+            .cloned()
+            .collect();
+        Ok(Self::build(&todos, context, &clock))
+    }
+
+    pub fn build(todos: &[Todo], context: Option<&str>, clock: &Clock) -> Self {
+        let today = clock.today();
+        let monday = today.week(Weekday::Mon).first_day();
+        let task = |date: NaiveDate, todo: &Todo| DigestTask { date, title: todo.title.clone(), project: todo.project.clone() };
+
+        let mut done: Vec<&Todo> = todos.iter()
+            .filter(|t| t.completed && t.completed_at.is_some_and(|at| clock.date_of(at) >= monday))
+            .collect();
+        done.sort_by_key(|t| t.completed_at);
+        let mut open: Vec<&Todo> = todos.iter().filter(|t| !t.completed && !t.someday).collect();
+        open.sort_by_key(|t| (t.due_date, t.due_time, t.id));
+        let due_between = |from: NaiveDate, to: NaiveDate| open.iter()
+            .filter_map(move |t| t.due_date.filter(|d| (from..to).contains(d)).map(|d| task(d, t)));
+
+        let title = format!("Week of {}", monday.format("%a %Y-%m-%d"));
+        Self {
+            title: match context {
+                Some(context) => format!("{} ({})", title, context),
+                None => title,
+            },
+            completed: done.iter().filter_map(|t| t.completed_at.map(|at| task(clock.date_of(at), t))).collect(),
+            overdue: due_between(NaiveDate::MIN, today).collect(),
+            upcoming: due_between(today, today + Duration::days(UPCOMING_DAYS)).collect(),
+        }
+    }
+
+    /// "Done 12, overdue 2, coming up 5"
+    pub fn summary(&self) -> String {
+        format!("Done {}, overdue {}, coming up {}", self.completed.len(), self.overdue.len(), self.upcoming.len())
+    }
+
+    fn sections(&self) -> [(&'static str, &'static str, &[DigestTask]); 3] {
+        [
+            ("Done this week", "Nothing done yet this week.", &self.completed),
+            ("Overdue", "Nothing overdue.", &self.overdue),
+            ("Coming up", "Nothing due in the next 7 days.", &self.upcoming),
+        ]
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{}\n{}\n", self.title, self.summary());
+        for (heading, empty, tasks) in self.sections() {
+            let _ = writeln!(text, "\n{}\n", heading);
+            if tasks.is_empty() {
+                let _ = writeln!(text, "{}", empty);
+            }
+            for task in tasks {
+                let project = task.project.as_ref().map(|p| format!(" [{}]", p)).unwrap_or_default();
+                let _ = writeln!(text, "- {}  {}{}", task.date.format("%a %m-%d"), task.title, project);
+            }
+        }
+        text
+    }
+
+    /// The digest as an HTML page; styles are inline, as mail programs drop style sheets
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        let _ = write!(html, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n\
+            <body style=\"font-family: system-ui, sans-serif; max-width: 640px; margin: 1em auto; padding: 0 1em; color: #222;\">\n\
+            <h1 style=\"font-size: 1.4em; margin-bottom: 0.2em;\">{}</h1>\n<p style=\"color: #777; margin-top: 0;\">{}</p>\n",
+            escape(&self.title), escape(&self.title), escape(&self.summary()));
+        for (heading, empty, tasks) in self.sections() {
+            let _ = writeln!(html, "<h2 style=\"font-size: 1.1em; border-bottom: 1px solid #ddd; padding-bottom: 0.2em;\">{}</h2>", heading);
+            if tasks.is_empty() {
+                let _ = writeln!(html, "<p style=\"color: #777;\">{}</p>", empty);
+                continue;
+            }
+            html.push_str("<table style=\"border-collapse: collapse; width: 100%;\">\n");
+            for task in tasks {
+                let project = task.project.as_deref().map(escape).unwrap_or_default();
+                let _ = writeln!(html, "<tr><td style=\"color: #777; padding: 0.15em 1em 0.15em 0; white-space: nowrap;\">{}</td>\
+                    <td style=\"padding: 0.15em 0;\">{}</td><td style=\"color: #777; padding: 0.15em 0 0.15em 1em;\">{}</td></tr>",
+                    task.date.format("%a %m-%d"), escape(&task.title), project);
+            }
+            html.push_str("</table>\n");
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+/// Mail the digest to the address in config; returns what was done
+pub fn send_configured(digest: &Digest) -> anyhow::Result<String> {
+    let config = Config::load(Config::get_default_path());
+    let Some(to) = config.digest.to.clone().filter(|to| !to.is_empty()) else {
+        anyhow::bail!("no address to send the digest to: add \"digest\": {{ \"to\": \"me@example.com\" }} to config");
+    };
+    let message = Message {
+        from: config.digest.from.clone().unwrap_or_else(|| to.clone()),
+        to: to.clone(),
+        subject: format!("tdui digest: {}", digest.title),
+        text: digest.to_text(),
+        html: digest.to_html(),
+    };
+    let how = mail::send(&config.mail, &message, Clock::from_config_or_warn(&config).now())?;
+    Ok(format!("Mailed the digest to {} {}", to, how))
+}
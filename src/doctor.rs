@@ -0,0 +1,268 @@
+// Doctor module - `tdui doctor`: checks what tdui depends on (the task files, config, being able
+// to write, backups and sync) and says what to do about anything wrong. The quick checks only
+// look at files, so they also run at startup, where the first problem goes in the footer.
+
+use crate::app::Tab;
+use crate::clock::Clock;
+use crate::config::{Config, expand_home};
+use crate::keymap::Keymap;
+use crate::storage::{FileStorage, GitRepo};
+use crate::validate;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Ok,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // pad rather than write_str, so "{:<8}" lines them up
+        f.pad(match self {
+            Severity::Ok => "ok",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Finding {
+    /// What was checked, e.g. "Backups"
+    pub area: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    /// What to do about it
+    pub fix: Option<String>,
+}
+
+impl Finding {
+    fn ok(area: &'static str, message: impl Into<String>) -> Self {
+        Self { area, severity: Severity::Ok, message: message.into(), fix: None }
+    }
+
+    fn warning(area: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { area, severity: Severity::Warning, message: message.into(), fix: Some(fix.into()) }
+    }
+
+    fn error(area: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { area, severity: Severity::Error, message: message.into(), fix: Some(fix.into()) }
+    }
+}
+
+/// The order findings are listed in
+const AREAS: &[&str] = &["Task files", "Config", "Write access", "Interrupted saves", "Backups", "Sync", "Vault", "Stats sources"];
+
+/// The folder holding todos.json
+fn data_dir() -> PathBuf {
+    GitRepo::get_default_path()
+}
+
+/// Every check, for `tdui doctor`; contacts the sync remote, so it can take a few seconds
+pub fn run() -> Vec<Finding> {
+    let config = Config::load(Config::get_default_path());
+    let storage = FileStorage::open(FileStorage::get_default_path());
+    let report = validate::run();
+    let mut findings = Vec::new();
+    check_task_files(&storage, &report, &mut findings);
+    check_config(&report, &mut findings);
+    findings.extend(quick(&config, &storage.file_paths()));
+    if !findings.iter().any(|finding| finding.area == "Write access") {
+        findings.push(Finding::ok("Write access", format!("{} can be written", data_dir().display())));
+    }
+    check_backups(&config, &mut findings);
+    check_sync(&config, &mut findings);
+    check_folders(&config, &mut findings);
+    findings.sort_by_key(|finding| AREAS.iter().position(|&area| area == finding.area));
+    findings
+}
+
+/// Problems that only take looking at files to find: none of the task `files` (or the folder)
+/// read-only, no save or git command left unfinished. Only problems are returned
+pub fn quick(config: &Config, files: &[PathBuf]) -> Vec<Finding> {
+    let dir = data_dir();
+    let mut findings = Vec::new();
+    if !dir.exists() {
+        return findings;
+    }
+
+    // A file held read-only (or by another program) can't be saved, and changes to it would be lost
+    let probe = dir.join("doctor.tmp");
+    let unwritable: Vec<PathBuf> = match fs::write(&probe, b"") {
+        Err(_) => vec![dir.clone()],
+        Ok(()) => {
+            let _ = fs::remove_file(&probe);
+            files.iter()
+                .filter(|path| OpenOptions::new().append(true).open(path).is_err())
+                .cloned()
+                .collect()
+        }
+    };
+    for path in unwritable {
+        findings.push(Finding::error(
+            "Write access",
+            format!("{} can't be written, so changes to it won't be saved", path.display()),
+            "Make it writable (chmod u+w), or close whatever program has it open",
+        ));
+    }
+
+    // Saves write a .tmp file and rename it over the real one; one left over means a save died midway
+    for path in leftover_temp_files(&dir) {
+        let saved = path.with_extension("");
+        findings.push(Finding::warning(
+            "Interrupted saves",
+            format!("{} was left by a save that didn't finish", path.display()),
+            format!("Check {} has your latest changes, then delete the .tmp file", saved.display()),
+        ));
+    }
+
+    if config.git.auto_commit && let Some(repo) = GitRepo::existing(dir.clone()) {
+        if let Some(lock) = repo.stale_lock() {
+            findings.push(Finding::error(
+                "Backups",
+                format!("git is locked by {}, so changes aren't being committed", lock.display()),
+                format!("If no git command is running, delete {}", lock.display()),
+            ));
+        }
+        if repo.rebase_in_progress() {
+            findings.push(Finding::error(
+                "Sync",
+                "a sync stopped partway through pulling, so the next one will fail",
+                format!("Finish it with git -C {0} rebase --continue, or undo it with git -C {0} rebase --abort", dir.display()),
+            ));
+        }
+    }
+    findings
+}
+
+fn leftover_temp_files(dir: &Path) -> Vec<PathBuf> {
+    [dir.to_path_buf(), dir.join("projects")].iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.to_string_lossy().ends_with(".json.tmp"))
+        .collect()
+}
+
+fn check_task_files(storage: &FileStorage, report: &validate::Report, findings: &mut Vec<Finding>) {
+    for file in storage.damaged_files() {
+        findings.push(Finding::error(
+            "Task files",
+            format!("{} can't be read: {}", file.path.display(), file.reason),
+            "Open tdui and choose whether to keep what can be recovered (the original is backed up first)",
+        ));
+    }
+    let config_path = Config::get_default_path();
+    let oddities = report.problems.iter().filter(|problem| problem.path != config_path).count();
+    if oddities > 0 {
+        findings.push(Finding::warning(
+            "Task files",
+            format!("{} spot{} a newer tdui (or a hand edit) may have written", oddities, if oddities == 1 { "" } else { "s" }),
+            "tdui --validate lists each one",
+        ));
+    }
+    if storage.damaged_files().is_empty() && oddities == 0 {
+        let files = report.checked.iter().filter(|(path, _)| *path != config_path).count();
+        findings.push(Finding::ok(
+            "Task files",
+            format!("{} file{}, {} tasks", files, if files == 1 { "" } else { "s" }, storage.todos().len()),
+        ));
+    }
+}
+
+fn check_config(report: &validate::Report, findings: &mut Vec<Finding>) {
+    let path = Config::get_default_path();
+    let config = match Config::read(path.clone()) {
+        Ok(config) => config,
+        Err(err) => {
+            findings.push(Finding::error("Config", err.to_string(), format!("Fix {}; tdui uses the defaults until then", path.display())));
+            return;
+        }
+    };
+    let before = findings.len();
+    if let Err(err) = Clock::from_config(config.timezone.as_deref()) {
+        findings.push(Finding::error("Config", err.to_string(), "Use an IANA name such as Europe/Berlin, or remove \"timezone\""));
+    }
+    for tab in config.tabs.iter().filter(|tab| Tab::from_kind(&tab.kind).is_none()) {
+        findings.push(Finding::warning("Config", format!("unknown tab \"{}\"", tab.kind), "See the README for the tab kinds"));
+    }
+    for problem in Keymap::from_config(&config.keys).1 {
+        findings.push(Finding::warning("Config", problem, "Fix the \"keys\" setting"));
+    }
+    for problem in report.problems.iter().filter(|problem| problem.path == path) {
+        let message = problem.to_string();
+        let message = message.strip_prefix(&format!("{}: ", path.display())).unwrap_or(&message).to_string();
+        findings.push(Finding::warning("Config", message, "Remove or correct it"));
+    }
+    if findings.len() == before {
+        findings.push(Finding::ok("Config", format!("{} is valid", path.display())));
+    }
+}
+
+fn check_backups(config: &Config, findings: &mut Vec<Finding>) {
+    if !config.git.auto_commit {
+        findings.push(Finding::warning(
+            "Backups",
+            "git history is off, so there's no earlier copy of your tasks to go back to",
+            "Set \"git\": { \"auto_commit\": true } in config.json",
+        ));
+        return;
+    }
+    match GitRepo::existing(data_dir()) {
+        None => findings.push(Finding::warning("Backups", "git history is on but the repo isn't there yet", "Start tdui once to create it")),
+        Some(repo) => match repo.last_commit() {
+            Some(at) => findings.push(Finding::ok("Backups", format!("last committed {}", at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")))),
+            None => findings.push(Finding::warning("Backups", "nothing has been committed yet", "Change a task in tdui and it's committed on save")),
+        },
+    }
+}
+
+fn check_sync(config: &Config, findings: &mut Vec<Finding>) {
+    let dir = data_dir();
+    let Some(repo) = config.git.auto_commit.then(|| GitRepo::existing(dir.clone())).flatten() else {
+        return;
+    };
+    match repo.remote() {
+        Err(err) => findings.push(Finding::error("Sync", err.to_string(), "Check git works in the data folder")),
+        Ok(None) => findings.push(Finding::warning(
+            "Sync",
+            "no remote is set, so S only commits on this machine",
+            format!("git -C {} remote add origin <URL>, then git -C {} push -u origin HEAD", dir.display(), dir.display()),
+        )),
+        Ok(Some(remote)) => match repo.check_remote(&remote) {
+            Ok(()) => findings.push(Finding::ok("Sync", format!("{} can be reached", remote))),
+            Err(err) => findings.push(Finding::error(
+                "Sync",
+                format!("{} can't be reached: {}", remote, err),
+                "Check the network, the remote's URL (git remote -v) and your git credentials",
+            )),
+        },
+    }
+}
+
+/// Folders config points at outside the data folder
+fn check_folders(config: &Config, findings: &mut Vec<Finding>) {
+    if let Some(vault) = &config.vault.path
+        && !expand_home(vault).is_dir()
+    {
+        findings.push(Finding::error("Vault", format!("{} isn't a folder", vault.display()), "Fix \"vault\": { \"path\" } in config.json"));
+    }
+    if let Some(folder) = &config.drop_folder.path
+        && !expand_home(folder).is_dir()
+    {
+        findings.push(Finding::error("Drop folder", format!("{} isn't a folder", folder.display()), "Fix \"drop_folder\": { \"path\" } in config.json"));
+    }
+    for source in &config.stats.sources {
+        if !expand_home(&source.path).is_dir() {
+            findings.push(Finding::warning(
+                "Stats sources",
+                format!("{} ({}) isn't a folder", source.name, source.path.display()),
+                "Fix its path under \"stats\": { \"sources\" }, or remove it",
+            ));
+        }
+    }
+}
@@ -0,0 +1,128 @@
+// Drop folder module - Tasks from text files dropped into a folder, for voice capture apps that
+// write each memo's transcript to disk. Each file becomes one task: its name (without the
+// extension) is the title and its text the description. Subtitle transcripts (.srt, .vtt) lose
+// their cue numbers and timings. A file is taken once it has sat unchanged for a moment, so one
+// still being written isn't cut short, and is moved into "imported" inside the folder so it's
+// only taken once. Other files, such as the recordings themselves, are left alone.
+
+use crate::config::{DropFolderConfig, expand_home};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Extensions of the files taken, in lowercase
+const EXTENSIONS: &[&str] = &["txt", "text", "md", "srt", "vtt"];
+/// How long a file must go unchanged before it's taken
+const SETTLE: Duration = Duration::from_secs(2);
+/// Where taken files are moved, inside the folder
+const IMPORTED: &str = "imported";
+
+/// A task's worth of dropped file
+#[derive(Debug, Clone)]
+pub struct Dropped {
+    pub title: String,
+    pub description: String,
+}
+
+pub struct DropFolder {
+    path: PathBuf,
+}
+
+impl DropFolder {
+    /// The configured folder, if there is one
+    pub fn from_config(config: &DropFolderConfig) -> Option<Self> {
+        config.path.as_ref().map(|path| Self { path: expand_home(path) })
+    }
+
+    /// Take every file that's ready, moving it out of the way; a file that can't be read or moved
+    /// is left for the next look and reported
+    pub fn take(&self) -> (Vec<Dropped>, Vec<String>) {
+        let mut dropped = Vec::new();
+        let mut errors = Vec::new();
+        let entries = match fs::read_dir(&self.path) {
+            Ok(entries) => entries,
+            Err(err) => return (dropped, vec![format!("couldn't read {}: {}", self.path.display(), err)]),
+        };
+        let mut files: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).filter(|path| is_ready(path)).collect();
+        // Oldest names first, which for timestamped memos is the order they were recorded
+        files.sort();
+        for file in files {
+            match self.take_file(&file) {
+                Ok(Some(task)) => dropped.push(task),
+                Ok(None) => {}
+                Err(err) => errors.push(format!("couldn't import {}: {}", file.display(), err)),
+            }
+        }
+        (dropped, errors)
+    }
+
+    fn take_file(&self, file: &Path) -> std::io::Result<Option<Dropped>> {
+        let text = fs::read_to_string(file)?;
+        let imported = self.path.join(IMPORTED);
+        fs::create_dir_all(&imported)?;
+        let Some(name) = file.file_name() else {
+            return Ok(None);
+        };
+        fs::rename(file, unused_path(&imported.join(name)))?;
+
+        let subtitles = matches!(extension(file).as_deref(), Some("srt" | "vtt"));
+        let description = if subtitles { subtitle_text(&text) } else { text.trim().to_string() };
+        let title = file.file_stem().map(|stem| title_from(&stem.to_string_lossy())).unwrap_or_default();
+        // An untitled memo still gets something to show in the list
+        let title = if title.is_empty() {
+            description.lines().next().unwrap_or("Voice memo").chars().take(80).collect()
+        } else {
+            title
+        };
+        Ok(Some(Dropped { title, description }))
+    }
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension().map(|extension| extension.to_string_lossy().to_lowercase())
+}
+
+/// A transcript that's finished being written, and isn't hidden or a temporary file
+fn is_ready(path: &Path) -> bool {
+    let hidden = path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.'));
+    let wanted = extension(path).is_some_and(|extension| EXTENSIONS.contains(&extension.as_str()));
+    let settled = fs::metadata(path)
+        .ok()
+        .filter(|metadata| metadata.is_file())
+        .and_then(|metadata| metadata.modified().ok())
+        .is_some_and(|modified| SystemTime::now().duration_since(modified).unwrap_or_default() >= SETTLE);
+    !hidden && wanted && settled
+}
+
+/// The file name as a title, with underscores as spaces: "call_the_dentist" reads "call the dentist"
+fn title_from(stem: &str) -> String {
+    stem.replace('_', " ").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// `path`, or the first "name (2).txt" style variant of it that doesn't exist yet
+fn unused_path(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    let extension = path.extension().map(|extension| format!(".{}", extension.to_string_lossy())).unwrap_or_default();
+    (2..)
+        .map(|n| path.with_file_name(format!("{} ({}){}", stem, n, extension)))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// The spoken text of an .srt or .vtt file, one cue per line
+fn subtitle_text(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| {
+            !line.is_empty()
+                && *line != "WEBVTT"
+                && !line.contains("-->")
+                && !line.chars().all(|c| c.is_ascii_digit())
+                && !line.starts_with("NOTE")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,57 @@
+// Duplicate module - Spots a new task that looks like one already open, so capturing "Buy milk"
+// twice asks first instead of leaving two copies. Titles are compared ignoring case, punctuation
+// and spacing, by how few typing slips (a letter added, dropped, changed or two swapped) turn one
+// into the other.
+
+use crate::models::Todo;
+
+/// How alike two titles must be (1.0 when they're the same once normalized) to count as one task
+const THRESHOLD: f64 = 0.8;
+
+/// Lowercase words, without punctuation or extra spaces
+fn normalize(title: &str) -> Vec<char> {
+    let cleaned: String = title.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    cleaned.split_whitespace().collect::<Vec<_>>().join(" ").chars().collect()
+}
+
+/// Edit distance between the normalized titles (swapping two neighbouring letters counts as one
+/// edit), relative to the longer one: 1.0 is the same title, 0.0 nothing in common
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (normalize(a), normalize(b));
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    // distance[i][j]: edits turning the first i letters of a into the first j of b
+    let mut distance = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distance.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in distance[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitute = distance[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let mut best = substitute.min(distance[i - 1][j] + 1).min(distance[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(distance[i - 2][j - 2] + 1);
+            }
+            distance[i][j] = best;
+        }
+    }
+    1.0 - distance[a.len()][b.len()] as f64 / longest as f64
+}
+
+/// The open task whose title is most like `title`, if any is close enough to be the same task
+pub fn find<'a>(title: &str, todos: &'a [Todo]) -> Option<&'a Todo> {
+    todos.iter()
+        .filter(|todo| !todo.completed && !todo.deleted)
+        .map(|todo| (todo, similarity(title, &todo.title)))
+        .filter(|(_, score)| *score >= THRESHOLD)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(todo, _)| todo)
+}
@@ -0,0 +1,77 @@
+// Error module - The ways storage, config and sync can fail
+// Core modules return these instead of opaque anyhow errors so the UI can react to the kind of
+// failure (keep edits in memory while a file is locked, run on defaults when config is broken)
+// rather than only print a message. Command line code keeps using anyhow, which wraps these.
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum TduiError {
+    /// A data file exists but doesn't hold what it should
+    StorageCorrupt { path: PathBuf, reason: String },
+    /// A data file can't be written because it's read-only or held by another program
+    StorageLocked { path: PathBuf },
+    /// Reading or writing a data file failed some other way (a full disk, say)
+    StorageIo { path: PathBuf, source: io::Error },
+    /// git couldn't set up, commit, pull or push the data directory
+    SyncFailed(String),
+    /// config.json isn't valid JSON for the settings
+    ConfigInvalid { path: PathBuf, reason: String },
+}
+
+pub type Result<T> = std::result::Result<T, TduiError>;
+
+impl TduiError {
+    pub fn io(path: &Path, source: io::Error) -> Self {
+        match source.kind() {
+            io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem | io::ErrorKind::ResourceBusy => {
+                TduiError::StorageLocked { path: path.to_path_buf() }
+            }
+            _ => TduiError::StorageIo { path: path.to_path_buf(), source },
+        }
+    }
+
+    pub fn corrupt(path: &Path, reason: impl fmt::Display) -> Self {
+        TduiError::StorageCorrupt { path: path.to_path_buf(), reason: reason.to_string() }
+    }
+}
+
+impl fmt::Display for TduiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TduiError::StorageCorrupt { path, reason } => write!(f, "{} is damaged: {}", path.display(), reason),
+            TduiError::StorageLocked { path } => write!(f, "{} is read-only or in use", path.display()),
+            TduiError::StorageIo { path, source } => write!(f, "{}: {}", path.display(), source),
+            TduiError::SyncFailed(reason) => write!(f, "{}", reason),
+            TduiError::ConfigInvalid { path, reason } => write!(f, "{} is invalid: {}", path.display(), reason),
+        }
+    }
+}
+
+impl std::error::Error for TduiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TduiError::StorageIo { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Name the file a failed read, write or (de)serialization was about
+pub trait WithPath<T> {
+    fn with_path(self, path: &Path) -> Result<T>;
+}
+
+impl<T> WithPath<T> for io::Result<T> {
+    fn with_path(self, path: &Path) -> Result<T> {
+        self.map_err(|err| TduiError::io(path, err))
+    }
+}
+
+impl<T> WithPath<T> for serde_json::Result<T> {
+    fn with_path(self, path: &Path) -> Result<T> {
+        self.map_err(|err| TduiError::corrupt(path, err))
+    }
+}
@@ -1,29 +1,14 @@
 // Event module - Handling keyboard and other terminal events
 // This module will handle input events from crossterm
 
-use crossterm::event::{self, Event, KeyCode, KeyEvent};
+use crossterm::event::{self, Event};
 use std::time::Duration;
 
-pub enum AppEvent {
-    // TODO: Define custom events for the app
-    // Examples:
-    // - Quit
-    // - AddTodo(String)
-    // - ToggleTodo(usize)
-    // - DeleteTodo(usize)
-    // - NavigateUp
-    // - NavigateDown
-    // etc.
-}
-
-pub fn read_event() -> anyhow::Result<Option<Event>> {
-    // TODO: Read events from terminal with timeout
-    // Use crossterm::event::poll and event::read
-    todo!("Implement event reading")
-}
-
-pub fn handle_key_event(key: KeyEvent) -> Option<AppEvent> {
-    // TODO: Map keyboard events to app events
-    // Define keybindings here
-    todo!("Implement key event handling")
+/// Poll for a terminal event, returning None if nothing arrived within `tick`
+pub fn read_event(tick: Duration) -> anyhow::Result<Option<Event>> {
+    if event::poll(tick)? {
+        Ok(Some(event::read()?))
+    } else {
+        Ok(None)
+    }
 }
@@ -0,0 +1,87 @@
+// Fake data module - Synthetic tasks for benchmarks and large-dataset testing
+// Generation is deterministic (a fixed-seed xorshift), so benchmark runs are comparable.
+
+use crate::clock::Clock;
+use crate::models::Todo;
+use crate::storage::{Archive, FileStorage};
+use chrono::{Duration, NaiveDate, Utc};
+
+const VERBS: [&str; 8] = ["Buy", "Email", "Call", "Review", "Fix", "Plan", "Write", "Clean"];
+const NOUNS: [&str; 8] = ["milk", "report", "dentist", "budget", "bike", "garden", "slides", "invoice"];
+const CONTEXTS: [&str; 3] = ["@home", "@work", "@errands"];
+
+/// Small deterministic PRNG so fake data doesn't need a dependency
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniform-ish integer in 0..bound
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
+
+/// Build `count` tasks with IDs starting at `first_id`, spread over the 120 days around `today`
+pub fn generate(count: usize, first_id: usize, today: NaiveDate) -> Vec<Todo> {
+    let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+    let now = Utc::now();
+
+    (0..count)
+        .map(|i| {
+            let title = format!(
+                "{} {} #{}",
+                VERBS[rng.below(VERBS.len() as u64) as usize],
+                NOUNS[rng.below(NOUNS.len() as u64) as usize],
+                first_id + i
+            );
+            let description = if rng.below(2) == 0 {
+                format!("Synthetic task for load testing. Tags: {} {}", NOUNS[rng.below(8) as usize], NOUNS[rng.below(8) as usize])
+            } else {
+                String::new()
+            };
+            // About a third of tasks have no due date, the rest fall within two months either way
+            let due_date = match rng.below(3) {
+                0 => None,
+                _ => Some(today + Duration::days(rng.below(120) as i64 - 60)),
+            };
+
+            let mut todo = Todo::new(first_id + i, title, description, due_date);
+            let age = Duration::days(rng.below(120) as i64) + Duration::minutes(rng.below(24 * 60) as i64);
+            todo.created_at = now - age;
+            todo.context = match rng.below(4) {
+                0 => None,
+                n => Some(CONTEXTS[n as usize - 1].to_string()),
+            };
+
+            // Roughly 60% open, 30% completed some time after creation, 10% deleted
+            match rng.below(10) {
+                0..=5 => {}
+                6..=8 => {
+                    todo.completed = true;
+                    todo.completed_at = Some(todo.created_at + Duration::minutes(rng.below(age.num_minutes() as u64 + 1) as i64));
+                }
+                _ => todo.deleted = true,
+            }
+            todo
+        })
+        .collect()
+}
+
+/// Append `count` fake tasks to the todo file, returning where they were written
+pub fn seed_storage(count: usize) -> anyhow::Result<std::path::PathBuf> {
+    let path = FileStorage::get_default_path();
+    let mut storage = FileStorage::open(path.clone());
+    // Refuse to overwrite a file we couldn't read
+    storage.load_todos()?;
+
+    let first_id = storage.next_free_id(&Archive::open(Archive::get_default_path()));
+    storage.append(generate(count, first_id, Clock::default().today()));
+    storage.flush()?;
+    Ok(path)
+}
@@ -0,0 +1,392 @@
+// ICS module - Read-only calendar subscriptions overlaid on the Calendar panel
+// Feeds are fetched on a background thread and cached on disk, so the UI never waits on
+// the network and the last copy still shows when offline. `parse_items` reads the same files'
+// to-dos and events one by one, unexpanded, for `tdui import`.
+
+use crate::clock::Clock;
+use crate::config::CalendarFeed;
+use crate::models::Recurrence;
+use crate::storage::FileStorage;
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+/// How often subscribed feeds are fetched again while the app is open
+const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30 * 60);
+/// Recurring events are expanded this far either side of today
+const EXPAND_DAYS: i64 = 400;
+/// Longest span a single event is drawn across, so a stray multi-year event can't fill the calendar
+const MAX_SPAN_DAYS: i64 = 60;
+
+/// One occurrence of an event, in local dates
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    pub summary: String,
+    pub start: NaiveDate,
+    /// Last day the event covers (same as `start` for most events)
+    pub end: NaiveDate,
+    /// Local start time, or None for all-day events
+    pub time: Option<NaiveTime>,
+}
+
+impl CalendarEvent {
+    pub fn covers(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+}
+
+/// A DTSTART/DTEND value: a whole day, or a moment converted to local time
+#[derive(Debug, Clone, Copy)]
+enum IcsTime {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+impl IcsTime {
+    fn date(&self) -> NaiveDate {
+        match self {
+            IcsTime::Date(date) => *date,
+            IcsTime::DateTime(datetime) => datetime.date(),
+        }
+    }
+
+    fn time(&self) -> Option<NaiveTime> {
+        match self {
+            IcsTime::Date(_) => None,
+            IcsTime::DateTime(datetime) => Some(datetime.time()),
+        }
+    }
+}
+
+/// Parse a property like `DTSTART;TZID=Europe/Berlin:20261016T090000` into local time
+fn parse_time(params: &str, value: &str, clock: &Clock) -> Option<IcsTime> {
+    if (params.contains("VALUE=DATE") && !params.contains("VALUE=DATE-TIME")) || value.len() == 8 {
+        return NaiveDate::parse_from_str(value, "%Y%m%d").ok().map(IcsTime::Date);
+    }
+    let (value, utc) = match value.strip_suffix('Z') {
+        Some(value) => (value, true),
+        None => (value, false),
+    };
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    let tzid = params.split(';').find_map(|param| param.strip_prefix("TZID="));
+
+    // UTC and zoned times are shown in the configured timezone; floating times as written
+    let instant = if utc {
+        Some(Utc.from_utc_datetime(&naive))
+    } else {
+        tzid.and_then(|name| name.trim_matches('"').parse::<Tz>().ok())
+            .and_then(|zone| zone.from_local_datetime(&naive).earliest())
+            .map(|datetime| datetime.with_timezone(&Utc))
+    };
+    Some(IcsTime::DateTime(instant.map_or(naive, |instant| clock.local(instant).naive_local())))
+}
+
+fn unescape(text: &str) -> String {
+    unescape_with(text, ' ')
+}
+
+/// `text` with its escapes undone, a line break becoming `newline`
+fn unescape_with(text: &str, newline: char) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n' | 'N') => out.push(newline),
+                Some(other) => out.push(other),
+                None => {}
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// The document's lines, with those starting with a space or tab joined to the one before
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match line.strip_prefix([' ', '\t']) {
+            Some(rest) if !lines.is_empty() => lines.last_mut().unwrap().push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// One VTODO or VEVENT as written, in local time
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IcsItem {
+    pub uid: Option<String>,
+    pub summary: String,
+    pub description: String,
+    /// A to-do's DUE, or else its DTSTART; an event's DTSTART
+    pub date: Option<NaiveDate>,
+    pub time: Option<NaiveTime>,
+    /// STATUS:COMPLETED, or a COMPLETED time
+    pub completed: bool,
+    pub completed_at: Option<NaiveDateTime>,
+    pub cancelled: bool,
+    /// 1 (highest) to 9 (lowest); 0 or missing for none
+    pub priority: u8,
+    pub categories: Vec<String>,
+    /// The RRULE, as written
+    pub rule: Option<String>,
+}
+
+/// Read every to-do and event of one .ics document, each once, however it repeats
+pub fn parse_items(text: &str, clock: &Clock) -> Vec<IcsItem> {
+    let mut items = Vec::new();
+    let mut current: Option<IcsItem> = None;
+    let mut due: Option<IcsTime> = None;
+    let mut start = None;
+    // Components inside the current one, like a VALARM with its own DESCRIPTION, are passed over
+    let mut nested = 0;
+    for line in &unfold(text) {
+        let Some((name, value)) = line.split_once(':') else { continue };
+        let (name, params) = name.split_once(';').unwrap_or((name, ""));
+        match name.to_uppercase().as_str() {
+            "BEGIN" if current.is_some() => nested += 1,
+            "END" if nested > 0 => nested -= 1,
+            "BEGIN" if value == "VTODO" || value == "VEVENT" => {
+                (current, due, start) = (Some(IcsItem::default()), None, None);
+            }
+            "END" if value == "VTODO" || value == "VEVENT" => {
+                let Some(mut item) = current.take() else { continue };
+                if let Some(time) = due.or(start) {
+                    item.date = Some(time.date());
+                    item.time = time.time();
+                }
+                items.push(item);
+            }
+            _ if nested > 0 => {}
+            name => {
+                let Some(item) = &mut current else { continue };
+                match name {
+                    "UID" => item.uid = Some(value.trim().to_string()).filter(|uid| !uid.is_empty()),
+                    "SUMMARY" => item.summary = unescape(value).trim().to_string(),
+                    "DESCRIPTION" => item.description = unescape_with(value, '\n').trim().to_string(),
+                    "DUE" => due = parse_time(params, value, clock),
+                    "DTSTART" => start = parse_time(params, value, clock),
+                    "STATUS" => match value.trim().to_uppercase().as_str() {
+                        "COMPLETED" => item.completed = true,
+                        "CANCELLED" => item.cancelled = true,
+                        _ => {}
+                    },
+                    "COMPLETED" => {
+                        item.completed = true;
+                        item.completed_at = match parse_time(params, value, clock) {
+                            Some(IcsTime::DateTime(at)) => Some(at),
+                            Some(IcsTime::Date(date)) => date.and_hms_opt(0, 0, 0),
+                            None => None,
+                        };
+                    }
+                    "PRIORITY" => item.priority = value.trim().parse().unwrap_or(0),
+                    "CATEGORIES" => item.categories.extend(value.split(',').map(unescape).filter(|category| !category.trim().is_empty())),
+                    "RRULE" => item.rule = Some(value.to_string()),
+                    _ => {}
+                }
+            }
+        }
+    }
+    items
+}
+
+/// Read the events of one .ics document, expanding recurring ones around today
+pub fn parse(text: &str, clock: &Clock) -> Vec<CalendarEvent> {
+    let lines = unfold(text);
+
+    let today = clock.today();
+    let (first, last) = (today - Duration::days(EXPAND_DAYS), today + Duration::days(EXPAND_DAYS));
+    let mut events = Vec::new();
+
+    let mut in_event = false;
+    let mut summary = String::new();
+    let (mut start, mut end): (Option<IcsTime>, Option<IcsTime>) = (None, None);
+    let mut rule: Option<String> = None;
+    let mut excluded: Vec<NaiveDate> = Vec::new();
+    for line in &lines {
+        let Some((name, value)) = line.split_once(':') else { continue };
+        let (name, params) = name.split_once(';').unwrap_or((name, ""));
+        match name {
+            "BEGIN" if value == "VEVENT" => {
+                in_event = true;
+                (summary, start, end, rule, excluded) = (String::new(), None, None, None, Vec::new());
+            }
+            "END" if value == "VEVENT" && in_event => {
+                in_event = false;
+                let Some(start) = start else { continue };
+                // All-day end dates are exclusive; a timed event ending at midnight doesn't cover that day
+                let span = match (start, end) {
+                    (IcsTime::Date(from), Some(IcsTime::Date(to))) => (to - from).num_days() - 1,
+                    (_, Some(IcsTime::DateTime(to))) => {
+                        let days = (to.date() - start.date()).num_days();
+                        if to.time() == NaiveTime::MIN && days > 0 { days - 1 } else { days }
+                    }
+                    _ => 0,
+                }.clamp(0, MAX_SPAN_DAYS);
+
+                let dates = match &rule {
+                    Some(rule) => match Recurrence::parse_lenient(rule) {
+                        Some(rule) => rule.dates(start.date(), last),
+                        // A rule that would be expanded onto the wrong days (the second Tuesday, say)
+                        // shows its first occurrence only
+                        None => vec![start.date()],
+                    },
+                    None => vec![start.date()],
+                };
+                for date in dates {
+                    if excluded.contains(&date) || date + Duration::days(span) < first || date > last {
+                        continue;
+                    }
+                    events.push(CalendarEvent {
+                        summary: summary.clone(),
+                        start: date,
+                        end: date + Duration::days(span),
+                        time: start.time(),
+                    });
+                }
+            }
+            _ if !in_event => {}
+            "SUMMARY" => summary = unescape(value),
+            "DTSTART" => start = parse_time(params, value, clock),
+            "DTEND" => end = parse_time(params, value, clock),
+            "RRULE" => rule = Some(value.to_string()),
+            "EXDATE" => excluded.extend(value.split(',').filter_map(|value| parse_time(params, value, clock)).map(|time| time.date())),
+            _ => {}
+        }
+    }
+
+    events.sort_by_key(|event| (event.start, event.time));
+    events
+}
+
+/// Fetch a feed's current text: http(s) and webcal URLs over the network, anything else as a file
+fn fetch(url: &str) -> anyhow::Result<String> {
+    let url = match url.strip_prefix("webcal://") {
+        Some(rest) => format!("https://{}", rest),
+        None => url.to_string(),
+    };
+    if url.starts_with("http://") || url.starts_with("https://") {
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(std::time::Duration::from_secs(30)))
+            .build()
+            .into();
+        Ok(agent.get(&url).call()?.body_mut().read_to_string()?)
+    } else {
+        let path = url.strip_prefix("file://").unwrap_or(&url);
+        Ok(fs::read_to_string(path)?)
+    }
+}
+
+struct FeedUpdate {
+    index: usize,
+    result: Result<String, String>,
+}
+
+/// Events of every subscribed feed, kept up to date from a background thread
+#[derive(Default)]
+pub struct CalendarFeeds {
+    names: Vec<String>,
+    /// Events per feed, in the order of `names`
+    events: Vec<Vec<CalendarEvent>>,
+    updates: Option<Receiver<FeedUpdate>>,
+    /// Bumped each time a feed's events are replaced
+    revision: u64,
+}
+
+impl CalendarFeeds {
+    /// Show each feed's cached copy straight away, and start fetching fresh ones
+    pub fn start(feeds: &[CalendarFeed], clock: &Clock) -> Self {
+        let names = feeds.iter().map(|feed| feed.name.clone()).collect();
+        let events = feeds.iter()
+            .map(|feed| {
+                fs::read_to_string(Self::cache_path(&feed.name))
+                    .map(|text| parse(&text, clock))
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let updates = (!feeds.is_empty()).then(|| {
+            let (sender, receiver) = mpsc::channel();
+            let feeds = feeds.to_vec();
+            thread::spawn(move || Self::refresh_forever(&feeds, &sender));
+            receiver
+        });
+
+        Self { names, events, updates, revision: 0 }
+    }
+
+    fn refresh_forever(feeds: &[CalendarFeed], sender: &Sender<FeedUpdate>) {
+        loop {
+            for (index, feed) in feeds.iter().enumerate() {
+                let result = fetch(&feed.url).map_err(|err| err.to_string());
+                if let Ok(text) = &result {
+                    let path = Self::cache_path(&feed.name);
+                    if let Some(parent) = path.parent() {
+                        let _ = fs::create_dir_all(parent);
+                    }
+                    let _ = fs::write(path, text);
+                }
+                // The app has exited
+                if sender.send(FeedUpdate { index, result }).is_err() {
+                    return;
+                }
+            }
+            thread::sleep(REFRESH_INTERVAL);
+        }
+    }
+
+    fn cache_path(name: &str) -> PathBuf {
+        // ~/.local/share/tdui/calendars/<name>.ics, with the name made safe for a file name
+        let file_name: String = name.chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        FileStorage::get_default_path()
+            .with_file_name("calendars")
+            .join(format!("{}.ics", file_name))
+    }
+
+    /// Take in any feeds fetched since the last call, returning a message for each that failed
+    pub fn poll(&mut self, clock: &Clock) -> Vec<String> {
+        let Some(updates) = &self.updates else {
+            return Vec::new();
+        };
+        let mut errors = Vec::new();
+        while let Ok(update) = updates.try_recv() {
+            match update.result {
+                Ok(text) => {
+                    self.events[update.index] = parse(&text, clock);
+                    self.revision += 1;
+                }
+                Err(err) => errors.push(format!("Calendar '{}' couldn't be refreshed: {}", self.names[update.index], err)),
+            }
+        }
+        errors
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Every subscribed event occurrence
+    pub fn events(&self) -> impl Iterator<Item = &CalendarEvent> {
+        self.events.iter().flatten()
+    }
+
+    /// Each feed's name with its events
+    pub fn feeds(&self) -> impl Iterator<Item = (&str, &[CalendarEvent])> {
+        self.names.iter().map(String::as_str).zip(self.events.iter().map(Vec::as_slice))
+    }
+
+    /// Events covering `date`, earliest first (all-day events lead)
+    pub fn events_on(&self, date: NaiveDate) -> Vec<&CalendarEvent> {
+        let mut events: Vec<&CalendarEvent> = self.events().filter(|event| event.covers(date)).collect();
+        events.sort_by_key(|event| event.time);
+        events
+    }
+}
@@ -0,0 +1,415 @@
+// Import module - Tasks from other tools' files, safe to import again
+// An importer turns its file into candidate tasks. Each is matched against the stored and
+// archived tasks by its ID in the source (when the format has one) or else by title and due
+// date, so re-running an import merges what changed instead of adding duplicates. A dry run
+// works out the same plan and saves nothing.
+// Formats read: todo.txt (.txt), iCalendar to-dos and events (.ics), CSV with a header row
+// (.csv) and Taskwarrior's `task export` (.json); another is one more `parse_*` function.
+
+use crate::clock::Clock;
+use crate::config::Config;
+use crate::ics;
+use crate::models::{Priority, Recurrence, Todo};
+use crate::storage::{Archive, FileStorage};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    Create,
+    /// Update the stored task with this ID, with a description of each change
+    Merge(usize, Vec<String>),
+    /// Leave it, with why (and the matching task's ID, if it's stored)
+    Skip(Option<usize>, String),
+}
+
+#[derive(Debug, Clone)]
+pub struct PlannedTask {
+    pub todo: Todo,
+    pub action: Action,
+}
+
+/// Read `path` and create or merge its tasks; with `dry_run`, only work out what would happen
+pub fn import(path: &Path, dry_run: bool) -> anyhow::Result<Vec<PlannedTask>> {
+    let config = Config::load(Config::get_default_path());
+
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| anyhow::anyhow!("couldn't read {}: {}", path.display(), err))?;
+    let extension = path.extension().map(|extension| extension.to_string_lossy().to_lowercase());
+    let mut candidates = match extension.as_deref() {
+        Some("txt") => parse_todo_txt(&text),
+        Some("ics") => parse_ics(&text, &Clock::from_config_or_warn(&config)),
+        Some("csv") => parse_csv(&text).map_err(|err| anyhow::anyhow!("couldn't read {}: {}", path.display(), err))?,
+        Some("json") => parse_taskwarrior(&text, &Clock::from_config_or_warn(&config))
+            .map_err(|err| anyhow::anyhow!("couldn't read {} as a Taskwarrior export: {}", path.display(), err))?,
+        _ => anyhow::bail!("{} isn't a format tdui can import (todo.txt files ending in .txt, .ics, .csv or a Taskwarrior .json export)", path.display()),
+    };
+    for todo in &mut candidates {
+        if todo.context.is_none() {
+            todo.context = config.active_context.clone();
+        }
+    }
+
+    let mut storage = FileStorage::open(FileStorage::get_default_path());
+    // Refuse to overwrite a file we couldn't read
+    storage.load_todos()?;
+    let archive = Archive::open(Archive::get_default_path());
+    let mut planned = plan(candidates, storage.todos(), &archive.load_all());
+    if dry_run {
+        return Ok(planned);
+    }
+
+    let mut next_id = storage.next_free_id(&archive);
+    for task in &mut planned {
+        match &task.action {
+            Action::Create => {
+                task.todo.id = next_id;
+                next_id += 1;
+                storage.upsert(task.todo.clone());
+            }
+            Action::Merge(..) => storage.upsert(task.todo.clone()),
+            Action::Skip(..) => {}
+        }
+    }
+    storage.flush()?;
+    Ok(planned)
+}
+
+/// Match each candidate against the stored and archived tasks, and against the ones before it in
+/// the same file
+pub fn plan(candidates: Vec<Todo>, stored: &[Todo], archived: &[Todo]) -> Vec<PlannedTask> {
+    let mut planned: Vec<PlannedTask> = Vec::new();
+    for candidate in candidates {
+        let action = if planned.iter().any(|earlier| same_task(&earlier.todo, &candidate)) {
+            Action::Skip(None, "listed twice in the file".to_string())
+        } else if let Some(existing) = stored.iter().find(|todo| same_task(todo, &candidate)) {
+            let (merged, changes) = merge(existing, &candidate);
+            let action = if existing.deleted {
+                Action::Skip(Some(existing.id), "deleted here".to_string())
+            } else if changes.is_empty() {
+                Action::Skip(Some(existing.id), "already imported".to_string())
+            } else {
+                Action::Merge(existing.id, changes)
+            };
+            planned.push(PlannedTask { todo: merged, action });
+            continue;
+        } else if let Some(existing) = archived.iter().find(|todo| same_task(todo, &candidate)) {
+            Action::Skip(Some(existing.id), "already imported and since archived".to_string())
+        } else {
+            Action::Create
+        };
+        planned.push(PlannedTask { todo: candidate, action });
+    }
+    planned
+}
+
+/// The same ID in the source when both have one, otherwise the same title and due date
+fn same_task(a: &Todo, b: &Todo) -> bool {
+    match (&a.source_id, &b.source_id) {
+        (Some(a_id), Some(b_id)) => a_id == b_id,
+        _ => a.title.trim().eq_ignore_ascii_case(b.title.trim()) && a.due_date == b.due_date,
+    }
+}
+
+/// `existing` with what the import adds to it: completion, and any priority, project, context
+/// or tags it doesn't have yet. Nothing set locally is overwritten.
+fn merge(existing: &Todo, imported: &Todo) -> (Todo, Vec<String>) {
+    let mut merged = existing.clone();
+    let mut changes = Vec::new();
+    if imported.completed && !existing.completed {
+        merged.completed = true;
+        merged.completed_at = imported.completed_at.or_else(|| Some(Utc::now()));
+        changes.push("done".to_string());
+    }
+    if existing.priority.is_none() && let Some(priority) = imported.priority {
+        merged.priority = Some(priority);
+        changes.push(format!("priority {}", priority.label().to_lowercase()));
+    }
+    if existing.project.is_none() && let Some(project) = &imported.project {
+        merged.project = Some(project.clone());
+        changes.push(format!("project {}", project));
+    }
+    if existing.context.is_none() && let Some(context) = &imported.context {
+        merged.context = Some(context.clone());
+        changes.push(format!("context {}", context));
+    }
+    let mut new_tags: Vec<String> = imported.tags.iter().filter(|tag| !existing.tags.contains(tag)).cloned().collect();
+    dedupe_tags(&mut new_tags);
+    if !new_tags.is_empty() {
+        merged.tags.extend(new_tags.iter().cloned());
+        changes.push(format!("tags {}", new_tags.iter().map(|tag| format!("+{}", tag)).collect::<Vec<_>>().join(" ")));
+    }
+    if merged.source_id.is_none() {
+        merged.source_id = imported.source_id.clone();
+    }
+    (merged, changes)
+}
+
+/// One task per line, as in `x 2026-10-02 2026-09-20 (A) Call mom +family @phone due:2026-10-20 id:42`:
+/// the first +project and @context are the task's, any others become tags
+pub fn parse_todo_txt(text: &str) -> Vec<Todo> {
+    let date = |word: &str| NaiveDate::parse_from_str(word, "%Y-%m-%d").ok();
+    let timestamp = |date: NaiveDate| -> DateTime<Utc> { date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc() };
+    let priority = |letter: &str| match letter {
+        "A" => Some(Priority::High),
+        "B" => Some(Priority::Medium),
+        letter if letter.len() == 1 && letter.chars().all(|c| c.is_ascii_uppercase()) => Some(Priority::Low),
+        _ => None,
+    };
+
+    let mut todos = Vec::new();
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        let mut words = line.split_whitespace().peekable();
+        let mut todo = Todo::new(0, String::new(), String::new(), None);
+
+        if words.next_if_eq(&"x").is_some() {
+            todo.completed = true;
+            todo.completed_at = words.next_if(|word| date(word).is_some()).and_then(date).map(timestamp).or_else(|| Some(Utc::now()));
+        }
+        if let Some(word) = words.next_if(|word| word.len() == 3 && word.starts_with('(') && word.ends_with(')')) {
+            todo.priority = priority(&word[1..2]);
+        }
+        if let Some(created) = words.next_if(|word| date(word).is_some()).and_then(date) {
+            todo.created_at = timestamp(created);
+        }
+
+        let mut title = Vec::new();
+        for word in words {
+            let tag = |name: &str| Todo::normalize_tag(name);
+            match word.split_once(':') {
+                Some(("due", value)) if date(value).is_some() => todo.due_date = date(value),
+                Some(("id", value)) if !value.is_empty() => todo.source_id = Some(format!("todotxt:{}", value)),
+                Some(("pri", value)) => todo.priority = priority(value),
+                _ if word.len() > 1 && word.starts_with('+') => match &todo.project {
+                    None => todo.project = Some(word[1..].to_string()),
+                    Some(_) => todo.tags.extend(tag(&word[1..])),
+                },
+                _ if word.len() > 1 && word.starts_with('@') => match &todo.context {
+                    None => todo.context = Todo::normalize_context(&word[1..]),
+                    Some(_) => todo.tags.extend(tag(&word[1..])),
+                },
+                _ => title.push(word),
+            }
+        }
+        todo.title = title.join(" ");
+        dedupe_tags(&mut todo.tags);
+        if !todo.title.is_empty() {
+            todos.push(todo);
+        }
+    }
+    todos
+}
+
+/// Each VTODO and VEVENT as a task, due on its DUE (or start) date: STATUS:COMPLETED or a
+/// COMPLETED time marks it done, PRIORITY 1-4 is high, 5 medium and 6-9 low, CATEGORIES become
+/// tags and a repeat rule tdui can expand is kept. Cancelled ones are left out.
+pub fn parse_ics(text: &str, clock: &Clock) -> Vec<Todo> {
+    ics::parse_items(text, clock)
+        .into_iter()
+        .filter(|item| !item.cancelled && !item.summary.is_empty())
+        .map(|item| {
+            let mut todo = Todo::new(0, item.summary, item.description, item.date);
+            todo.due_time = item.time;
+            todo.source_id = item.uid.map(|uid| format!("ics:{}", uid));
+            todo.completed = item.completed;
+            if item.completed {
+                todo.completed_at = item.completed_at
+                    .and_then(|at| clock.at(at.date(), at.time()))
+                    .map(|at| at.with_timezone(&Utc))
+                    .or_else(|| Some(Utc::now()));
+            }
+            todo.priority = match item.priority {
+                1..=4 => Some(Priority::High),
+                5 => Some(Priority::Medium),
+                6..=9 => Some(Priority::Low),
+                _ => None,
+            };
+            todo.tags = item.categories.iter().filter_map(|category| Todo::normalize_tag(category)).collect();
+            dedupe_tags(&mut todo.tags);
+            todo.recurrence = item.rule.and_then(|rule| Recurrence::parse(&rule).ok());
+            todo
+        })
+        .collect()
+}
+
+/// A header row naming the columns, then one task per row. The columns read, in any order and
+/// case: title (or name, task, summary), description (or notes), due (YYYY-MM-DD, optionally
+/// followed by HH:MM), priority (high, medium, low, or A, B, C as in todo.txt), project, context,
+/// tags (split on commas, semicolons or spaces), done (x, yes, true, 1 or done), completed_at
+/// (or done_at, completion_date; the import time if a done row has none) and id; others are
+/// ignored. Rows without a title are skipped.
+pub fn parse_csv(text: &str) -> anyhow::Result<Vec<Todo>> {
+    let mut records = csv_records(text.trim_start_matches('\u{feff}')).into_iter();
+    let header: Vec<String> = records.next().unwrap_or_default().iter()
+        .map(|name| name.trim().to_lowercase().replace([' ', '-'], "_"))
+        .collect();
+    let column = |names: &[&str]| header.iter().position(|name| names.contains(&name.as_str()));
+    let Some(title) = column(&["title", "name", "task", "summary"]) else {
+        anyhow::bail!("the header row has no title column");
+    };
+    let description = column(&["description", "notes", "note"]);
+    let due = column(&["due", "due_date"]);
+    let priority = column(&["priority"]);
+    let project = column(&["project"]);
+    let context = column(&["context"]);
+    let tags = column(&["tags", "tag", "labels"]);
+    let done = column(&["done", "completed", "status"]);
+    let completed_at = column(&["completed_at", "done_at", "completion_date", "done_date"]);
+    let id = column(&["id"]);
+    // YYYY-MM-DD, optionally followed by HH:MM
+    let date_and_time = |value: &str| {
+        let (date, time) = value.split_once([' ', 'T']).unwrap_or((value, ""));
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok();
+        (date, NaiveTime::parse_from_str(time.get(..5).unwrap_or(time), "%H:%M").ok().filter(|_| date.is_some()))
+    };
+
+    let mut todos = Vec::new();
+    for record in records {
+        let field = |index: Option<usize>| index.and_then(|index| record.get(index)).map(|value| value.trim()).filter(|value| !value.is_empty());
+        let Some(title) = field(Some(title)) else { continue };
+        let mut todo = Todo::new(0, title.to_string(), field(description).unwrap_or_default().to_string(), None);
+        if let Some(value) = field(due) {
+            (todo.due_date, todo.due_time) = date_and_time(value);
+        }
+        todo.priority = field(priority).and_then(|value| Priority::parse(value).or(match value {
+            "A" => Some(Priority::High),
+            "B" => Some(Priority::Medium),
+            "C" => Some(Priority::Low),
+            _ => None,
+        }));
+        todo.project = field(project).map(|project| project.trim_start_matches('+').to_string());
+        todo.context = field(context).and_then(Todo::normalize_context);
+        todo.tags = field(tags).unwrap_or_default()
+            .split([',', ';', ' '])
+            .filter_map(Todo::normalize_tag)
+            .collect();
+        dedupe_tags(&mut todo.tags);
+        todo.completed = field(done).is_some_and(|value| {
+            matches!(value.to_lowercase().as_str(), "x" | "yes" | "y" | "true" | "1" | "done" | "completed")
+        });
+        if todo.completed {
+            todo.completed_at = field(completed_at)
+                .map(date_and_time)
+                .and_then(|(date, time)| Some(date?.and_time(time.unwrap_or_default()).and_utc()))
+                .or_else(|| Some(Utc::now()));
+        }
+        todo.source_id = field(id).map(|id| format!("csv:{}", id));
+        todos.push(todo);
+    }
+    Ok(todos)
+}
+
+/// A task as `task export` writes it; the fields tdui has no use for are ignored
+#[derive(Deserialize)]
+struct TaskwarriorTask {
+    uuid: Option<String>,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    status: String,
+    entry: Option<String>,
+    due: Option<String>,
+    end: Option<String>,
+    project: Option<String>,
+    priority: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    annotations: Vec<TaskwarriorAnnotation>,
+}
+
+#[derive(Deserialize)]
+struct TaskwarriorAnnotation {
+    #[serde(default)]
+    description: String,
+}
+
+/// What `task export` writes: a JSON array, or one task per line from older versions. Pending,
+/// waiting and completed tasks are read, done at their end time; H, M and L priorities are high,
+/// medium and low, annotations become the description and the uuid is the ID in the source.
+/// Deleted tasks and recurring templates (whose instances are exported as well) are left out.
+pub fn parse_taskwarrior(text: &str, clock: &Clock) -> anyhow::Result<Vec<Todo>> {
+    let text = text.trim();
+    let tasks: Vec<TaskwarriorTask> = if text.starts_with('[') {
+        serde_json::from_str(text)?
+    } else {
+        text.lines()
+            .map(|line| line.trim().trim_end_matches(','))
+            .filter(|line| !line.is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?
+    };
+    let timestamp = |value: &Option<String>| value.as_deref()
+        .and_then(|value| NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ").ok())
+        .map(|at| at.and_utc());
+
+    Ok(tasks.into_iter()
+        .filter(|task| !matches!(task.status.as_str(), "deleted" | "recurring") && !task.description.trim().is_empty())
+        .map(|task| {
+            let notes: Vec<&str> = task.annotations.iter().map(|note| note.description.as_str()).collect();
+            let mut todo = Todo::new(0, task.description.trim().to_string(), notes.join("\n"), None);
+            if let Some(due) = timestamp(&task.due).map(|at| clock.local(at)) {
+                todo.due_date = Some(due.date_naive());
+                // A due date without a time is kept as local midnight
+                todo.due_time = Some(due.time()).filter(|&time| time != NaiveTime::MIN);
+            }
+            if let Some(entry) = timestamp(&task.entry) {
+                todo.created_at = entry;
+            }
+            if task.status == "completed" {
+                todo.completed = true;
+                todo.completed_at = timestamp(&task.end).or_else(|| Some(Utc::now()));
+            }
+            todo.priority = match task.priority.as_deref() {
+                Some("H") => Some(Priority::High),
+                Some("M") => Some(Priority::Medium),
+                Some("L") => Some(Priority::Low),
+                _ => None,
+            };
+            todo.project = task.project;
+            todo.tags = task.tags.iter().filter_map(|tag| Todo::normalize_tag(tag)).collect();
+            dedupe_tags(&mut todo.tags);
+            todo.source_id = task.uuid.map(|uuid| format!("taskwarrior:{}", uuid));
+            todo
+        })
+        .collect())
+}
+
+/// Drop repeated tags, keeping the first of each in place
+fn dedupe_tags(tags: &mut Vec<String>) {
+    let mut seen = HashSet::new();
+    tags.retain(|tag| seen.insert(tag.clone()));
+}
+
+/// The rows of a CSV file as fields: a quoted field may hold commas, line breaks and doubled
+/// quotes (RFC 4180); blank lines are dropped
+fn csv_records(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.next_if_eq(&'"').is_some() => field.push('"'),
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            _ if quoted => field.push(c),
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records.retain(|record| record.iter().any(|field| !field.trim().is_empty()));
+    records
+}
@@ -0,0 +1,227 @@
+// Keymap module - Custom keys for the main screen's actions, from the "keys" config
+// Each action has a default key; config can move it to another key or turn it off with "none".
+// A custom key is translated back to the action's default before the usual key handling runs,
+// so the handlers only ever see default keys. Entries that can't be used (an unknown action, an
+// unreadable key, a clash) fall back to the default for that action and are reported at startup.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::{BTreeMap, HashMap};
+
+/// Every action that can be rebound: (config name, default key, whether it must stay bound)
+const ACTIONS: &[(&str, &str, bool)] = &[
+    ("quit", "q", true),
+    ("new_task", "+", true),
+    ("inline_task", "o", false),
+    ("add_url", "U", false),
+    ("add_subtask", "A", false),
+    ("edit_field", "e", false),
+    ("history", "H", false),
+    ("done", "d", false),
+    ("delete", "-", false),
+    ("search", "/", false),
+    ("context", "c", false),
+    ("workspace", "w", false),
+    ("close_workspace", "ctrl+w", false),
+    ("plan", "p", false),
+    ("zoom", "z", false),
+    ("split", "v", false),
+    ("split_filter", "f", false),
+    ("move_to_pane", "m", false),
+    ("mark", "space", false),
+    ("batch_edit", "B", false),
+    ("move_to_project", "T", false),
+    ("next_status", "s", false),
+    ("previous_status", "alt+s", false),
+    ("waiting_on", "W", false),
+    ("ping", "P", false),
+    ("someday", "M", false),
+    ("project_settings", "C", false),
+    ("group", "g", false),
+    ("collapse_group", "x", false),
+    ("expand_groups", "X", false),
+    ("tree", "i", false),
+    ("sort", "O", false),
+    ("postpone", ">", false),
+    ("bring_forward", "<", false),
+    ("focus", "F", false),
+    ("today", "t", false),
+    ("note", "n", false),
+    ("blackout", "b", false),
+    ("jump_hints", "j", false),
+    ("jump_to_id", ":", false),
+    ("sync", "S", false),
+    ("empty_trash", "D", false),
+    ("slack", "Y", false),
+    ("slack_plan", "alt+y", false),
+    ("usage", "u", false),
+    ("estimates", "E", false),
+    ("all_sources", "a", false),
+];
+
+/// A key as written in config: a character or "space", optionally with ctrl+ / alt+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub fn parse(text: &str) -> Option<Key> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = text.trim();
+        loop {
+            let lower = rest.to_lowercase();
+            if lower.starts_with("ctrl+") {
+                modifiers |= KeyModifiers::CONTROL;
+            } else if lower.starts_with("alt+") {
+                modifiers |= KeyModifiers::ALT;
+            } else {
+                break;
+            }
+            rest = &rest[rest.find('+')? + 1..];
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            _ if rest.eq_ignore_ascii_case("space") => KeyCode::Char(' '),
+            (Some(c), None) if !c.is_whitespace() => KeyCode::Char(c),
+            _ => return None,
+        };
+        Some(Key { code, modifiers })
+    }
+
+    /// The key pressed, ignoring shift (already part of the character)
+    fn of(event: &KeyEvent) -> Key {
+        Key { code: event.code, modifiers: event.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT) }
+    }
+
+    /// How the key is written in config and shown in hints
+    pub fn label(&self) -> String {
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("alt+");
+        }
+        match self.code {
+            KeyCode::Char(' ') => label.push_str("space"),
+            KeyCode::Char(c) => label.push(c),
+            _ => {}
+        }
+        label
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    /// The key each action ended up on (None if turned off)
+    bindings: HashMap<&'static str, Option<Key>>,
+    /// Keys that differ from the defaults: the default key they stand for, or None to ignore the key
+    translations: HashMap<Key, Option<Key>>,
+}
+
+impl Keymap {
+    /// Build the keymap from the "keys" config (action name to key), returning it with a
+    /// description of every entry that couldn't be used
+    pub fn from_config(keys: &BTreeMap<String, String>) -> (Self, Vec<String>) {
+        let defaults: HashMap<&'static str, Key> = ACTIONS.iter()
+            .map(|&(action, key, _)| (action, Key::parse(key).expect("default keys parse")))
+            .collect();
+        let mut bindings: HashMap<&'static str, Option<Key>> = defaults.iter().map(|(&action, &key)| (action, Some(key))).collect();
+        let mut problems = Vec::new();
+
+        for (name, value) in keys {
+            let Some(&(action, _, required)) = ACTIONS.iter().find(|(action, _, _)| action == name) else {
+                problems.push(format!("\"{}\" isn't an action that can be rebound", name));
+                continue;
+            };
+            if value.trim().eq_ignore_ascii_case("none") {
+                if required {
+                    problems.push(format!("{} can't be turned off, keeping {}", action, defaults[action].label()));
+                } else {
+                    bindings.insert(action, None);
+                }
+                continue;
+            }
+            match Key::parse(value) {
+                Some(Key { code: KeyCode::Char(c), modifiers }) if c.is_ascii_digit() && modifiers.is_empty() => {
+                    problems.push(format!("{}: digits jump to list numbers, keeping {}", action, defaults[action].label()));
+                }
+                Some(key) => {
+                    bindings.insert(action, Some(key));
+                }
+                None => problems.push(format!("{}: can't read key \"{}\", keeping {}", action, value, defaults[action].label())),
+            }
+        }
+
+        // Two actions on one key: the custom binding gives way, which can free up or take another
+        // key, so repeat until nothing clashes (default keys never clash with each other)
+        loop {
+            let mut by_key: HashMap<Key, Vec<&'static str>> = HashMap::new();
+            for &(action, _, _) in ACTIONS {
+                if let Some(key) = bindings[action] {
+                    by_key.entry(key).or_default().push(action);
+                }
+            }
+            let clash = by_key.into_iter()
+                .filter(|(_, actions)| actions.len() > 1)
+                .min_by_key(|(key, _)| key.label());
+            let Some((key, actions)) = clash else { break };
+            let custom: Vec<&'static str> = actions.iter().copied()
+                .filter(|&action| bindings[action] != Some(defaults[action]))
+                .collect();
+            for action in custom {
+                let others: Vec<&str> = actions.iter().copied().filter(|&other| other != action).collect();
+                problems.push(format!(
+                    "{} is taken by {}, so {} keeps {}",
+                    key.label(), others.join(" and "), action, defaults[action].label(),
+                ));
+                bindings.insert(action, Some(defaults[action]));
+            }
+        }
+
+        let mut translations = HashMap::new();
+        for &(action, _, _) in ACTIONS {
+            let default = defaults[action];
+            if bindings[action] == Some(default) {
+                continue;
+            }
+            if let Some(key) = bindings[action] {
+                translations.insert(key, Some(default));
+            }
+            // The old key stops doing anything unless another action moved onto it
+            if !bindings.values().any(|&key| key == Some(default)) {
+                translations.entry(default).or_insert(None);
+            }
+        }
+        (Self { bindings, translations }, problems)
+    }
+
+    /// The key as the main screen's handlers know it: a custom key becomes its action's default,
+    /// a default key whose action moved elsewhere becomes None
+    pub fn translate(&self, event: KeyEvent) -> Option<KeyEvent> {
+        match self.translations.get(&Key::of(&event)) {
+            Some(Some(default)) => Some(KeyEvent::new(default.code, default.modifiers)),
+            Some(None) => None,
+            None => Some(event),
+        }
+    }
+
+    /// The action a default key stands for, as the handlers see keys after `translate`
+    pub fn action_of(event: &KeyEvent) -> Option<&'static str> {
+        let key = Key::of(event);
+        ACTIONS.iter()
+            .find(|&&(_, default, _)| Key::parse(default) == Some(key))
+            .map(|&(action, _, _)| action)
+    }
+
+    /// Every action that can be rebound, by config name
+    pub fn actions() -> impl Iterator<Item = &'static str> {
+        ACTIONS.iter().map(|&(action, _, _)| action)
+    }
+
+    /// The key bound to `action`, as written in hints, or None if it's turned off
+    pub fn label(&self, action: &str) -> Option<String> {
+        self.bindings.get(action).copied().flatten().map(|key| key.label())
+    }
+}
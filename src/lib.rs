@@ -0,0 +1,47 @@
+// tuiDO - A TUI-based Todo application
+// Library root: the binary in main.rs and the benchmarks in benches/ share these modules
+
+pub mod app;
+pub mod batch;
+pub mod board;
+pub mod cli;
+pub mod clock;
+pub mod config;
+pub mod config_bundle;
+pub mod diff;
+pub mod digest;
+pub mod doctor;
+pub mod drop_folder;
+pub mod duplicate;
+pub mod error;
+pub mod event;
+pub mod fake;
+pub mod ics;
+pub mod import;
+pub mod keymap;
+pub mod mail;
+pub mod mcp;
+pub mod models;
+pub mod notify;
+pub mod planner;
+pub mod quick_add;
+pub mod read_later;
+pub mod report;
+pub mod search;
+pub mod secrets;
+pub mod server;
+pub mod session;
+pub mod slack;
+pub mod stats;
+pub mod storage;
+pub mod template;
+pub mod text_buffer;
+pub mod theme;
+pub mod transfer;
+pub mod triage;
+pub mod ui;
+pub mod urgency;
+pub mod usage;
+pub mod validate;
+pub mod vault;
+pub mod weekly_plan;
@@ -0,0 +1,231 @@
+// Mail module - Sends a message through an SMTP server, or by piping it to a sendmail command
+// Used by `tdui digest --week --send`. Only as much SMTP as one message needs: TLS from the start
+// on port 465, otherwise STARTTLS whenever the server offers it (a password is never sent without
+// it), then AUTH PLAIN when a username is set.
+
+use crate::config::MailConfig;
+use crate::secrets;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, FixedOffset};
+use rustls::pki_types::ServerName;
+use rustls::{ClientConfig, ClientConnection, RootCertStore, StreamOwned};
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Port where the connection is TLS from the first byte
+const TLS_PORT: u16 = 465;
+
+/// An email with a plain-text and an HTML version of the same body
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub from: String,
+    pub to: String,
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+impl Message {
+    /// The message as sent: headers, then both bodies as multipart/alternative, base64-encoded so
+    /// any text survives the trip; lines end in "\n"
+    pub fn to_mime(&self, date: DateTime<FixedOffset>) -> String {
+        let boundary = format!("tdui-{}", date.timestamp());
+        let mut mime = String::new();
+        let _ = writeln!(mime, "From: {}", self.from);
+        let _ = writeln!(mime, "To: {}", self.to);
+        let _ = writeln!(mime, "Subject: {}", encode_header(&self.subject));
+        let _ = writeln!(mime, "Date: {}", date.to_rfc2822());
+        mime.push_str("MIME-Version: 1.0\n");
+        let _ = writeln!(mime, "Content-Type: multipart/alternative; boundary=\"{}\"\n", boundary);
+        for (kind, body) in [("plain", &self.text), ("html", &self.html)] {
+            let _ = writeln!(mime, "--{}", boundary);
+            let _ = writeln!(mime, "Content-Type: text/{}; charset=utf-8", kind);
+            mime.push_str("Content-Transfer-Encoding: base64\n\n");
+            let encoded = BASE64.encode(body);
+            // 76 characters a line at most
+            for line in encoded.as_bytes().chunks(76) {
+                mime.push_str(std::str::from_utf8(line).unwrap_or_default());
+                mime.push('\n');
+            }
+        }
+        let _ = writeln!(mime, "--{}--", boundary);
+        mime
+    }
+}
+
+/// Send `message` the way config says to; returns how it went, e.g. "through smtp.example.com"
+pub fn send(config: &MailConfig, message: &Message, date: DateTime<FixedOffset>) -> anyhow::Result<String> {
+    let mime = message.to_mime(date);
+    if let Some((program, args)) = config.sendmail.split_first() {
+        pipe_to(program, args, &mime)?;
+        return Ok(format!("with {}", program));
+    }
+    let Some(host) = config.smtp_host.as_deref().filter(|host| !host.is_empty()) else {
+        anyhow::bail!("no way to send mail is set: set \"mail\": {{ \"smtp_host\": ... }} or {{ \"sendmail\": [\"sendmail\", \"-t\"] }} in config");
+    };
+    send_smtp(config, host, message, &mime)?;
+    Ok(format!("through {}", host))
+}
+
+/// Hand the message to a local mail program on its stdin
+fn pipe_to(program: &str, args: &[String], mime: &str) -> anyhow::Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|err| anyhow::anyhow!("couldn't run {}: {}", program, err))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(mime.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        anyhow::bail!("{} failed ({})", program, status);
+    }
+    Ok(())
+}
+
+trait Stream: Read + Write {}
+impl<T: Read + Write> Stream for T {}
+
+/// One conversation with an SMTP server
+struct Smtp {
+    stream: BufReader<Box<dyn Stream>>,
+}
+
+impl Smtp {
+    fn new(stream: Box<dyn Stream>) -> Self {
+        Self { stream: BufReader::new(stream) }
+    }
+
+    /// Send one command line and read the reply
+    fn command(&mut self, line: &str, expect: u16) -> anyhow::Result<Vec<String>> {
+        let stream = self.stream.get_mut();
+        stream.write_all(format!("{}\r\n", line).as_bytes())?;
+        stream.flush()?;
+        self.reply(expect)
+    }
+
+    /// The lines of the next reply (without their codes), if its code is of the same kind as
+    /// `expect`: 2xx for done, 3xx for go on
+    fn reply(&mut self, expect: u16) -> anyhow::Result<Vec<String>> {
+        let mut lines = Vec::new();
+        loop {
+            let mut line = String::new();
+            if self.stream.read_line(&mut line)? == 0 {
+                anyhow::bail!("the mail server closed the connection");
+            }
+            let line = line.trim_end();
+            let code: u16 = line.get(..3)
+                .and_then(|code| code.parse().ok())
+                .ok_or_else(|| anyhow::anyhow!("unexpected reply from the mail server: {}", line))?;
+            lines.push(line.get(4..).unwrap_or_default().to_string());
+            // "250-" goes on to another line; "250 " is the last
+            if line.as_bytes().get(3) != Some(&b'-') {
+                if code / 100 != expect / 100 {
+                    anyhow::bail!("the mail server said: {}", line);
+                }
+                return Ok(lines);
+            }
+        }
+    }
+}
+
+fn send_smtp(config: &MailConfig, host: &str, message: &Message, mime: &str) -> anyhow::Result<()> {
+    let port = config.smtp_port;
+    let tcp = connect(host, port)?;
+    let stream: Box<dyn Stream> = if port == TLS_PORT { tls(host, Box::new(tcp))? } else { Box::new(tcp) };
+    let mut smtp = Smtp::new(stream);
+    smtp.reply(220)?;
+    let features = smtp.command("EHLO localhost", 250)?;
+    let username = config.smtp_username.as_deref().filter(|username| !username.is_empty());
+
+    if port != TLS_PORT {
+        let starttls = features.iter().any(|feature| feature.split_whitespace().next().is_some_and(|word| word.eq_ignore_ascii_case("STARTTLS")));
+        if starttls {
+            smtp.command("STARTTLS", 220)?;
+            smtp = Smtp::new(tls(host, smtp.stream.into_inner())?);
+            smtp.command("EHLO localhost", 250)?;
+        } else if username.is_some() {
+            anyhow::bail!("{} doesn't offer STARTTLS, so the password wasn't sent (use port 465 if it takes TLS from the start)", host);
+        }
+    }
+
+    if let Some(username) = username {
+        let password = secrets::resolve(config.smtp_password.as_deref(), "smtp")?
+            .ok_or_else(|| anyhow::anyhow!("no SMTP password is set: run tdui auth smtp"))?;
+        let login = BASE64.encode(format!("\0{}\0{}", username, password));
+        smtp.command(&format!("AUTH PLAIN {}", login), 235)?;
+    }
+    smtp.command(&format!("MAIL FROM:<{}>", address(&message.from)), 250)?;
+    smtp.command(&format!("RCPT TO:<{}>", address(&message.to)), 250)?;
+    smtp.command("DATA", 354)?;
+
+    // A line starting with a dot gets another, so it isn't taken for the end of the message
+    let mut data = String::new();
+    for line in mime.lines() {
+        if line.starts_with('.') {
+            data.push('.');
+        }
+        data.push_str(line);
+        data.push_str("\r\n");
+    }
+    data.push_str(".\r\n");
+    let stream = smtp.stream.get_mut();
+    stream.write_all(data.as_bytes())?;
+    stream.flush()?;
+    smtp.reply(250)?;
+    // The message is accepted; a server that hangs up without a goodbye changes nothing
+    let _ = smtp.command("QUIT", 221);
+    Ok(())
+}
+
+fn connect(host: &str, port: u16) -> anyhow::Result<TcpStream> {
+    let mut last_error = None;
+    for address in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&address, TIMEOUT) {
+            Ok(tcp) => {
+                tcp.set_read_timeout(Some(TIMEOUT))?;
+                tcp.set_write_timeout(Some(TIMEOUT))?;
+                return Ok(tcp);
+            }
+            Err(err) => last_error = Some(err),
+        }
+    }
+    match last_error {
+        Some(err) => anyhow::bail!("couldn't connect to {}:{}: {}", host, port, err),
+        None => anyhow::bail!("couldn't find {}", host),
+    }
+}
+
+/// `stream` with TLS on top, checked against the usual web root certificates
+fn tls(host: &str, stream: Box<dyn Stream>) -> anyhow::Result<Box<dyn Stream>> {
+    let roots = RootCertStore { roots: webpki_roots::TLS_SERVER_ROOTS.to_vec() };
+    let config = ClientConfig::builder().with_root_certificates(roots).with_no_client_auth();
+    let name = ServerName::try_from(host.to_string())?;
+    let connection = ClientConnection::new(Arc::new(config), name)?;
+    Ok(Box::new(StreamOwned::new(connection, stream)))
+}
+
+/// The bare address in "Name <me@example.com>", or the whole of a bare one
+fn address(mailbox: &str) -> &str {
+    match (mailbox.find('<'), mailbox.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &mailbox[start + 1..end],
+        _ => mailbox.trim(),
+    }
+}
+
+/// A header value as it may be sent: as it is if it's plain ASCII, else encoded (RFC 2047)
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        value.to_string()
+    } else {
+        format!("=?utf-8?B?{}?=", BASE64.encode(value))
+    }
+}
@@ -1,20 +1,167 @@
 // tuiDO - A TUI-based Todo application
 // Entry point for the application
 
-mod app;
-mod event;
-mod models;
-mod storage;
-mod ui;
-
 use crossterm::{
+    event::{DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io;
+use tdui::models::Todo;
+use tdui::import::{self, Action};
+use tdui::{app, cli, config_bundle, digest, doctor, fake, quick_add, read_later, report, secrets, slack, validate, vault};
+use tdui::digest::Digest;
+use tdui::weekly_plan::WeeklyPlan;
 
 fn main() -> anyhow::Result<()> {
+    // Parse arguments before touching the terminal so errors print normally
+    let args = cli::Args::parse()?;
+    if args.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+    if let Some(count) = args.generate_fake {
+        let path = fake::seed_storage(count)?;
+        println!("Added {} fake tasks to {}", count, path.display());
+        return Ok(());
+    }
+    if args.doctor {
+        let findings = doctor::run();
+        for finding in &findings {
+            println!("{:<8} {}: {}", finding.severity, finding.area, finding.message);
+            if let Some(fix) = &finding.fix {
+                println!("         -> {}", fix);
+            }
+        }
+        let errors = findings.iter().filter(|finding| finding.severity == doctor::Severity::Error).count();
+        let warnings = findings.iter().filter(|finding| finding.severity == doctor::Severity::Warning).count();
+        match (errors, warnings) {
+            (0, 0) => println!("Everything looks healthy"),
+            (0, n) => println!("{} warning{}, nothing broken", n, if n == 1 { "" } else { "s" }),
+            (n, _) => anyhow::bail!("found {} problem{} to fix", n, if n == 1 { "" } else { "s" }),
+        }
+        return Ok(());
+    }
+    if args.validate {
+        let report = validate::run();
+        for (path, tasks) in &report.checked {
+            match tasks {
+                Some(count) => println!("Checked {} ({} task{})", path.display(), count, if *count == 1 { "" } else { "s" }),
+                None => println!("Checked {}", path.display()),
+            }
+        }
+        for problem in &report.problems {
+            println!("  {}", problem);
+        }
+        match report.problems.len() {
+            0 => println!("No problems found"),
+            n => anyhow::bail!("found {} problem{}", n, if n == 1 { "" } else { "s" }),
+        }
+        return Ok(());
+    }
+    if let Some(input) = &args.quick_add {
+        print_added(&quick_add::add_task(input, "", args.force)?);
+        return Ok(());
+    }
+    if let Some(url) = &args.add_url {
+        print_added(&read_later::add_url(url)?);
+        return Ok(());
+    }
+    if args.report && let Some(path) = &args.html {
+        report::write_html(path)?;
+        println!("Wrote report to {}", path.display());
+        return Ok(());
+    }
+    if args.plan_week {
+        let plan = WeeklyPlan::load()?;
+        match &args.html {
+            Some(path) => {
+                std::fs::write(path, plan.to_html())
+                    .map_err(|err| anyhow::anyhow!("couldn't write {}: {}", path.display(), err))?;
+                println!("Wrote the weekly plan to {}", path.display());
+            }
+            None => print!("{}", plan.to_markdown()),
+        }
+        return Ok(());
+    }
+    if args.digest_week {
+        let digest = Digest::load()?;
+        if args.send {
+            println!("{}", digest::send_configured(&digest)?);
+            return Ok(());
+        }
+        match &args.html {
+            Some(path) => {
+                std::fs::write(path, digest.to_html())
+                    .map_err(|err| anyhow::anyhow!("couldn't write {}: {}", path.display(), err))?;
+                println!("Wrote the digest to {}", path.display());
+            }
+            None => print!("{}", digest.to_html()),
+        }
+        return Ok(());
+    }
+    if let Some(path) = &args.config_export {
+        config_bundle::export(path)?;
+        println!("Exported settings to {}", path.display());
+        return Ok(());
+    }
+    if let Some(path) = &args.config_import {
+        let projects = config_bundle::import(path)?;
+        println!("Imported settings from {} ({} project{})", path.display(), projects, if projects == 1 { "" } else { "s" });
+        return Ok(());
+    }
+    if args.vault_sync {
+        let report = vault::sync_configured()?;
+        println!("Synced the vault: {}", report.describe());
+        return Ok(());
+    }
+    if let Some((service, project)) = &args.auth {
+        println!("{}", secrets::auth(service, project.as_deref())?);
+        return Ok(());
+    }
+    if args.slack_today {
+        slack::post_today()?;
+        println!("Posted today's plan to Slack");
+        return Ok(());
+    }
+    if let Some(path) = &args.import {
+        let planned = import::import(path, args.dry_run)?;
+        let (mut created, mut merged, mut skipped) = (0, 0, 0);
+        for task in &planned {
+            match &task.action {
+                Action::Create => {
+                    created += 1;
+                    match task.todo.due_date {
+                        Some(due_date) => println!("  + {} (due {})", task.todo.title, due_date.format("%Y-%m-%d")),
+                        None => println!("  + {}", task.todo.title),
+                    }
+                }
+                Action::Merge(id, changes) => {
+                    merged += 1;
+                    println!("  ~ #{} {}: {}", id, task.todo.title, changes.join(", "));
+                }
+                Action::Skip(id, reason) => {
+                    skipped += 1;
+                    let id = id.map_or(String::new(), |id| format!("#{} ", id));
+                    println!("  = {}{}: {}", id, task.todo.title, reason);
+                }
+            }
+        }
+        if args.dry_run {
+            println!("Would create {}, merge {} and skip {} from {} (dry run, nothing saved)", created, merged, skipped, path.display());
+        } else {
+            println!("Created {}, merged {} and skipped {} from {}", created, merged, skipped, path.display());
+        }
+        return Ok(());
+    }
+    if args.add_from_stdin {
+        let text = io::read_to_string(io::stdin())?;
+        let (title, description) = quick_add::split_stdin(&text);
+        print_added(&quick_add::add_task(title, description, args.force)?);
+        return Ok(());
+    }
+
     // Initialize the terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -23,12 +170,17 @@ fn main() -> anyhow::Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     // Create and run the app
-    let mut app = app::App::new();
+    let mut app = app::App::new(&args);
+    if app.config.mouse {
+        execute!(terminal.backend_mut(), EnableMouseCapture)?;
+    }
+    // Knowing when the terminal is in the background lets tdui rest there and catch up on return
+    execute!(terminal.backend_mut(), EnableFocusChange)?;
     let result = app.run(&mut terminal);
 
     // Cleanup and restore terminal on exit
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(terminal.backend_mut(), DisableMouseCapture, DisableFocusChange, LeaveAlternateScreen)?;
     terminal.show_cursor()?;
 
     // Handle any errors that occurred during app execution
@@ -38,3 +190,17 @@ fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Confirm a task added from the command line
+fn print_added(todo: &Todo) {
+    let mut details: Vec<String> = Vec::new();
+    if let Some(due_date) = todo.due_date {
+        details.push(format!("due {}", due_date.format("%a %Y-%m-%d")));
+    }
+    details.extend(todo.context.clone());
+    if details.is_empty() {
+        println!("Added task {}: {}", todo.id, todo.title);
+    } else {
+        println!("Added task {}: {} ({})", todo.id, todo.title, details.join(", "));
+    }
+}
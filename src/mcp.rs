@@ -0,0 +1,305 @@
+// MCP module - Task tools for AI assistants, spoken as JSON-RPC 2.0 the way the Model Context
+// Protocol does (`initialize`, `tools/list`, `tools/call`), posted to the web server's /mcp.
+// Nothing here touches storage: each tool call goes to the app, which answers a read straight
+// away and asks in the TUI before adding, changing, completing or deleting a task. A call nobody
+// answers in time is turned down, and the app drops it from its queue.
+
+use crate::models::{Priority, Todo};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::sync::mpsc::{self, Sender};
+use std::time::{Duration, Instant};
+
+/// How long a tool call waits for the app, and for a yes or no in the TUI
+pub const ANSWER_TIMEOUT: Duration = Duration::from_secs(120);
+/// The protocol version answered when the client doesn't say which it speaks
+const PROTOCOL_VERSION: &str = "2025-03-26";
+/// Most tasks list_tasks hands back
+const LIST_LIMIT: usize = 100;
+
+/// What a tool call can set on a task; anything left out stays as it is
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TaskFields {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// YYYY-MM-DD
+    pub due_date: Option<NaiveDate>,
+    pub project: Option<String>,
+    pub priority: Option<Priority>,
+    pub tags: Option<Vec<String>>,
+}
+
+impl TaskFields {
+    pub fn apply(&self, todo: &mut Todo) {
+        if let Some(title) = &self.title {
+            todo.title = title.clone();
+        }
+        if let Some(description) = &self.description {
+            todo.description = description.clone();
+        }
+        if self.due_date.is_some() {
+            todo.due_date = self.due_date;
+        }
+        if let Some(project) = &self.project {
+            todo.project = Some(project.trim().to_string()).filter(|project| !project.is_empty());
+        }
+        if self.priority.is_some() {
+            todo.priority = self.priority;
+        }
+        if let Some(tags) = &self.tags {
+            todo.tags = tags.iter().filter_map(|tag| Todo::normalize_tag(tag)).collect();
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ToolCall {
+    /// Open tasks (and done ones with `include_done`) whose title, description, project or tags
+    /// contain `query`
+    ListTasks { query: Option<String>, include_done: bool },
+    GetTask { id: usize },
+    AddTask(TaskFields),
+    UpdateTask { id: usize, fields: TaskFields },
+    CompleteTask { id: usize },
+    DeleteTask { id: usize },
+}
+
+impl ToolCall {
+    /// The call named `name`, from its JSON arguments
+    fn parse(name: &str, arguments: Value) -> Result<Self, String> {
+        #[derive(Deserialize)]
+        struct Id {
+            id: usize,
+        }
+        #[derive(Deserialize)]
+        struct List {
+            #[serde(default)]
+            query: Option<String>,
+            #[serde(default)]
+            include_done: bool,
+        }
+        #[derive(Deserialize)]
+        struct Update {
+            id: usize,
+            #[serde(flatten)]
+            fields: TaskFields,
+        }
+        let arguments = if arguments.is_null() { json!({}) } else { arguments };
+        let call = match name {
+            "list_tasks" => serde_json::from_value(arguments).map(|List { query, include_done }| ToolCall::ListTasks { query, include_done }),
+            "get_task" => serde_json::from_value(arguments).map(|Id { id }| ToolCall::GetTask { id }),
+            "add_task" => serde_json::from_value(arguments).map(ToolCall::AddTask),
+            "update_task" => serde_json::from_value(arguments).map(|Update { id, fields }| ToolCall::UpdateTask { id, fields }),
+            "complete_task" => serde_json::from_value(arguments).map(|Id { id }| ToolCall::CompleteTask { id }),
+            "delete_task" => serde_json::from_value(arguments).map(|Id { id }| ToolCall::DeleteTask { id }),
+            _ => return Err(format!("No tool called '{}'", name)),
+        };
+        call.map_err(|err| format!("Bad arguments for {}: {}", name, err))
+    }
+
+    /// Whether the call changes tasks, and so needs a yes in the TUI
+    pub fn is_write(&self) -> bool {
+        !matches!(self, ToolCall::ListTasks { .. } | ToolCall::GetTask { .. })
+    }
+
+    /// What the call would do, for the question in the TUI
+    pub fn describe(&self, todos: &[Todo]) -> String {
+        let title = |id: &usize| todos.iter().find(|t| t.id == *id)
+            .map(|t| format!("#{} \"{}\"", t.id, t.title))
+            .unwrap_or_else(|| format!("#{}", id));
+        match self {
+            ToolCall::ListTasks { .. } => "List tasks".to_string(),
+            ToolCall::GetTask { id } => format!("Read {}", title(id)),
+            ToolCall::AddTask(fields) => {
+                let rest = TaskFields { title: None, ..fields.clone() };
+                format!("Add \"{}\"{}", fields.title.as_deref().unwrap_or_default(), describe_fields(&rest))
+            }
+            ToolCall::UpdateTask { id, fields } => format!("Change {}:{}", title(id), describe_fields(fields)),
+            ToolCall::CompleteTask { id } => format!("Mark {} done", title(id)),
+            ToolCall::DeleteTask { id } => format!("Delete {}", title(id)),
+        }
+    }
+}
+
+fn describe_fields(fields: &TaskFields) -> String {
+    let mut parts = Vec::new();
+    if let Some(title) = &fields.title {
+        parts.push(format!("title \"{}\"", title));
+    }
+    if fields.description.is_some() {
+        parts.push("new description".to_string());
+    }
+    if let Some(due) = fields.due_date {
+        parts.push(format!("due {}", due));
+    }
+    if let Some(project) = &fields.project {
+        parts.push(format!("project {}", project));
+    }
+    if let Some(priority) = fields.priority {
+        parts.push(format!("{} priority", priority.label()));
+    }
+    if let Some(tags) = &fields.tags {
+        parts.push(format!("tags {}", tags.join(", ")));
+    }
+    parts.iter().map(|part| format!(" {}", part)).collect::<Vec<_>>().join(",")
+}
+
+/// A tool call waiting for the app to answer it
+pub struct PendingCall {
+    pub call: ToolCall,
+    reply: Sender<Result<Value, String>>,
+    received: Instant,
+}
+
+impl PendingCall {
+    pub fn answer(self, result: Result<Value, String>) {
+        let _ = self.reply.send(result);
+    }
+
+    /// Whether the client has stopped waiting for the answer
+    pub fn expired(&self) -> bool {
+        self.received.elapsed() >= ANSWER_TIMEOUT
+    }
+}
+
+/// A task as the tools hand it out
+pub fn task_json(todo: &Todo) -> Value {
+    json!({
+        "id": todo.id,
+        "title": todo.title,
+        "description": todo.description,
+        "due_date": todo.due_date,
+        "due_time": todo.due_time.map(|time| time.format("%H:%M").to_string()),
+        "priority": todo.priority,
+        "project": todo.project,
+        "tags": todo.tags,
+        "context": todo.context,
+        "status": todo.status(),
+        "completed": todo.completed,
+        "completed_at": todo.completed_at,
+        "parent": todo.parent,
+    })
+}
+
+/// The tasks list_tasks hands back, soonest due first
+pub fn list_tasks(todos: &[Todo], query: Option<&str>, include_done: bool) -> Value {
+    let query = query.map(str::to_lowercase).filter(|query| !query.is_empty());
+    let mut found: Vec<&Todo> = todos.iter()
+        .filter(|t| !t.deleted && (include_done || !t.completed))
+        .filter(|t| query.as_ref().is_none_or(|query| {
+            t.title.to_lowercase().contains(query)
+                || t.description.to_lowercase().contains(query)
+                || t.project.as_ref().is_some_and(|project| project.to_lowercase().contains(query))
+                || t.tags.iter().any(|tag| tag.contains(query))
+        }))
+        .collect();
+    found.sort_by_key(|t| (t.completed, t.due_date.is_none(), t.due_date, t.id));
+    Value::Array(found.into_iter().take(LIST_LIMIT).map(task_json).collect())
+}
+
+/// Answer one JSON-RPC message, passing tool calls to the app through `calls`; None for a
+/// notification, which gets no answer
+pub fn handle(body: &str, calls: &Sender<PendingCall>) -> Option<String> {
+    let request: Value = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(err) => return Some(error(Value::Null, -32700, &format!("Not JSON: {}", err))),
+    };
+    // Notifications, e.g. "notifications/initialized", carry no id
+    let id = request.get("id")?.clone();
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+    let result = match request.get("method").and_then(Value::as_str).unwrap_or_default() {
+        "initialize" => json!({
+            "protocolVersion": params.get("protocolVersion").and_then(Value::as_str).unwrap_or(PROTOCOL_VERSION),
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "tdui", "version": env!("CARGO_PKG_VERSION") },
+        }),
+        "ping" => json!({}),
+        "tools/list" => json!({ "tools": tools() }),
+        "tools/call" => {
+            let name = params.get("name").and_then(Value::as_str).unwrap_or_default();
+            let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
+            match ToolCall::parse(name, arguments) {
+                Ok(call) => tool_result(call_app(call, calls)),
+                Err(message) => tool_result(Err(message)),
+            }
+        }
+        method => return Some(error(id, -32601, &format!("No method called '{}'", method))),
+    };
+    Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string())
+}
+
+/// Hand the call to the app and wait for its answer
+fn call_app(call: ToolCall, calls: &Sender<PendingCall>) -> Result<Value, String> {
+    let (reply, answer) = mpsc::channel();
+    let write = call.is_write();
+    calls.send(PendingCall { call, reply, received: Instant::now() }).map_err(|_| "tdui is closing".to_string())?;
+    answer.recv_timeout(ANSWER_TIMEOUT).unwrap_or_else(|_| Err(if write {
+        "Nobody answered the permission prompt in tdui".to_string()
+    } else {
+        "tdui didn't answer".to_string()
+    }))
+}
+
+/// A tool's answer as MCP expects it: the JSON as text, or the reason it failed
+fn tool_result(result: Result<Value, String>) -> Value {
+    match result {
+        Ok(value) => json!({ "content": [{ "type": "text", "text": value.to_string() }], "isError": false }),
+        Err(message) => json!({ "content": [{ "type": "text", "text": message }], "isError": true }),
+    }
+}
+
+fn error(id: Value, code: i32, message: &str) -> String {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }).to_string()
+}
+
+/// The tools, with their arguments as JSON Schema
+fn tools() -> Value {
+    let id = json!({ "type": "integer", "description": "The task's ID" });
+    let fields = json!({
+        "title": { "type": "string" },
+        "description": { "type": "string" },
+        "due_date": { "type": "string", "description": "YYYY-MM-DD" },
+        "project": { "type": "string" },
+        "priority": { "type": "string", "enum": ["high", "medium", "low"] },
+        "tags": { "type": "array", "items": { "type": "string" } },
+    });
+    let mut update = fields.clone();
+    update["id"] = id.clone();
+    json!([
+        {
+            "name": "list_tasks",
+            "description": "List open tasks, soonest due first; optionally only those matching a search and including done ones",
+            "inputSchema": { "type": "object", "properties": {
+                "query": { "type": "string", "description": "Text to look for in title, description, project or tags" },
+                "include_done": { "type": "boolean" },
+            } },
+        },
+        {
+            "name": "get_task",
+            "description": "Read one task by ID",
+            "inputSchema": { "type": "object", "properties": { "id": id }, "required": ["id"] },
+        },
+        {
+            "name": "add_task",
+            "description": "Add a task; the user is asked to allow it",
+            "inputSchema": { "type": "object", "properties": fields, "required": ["title"] },
+        },
+        {
+            "name": "update_task",
+            "description": "Change a task's fields; those left out stay as they are. The user is asked to allow it",
+            "inputSchema": { "type": "object", "properties": update, "required": ["id"] },
+        },
+        {
+            "name": "complete_task",
+            "description": "Mark a task done; the user is asked to allow it",
+            "inputSchema": { "type": "object", "properties": { "id": id }, "required": ["id"] },
+        },
+        {
+            "name": "delete_task",
+            "description": "Move a task to the trash; the user is asked to allow it",
+            "inputSchema": { "type": "object", "properties": { "id": id }, "required": ["id"] },
+        },
+    ])
+}
@@ -0,0 +1,35 @@
+// Blackout model - A stretch of days away (a vacation, a trip) when no work gets scheduled
+// Planning, rescheduling and triage treat these days like weekends, and the calendar shades them.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Blackout {
+    /// First and last day away, both included
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// What it's for, e.g. "Lisbon trip"
+    #[serde(default)]
+    pub label: String,
+}
+
+impl Blackout {
+    pub fn covers(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+    /// "Lisbon trip (2026-11-02 - 2026-11-06)", or just the dates without a label
+    pub fn describe(&self) -> String {
+        let dates = if self.start == self.end {
+            self.start.format("%Y-%m-%d").to_string()
+        } else {
+            format!("{} - {}", self.start.format("%Y-%m-%d"), self.end.format("%Y-%m-%d"))
+        };
+        if self.label.is_empty() {
+            dates
+        } else {
+            format!("{} ({})", self.label, dates)
+        }
+    }
+}
@@ -0,0 +1,97 @@
+// Habit model - A recurring routine checked off once per day or week, separate from tasks
+
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HabitFrequency {
+    #[default]
+    Daily,
+    /// Once at any point in the Monday-to-Sunday week
+    Weekly,
+}
+
+impl HabitFrequency {
+    pub fn label(&self) -> &'static str {
+        match self {
+            HabitFrequency::Daily => "Daily",
+            HabitFrequency::Weekly => "Weekly",
+        }
+    }
+
+    pub fn toggle(&self) -> Self {
+        match self {
+            HabitFrequency::Daily => HabitFrequency::Weekly,
+            HabitFrequency::Weekly => HabitFrequency::Daily,
+        }
+    }
+
+    /// Length of one period
+    pub fn period(&self) -> Duration {
+        match self {
+            HabitFrequency::Daily => Duration::days(1),
+            HabitFrequency::Weekly => Duration::weeks(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Habit {
+    pub id: usize,
+    pub name: String,
+    pub frequency: HabitFrequency,
+    pub created: NaiveDate,
+    /// Days the habit was checked off
+    #[serde(default)]
+    pub done: BTreeSet<NaiveDate>,
+}
+
+impl Habit {
+    pub fn new(id: usize, name: String, frequency: HabitFrequency, today: NaiveDate) -> Self {
+        Self { id, name, frequency, created: today, done: BTreeSet::new() }
+    }
+
+    /// First day of the period (the day itself, or that week's Monday) containing `date`
+    pub fn period_start(&self, date: NaiveDate) -> NaiveDate {
+        match self.frequency {
+            HabitFrequency::Daily => date,
+            HabitFrequency::Weekly => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+        }
+    }
+
+    /// Whether the habit was checked off at some point in the period containing `date`
+    pub fn is_done(&self, date: NaiveDate) -> bool {
+        let start = self.period_start(date);
+        self.done.range(start..start + self.frequency.period()).next().is_some()
+    }
+
+    /// Check the habit off for the current period, or undo that if it already was
+    pub fn toggle(&mut self, today: NaiveDate) {
+        let start = self.period_start(today);
+        let checked: Vec<NaiveDate> = self.done.range(start..start + self.frequency.period()).copied().collect();
+        if checked.is_empty() {
+            self.done.insert(today);
+        } else {
+            for date in checked {
+                self.done.remove(&date);
+            }
+        }
+    }
+
+    /// Consecutive periods checked off, up to the current one; an unchecked current period
+    /// doesn't break the streak until it's over
+    pub fn streak(&self, today: NaiveDate) -> usize {
+        let mut period = self.period_start(today);
+        if !self.is_done(period) {
+            period -= self.frequency.period();
+        }
+        let mut streak = 0;
+        while self.is_done(period) {
+            streak += 1;
+            period -= self.frequency.period();
+        }
+        streak
+    }
+}
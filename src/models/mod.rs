@@ -1,5 +1,19 @@
 // Models module - Data structures for the application
 
+mod blackout;
+mod habit;
+mod priority;
+mod project;
+mod recurrence;
+mod reminder;
+mod time_entry;
 mod todo;
 
-pub use todo::Todo;
+pub use blackout::Blackout;
+pub use habit::{Habit, HabitFrequency};
+pub use priority::Priority;
+pub use project::Project;
+pub use recurrence::{Frequency, Recurrence};
+pub use reminder::ReminderOffset;
+pub use time_entry::TimeEntry;
+pub use todo::{ParentCompletion, Todo};
@@ -0,0 +1,31 @@
+// Priority model - How important a task is; High sorts before Medium before Low
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    High,
+    Medium,
+    Low,
+}
+
+impl Priority {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Priority::High => "High",
+            Priority::Medium => "Medium",
+            Priority::Low => "Low",
+        }
+    }
+
+    /// Parse "high", "medium", "low" or their first letters (any case)
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "high" | "h" => Some(Priority::High),
+            "medium" | "med" | "m" => Some(Priority::Medium),
+            "low" | "l" => Some(Priority::Low),
+            _ => None,
+        }
+    }
+}
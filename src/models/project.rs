@@ -0,0 +1,25 @@
+// Project model - Settings for a project, kept apart from the tasks that name it
+// Tasks refer to a project by name; a project only needs settings once something is customized.
+// Whether its tasks live in their own file is up to storage, which knows from the files on disk.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    pub name: String,
+    /// A color name ("cyan") or hex ("#ff8800") for the project's swatch and calendar days
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Tags added to a task when it's put in the project
+    #[serde(default)]
+    pub default_tags: Vec<String>,
+    /// Archive the project's closed tasks after this many days instead of the configured default
+    #[serde(default)]
+    pub archive_after_days: Option<u32>,
+}
+
+impl Project {
+    pub fn new(name: String) -> Self {
+        Self { name, color: None, default_tags: Vec::new(), archive_after_days: None }
+    }
+}
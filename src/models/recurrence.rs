@@ -0,0 +1,270 @@
+// Recurrence model - How a task repeats, as an iCalendar RRULE
+// Rules are stored in RRULE form ("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE;COUNT=6") so they mean
+// the same thing here as in any calendar that reads them. The parts tdui can expand are FREQ,
+// INTERVAL, COUNT, UNTIL and plain weekdays in BYDAY; a rule using anything else is refused
+// rather than silently repeating on different days. Calendar feeds use the lenient reading,
+// which also passes over parts that only set the time of day (BYHOUR and the like) or the week
+// start, but still turns down a rule such as "the second Tuesday" it would expand wrongly.
+
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Periods searched for the next occurrence; the longest real gap is Feb 29 in a yearly rule
+const MAX_PERIODS: u32 = 1000;
+
+const WEEKDAYS: [(Weekday, &str); 7] = [
+    (Weekday::Mon, "MO"),
+    (Weekday::Tue, "TU"),
+    (Weekday::Wed, "WE"),
+    (Weekday::Thu, "TH"),
+    (Weekday::Fri, "FR"),
+    (Weekday::Sat, "SA"),
+    (Weekday::Sun, "SU"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    fn name(&self) -> &'static str {
+        match self {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match self {
+            Frequency::Daily => "day",
+            Frequency::Weekly => "week",
+            Frequency::Monthly => "month",
+            Frequency::Yearly => "year",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    /// Every how many days, weeks, months or years
+    pub interval: u32,
+    /// How many occurrences in all, counting the first
+    pub count: Option<u32>,
+    /// Last day an occurrence may fall on
+    pub until: Option<NaiveDate>,
+    /// Days of the week it falls on (daily and weekly rules), in week order; empty for the start's
+    pub by_day: Vec<Weekday>,
+}
+
+impl Recurrence {
+    /// Parse an RRULE, with or without the "RRULE:" prefix, refusing parts that can't be expanded
+    pub fn parse(input: &str) -> anyhow::Result<Self> {
+        Self::read(input, true)
+    }
+
+    /// Parse an RRULE from a calendar feed, passing over the parts that don't change which days it
+    /// falls on; None if any other part can't be expanded, so the event is shown on its first day only
+    pub fn parse_lenient(input: &str) -> Option<Self> {
+        Self::read(input, false).ok()
+    }
+
+    fn read(input: &str, strict: bool) -> anyhow::Result<Self> {
+        let input = input.trim();
+        let input = input.strip_prefix("RRULE:").unwrap_or(input);
+        let mut frequency = None;
+        let mut rule = Recurrence { frequency: Frequency::Daily, interval: 1, count: None, until: None, by_day: Vec::new() };
+
+        for part in input.split(';').filter(|part| !part.is_empty()) {
+            let Some((key, value)) = part.split_once('=') else {
+                anyhow::bail!("'{}' in the repeat rule isn't KEY=VALUE", part);
+            };
+            let value = value.to_uppercase();
+            match key.to_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => anyhow::bail!("unsupported FREQ '{}' (use DAILY, WEEKLY, MONTHLY or YEARLY)", value),
+                    });
+                }
+                "INTERVAL" => match value.parse() {
+                    Ok(interval) if interval > 0 => rule.interval = interval,
+                    _ => anyhow::bail!("INTERVAL must be a whole number above 0, not '{}'", value),
+                },
+                "COUNT" => match value.parse() {
+                    Ok(count) if count > 0 => rule.count = Some(count),
+                    _ => anyhow::bail!("COUNT must be a whole number above 0, not '{}'", value),
+                },
+                // A date, or the date part of a date-time
+                "UNTIL" => match NaiveDate::parse_from_str(value.get(..8).unwrap_or(&value), "%Y%m%d") {
+                    Ok(until) => rule.until = Some(until),
+                    Err(_) => anyhow::bail!("UNTIL must be a date like 20271231, not '{}'", value),
+                },
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        match WEEKDAYS.iter().find(|(_, name)| *name == day) {
+                            Some(&(weekday, _)) => rule.by_day.push(weekday),
+                            // Positional forms like "2MO" (the second Monday) aren't expanded
+                            None => anyhow::bail!("unsupported BYDAY '{}' (use MO, TU, WE, TH, FR, SA or SU)", day),
+                        }
+                    }
+                }
+                // The days stay the same whatever these say
+                "WKST" | "BYHOUR" | "BYMINUTE" | "BYSECOND" if !strict => {}
+                key => anyhow::bail!("{} isn't supported in repeat rules", key),
+            }
+        }
+
+        let Some(frequency) = frequency else {
+            anyhow::bail!("the repeat rule needs a FREQ, e.g. FREQ=WEEKLY");
+        };
+        rule.frequency = frequency;
+        rule.by_day.sort_by_key(|day| day.num_days_from_monday());
+        rule.by_day.dedup();
+        if strict && rule.count.is_some() && rule.until.is_some() {
+            anyhow::bail!("use COUNT or UNTIL, not both");
+        }
+        if !rule.by_day.is_empty() && matches!(frequency, Frequency::Monthly | Frequency::Yearly) {
+            anyhow::bail!("BYDAY only works with DAILY or WEEKLY rules");
+        }
+        Ok(rule)
+    }
+
+    /// Occurrence dates from `start` up to `last`, honouring COUNT and UNTIL
+    pub fn dates(&self, start: NaiveDate, last: NaiveDate) -> Vec<NaiveDate> {
+        let last = self.until.map_or(last, |until| until.min(last));
+        let limit = self.count.map_or(usize::MAX, |count| count as usize);
+        let mut dates = Vec::new();
+        for period in 0.. {
+            // Stop once a whole period starts past the end (monthly skips can leave gaps)
+            let Some((period_start, candidates)) = self.period(start, period) else {
+                return dates;
+            };
+            if period_start > last {
+                return dates;
+            }
+            for date in candidates {
+                if date > last || dates.len() >= limit {
+                    return dates;
+                }
+                dates.push(date);
+            }
+        }
+        dates
+    }
+
+    /// The occurrence after the one on `date`, with the rule for the occurrences left after it
+    /// (COUNT goes down by one), or None once the series is over
+    pub fn next_after(&self, date: NaiveDate) -> Option<(NaiveDate, Recurrence)> {
+        if self.count.is_some_and(|count| count <= 1) {
+            return None;
+        }
+        let next = (0..MAX_PERIODS)
+            .map_while(|period| self.period(date, period))
+            .flat_map(|(_, candidates)| candidates)
+            .find(|&candidate| candidate > date)?;
+        if self.until.is_some_and(|until| next > until) {
+            return None;
+        }
+        let rest = Recurrence { count: self.count.map(|count| count - 1), ..self.clone() };
+        Some((next, rest))
+    }
+
+    /// The first day of the `index`th period counting from `start`'s, and the occurrence dates
+    /// in it (none on or after `start` for a month without that day, as RFC 5545 says)
+    fn period(&self, start: NaiveDate, index: u32) -> Option<(NaiveDate, Vec<NaiveDate>)> {
+        let step = index.checked_mul(self.interval)?;
+        let on_day = |date: &NaiveDate| self.by_day.is_empty() || self.by_day.contains(&date.weekday());
+        match self.frequency {
+            Frequency::Daily => {
+                let date = start.checked_add_signed(Duration::days(step as i64))?;
+                Some((date, Some(date).filter(on_day).into_iter().collect()))
+            }
+            Frequency::Weekly if !self.by_day.is_empty() => {
+                let monday = start - Duration::days(start.weekday().num_days_from_monday() as i64);
+                let monday = monday.checked_add_signed(Duration::weeks(step as i64))?;
+                let days = self.by_day.iter()
+                    .map(|day| monday + Duration::days(day.num_days_from_monday() as i64))
+                    .filter(|date| *date >= start)
+                    .collect();
+                Some((monday.max(start), days))
+            }
+            Frequency::Weekly => {
+                let date = start.checked_add_signed(Duration::weeks(step as i64))?;
+                Some((date, vec![date]))
+            }
+            Frequency::Monthly | Frequency::Yearly => {
+                let months = if self.frequency == Frequency::Yearly { step.checked_mul(12)? } else { step };
+                let first = start.with_day(1)?.checked_add_months(Months::new(months))?;
+                let date = start.checked_add_months(Months::new(months)).filter(|date| date.day() == start.day());
+                Some((first.max(start), date.into_iter().collect()))
+            }
+        }
+    }
+
+    /// The rule in words, e.g. "every 2 weeks on Mon, Wed, 6 times"
+    pub fn describe(&self) -> String {
+        let mut text = match self.interval {
+            1 => format!("every {}", self.frequency.unit()),
+            interval => format!("every {} {}s", interval, self.frequency.unit()),
+        };
+        if !self.by_day.is_empty() {
+            let days: Vec<String> = self.by_day.iter().map(|day| day.to_string()).collect();
+            text.push_str(&format!(" on {}", days.join(", ")));
+        }
+        if let Some(count) = self.count {
+            text.push_str(&format!(", {} time{}", count, if count == 1 { "" } else { "s" }));
+        }
+        if let Some(until) = self.until {
+            text.push_str(&format!(", until {}", until.format("%Y-%m-%d")));
+        }
+        text
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FREQ={}", self.frequency.name())?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%d"))?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<&str> = self.by_day.iter()
+                .filter_map(|day| WEEKDAYS.iter().find(|(weekday, _)| weekday == day).map(|(_, name)| *name))
+                .collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<String> for Recurrence {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<Recurrence> for String {
+    fn from(rule: Recurrence) -> Self {
+        rule.to_string()
+    }
+}
@@ -0,0 +1,89 @@
+// Reminder model - How long before a task's due time a reminder should fire
+
+use chrono::{Duration, TimeDelta};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Longest offset accepted, about five years; a longer one is a typo, not a plan
+const MAX_MINUTES: i64 = 60 * 24 * 7 * 260;
+
+/// An offset before the due time, written like "1d", "2h", "30m" or "1w"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ReminderOffset {
+    minutes: i64,
+}
+
+impl ReminderOffset {
+    pub fn from_minutes(minutes: i64) -> Self {
+        Self { minutes }
+    }
+
+    /// Offsets too long for a duration (only possible through `from_minutes`) are as long as one can be
+    pub fn duration(&self) -> Duration {
+        Duration::try_minutes(self.minutes).unwrap_or(TimeDelta::MAX)
+    }
+
+    /// Parse a single offset like "1d"; a bare number means minutes
+    pub fn parse(input: &str) -> anyhow::Result<Self> {
+        let input = input.trim().to_lowercase();
+        let split = input.find(|c: char| !c.is_ascii_digit()).unwrap_or(input.len());
+        let (amount, unit) = input.split_at(split);
+        let amount: i64 = amount.parse()
+            .map_err(|_| anyhow::anyhow!("invalid reminder offset '{}'", input))?;
+
+        let per_unit = match unit {
+            "" | "m" | "min" => 1,
+            "h" => 60,
+            "d" => 60 * 24,
+            "w" => 60 * 24 * 7,
+            _ => anyhow::bail!("invalid reminder unit in '{}' (use m, h, d or w)", input),
+        };
+        // Typos like "99999999w" are turned down rather than overflowing later
+        let minutes = amount.checked_mul(per_unit)
+            .filter(|&minutes| minutes <= MAX_MINUTES)
+            .ok_or_else(|| anyhow::anyhow!("reminder offset '{}' is too far ahead (at most 260w)", input))?;
+        Ok(Self { minutes })
+    }
+
+    /// Parse a comma or space separated list like "1d, 1h"
+    pub fn parse_list(input: &str) -> anyhow::Result<Vec<Self>> {
+        input
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|part| !part.is_empty())
+            .map(Self::parse)
+            .collect()
+    }
+
+    pub fn format_list(offsets: &[Self]) -> String {
+        offsets.iter().map(|o| o.to_string()).collect::<Vec<_>>().join(", ")
+    }
+}
+
+impl fmt::Display for ReminderOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Use the largest unit that divides evenly
+        const WEEK: i64 = 60 * 24 * 7;
+        const DAY: i64 = 60 * 24;
+        match self.minutes {
+            m if m != 0 && m % WEEK == 0 => write!(f, "{}w", m / WEEK),
+            m if m != 0 && m % DAY == 0 => write!(f, "{}d", m / DAY),
+            m if m != 0 && m % 60 == 0 => write!(f, "{}h", m / 60),
+            m => write!(f, "{}m", m),
+        }
+    }
+}
+
+impl TryFrom<String> for ReminderOffset {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<ReminderOffset> for String {
+    fn from(offset: ReminderOffset) -> Self {
+        offset.to_string()
+    }
+}
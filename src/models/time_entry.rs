@@ -0,0 +1,48 @@
+// Time entry model - A stretch of time spent on a task, logged by focus sessions
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+}
+
+impl TimeEntry {
+    pub fn duration(&self) -> Duration {
+        self.ended_at - self.started_at
+    }
+
+    /// Parse a duration typed as "45m", "2h", "1h30m" or "1h 30m" (a bare number is minutes)
+    pub fn parse_duration(input: &str) -> anyhow::Result<Duration> {
+        let compact: String = input.to_lowercase().split_whitespace().collect();
+        anyhow::ensure!(!compact.is_empty(), "no duration given");
+        let mut minutes = 0;
+        let mut rest = compact.as_str();
+        while !rest.is_empty() {
+            let split = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            let amount: i64 = rest[..split].parse()
+                .map_err(|_| anyhow::anyhow!("invalid duration '{}' (use e.g. 45m or 1h30m)", input.trim()))?;
+            rest = &rest[split..];
+            let unit_len = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+            minutes += match &rest[..unit_len] {
+                "" | "m" | "min" => amount,
+                "h" => amount * 60,
+                _ => anyhow::bail!("invalid duration unit in '{}' (use h and m)", input.trim()),
+            };
+            rest = &rest[unit_len..];
+        }
+        Ok(Duration::minutes(minutes))
+    }
+
+    /// A duration as "1h 05m" or "25m"
+    pub fn format_duration(duration: Duration) -> String {
+        let minutes = duration.num_minutes();
+        if minutes >= 60 {
+            format!("{}h {:02}m", minutes / 60, minutes % 60)
+        } else {
+            format!("{}m", minutes)
+        }
+    }
+}
@@ -1,19 +1,108 @@
 // Todo model - Represents a single todo item
 
-use chrono::{DateTime, Utc, NaiveDate};
-use serde::{Deserialize, Serialize};
+use super::{Priority, Recurrence, ReminderOffset, TimeEntry};
+use chrono::{DateTime, Duration, Utc, NaiveDate, NaiveTime};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// What finishing a task's last open subtask does to the task itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParentCompletion {
+    /// Ask whether to mark it done too
+    Ask,
+    /// Mark it done too, without asking
+    Auto,
+    /// Leave it open
+    Off,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Todo {
     pub id: usize,
     pub title: String,
+    #[serde(default)]
     pub description: String,
+    #[serde(default)]
     pub completed: bool,
     #[serde(default)]
     pub deleted: bool,
     pub created_at: DateTime<Utc>,
     pub due_date: Option<NaiveDate>,
+    /// Time of day the task is due, if it has one; reminders otherwise go by the configured due time
+    #[serde(default)]
+    pub due_time: Option<NaiveTime>,
     pub completed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub context: Option<String>,
+    /// Reminder offsets for this task; None falls back to the configured defaults
+    #[serde(default)]
+    pub reminders: Option<Vec<ReminderOffset>>,
+    /// Free-form labels, stored lowercase without the leading '#'
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, deserialize_with = "unknown_as_none")]
+    pub priority: Option<Priority>,
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Time logged against the task by focus sessions
+    #[serde(default)]
+    pub time_entries: Vec<TimeEntry>,
+    /// How long the task is expected to take, in minutes
+    #[serde(default)]
+    pub estimate_minutes: Option<u32>,
+    /// Last time the task was edited, rescheduled or reviewed; None if never since it was created
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Workflow status while open, e.g. "In Progress"; None is the workflow's first status. Read
+    /// and set through `status` and `set_status`, so it can't disagree with `completed`/`deleted`
+    #[serde(default)]
+    status: Option<String>,
+    /// Who the task is waiting on, if anyone
+    #[serde(default)]
+    pub delegated_to: Option<String>,
+    /// When the task started waiting on someone else; None when it isn't waiting
+    #[serde(default)]
+    pub waiting_since: Option<DateTime<Utc>>,
+    /// Last time whoever it's waiting on was pinged about it
+    #[serde(default)]
+    pub last_pinged: Option<DateTime<Utc>>,
+    /// The task's ID in the tool it was imported from, e.g. "todotxt:42", matched on re-import
+    #[serde(default)]
+    pub source_id: Option<String>,
+    /// A web page the task is about, e.g. one saved to read later
+    #[serde(default)]
+    pub url: Option<String>,
+    /// How the task repeats; completing it adds the next occurrence as a new task
+    #[serde(default, deserialize_with = "unknown_as_none")]
+    pub recurrence: Option<Recurrence>,
+    /// Parked for some day: kept and searchable, but out of the default lists and never overdue
+    #[serde(default)]
+    pub someday: bool,
+    /// The task this is a subtask of
+    #[serde(default)]
+    pub parent: Option<usize>,
+    /// What finishing every subtask does to this task; None follows the "subtasks" config
+    #[serde(default, deserialize_with = "unknown_as_none")]
+    pub parent_completion: Option<ParentCompletion>,
+    /// Place among the tasks due the same day, set by dragging it in the list; tasks without one
+    /// follow in the order they were created
+    #[serde(default)]
+    pub rank: Option<u32>,
+    /// Fields this version doesn't know (written by a newer tdui), kept so saving doesn't drop them
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+/// Read a value this version doesn't recognise (a newer tdui's priority, say) as None instead of failing the task
+fn unknown_as_none<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).ok())
 }
 
 impl Todo {
@@ -26,12 +115,49 @@ impl Todo {
             deleted: false,
             created_at: Utc::now(),
             due_date,
+            due_time: None,
             completed_at: None,
+            context: None,
+            reminders: None,
+            tags: Vec::new(),
+            priority: None,
+            project: None,
+            time_entries: Vec::new(),
+            estimate_minutes: None,
+            updated_at: None,
+            status: None,
+            delegated_to: None,
+            waiting_since: None,
+            last_pinged: None,
+            source_id: None,
+            url: None,
+            recurrence: None,
+            someday: false,
+            parent: None,
+            parent_completion: None,
+            rank: None,
+            extra: BTreeMap::new(),
+        }
+    }
+
+    /// The open workflow status; a done or deleted task has none, whatever an older file says
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref().filter(|_| !self.completed && !self.deleted)
+    }
+
+    /// Move an open task to a workflow status (None for the first); done and deleted tasks are
+    /// past the workflow, so they're left as they are
+    pub fn set_status(&mut self, status: Option<String>) {
+        if !self.completed && !self.deleted {
+            self.status = status;
         }
     }
 
     pub fn toggle_completed(&mut self) {
         self.completed = !self.completed;
+        // Done ends the workflow, so a reopened task starts it over
+        self.status = None;
+        self.waiting_since = None;
         self.completed_at = if self.completed {
             Some(Utc::now())
         } else {
@@ -39,13 +165,163 @@ impl Todo {
         };
     }
 
+    /// The next occurrence of a repeating task as a new open task with `id`: the same details,
+    /// due on the rule's next date after this one's (or after `today` without a due date).
+    /// None if the task doesn't repeat or its series is over.
+    pub fn next_occurrence(&self, id: usize, today: NaiveDate) -> Option<Todo> {
+        let (due_date, recurrence) = self.recurrence.as_ref()?.next_after(self.due_date.unwrap_or(today))?;
+        let mut next = Todo::new(id, self.title.clone(), self.description.clone(), Some(due_date));
+        next.due_time = self.due_time;
+        next.context = self.context.clone();
+        next.reminders = self.reminders.clone();
+        next.tags = self.tags.clone();
+        next.priority = self.priority;
+        next.project = self.project.clone();
+        next.estimate_minutes = self.estimate_minutes;
+        next.recurrence = Some(recurrence);
+        next.parent = self.parent;
+        next.parent_completion = self.parent_completion;
+        Some(next)
+    }
+
+    /// Total time logged against the task
+    pub fn time_spent(&self) -> Duration {
+        self.time_entries.iter().map(TimeEntry::duration).sum()
+    }
+
+    pub fn estimate(&self) -> Option<Duration> {
+        self.estimate_minutes.map(|minutes| Duration::minutes(minutes as i64))
+    }
+
+    /// Record that the task was just changed or reviewed
+    pub fn touch(&mut self) {
+        self.updated_at = Some(Utc::now());
+    }
+
+    pub fn is_waiting(&self) -> bool {
+        self.waiting_since.is_some()
+    }
+
+    /// Start (or stop) waiting on someone else, keeping the original start if already waiting
+    pub fn set_waiting(&mut self, waiting: bool) {
+        self.waiting_since = if waiting { self.waiting_since.or(Some(Utc::now())) } else { None };
+    }
+
+    /// Whether a waiting task has gone `after_days` without a ping (counting from when it started waiting)
+    pub fn needs_ping(&self, now: DateTime<Utc>, after_days: u32) -> bool {
+        let since = self.waiting_since.into_iter().chain(self.last_pinged).max();
+        after_days > 0 && since.is_some_and(|since| now - since >= Duration::days(after_days as i64))
+    }
+
+    /// When the task was last changed or reviewed, or created if never
+    pub fn last_touched(&self) -> DateTime<Utc> {
+        self.updated_at.unwrap_or(self.created_at)
+    }
+
+    /// Open, still wanted and past its due date; someday tasks have no deadline to miss
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        !self.completed && !self.deleted && !self.someday && self.due_date.is_some_and(|d| d < today)
+    }
+
+    /// Move the task to the trash; it counts as changed, so the trash can tell how long it's been there
     pub fn mark_deleted(&mut self) {
         self.deleted = true;
+        self.touch();
+    }
+
+    pub fn in_context(&self, context: Option<&str>) -> bool {
+        match context {
+            Some(context) => self.context.as_deref() == Some(context),
+            None => true,
+        }
+    }
+
+    /// Normalize user input into a context name like "@home", or None if blank
+    pub fn normalize_context(input: &str) -> Option<String> {
+        let name = input.trim().trim_start_matches('@').to_lowercase();
+        if name.is_empty() {
+            None
+        } else {
+            Some(format!("@{}", name))
+        }
+    }
+
+    /// Normalize user input into a tag name like "urgent" (from "#Urgent"), or None if blank
+    pub fn normalize_tag(input: &str) -> Option<String> {
+        let name = input.trim().trim_start_matches('#').to_lowercase();
+        if name.is_empty() {
+            None
+        } else {
+            Some(name)
+        }
+    }
+
+    /// References to other tasks written as `#<id>` in `text`, as (byte range, id); a `#` in the
+    /// middle of a word or followed by letters (a tag) isn't one
+    pub fn task_links(text: &str) -> Vec<(Range<usize>, usize)> {
+        let bytes = text.as_bytes();
+        let word_byte = |index: usize| bytes.get(index).is_some_and(|b| b.is_ascii_alphanumeric() || *b == b'_');
+        let mut links = Vec::new();
+        for (start, _) in text.match_indices('#') {
+            if start > 0 && (word_byte(start - 1) || bytes[start - 1] == b'&') {
+                continue;
+            }
+            let digits = bytes[start + 1..].iter().take_while(|b| b.is_ascii_digit()).count();
+            let end = start + 1 + digits;
+            if digits == 0 || word_byte(end) {
+                continue;
+            }
+            if let Ok(id) = text[start + 1..end].parse() {
+                links.push((start..end, id));
+            }
+        }
+        links
+    }
+
+    /// IDs of the tasks this one's description refers to
+    pub fn linked_ids(&self) -> Vec<usize> {
+        Self::task_links(&self.description).into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// Sort tasks by due date (earliest first, undated last), then by due time, rank and creation time
+    pub fn sort_by_due(todos: &mut [Todo]) {
+        // Ranked tasks go first, in rank order
+        let by_rank = |a: &Todo, b: &Todo| {
+            (a.rank.is_none(), a.rank, a.created_at).cmp(&(b.rank.is_none(), b.rank, b.created_at))
+        };
+        todos.sort_by(|a, b| {
+            // First sort by due date (ascending, None comes last)
+            match (a.due_date, b.due_date) {
+                (Some(date_a), Some(date_b)) => {
+                    // Both have due dates, compare them
+                    match date_a.cmp(&date_b) {
+                        std::cmp::Ordering::Equal => {
+                            // If due dates are equal, timed tasks go first by time, then by rank and created date
+                            (a.due_time.is_none(), a.due_time)
+                                .cmp(&(b.due_time.is_none(), b.due_time))
+                                .then_with(|| by_rank(a, b))
+                        }
+                        other => other,
+                    }
+                }
+                (Some(_), None) => std::cmp::Ordering::Less,  // Tasks with due dates come first
+                (None, Some(_)) => std::cmp::Ordering::Greater, // Tasks without due dates come last
+                (None, None) => by_rank(a, b), // Both have no due date, sort by rank and created
+            }
+        });
+    }
+
+    /// "2026-10-16", or "2026-10-16 14:30" with a due time
+    pub fn due_label(&self, due_date: NaiveDate) -> String {
+        match self.due_time {
+            Some(time) => format!("{} {}", due_date.format("%Y-%m-%d"), time.format("%H:%M")),
+            None => due_date.format("%Y-%m-%d").to_string(),
+        }
     }
 
     pub fn display_string(&self) -> String {
         if let Some(due_date) = self.due_date {
-            format!("{} (Due: {})", self.title, due_date.format("%Y-%m-%d"))
+            format!("{} (Due: {})", self.title, self.due_label(due_date))
         } else {
             self.title.clone()
         }
@@ -0,0 +1,123 @@
+// Backend module - Where notifications go besides the footer
+// Each destination is a `Backend`: a desktop notification, the terminal bell, a webhook, a
+// command of your own, or a chat (see chat.rs). Config picks any number of them and every
+// notification goes to each one, on a background thread so a slow one never holds up the UI.
+
+use crate::config::{BackendConfig, CommandConfig, WebhookConfig};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+pub trait Backend {
+    /// Names the backend in errors, e.g. "Telegram"
+    fn name(&self) -> &'static str;
+
+    fn send(&self, title: &str, message: &str) -> anyhow::Result<()>;
+}
+
+struct Desktop;
+
+impl Backend for Desktop {
+    fn name(&self) -> &'static str {
+        "the desktop"
+    }
+
+    fn send(&self, title: &str, message: &str) -> anyhow::Result<()> {
+        notify_rust::Notification::new()
+            .appname("tdui")
+            .summary(title)
+            .body(message)
+            .show()?;
+        Ok(())
+    }
+}
+
+struct Bell;
+
+impl Backend for Bell {
+    fn name(&self) -> &'static str {
+        "the terminal bell"
+    }
+
+    fn send(&self, _title: &str, _message: &str) -> anyhow::Result<()> {
+        let mut stdout = std::io::stdout();
+        stdout.write_all(b"\x07")?;
+        stdout.flush()?;
+        Ok(())
+    }
+}
+
+impl Backend for WebhookConfig {
+    fn name(&self) -> &'static str {
+        "the webhook"
+    }
+
+    fn send(&self, title: &str, message: &str) -> anyhow::Result<()> {
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(TIMEOUT))
+            .build()
+            .into();
+        // "text" is what Slack, Mattermost and most chat webhooks show
+        let body = serde_json::json!({
+            "title": title,
+            "message": message,
+            "text": format!("{}\n{}", title, message),
+        });
+        agent.post(&self.url).header("Content-Type", "application/json").send(&body.to_string())?;
+        Ok(())
+    }
+}
+
+impl Backend for CommandConfig {
+    fn name(&self) -> &'static str {
+        "the command"
+    }
+
+    fn send(&self, title: &str, message: &str) -> anyhow::Result<()> {
+        let fill = |arg: &String| arg.replace("{title}", title).replace("{message}", message);
+        let Some((program, args)) = self.command.split_first() else {
+            anyhow::bail!("no command is set");
+        };
+        // Run directly rather than through a shell, and kept off the terminal tdui is drawing on
+        let status = Command::new(fill(program))
+            .args(args.iter().map(fill))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(|err| anyhow::anyhow!("couldn't run {}: {}", program, err))?;
+        if !status.success() {
+            anyhow::bail!("{} failed ({})", program, status);
+        }
+        Ok(())
+    }
+}
+
+impl BackendConfig {
+    pub fn backend(&self) -> &dyn Backend {
+        match self {
+            BackendConfig::Desktop => &Desktop,
+            BackendConfig::Bell => &Bell,
+            BackendConfig::Webhook(webhook) => webhook,
+            BackendConfig::Command(command) => command,
+            BackendConfig::Telegram(telegram) => telegram,
+            BackendConfig::Matrix(matrix) => matrix,
+        }
+    }
+}
+
+/// Send to every backend on another thread; the receiver gets each failure
+pub fn send_in_background(backends: Vec<BackendConfig>, title: String, message: String) -> Receiver<anyhow::Result<()>> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for config in &backends {
+            let backend = config.backend();
+            let result = backend.send(&title, &message).map_err(|err| anyhow::anyhow!("{}: {}", backend.name(), err));
+            let _ = sender.send(result);
+        }
+    });
+    receiver
+}
@@ -0,0 +1,69 @@
+// Chat backends - Notifications sent to a Telegram chat or a Matrix room, for reading on a phone
+// Both go over plain HTTPS APIs: Telegram's Bot API with a bot token, and the Matrix client-server
+// API with an access token, each from config or the keyring.
+
+use super::Backend;
+use crate::config::{MatrixConfig, TelegramConfig};
+use crate::secrets;
+use chrono::Utc;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+fn agent() -> ureq::Agent {
+    ureq::Agent::config_builder()
+        .timeout_global(Some(TIMEOUT))
+        .build()
+        .into()
+}
+
+impl Backend for TelegramConfig {
+    fn name(&self) -> &'static str {
+        "Telegram"
+    }
+
+    fn send(&self, title: &str, message: &str) -> anyhow::Result<()> {
+        let bot_token = secrets::resolve(self.bot_token.as_deref(), "telegram")?
+            .ok_or_else(|| anyhow::anyhow!("no bot token is set: run tdui auth telegram"))?;
+        let url = format!("https://api.telegram.org/bot{}/sendMessage", bot_token);
+        let text = format!("{}\n{}", title, message);
+        let body = serde_json::json!({ "chat_id": self.chat_id, "text": text }).to_string();
+        agent().post(&url).header("Content-Type", "application/json").send(&body)?;
+        Ok(())
+    }
+}
+
+impl Backend for MatrixConfig {
+    fn name(&self) -> &'static str {
+        "Matrix"
+    }
+
+    fn send(&self, title: &str, message: &str) -> anyhow::Result<()> {
+        let access_token = secrets::resolve(self.access_token.as_deref(), "matrix")?
+            .ok_or_else(|| anyhow::anyhow!("no access token is set: run tdui auth matrix"))?;
+        // Each message needs its own transaction ID, or the server treats it as a resend
+        let url = format!(
+            "{}/_matrix/client/v3/rooms/{}/send/m.room.message/tdui-{}",
+            self.homeserver.trim_end_matches('/'),
+            encode_path_segment(&self.room_id),
+            Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+        );
+        let text = format!("{}\n{}", title, message);
+        let body = serde_json::json!({ "msgtype": "m.text", "body": text }).to_string();
+        agent().put(&url)
+            .header("Authorization", &format!("Bearer {}", access_token))
+            .header("Content-Type", "application/json")
+            .send(&body)?;
+        Ok(())
+    }
+}
+
+/// Room IDs look like "!abc:example.org", so the ! and : need escaping in a URL path
+fn encode_path_segment(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
@@ -0,0 +1,83 @@
+// Notify module - Works out which task reminders are due and hands them to the UI
+// Each task fires a reminder at (due date + due time - offset) for every offset it has, falling
+// back to the global defaults when it sets none; tasks without a due time use the configured one.
+
+mod backend;
+mod chat;
+
+pub use backend::{Backend, send_in_background};
+
+use crate::clock::Clock;
+use crate::config::ReminderConfig;
+use crate::models::{ReminderOffset, Todo};
+use chrono::{DateTime, FixedOffset, NaiveDate};
+
+#[derive(Debug, Clone)]
+pub struct Reminder {
+    pub title: String,
+    pub due_date: NaiveDate,
+    pub offset: ReminderOffset,
+    pub fire_at: DateTime<FixedOffset>,
+}
+
+impl Reminder {
+    pub fn message(&self) -> String {
+        format!("Reminder: {}", self.description())
+    }
+
+    /// What's due when, e.g. "Call mom" is due 2026-10-20 (in 1d)
+    pub fn description(&self) -> String {
+        format!("\"{}\" is due {} (in {})", self.title, self.due_date.format("%Y-%m-%d"), self.offset)
+    }
+}
+
+/// Every reminder a task will fire, whether or not its time has passed
+pub fn reminders_for(todo: &Todo, config: &ReminderConfig, clock: &Clock) -> Vec<Reminder> {
+    let Some(due_date) = todo.due_date else {
+        return Vec::new();
+    };
+    // Someday tasks are parked, so their dates don't call for a nudge
+    if todo.completed || todo.deleted || todo.someday {
+        return Vec::new();
+    }
+    let Some(due_at) = clock.at(due_date, todo.due_time.unwrap_or(config.due_time)) else {
+        return Vec::new();
+    };
+
+    // An offset reaching back past the start of the calendar has no time to fire at
+    todo.reminders.as_ref().unwrap_or(&config.default_offsets)
+        .iter()
+        .filter_map(|&offset| {
+            Some(Reminder {
+                title: todo.title.clone(),
+                due_date,
+                offset,
+                fire_at: due_at.checked_sub_signed(offset.duration())?,
+            })
+        })
+        .collect()
+}
+
+/// Remembers when it last looked so each reminder fires once per session
+pub struct Notifier {
+    last_check: DateTime<FixedOffset>,
+}
+
+impl Notifier {
+    pub fn new(clock: Clock) -> Self {
+        // Reminders that came due while the app was closed aren't replayed on startup
+        Self { last_check: clock.now() }
+    }
+
+    /// Reminders whose time fell between the previous check and now, earliest first
+    pub fn poll(&mut self, todos: &[Todo], config: &ReminderConfig, clock: &Clock) -> Vec<Reminder> {
+        let now = clock.now();
+        let mut due: Vec<Reminder> = todos.iter()
+            .flat_map(|todo| reminders_for(todo, config, clock))
+            .filter(|reminder| reminder.fire_at > self.last_check && reminder.fire_at <= now)
+            .collect();
+        due.sort_by_key(|reminder| reminder.fire_at);
+        self.last_check = now;
+        due
+    }
+}
@@ -0,0 +1,95 @@
+// Planner module - "Schedule for me": spread undated and overdue tasks over upcoming working days
+// The plan is only a proposal; the App shows it in a popup and writes due dates once accepted.
+
+use crate::config::PlanningConfig;
+use crate::models::Todo;
+use chrono::{Duration, NaiveDate};
+use std::collections::HashMap;
+
+/// How far ahead the planner looks for room before leaving a task unplanned
+const HORIZON_DAYS: i64 = 90;
+
+/// How much a working day holds: a number of tasks, or hours of estimated work when
+/// `planning.daily_hours` is set (a task without an estimate then counts as none)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Capacity {
+    Tasks(usize),
+    Hours(f64),
+}
+
+impl Capacity {
+    pub fn from_config(planning: &PlanningConfig) -> Self {
+        match planning.daily_hours {
+            Some(hours) => Capacity::Hours(hours),
+            None => Capacity::Tasks(planning.daily_capacity),
+        }
+    }
+
+    /// What a day holds, in tasks or hours
+    pub fn limit(&self) -> f64 {
+        match *self {
+            Capacity::Tasks(tasks) => tasks as f64,
+            Capacity::Hours(hours) => hours,
+        }
+    }
+
+    /// What a task adds to the load of the day it's due
+    pub fn load(&self, todo: &Todo) -> f64 {
+        match self {
+            Capacity::Tasks(_) => 1.0,
+            Capacity::Hours(_) => todo.estimate_minutes.unwrap_or(0) as f64 / 60.0,
+        }
+    }
+}
+
+/// One task's proposed move
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedTask {
+    pub id: usize,
+    pub title: String,
+    /// Due date before planning (None for undated tasks)
+    pub from: Option<NaiveDate>,
+    /// Proposed due date, or None if there was no room within the horizon
+    pub to: Option<NaiveDate>,
+    /// Whether accepting the plan applies this move
+    pub include: bool,
+}
+
+/// Propose due dates for open tasks that are undated or overdue (leaving someday ones be), putting
+/// each on the first working day from today with room for it under `capacity` (counting what's
+/// already due that day); a task longer than a whole day gets a day to itself. Overdue tasks go
+/// first, oldest due date first, then undated ones in the order they were created.
+pub fn plan(todos: &[Todo], today: NaiveDate, capacity: Capacity, is_working_day: impl Fn(NaiveDate) -> bool) -> Vec<PlannedTask> {
+    let open: Vec<&Todo> = todos.iter().filter(|t| !t.completed && !t.deleted).collect();
+
+    let mut load: HashMap<NaiveDate, f64> = HashMap::new();
+    for todo in open.iter().filter(|t| !t.someday) {
+        if let Some(due_date) = todo.due_date.filter(|d| *d >= today) {
+            *load.entry(due_date).or_insert(0.0) += capacity.load(todo);
+        }
+    }
+
+    let mut candidates: Vec<&Todo> = open.into_iter()
+        .filter(|t| !t.someday && t.due_date.is_none_or(|d| d < today))
+        .collect();
+    candidates.sort_by_key(|t| (t.due_date.is_none(), t.due_date, t.created_at));
+
+    let limit = capacity.limit();
+    let days: Vec<NaiveDate> = (0..=HORIZON_DAYS)
+        .map(|offset| today + Duration::days(offset))
+        .filter(|&day| is_working_day(day))
+        .collect();
+    candidates.into_iter()
+        .map(|todo| {
+            let needed = capacity.load(todo);
+            let to = days.iter().copied().find(|day| {
+                let booked = load.get(day).copied().unwrap_or(0.0);
+                booked + needed <= limit || (booked == 0.0 && limit > 0.0)
+            });
+            if let Some(to) = to {
+                *load.entry(to).or_insert(0.0) += needed;
+            }
+            PlannedTask { id: todo.id, title: todo.title.clone(), from: todo.due_date, to, include: to.is_some() }
+        })
+        .collect()
+}
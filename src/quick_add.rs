@@ -0,0 +1,154 @@
+// Quick add module - Natural-language task capture ("buy milk tomorrow @home")
+// Used by `tdui <words>` and `tdui add -` to add a task from the shell without opening the UI.
+
+use crate::clock::Clock;
+use crate::config::Config;
+use crate::duplicate;
+use crate::models::Todo;
+use crate::storage::{Archive, FileStorage};
+use crate::template;
+use chrono::{Datelike, Days, Duration, Months, NaiveDate, Weekday};
+
+/// A task as read from one line of text
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuickAdd {
+    pub title: String,
+    pub due_date: Option<NaiveDate>,
+    pub context: Option<String>,
+}
+
+impl QuickAdd {
+    /// Pull a due date phrase and an @context out of `input`; the remaining words are the title.
+    /// Understands today, tomorrow, weekday names (the next one, counting today), "next friday"
+    /// (in the following week), "next week", "in 3 days/weeks/months" and 2026-10-20.
+    pub fn parse(input: &str, today: NaiveDate) -> Self {
+        let words: Vec<&str> = input.split_whitespace().collect();
+        let mut title: Vec<&str> = Vec::new();
+        let mut due_date = None;
+        let mut context = None;
+
+        let mut i = 0;
+        while i < words.len() {
+            let word = words[i];
+            if word.len() > 1 && word.starts_with('@') && context.is_none() {
+                context = Todo::normalize_context(word);
+                i += 1;
+                continue;
+            }
+            if due_date.is_none()
+                && let Some((date, used)) = parse_date(&words[i..], today)
+            {
+                // "on friday", "by tomorrow", "due 2026-10-20": the connecting word goes too
+                if matches!(title.last().map(|w| w.to_lowercase()).as_deref(), Some("on" | "by" | "due")) {
+                    title.pop();
+                }
+                due_date = Some(date);
+                i += used;
+                continue;
+            }
+            title.push(word);
+            i += 1;
+        }
+
+        Self { title: title.join(" "), due_date, context }
+    }
+}
+
+/// A due date typed on its own, in any form `QuickAdd::parse` understands
+pub fn parse_due_date(input: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    parse_date(&words, today).filter(|&(_, used)| used == words.len()).map(|(date, _)| date)
+}
+
+/// A date phrase at the start of `words`, and how many words it took
+fn parse_date(words: &[&str], today: NaiveDate) -> Option<(NaiveDate, usize)> {
+    let lower: Vec<String> = words.iter().take(3).map(|w| w.to_lowercase()).collect();
+    let first = lower.first()?.as_str();
+
+    match first {
+        "today" | "tonight" => return Some((today, 1)),
+        "tomorrow" | "tmr" | "tmrw" => return Some((today + Duration::days(1), 1)),
+        _ => {}
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(first, "%Y-%m-%d") {
+        return Some((date, 1));
+    }
+    if let Some(weekday) = parse_weekday(first) {
+        let ahead = (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+        return Some((today + Duration::days(ahead as i64), 1));
+    }
+
+    let second = lower.get(1).map(String::as_str);
+    if first == "next" {
+        // Monday of next week, then the named day within it
+        let next_monday = today + Duration::days(7 - today.weekday().num_days_from_monday() as i64);
+        return match second? {
+            "week" => Some((next_monday, 2)),
+            day => parse_weekday(day)
+                .map(|weekday| (next_monday + Duration::days(weekday.num_days_from_monday() as i64), 2)),
+        };
+    }
+    if first == "in" {
+        let count: u32 = second?.parse().ok()?;
+        // A count too big for the calendar isn't a date, so the words stay in the title
+        let date = match lower.get(2)?.trim_end_matches('s') {
+            "day" => today.checked_add_days(Days::new(count as u64))?,
+            "week" => today.checked_add_days(Days::new(count as u64 * 7))?,
+            "month" => today.checked_add_months(Months::new(count))?,
+            _ => return None,
+        };
+        return Some((date, 3));
+    }
+    None
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    // No "sat" or "sun": too easily part of a title ("buy sun cream")
+    match word {
+        "monday" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tue" | "tues" => Some(Weekday::Tue),
+        "wednesday" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thu" | "thur" | "thurs" => Some(Weekday::Thu),
+        "friday" | "fri" => Some(Weekday::Fri),
+        "saturday" => Some(Weekday::Sat),
+        "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Split piped text into the title line (first non-blank line) and the description (the rest)
+pub fn split_stdin(text: &str) -> (&str, &str) {
+    let text = text.trim_start_matches(['\r', '\n']);
+    let (title, description) = text.split_once('\n').unwrap_or((text, ""));
+    (title.trim(), description.trim_matches(['\r', '\n']).trim_end())
+}
+
+/// Parse `input` and save it as a new task with `description`, in the active context unless it names one;
+/// a "/name" title stamps out that template. Unless `force`, an open task with nearly the same title stops it
+pub fn add_task(input: &str, description: &str, force: bool) -> anyhow::Result<Todo> {
+    let config = Config::load(Config::get_default_path());
+    let clock = Clock::from_config_or_warn(&config);
+    let parsed = QuickAdd::parse(input, clock.today());
+    if parsed.title.is_empty() {
+        anyhow::bail!("nothing to add: the task needs a title");
+    }
+
+    let mut storage = FileStorage::open(FileStorage::get_default_path());
+    // Refuse to overwrite a file we couldn't read
+    storage.load_todos()?;
+    if !force
+        && config.confirm.duplicates
+        && let Some(existing) = duplicate::find(&parsed.title, storage.todos())
+    {
+        anyhow::bail!("task {} looks the same and is still open: {} (add it anyway with --force)", existing.id, existing.title);
+    }
+
+    let id = storage.next_free_id(&Archive::open(Archive::get_default_path()));
+
+    let (title, description) = template::resolve(&config.templates, &parsed.title, description, clock.today());
+    let mut todo = Todo::new(id, title, description, parsed.due_date);
+    todo.context = parsed.context.or(config.active_context);
+    storage.upsert(todo.clone());
+    storage.flush()?;
+    Ok(todo)
+}
@@ -0,0 +1,115 @@
+// Read later module - Tasks made from web links, titled with the page's own title
+// `tdui add-url <URL>` and the in-app `U` both keep the link on the task (shown as "Link" in its
+// details). The title is the page's <title>; when the page can't be fetched or has none, the
+// task keeps the URL as its title. In the app the page is fetched in the background, so the task
+// shows up straight away and is retitled once the title arrives.
+
+use crate::config::Config;
+use crate::models::Todo;
+use crate::storage::{Archive, FileStorage};
+use std::io::Read;
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How much of a page is read looking for its title
+const MAX_BYTES: u64 = 512 * 1024;
+
+/// The link as typed, if it's an http(s) URL
+pub fn check_url(input: &str) -> anyhow::Result<String> {
+    let url = input.trim();
+    if !(url.starts_with("http://") || url.starts_with("https://")) || url.contains(char::is_whitespace) {
+        anyhow::bail!("\"{}\" isn't a web link (it should start with http:// or https://)", url);
+    }
+    Ok(url.to_string())
+}
+
+/// Fetch `url` and read the title of the page
+pub fn fetch_title(url: &str) -> anyhow::Result<String> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(TIMEOUT))
+        .build()
+        .into();
+    let mut response = agent.get(url).call()?;
+    let mut page = Vec::new();
+    response.body_mut().as_reader().take(MAX_BYTES).read_to_end(&mut page)?;
+    page_title(&String::from_utf8_lossy(&page)).ok_or_else(|| anyhow::anyhow!("the page has no title"))
+}
+
+/// Fetch the title of `url` on another thread; the receiver gets it with the task's ID
+pub fn fetch_title_in_background(id: usize, url: String) -> Receiver<(usize, anyhow::Result<String>)> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send((id, fetch_title(&url)));
+    });
+    receiver
+}
+
+/// The text of the page's <title>, on one line with entities decoded
+fn page_title(html: &str) -> Option<String> {
+    // ASCII lowercasing keeps every byte where it was, so offsets carry over to `html`
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    let title = decode_entities(&html[start..end]).split_whitespace().collect::<Vec<_>>().join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let entity = rest.find(';').filter(|&end| end <= 10).map(|end| &rest[1..end]);
+        let character = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => match entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                None => entity.strip_prefix('#').and_then(|decimal| decimal.parse().ok()).and_then(char::from_u32),
+            },
+        });
+        match (entity, character) {
+            (Some(entity), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Add a task for `url` titled with its page's title, for `tdui add-url`
+pub fn add_url(url: &str) -> anyhow::Result<Todo> {
+    let url = check_url(url)?;
+    let config = Config::load(Config::get_default_path());
+    let title = fetch_title(&url).unwrap_or_else(|err| {
+        eprintln!("Warning: couldn't read the page title ({}), using the link", err);
+        url.clone()
+    });
+
+    let mut storage = FileStorage::open(FileStorage::get_default_path());
+    // Refuse to overwrite a file we couldn't read
+    storage.load_todos()?;
+
+    let id = storage.next_free_id(&Archive::open(Archive::get_default_path()));
+
+    let mut todo = Todo::new(id, title, String::new(), None);
+    todo.url = Some(url);
+    todo.context = config.active_context;
+    storage.upsert(todo.clone());
+    storage.flush()?;
+    Ok(todo)
+}
@@ -0,0 +1,171 @@
+// Report module - A static HTML page of the Stats tab, for sharing or archiving
+// Used by `tdui report --html out.html`: the same Stats engine as the UI, with the charts
+// drawn as inline SVG so the file has no outside dependencies.
+
+use crate::clock::Clock;
+use crate::config::Config;
+use crate::models::{Habit, Todo};
+use crate::stats::{HISTORY_DAYS, Stats};
+use crate::storage::{Archive, FileStorage, HabitStorage};
+use chrono::Duration;
+use std::fmt::Write;
+use std::path::Path;
+
+const CHART_WIDTH: f64 = 720.0;
+const CHART_HEIGHT: f64 = 200.0;
+
+/// Read the stored tasks and habits and write the report to `path`
+pub fn write_html(path: &Path) -> anyhow::Result<()> {
+    let config = Config::load(Config::get_default_path());
+    let clock = Clock::from_config_or_warn(&config);
+
+    let storage = FileStorage::open(FileStorage::get_default_path());
+    storage.load_todos()?;
+    let habit_storage = HabitStorage::open(HabitStorage::get_default_path());
+    let archive = Archive::open(Archive::get_default_path());
+
+    let context = config.active_context.as_deref();
+    let today = clock.today();
+    let todos: Vec<Todo> = storage.todos().iter()
+        .filter(|t| t.in_context(context))
+        .cloned()
+        .collect();
+    let archived: Vec<Todo> = archive.load_range(today - Duration::days(HISTORY_DAYS), today)
+        .into_iter()
+        .filter(|t| t.in_context(context))
+        .collect();
+    let stats = Stats::compute(&todos, &archived, archive.totals(context), habit_storage.habits(), &config.workflow, &clock);
+
+    let html = render_html(&todos, &stats, habit_storage.habits(), context, &clock);
+    std::fs::write(path, html)
+        .map_err(|err| anyhow::anyhow!("couldn't write {}: {}", path.display(), err))
+}
+
+/// The whole report page
+pub fn render_html(todos: &[Todo], stats: &Stats, habits: &[Habit], context: Option<&str>, clock: &Clock) -> String {
+    let today = clock.today();
+    let mut open: Vec<&Todo> = todos.iter().filter(|t| !t.completed && !t.deleted).collect();
+    open.sort_by_key(|t| (t.due_date.is_none(), t.due_date, t.id));
+    let (overdue, open): (Vec<&Todo>, Vec<&Todo>) = open.into_iter()
+        .partition(|t| t.is_overdue(today));
+
+    let mut html = String::new();
+    let title = match context {
+        Some(context) => format!("tdui report: {}", context),
+        None => "tdui report".to_string(),
+    };
+    let _ = write!(html, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n",
+        escape(&title), STYLE);
+    let _ = writeln!(html, "<h1>{}</h1>\n<p class=\"muted\">Generated {}</p>", escape(&title), clock.now().format("%a %Y-%m-%d %H:%M"));
+
+    // Headline numbers
+    let days = |value: Option<f64>| value.map_or("-".to_string(), |days| format!("{:.1} days", days));
+    html.push_str("<section class=\"numbers\">\n");
+    for (label, value) in [
+        ("Open", stats.todo_count.to_string()),
+        ("Overdue", stats.overdue_count.to_string()),
+        ("Done", stats.done_count.to_string()),
+        ("Deleted", stats.deleted_count.to_string()),
+        ("Mean time to done", days(stats.mean_time_to_done)),
+        ("Average backlog age", days(stats.average_backlog_age)),
+    ].into_iter().chain(stats.status_counts.iter().map(|(name, count)| (name.as_str(), count.to_string()))) {
+        let _ = writeln!(html, "<div><span class=\"value\">{}</span><span class=\"label\">{}</span></div>", escape(&value), escape(label));
+    }
+    html.push_str("</section>\n");
+
+    let _ = writeln!(html, "<h2>Last {} days</h2>", HISTORY_DAYS);
+    html.push_str(&line_chart(stats));
+
+    let _ = writeln!(html, "<h2>Overdue ({})</h2>", overdue.len());
+    html.push_str(&task_table(&overdue, clock));
+    let _ = writeln!(html, "<h2>Open ({})</h2>", open.len());
+    html.push_str(&task_table(&open, clock));
+
+    if !habits.is_empty() {
+        html.push_str("<h2>Habit streaks</h2>\n<table>\n<tr><th>Habit</th><th>Every</th><th>Streak</th></tr>\n");
+        for habit in habits {
+            let _ = writeln!(html, "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape(&habit.name), habit.frequency.label(), habit.streak(today));
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Created, completed and overdue tasks per day, as on the Stats tab
+fn line_chart(stats: &Stats) -> String {
+    let series = [
+        ("Created", "created", &stats.created),
+        ("Completed", "completed", &stats.completed),
+        ("Overdue", "overdue", &stats.overdue),
+    ];
+    let max = series.iter()
+        .flat_map(|(_, _, points)| points.iter().map(|&(_, count)| count))
+        .fold(1.0, f64::max);
+    let x = |offset: f64| offset / HISTORY_DAYS as f64 * CHART_WIDTH;
+    let y = |count: f64| CHART_HEIGHT - count / max * CHART_HEIGHT;
+
+    let mut svg = String::new();
+    let _ = writeln!(svg, "<svg viewBox=\"-30 -10 {} {}\" role=\"img\" aria-label=\"Tasks per day\">",
+        CHART_WIDTH + 40.0, CHART_HEIGHT + 40.0);
+    let _ = writeln!(svg, "<line class=\"axis\" x1=\"0\" y1=\"{h}\" x2=\"{w}\" y2=\"{h}\"/><line class=\"axis\" x1=\"0\" y1=\"0\" x2=\"0\" y2=\"{h}\"/>",
+        w = CHART_WIDTH, h = CHART_HEIGHT);
+    let _ = writeln!(svg, "<text x=\"-6\" y=\"4\" text-anchor=\"end\">{}</text><text x=\"-6\" y=\"{}\" text-anchor=\"end\">0</text>",
+        max, CHART_HEIGHT + 4.0);
+    let end = stats.start + Duration::days(HISTORY_DAYS);
+    let _ = writeln!(svg, "<text x=\"0\" y=\"{y}\">{}</text><text x=\"{}\" y=\"{y}\" text-anchor=\"end\">{}</text>",
+        stats.start.format("%Y-%m-%d"), CHART_WIDTH, end.format("%Y-%m-%d"), y = CHART_HEIGHT + 18.0);
+    for (index, (label, class, points)) in series.iter().enumerate() {
+        let coordinates: Vec<String> = points.iter()
+            .map(|&(offset, count)| format!("{:.1},{:.1}", x(offset), y(count)))
+            .collect();
+        let _ = writeln!(svg, "<polyline class=\"{}\" points=\"{}\"/>", class, coordinates.join(" "));
+        let _ = writeln!(svg, "<text class=\"{}\" x=\"{}\" y=\"{}\">{}</text>",
+            class, 200.0 + index as f64 * 120.0, CHART_HEIGHT + 18.0, label);
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn task_table(todos: &[&Todo], clock: &Clock) -> String {
+    if todos.is_empty() {
+        return "<p class=\"muted\">None</p>\n".to_string();
+    }
+    let today = clock.today();
+    let mut table = String::from("<table>\n<tr><th>#</th><th>Task</th><th>Due</th><th>Context</th><th>Open for</th></tr>\n");
+    for todo in todos {
+        let due = todo.due_date.map_or(String::new(), |d| d.format("%a %Y-%m-%d").to_string());
+        let age = (today - clock.date_of(todo.created_at)).num_days().max(0);
+        let _ = writeln!(table, "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}d</td></tr>",
+            todo.id, escape(&todo.title), due, escape(todo.context.as_deref().unwrap_or("")), age);
+    }
+    table.push_str("</table>\n");
+    table
+}
+
+/// Make text safe to put in HTML content or attributes
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = "
+body { font-family: system-ui, sans-serif; max-width: 860px; margin: 2em auto; padding: 0 1em; color: #222; }
+.muted { color: #777; }
+.numbers { display: flex; flex-wrap: wrap; gap: 1em; }
+.numbers div { border: 1px solid #ddd; border-radius: 6px; padding: 0.6em 1em; min-width: 8em; }
+.numbers .value { display: block; font-size: 1.5em; font-weight: bold; }
+.numbers .label { color: #777; font-size: 0.9em; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: 0.3em 0.6em; border-bottom: 1px solid #eee; }
+svg { width: 100%; height: auto; font-size: 12px; }
+svg .axis { stroke: #999; }
+svg polyline { fill: none; stroke-width: 2; }
+polyline.created { stroke: #1f77b4; } text.created { fill: #1f77b4; }
+polyline.completed { stroke: #2ca02c; } text.completed { fill: #2ca02c; }
+polyline.overdue { stroke: #d62728; } text.overdue { fill: #d62728; }
+";
@@ -0,0 +1,246 @@
+// Search index - Inverted index over task titles and descriptions
+// The index is persisted next to the todos file and only re-tokenizes tasks whose
+// content changed since the last sync, so large histories stay cheap to search.
+
+use crate::models::Todo;
+use crate::storage::FileStorage;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum TaskSource {
+    Active,
+    /// A daily note rather than a task (never stored in the index)
+    Note,
+    /// Open but parked for some day
+    Someday,
+    Completed,
+    /// Closed and moved out of todos.json into the archive
+    Archived,
+    Deleted,
+}
+
+impl TaskSource {
+    pub fn of(todo: &Todo) -> Self {
+        if todo.deleted {
+            TaskSource::Deleted
+        } else if todo.completed {
+            TaskSource::Completed
+        } else if todo.someday {
+            TaskSource::Someday
+        } else {
+            TaskSource::Active
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TaskSource::Active => "Active",
+            TaskSource::Note => "Note",
+            TaskSource::Completed => "Done",
+            TaskSource::Archived => "Archived",
+            TaskSource::Deleted => "Deleted",
+            TaskSource::Someday => "Someday",
+        }
+    }
+}
+
+/// A task, or for notes the note's first line with `due_date` set to its day (`id` is unused)
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub id: usize,
+    pub source: TaskSource,
+    pub title: String,
+    pub due_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedDoc {
+    fingerprint: u64,
+    source: TaskSource,
+    title: String,
+    due_date: Option<NaiveDate>,
+    terms: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SearchIndex {
+    docs: HashMap<usize, IndexedDoc>,
+    postings: BTreeMap<String, BTreeSet<usize>>,
+    #[serde(skip)]
+    file_path: PathBuf,
+}
+
+impl SearchIndex {
+    pub fn open(file_path: PathBuf) -> Self {
+        // A missing or unreadable index is just a cold cache - it gets rebuilt on the next sync
+        let mut index = fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<SearchIndex>(&contents).ok())
+            .unwrap_or_default();
+        index.file_path = file_path;
+        index
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        if let Some(parent) = self.file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let json = serde_json::to_string(self)?;
+        fs::write(&self.file_path, json)?;
+
+        Ok(())
+    }
+
+    pub fn get_default_path() -> PathBuf {
+        // Keep the index alongside todos.json: ~/.local/share/tdui/search_index.json
+        FileStorage::get_default_path().with_file_name("search_index.json")
+    }
+
+    /// Bring the index in line with the stored tasks and the archived ones, returning true if
+    /// anything changed
+    pub fn sync(&mut self, todos: &[Todo], archived: &[Todo]) -> bool {
+        let mut changed = false;
+
+        // A task in both (archived, then restored by a sync) counts as stored
+        let stored_ids: BTreeSet<usize> = todos.iter().map(|t| t.id).collect();
+        let tasks: Vec<(&Todo, TaskSource)> = todos.iter()
+            .map(|todo| (todo, TaskSource::of(todo)))
+            .chain(archived.iter().filter(|todo| !stored_ids.contains(&todo.id)).map(|todo| (todo, TaskSource::Archived)))
+            .collect();
+
+        // Drop documents for tasks that no longer exist
+        let live_ids: BTreeSet<usize> = tasks.iter().map(|(todo, _)| todo.id).collect();
+        let stale_ids: Vec<usize> = self.docs.keys()
+            .filter(|id| !live_ids.contains(id))
+            .copied()
+            .collect();
+        for id in stale_ids {
+            self.remove_doc(id);
+            changed = true;
+        }
+
+        // Re-index only the tasks whose content or state changed
+        for (todo, source) in tasks {
+            let fingerprint = fingerprint(todo, source);
+            if self.docs.get(&todo.id).map(|doc| doc.fingerprint) == Some(fingerprint) {
+                continue;
+            }
+
+            self.remove_doc(todo.id);
+            let terms = tokenize(&format!("{} {}", todo.title, todo.description));
+            for term in &terms {
+                self.postings.entry(term.clone()).or_default().insert(todo.id);
+            }
+            self.docs.insert(todo.id, IndexedDoc {
+                fingerprint,
+                source,
+                title: todo.title.clone(),
+                due_date: todo.due_date,
+                terms,
+            });
+            changed = true;
+        }
+
+        changed
+    }
+
+    /// Find tasks containing every query term (the terms match as prefixes, so results update as you type)
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let terms = tokenize(query);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matching: Option<BTreeSet<usize>> = None;
+        for term in &terms {
+            let ids: BTreeSet<usize> = self.postings
+                .range(term.clone()..)
+                .take_while(|(key, _)| key.starts_with(term.as_str()))
+                .flat_map(|(_, ids)| ids.iter().copied())
+                .collect();
+
+            matching = Some(match matching {
+                Some(previous) => previous.intersection(&ids).copied().collect(),
+                None => ids,
+            });
+        }
+
+        let mut hits: Vec<SearchHit> = matching
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|id| {
+                self.docs.get(&id).map(|doc| SearchHit {
+                    id,
+                    source: doc.source,
+                    title: doc.title.clone(),
+                    due_date: doc.due_date,
+                })
+            })
+            .collect();
+
+        // Active tasks first, newest first within each group
+        hits.sort_by(|a, b| a.source.cmp(&b.source).then(b.id.cmp(&a.id)));
+        hits
+    }
+
+    fn remove_doc(&mut self, id: usize) {
+        if let Some(doc) = self.docs.remove(&id) {
+            for term in doc.terms {
+                if let Some(ids) = self.postings.get_mut(&term) {
+                    ids.remove(&id);
+                    if ids.is_empty() {
+                        self.postings.remove(&term);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Find daily notes containing every query term, newest first. Notes are few and short,
+/// so they're matched directly instead of going through the index.
+pub fn search_notes(notes: &BTreeMap<NaiveDate, String>, query: &str) -> Vec<SearchHit> {
+    let terms = tokenize(query);
+    if terms.is_empty() {
+        return Vec::new();
+    }
+
+    notes.iter()
+        .rev()
+        .filter(|(_, text)| {
+            let words = tokenize(text);
+            terms.iter().all(|term| words.iter().any(|word| word.starts_with(term.as_str())))
+        })
+        .map(|(date, text)| SearchHit {
+            id: 0,
+            source: TaskSource::Note,
+            title: text.lines().find(|line| !line.trim().is_empty()).unwrap_or_default().trim().to_string(),
+            due_date: Some(*date),
+        })
+        .collect()
+}
+
+fn fingerprint(todo: &Todo, source: TaskSource) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    todo.title.hash(&mut hasher);
+    todo.description.hash(&mut hasher);
+    todo.due_date.hash(&mut hasher);
+    source.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    let terms: BTreeSet<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect();
+    terms.into_iter().collect()
+}
@@ -0,0 +1,5 @@
+// Search module - Full-text search across every task in storage and the archive, and the daily notes
+
+mod index;
+
+pub use index::{search_notes, SearchHit, SearchIndex, TaskSource};
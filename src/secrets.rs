@@ -0,0 +1,137 @@
+// Secrets module - Tokens and webhook URLs for integrations, kept in the OS keyring
+// `tdui auth <service>` stores one in the macOS Keychain, the Windows Credential Manager or the
+// Secret Service on Linux, so it never has to sit in config.json as plain text. A value set in
+// config still wins, for machines without a keyring.
+
+use crate::config::{BackendConfig, Config};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use keyring::Entry;
+use std::io::{BufRead, IsTerminal, Write};
+
+/// Every secret is filed under this service name, keyed by what it's for
+const SERVICE: &str = "tdui";
+
+/// What `tdui auth` can store: (service, what it asks for)
+pub const SERVICES: &[(&str, &str)] = &[
+    ("slack", "Slack webhook URL"),
+    ("telegram", "Telegram bot token"),
+    ("matrix", "Matrix access token"),
+    ("smtp", "SMTP password"),
+];
+
+/// The secret stored under `name`, if any
+pub fn get(name: &str) -> anyhow::Result<Option<String>> {
+    match Entry::new(SERVICE, name)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => anyhow::bail!("couldn't read the {} secret from the keyring ({}); set it in config.json instead", name, err),
+    }
+}
+
+/// The value set in config if there is one, else the one stored in the keyring under `name`
+pub fn resolve(configured: Option<&str>, name: &str) -> anyhow::Result<Option<String>> {
+    match configured.filter(|value| !value.is_empty()) {
+        Some(value) => Ok(Some(value.to_string())),
+        None => get(name),
+    }
+}
+
+/// Keyring name of the Slack webhook for `project`, or the default one
+pub fn slack_name(project: Option<&str>) -> String {
+    match project {
+        Some(project) => format!("slack/{}", project),
+        None => "slack".to_string(),
+    }
+}
+
+/// `tdui auth <service> [PROJECT]`: ask for the secret, store it (an empty answer removes it) and
+/// take any plain-text copy out of config.json; returns what was done
+pub fn auth(service: &str, project: Option<&str>) -> anyhow::Result<String> {
+    let Some(&(_, label)) = SERVICES.iter().find(|(name, _)| *name == service) else {
+        let names: Vec<&str> = SERVICES.iter().map(|(name, _)| *name).collect();
+        anyhow::bail!("unknown service '{}' (use one of: {})", service, names.join(", "));
+    };
+    if project.is_some() && service != "slack" {
+        anyhow::bail!("only Slack webhooks can be set per project");
+    }
+    let label = match project {
+        Some(project) => format!("{} for {}", label, project),
+        None => label.to_string(),
+    };
+    let name = if service == "slack" { slack_name(project) } else { service.to_string() };
+
+    let secret = read_secret(&format!("{} (leave empty to remove it)", label))?;
+    let entry = Entry::new(SERVICE, &name)?;
+    let mut done = if secret.is_empty() {
+        match entry.delete_credential() {
+            Ok(()) => format!("Removed the {} from the keyring", label),
+            Err(keyring::Error::NoEntry) => format!("No {} was stored", label),
+            Err(err) => anyhow::bail!("couldn't remove the {} from the keyring: {}", label, err),
+        }
+    } else {
+        entry.set_password(&secret)
+            .map_err(|err| anyhow::anyhow!("couldn't store the {} in the keyring: {}", label, err))?;
+        format!("Stored the {} in the keyring", label)
+    };
+
+    // Otherwise the copy in config would keep winning over the keyring
+    let mut config = Config::load(Config::get_default_path());
+    let had_copy = match (service, project) {
+        ("slack", Some(project)) => config.slack.projects.remove(project).is_some(),
+        ("slack", None) => config.slack.webhook_url.take().is_some(),
+        ("smtp", _) => config.mail.smtp_password.take().is_some(),
+        (service, _) => {
+            let taken = config.notifications.backends.iter_mut().filter_map(|backend| match (service, backend) {
+                ("telegram", BackendConfig::Telegram(telegram)) => telegram.bot_token.take(),
+                ("matrix", BackendConfig::Matrix(matrix)) => matrix.access_token.take(),
+                _ => None,
+            });
+            taken.count() > 0
+        }
+    };
+    if had_copy {
+        config.save()?;
+        done.push_str(", and took the plain-text copy out of config.json");
+    }
+    Ok(done)
+}
+
+/// Read a line without echoing it when typed at a terminal; piped input is read as it is
+fn read_secret(prompt: &str) -> anyhow::Result<String> {
+    if !std::io::stdin().is_terminal() {
+        let mut line = String::new();
+        std::io::stdin().lock().read_line(&mut line)?;
+        return Ok(line.trim().to_string());
+    }
+
+    eprint!("{}: ", prompt);
+    std::io::stderr().flush()?;
+    enable_raw_mode()?;
+    let result = read_hidden_line();
+    disable_raw_mode()?;
+    eprintln!();
+    result.map(|secret| secret.trim().to_string())
+}
+
+fn read_hidden_line() -> anyhow::Result<String> {
+    let mut secret = String::new();
+    loop {
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Enter => return Ok(secret),
+            KeyCode::Esc => anyhow::bail!("cancelled"),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => anyhow::bail!("cancelled"),
+            KeyCode::Char(c) => secret.push(c),
+            KeyCode::Backspace => {
+                secret.pop();
+            }
+            _ => {}
+        }
+    }
+}
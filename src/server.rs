@@ -0,0 +1,412 @@
+// Server module - A read-only web dashboard served while tdui is open, for a glance at the day's
+// tasks from a phone on the LAN. The app publishes the JSON whenever its tasks (or the day)
+// change, so answering a request is only handing out the latest copy; each connection gets a
+// thread of its own and a slow client never holds up the UI. /api/events is a WebSocket that
+// pushes each task change from storage's event bus as it happens, sent only once the JSON it
+// affects has been published, so a page that refetches on a change sees it; its thread stays to
+// answer the client's pings and close, and pings the client itself so a vanished one is let go.
+// With `tools` on, POST /mcp takes task tool calls from AI assistants (see mcp.rs) and waits for
+// the app's answer. Listening beyond this machine, or taking tool calls, needs a token.
+
+use crate::clock::Clock;
+use crate::config::ServerConfig;
+use crate::mcp::{self, PendingCall};
+use crate::models::Todo;
+use crate::stats::Stats;
+use crate::storage::Entry;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde_json::json;
+use sha1::{Digest, Sha1};
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How long a client gets to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long a WebSocket client may leave a change unread before it's dropped
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+/// How often WebSocket clients are pinged when no change has gone out
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+/// How long a WebSocket client may stay silent, pongs included, before it's taken as gone
+const IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+/// The largest request body read, for a tool call
+const MAX_BODY: usize = 1 << 20;
+/// Appended to a client's key to prove the handshake was understood (RFC 6455)
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+/// WebSocket frame types (RFC 6455 section 5.2)
+const TEXT: u8 = 0x1;
+const CLOSE: u8 = 0x8;
+const PING: u8 = 0x9;
+const PONG: u8 = 0xA;
+
+/// The open WebSocket connections, by the client's address, for sending changes to
+type Sockets = Mutex<Vec<(SocketAddr, TcpStream)>>;
+
+/// The latest JSON for each endpoint
+#[derive(Debug, Default)]
+struct Published {
+    today: String,
+    stats: String,
+}
+
+pub struct Server {
+    published: Arc<Mutex<Published>>,
+    /// Changes from storage not yet passed on to the WebSocket clients
+    events: Receiver<Entry>,
+    /// The changes to send, as messages, to the thread that writes to the WebSocket clients
+    outbox: Sender<String>,
+    /// Tool calls from assistants, for the app to answer
+    calls: Receiver<PendingCall>,
+}
+
+impl Server {
+    /// Listen on `listen` (e.g. "0.0.0.0:8080") and answer requests on a thread of its own;
+    /// the changes from `events` go out to WebSocket clients as the JSON is published. Without a
+    /// token it only starts on a loopback address, and never with tools on
+    pub fn start(listen: &str, config: &ServerConfig, events: Receiver<Entry>) -> anyhow::Result<Self> {
+        let token = config.token.clone().filter(|token| !token.is_empty());
+        let listener = TcpListener::bind(listen).map_err(|err| anyhow::anyhow!("couldn't listen on {}: {}", listen, err))?;
+        if token.is_none() {
+            anyhow::ensure!(!config.tools, "tools need a token set under \"server\"");
+            let address = listener.local_addr()?;
+            anyhow::ensure!(address.ip().is_loopback(), "{} can be reached from other machines, so set a token under \"server\" (or listen on 127.0.0.1)", listen);
+        }
+        let published = Arc::new(Mutex::new(Published::default()));
+        let sockets: Arc<Sockets> = Arc::new(Mutex::new(Vec::new()));
+        let (caller, calls) = mpsc::channel();
+        // Without `tools` there's nobody to take the calls
+        let caller = config.tools.then_some(caller);
+        let shared = Arc::clone(&published);
+        let listeners = Arc::clone(&sockets);
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let published = Arc::clone(&shared);
+                let sockets = Arc::clone(&listeners);
+                let token = token.clone();
+                let caller = caller.clone();
+                std::thread::spawn(move || {
+                    let _ = answer(stream, &published, &sockets, caller.as_ref(), token.as_deref());
+                });
+            }
+        });
+
+        // A client that can't keep up, or has gone, is dropped rather than waited for
+        let (outbox, inbox) = mpsc::channel::<String>();
+        std::thread::spawn(move || {
+            loop {
+                let (opcode, message) = match inbox.recv_timeout(PING_INTERVAL) {
+                    Ok(message) => (TEXT, message),
+                    Err(RecvTimeoutError::Timeout) => (PING, String::new()),
+                    Err(RecvTimeoutError::Disconnected) => return,
+                };
+                if let Ok(mut sockets) = sockets.lock() {
+                    sockets.retain_mut(|(_, socket)| send_frame(socket, opcode, message.as_bytes()).is_ok());
+                }
+            }
+        });
+        Ok(Self { published, events, outbox, calls })
+    }
+
+    /// The tool calls that came in since last asked
+    pub fn tool_calls(&self) -> impl Iterator<Item = PendingCall> + '_ {
+        self.calls.try_iter()
+    }
+
+    /// Replace what the JSON endpoints hand out, then tell the WebSocket clients what changed
+    pub fn publish(&self, today: String, stats: String) {
+        if let Ok(mut published) = self.published.lock() {
+            *published = Published { today, stats };
+        }
+        for entry in self.events.try_iter() {
+            if let Ok(message) = serde_json::to_string(&entry) {
+                let _ = self.outbox.send(message);
+            }
+        }
+    }
+}
+
+/// Read one request and answer it
+fn answer(
+    stream: TcpStream,
+    published: &Mutex<Published>,
+    sockets: &Sockets,
+    caller: Option<&Sender<PendingCall>>,
+    token: Option<&str>,
+) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Only the token, the WebSocket handshake and a tool call's body need anything from the headers
+    let mut websocket_key = None;
+    let mut content_length = 0;
+    let mut bearer = None;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        if let Some((name, value)) = header.split_once(':') {
+            let name = name.trim();
+            if name.eq_ignore_ascii_case("sec-websocket-key") {
+                websocket_key = Some(value.trim().to_string());
+            } else if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            } else if name.eq_ignore_ascii_case("authorization") {
+                bearer = value.trim().strip_prefix("Bearer ").map(|token| token.trim().to_string());
+            }
+        }
+        header.clear();
+    }
+    let mut body = vec![0; content_length.min(MAX_BODY)];
+    reader.read_exact(&mut body)?;
+    let mut stream = reader.into_inner();
+
+    let mut words = request_line.split_whitespace();
+    let (method, target) = (words.next().unwrap_or_default(), words.next().unwrap_or_default());
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let tools = path == "/mcp" && caller.is_some();
+    if method != "GET" && !(tools && method == "POST") {
+        return respond(&mut stream, "405 Method Not Allowed", "text/plain", "Only GET is served\n");
+    }
+    if let Some(token) = token
+        && bearer.as_deref() != Some(token)
+        && !query.split('&').any(|pair| pair.strip_prefix("token=") == Some(token))
+    {
+        return respond(&mut stream, "403 Forbidden", "text/plain", "Send the token from config as ?token= or an Authorization: Bearer header\n");
+    }
+    if let Some(caller) = caller.filter(|_| tools) {
+        if method == "GET" {
+            return respond(&mut stream, "405 Method Not Allowed", "text/plain", "POST JSON-RPC messages here\n");
+        }
+        return match mcp::handle(&String::from_utf8_lossy(&body), caller) {
+            Some(reply) => respond(&mut stream, "200 OK", "application/json", &reply),
+            None => respond(&mut stream, "202 Accepted", "text/plain", ""),
+        };
+    }
+    if path == "/api/events" {
+        let Some(key) = websocket_key else {
+            return respond(&mut stream, "426 Upgrade Required", "text/plain", "Connect with a WebSocket\n");
+        };
+        let accept = BASE64.encode(Sha1::digest(format!("{}{}", key, WEBSOCKET_GUID)));
+        write!(stream, "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n", accept)?;
+        stream.flush()?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+        let peer = stream.peer_addr()?;
+        if let Ok(mut sockets) = sockets.lock() {
+            sockets.push((peer, stream.try_clone()?));
+        }
+        let result = listen_to_client(&mut stream, sockets);
+        if let Ok(mut sockets) = sockets.lock() {
+            sockets.retain(|(address, _)| *address != peer);
+        }
+        let _ = stream.shutdown(Shutdown::Both);
+        return result;
+    }
+
+    let published = published.lock().map(|published| (published.today.clone(), published.stats.clone()));
+    let (today, stats) = published.unwrap_or_default();
+    match path {
+        "/" => respond(&mut stream, "200 OK", "text/html; charset=utf-8", PAGE),
+        "/api/today" => respond(&mut stream, "200 OK", "application/json", &today),
+        "/api/stats" => respond(&mut stream, "200 OK", "application/json", &stats),
+        _ => respond(&mut stream, "404 Not Found", "text/plain", "Not found\n"),
+    }
+}
+
+fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) -> std::io::Result<()> {
+    write!(stream, "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status, content_type, body.len(), body)?;
+    stream.flush()
+}
+
+/// Read what a WebSocket client sends until it closes the connection: pings are answered with a
+/// pong and a close with a close, under the lock so they don't land in the middle of a change
+/// being sent; anything else is ignored
+fn listen_to_client(stream: &mut TcpStream, sockets: &Sockets) -> std::io::Result<()> {
+    loop {
+        let (opcode, payload) = read_frame(stream)?;
+        let reply = match opcode {
+            PING => PONG,
+            CLOSE => CLOSE,
+            _ => continue,
+        };
+        let _sending = sockets.lock();
+        send_frame(stream, reply, &payload)?;
+        if opcode == CLOSE {
+            return Ok(());
+        }
+    }
+}
+
+/// Read one WebSocket frame, returning its opcode and unmasked payload
+fn read_frame(stream: &mut impl Read) -> std::io::Result<(u8, Vec<u8>)> {
+    let mut head = [0; 2];
+    stream.read_exact(&mut head)?;
+    let opcode = head[0] & 0x0f;
+    let len = match head[1] & 0x7f {
+        126 => {
+            let mut len = [0; 2];
+            stream.read_exact(&mut len)?;
+            u16::from_be_bytes(len) as u64
+        }
+        127 => {
+            let mut len = [0; 8];
+            stream.read_exact(&mut len)?;
+            u64::from_be_bytes(len)
+        }
+        len => len as u64,
+    };
+    // Control frames carry at most 125 bytes, and nothing a client sends here needs more than a body's worth
+    if len > MAX_BODY as u64 || (opcode >= CLOSE && len > 125) {
+        return Err(std::io::Error::new(ErrorKind::InvalidData, "WebSocket frame too long"));
+    }
+    let mut mask = [0; 4];
+    if head[1] & 0x80 != 0 {
+        stream.read_exact(&mut mask)?;
+    }
+    let mut payload = vec![0; len as usize];
+    stream.read_exact(&mut payload)?;
+    for (index, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[index % 4];
+    }
+    Ok((opcode, payload))
+}
+
+/// Send `payload` as one unmasked WebSocket frame of the given type
+fn send_frame(stream: &mut TcpStream, opcode: u8, payload: &[u8]) -> std::io::Result<()> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        len if len < 126 => frame.push(len as u8),
+        len if len <= u16::MAX as usize => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    stream.write_all(&frame)?;
+    stream.flush()
+}
+
+/// The open tasks due today, the overdue ones and those done today, for /api/today
+pub fn today_json(open: &[Todo], done_today: &[Todo], context: Option<&str>, clock: &Clock) -> String {
+    let today = clock.today();
+    let task = |todo: &Todo| json!({
+        "id": todo.id,
+        "title": todo.title,
+        "due_date": todo.due_date,
+        "due_time": todo.due_time.map(|time| time.format("%H:%M").to_string()),
+        "priority": todo.priority,
+        "project": todo.project,
+        "tags": todo.tags,
+    });
+    let mut open: Vec<&Todo> = open.iter().filter(|t| !t.completed && !t.deleted && !t.someday).collect();
+    open.sort_by_key(|t| (t.due_date, t.due_time.is_none(), t.due_time, t.id));
+    json!({
+        "date": today,
+        "context": context,
+        "overdue": open.iter().filter(|t| t.is_overdue(today)).map(|t| task(t)).collect::<Vec<_>>(),
+        "today": open.iter().filter(|t| t.due_date == Some(today)).map(|t| task(t)).collect::<Vec<_>>(),
+        "done": done_today.iter().map(task).collect::<Vec<_>>(),
+    }).to_string()
+}
+
+/// The headline numbers of the Stats tab, for /api/stats
+pub fn stats_json(stats: &Stats, done_today: usize, streak: usize) -> String {
+    json!({
+        "open": stats.todo_count,
+        "overdue": stats.overdue_count,
+        "done": stats.done_count,
+        "done_today": done_today,
+        "streak_days": streak,
+        "average_backlog_age_days": stats.average_backlog_age,
+        "mean_time_to_done_days": stats.mean_time_to_done,
+        "done_last_7_days": stats.completed.iter().rev().take(7).map(|(_, count)| count).sum::<f64>(),
+    }).to_string()
+}
+
+/// The dashboard: fetches the JSON with the page's own token, again on each change pushed over
+/// the WebSocket, and every half minute in case that connection is lost
+const PAGE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>tdui</title>
+<style>
+body { font-family: system-ui, sans-serif; max-width: 640px; margin: 0 auto; padding: 1em; color: #222; background: #fafafa; }
+h1 { font-size: 1.3em; margin: 0 0 0.6em; }
+h2 { font-size: 1em; margin: 1.2em 0 0.4em; color: #555; }
+.numbers { display: flex; flex-wrap: wrap; gap: 0.5em; }
+.numbers div { background: #fff; border: 1px solid #ddd; border-radius: 6px; padding: 0.4em 0.8em; min-width: 5em; }
+.numbers b { display: block; font-size: 1.4em; }
+ul { list-style: none; padding: 0; margin: 0; }
+li { background: #fff; border: 1px solid #e3e3e3; border-radius: 4px; padding: 0.5em 0.7em; margin-bottom: 0.3em; }
+li small { color: #777; margin-left: 0.5em; }
+.overdue li { border-left: 4px solid #c0392b; }
+.done li { color: #777; text-decoration: line-through; }
+.empty { color: #999; }
+</style>
+</head>
+<body>
+<h1 id="title">tdui</h1>
+<div class="numbers" id="numbers"></div>
+<h2>Overdue</h2><ul class="overdue" id="overdue"></ul>
+<h2>Today</h2><ul id="today"></ul>
+<h2>Done today</h2><ul class="done" id="done"></ul>
+<script>
+const query = location.search;
+function list(id, tasks) {
+  const ul = document.getElementById(id);
+  ul.replaceChildren();
+  if (tasks.length === 0) {
+    const li = document.createElement('li');
+    li.className = 'empty';
+    li.textContent = 'Nothing';
+    ul.append(li);
+  }
+  for (const task of tasks) {
+    const li = document.createElement('li');
+    li.textContent = task.title;
+    const details = [task.due_time, task.due_date && id === 'overdue' ? 'due ' + task.due_date : null, task.project].filter(Boolean);
+    if (details.length) {
+      const small = document.createElement('small');
+      small.textContent = details.join(' · ');
+      li.append(small);
+    }
+    ul.append(li);
+  }
+}
+async function refresh() {
+  const [today, stats] = await Promise.all(['today', 'stats'].map(name => fetch('/api/' + name + query).then(r => r.json())));
+  document.getElementById('title').textContent = 'tdui · ' + today.date + (today.context ? ' · ' + today.context : '');
+  const numbers = document.getElementById('numbers');
+  numbers.replaceChildren();
+  for (const [label, value] of [['open', stats.open], ['overdue', stats.overdue], ['done today', stats.done_today], ['streak', stats.streak_days + 'd']]) {
+    const div = document.createElement('div');
+    const b = document.createElement('b');
+    b.textContent = value;
+    div.append(b, label);
+    numbers.append(div);
+  }
+  list('overdue', today.overdue);
+  list('today', today.today);
+  list('done', today.done);
+}
+function listen() {
+  const events = new WebSocket((location.protocol === 'https:' ? 'wss://' : 'ws://') + location.host + '/api/events' + query);
+  events.onmessage = refresh;
+  events.onclose = () => setTimeout(listen, 5000);
+}
+refresh();
+listen();
+setInterval(refresh, 30000);
+</script>
+</body>
+</html>
+"#;
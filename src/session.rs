@@ -0,0 +1,58 @@
+// Session module - What happened to the task list while the app was open
+// The App snapshots the tasks at startup, and on quit compares against it for the
+// exit summary: completed, added, rescheduled, and what's still overdue.
+
+use crate::models::Todo;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::HashMap;
+
+/// The task list as it was when the app started
+#[derive(Debug, Clone)]
+pub struct SessionStart {
+    started_at: DateTime<Utc>,
+    /// Due dates of the tasks open at startup, by task ID
+    due_dates: HashMap<usize, Option<NaiveDate>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionSummary {
+    pub completed: usize,
+    pub added: usize,
+    /// Tasks open at startup, and still open, whose due date changed
+    pub rescheduled: usize,
+    pub still_overdue: usize,
+}
+
+impl SessionStart {
+    pub fn capture(todos: &[Todo]) -> Self {
+        Self {
+            started_at: Utc::now(),
+            due_dates: todos.iter()
+                .filter(|todo| !todo.completed && !todo.deleted)
+                .map(|todo| (todo.id, todo.due_date))
+                .collect(),
+        }
+    }
+
+    pub fn summary(&self, todos: &[Todo], today: NaiveDate) -> SessionSummary {
+        let mut summary = SessionSummary::default();
+        for todo in todos {
+            if todo.completed_at.is_some_and(|at| at >= self.started_at) && !todo.deleted {
+                summary.completed += 1;
+            }
+            if todo.created_at >= self.started_at && !todo.deleted {
+                summary.added += 1;
+            }
+            if todo.completed || todo.deleted {
+                continue;
+            }
+            if self.due_dates.get(&todo.id).is_some_and(|due_date| *due_date != todo.due_date) {
+                summary.rescheduled += 1;
+            }
+            if todo.is_overdue(today) {
+                summary.still_overdue += 1;
+            }
+        }
+        summary
+    }
+}
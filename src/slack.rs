@@ -0,0 +1,141 @@
+// Slack module - Posts a task, or today's plan, to a Slack channel through an incoming webhook
+// Each project can have its own webhook (and so its own channel); tasks without one, and the
+// day's plan, go to the default. In the app the post is sent in the background and its outcome
+// shown as a notice, so a slow network never holds up the UI.
+
+use crate::clock::Clock;
+use crate::config::{Config, SlackConfig};
+use crate::models::{Priority, Todo};
+use crate::secrets;
+use crate::storage::FileStorage;
+use chrono::NaiveDate;
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Lines of a task's description included in its post
+const DESCRIPTION_LINES: usize = 5;
+
+/// The webhook for a task in `project`: the project's own, else the default, each from config or
+/// else the keyring
+pub fn webhook_for(config: &SlackConfig, project: Option<&str>) -> anyhow::Result<String> {
+    if let Some(project) = project {
+        let configured = config.projects.get(project).map(String::as_str);
+        if let Some(webhook) = secrets::resolve(configured, &secrets::slack_name(Some(project)))? {
+            return Ok(webhook);
+        }
+    }
+    secrets::resolve(config.webhook_url.as_deref(), &secrets::slack_name(None))?
+        .ok_or_else(|| anyhow::anyhow!("no Slack webhook is set: run tdui auth slack"))
+}
+
+/// Send `text` to the channel behind `webhook`
+pub fn post(webhook: &str, text: &str) -> anyhow::Result<()> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(TIMEOUT))
+        .build()
+        .into();
+    let body = serde_json::json!({ "text": text }).to_string();
+    agent.post(webhook).header("Content-Type", "application/json").send(&body)?;
+    Ok(())
+}
+
+/// Post on another thread; the receiver gets `done` back once it's sent, or why it wasn't
+pub fn post_in_background(webhook: String, text: String, done: String) -> Receiver<anyhow::Result<String>> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(post(&webhook, &text).map(|_| done));
+    });
+    receiver
+}
+
+/// A task as a Slack message: the title in bold, its due date, priority and context, the start
+/// of its description quoted and its link
+pub fn task_message(todo: &Todo) -> String {
+    let mut details = Vec::new();
+    if let Some(due_date) = todo.due_date {
+        details.push(format!("due {}", due_date.format("%a %-d %b %Y")));
+    }
+    match todo.priority {
+        Some(Priority::High) => details.push("high priority".to_string()),
+        Some(Priority::Medium) => details.push("medium priority".to_string()),
+        Some(Priority::Low) => details.push("low priority".to_string()),
+        None => {}
+    }
+    if let Some(context) = &todo.context {
+        details.push(context.clone());
+    }
+
+    let mut message = format!("*{}*", escape(&todo.title));
+    if todo.completed {
+        message.push_str(" (done)");
+    }
+    if !details.is_empty() {
+        message.push_str(&format!(" - {}", escape(&details.join(", "))));
+    }
+    for line in todo.description.lines().filter(|line| !line.trim().is_empty()).take(DESCRIPTION_LINES) {
+        message.push_str(&format!("\n> {}", escape(line)));
+    }
+    if let Some(url) = &todo.url {
+        message.push_str(&format!("\n{}", url));
+    }
+    message
+}
+
+/// The open tasks overdue or due today, and what's been done today, as a Slack message
+pub fn plan_message(todos: &[Todo], today: NaiveDate, clock: &Clock) -> String {
+    let mut sorted: Vec<&Todo> = todos.iter().filter(|t| !t.deleted && !t.someday).collect();
+    sorted.sort_by_key(|t| (t.due_date, t.id));
+    let overdue: Vec<&&Todo> = sorted.iter().filter(|t| !t.completed && t.is_overdue(today)).collect();
+    let due_today: Vec<&&Todo> = sorted.iter().filter(|t| !t.completed && t.due_date == Some(today)).collect();
+    let done_today: Vec<&&Todo> = sorted.iter()
+        .filter(|t| t.completed && t.completed_at.is_some_and(|at| clock.date_of(at) == today))
+        .collect();
+
+    let mut message = format!("*Plan for {}*", today.format("%A %-d %B"));
+    if overdue.is_empty() && due_today.is_empty() && done_today.is_empty() {
+        message.push_str("\nNothing due today.");
+    }
+    let mut section = |heading: &str, tasks: &[&&Todo], done: bool| {
+        if tasks.is_empty() {
+            return;
+        }
+        message.push_str(&format!("\n{}:", heading));
+        for todo in tasks {
+            let title = escape(&todo.title);
+            match (done, todo.due_date) {
+                (true, _) => message.push_str(&format!("\n• ~{}~", title)),
+                (false, Some(due_date)) if due_date < today => {
+                    message.push_str(&format!("\n• {} (due {})", title, due_date.format("%-d %b")))
+                }
+                (false, _) => message.push_str(&format!("\n• {}", title)),
+            }
+        }
+    };
+    section("Overdue", &overdue, false);
+    section("Today", &due_today, false);
+    section("Done", &done_today, true);
+    message
+}
+
+/// Slack reads &, < and > in message text as markup
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Post today's plan for the active context to the default webhook, for `tdui slack today`
+pub fn post_today() -> anyhow::Result<()> {
+    let config = Config::load(Config::get_default_path());
+    let webhook = webhook_for(&config.slack, None)?;
+    let clock = Clock::from_config_or_warn(&config);
+    let storage = FileStorage::open(FileStorage::get_default_path());
+    storage.load_todos()?;
+
+    let context = config.active_context.as_deref();
+    let todos: Vec<Todo> = storage.todos().iter()
+        .filter(|t| t.in_context(context))
+        .cloned()
+        .collect();
+    post(&webhook, &plan_message(&todos, clock.today(), &clock))
+}
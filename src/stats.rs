@@ -0,0 +1,300 @@
+// Stats module - Counts and 90-day series shown on the Stats tab
+// Computed once per change to the stored tasks (or per new day) and cached on the App,
+// so drawing the Stats tab ten times a second doesn't redo the work.
+
+use crate::clock::Clock;
+use crate::config::WorkflowConfig;
+use crate::models::{Habit, Todo};
+use crate::storage::ArchiveTotals;
+use chrono::{Duration, NaiveDate};
+use std::collections::{BTreeMap, HashMap};
+
+/// How many days back the charts reach
+pub const HISTORY_DAYS: i64 = 90;
+
+/// How many recent weeks of completions set the pace for forecasts
+pub const FORECAST_WEEKS: i64 = 8;
+
+/// What a cached `Stats` was computed from; any difference means it's stale
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsKey {
+    pub revision: u64,
+    pub habits_revision: u64,
+    pub today: NaiveDate,
+    pub context: Option<String>,
+    pub all_sources: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Stats {
+    pub overdue_count: usize,
+    pub todo_count: usize,
+    pub done_count: usize,
+    pub deleted_count: usize,
+    /// First day of the chart series
+    pub start: NaiveDate,
+    /// (days since `start`, count) points for the charts
+    pub created: Vec<(f64, f64)>,
+    pub overdue: Vec<(f64, f64)>,
+    pub completed: Vec<(f64, f64)>,
+    /// Tasks completed plus habits checked off, per day from `start` to today
+    pub activity: Vec<usize>,
+    /// Mean and largest age in days of the open tasks, None when there are none
+    pub average_backlog_age: Option<f64>,
+    pub oldest_backlog_age: Option<i64>,
+    /// Mean days from creation to completion over the charted tasks, None when none were completed
+    pub mean_time_to_done: Option<f64>,
+    /// Open tasks in each open workflow status, in workflow order
+    pub status_counts: Vec<(String, usize)>,
+    /// When the whole backlog would be done at the recent pace
+    pub forecast: Forecast,
+    /// The same for each project with open tasks, by project name
+    pub project_forecasts: Vec<(String, Forecast)>,
+    /// Time logged against the estimates of each project's estimated tasks, by project name
+    pub project_effort: Vec<(String, Effort)>,
+    /// Charted completed tasks with an estimate and time logged, furthest over their estimate first
+    pub estimate_accuracy: Vec<EstimatePoint>,
+    /// Each data folder merged into these stats, when they cover all sources; empty otherwise
+    pub sources: Vec<SourceStats>,
+}
+
+/// One data folder's share of merged stats
+#[derive(Debug, Clone)]
+pub struct SourceStats {
+    pub name: String,
+    /// Color configured for it, if any
+    pub color: Option<String>,
+    pub open: usize,
+    pub overdue: usize,
+    /// (days since the stats' `start`, count) of its tasks completed each day
+    pub completed: Vec<(f64, f64)>,
+}
+
+impl SourceStats {
+    pub fn compute(name: String, color: Option<String>, live: &[Todo], archived: &[Todo], clock: &Clock) -> Self {
+        let today = clock.today();
+        let start = today - Duration::days(HISTORY_DAYS);
+        let mut completed_counts: HashMap<NaiveDate, usize> = HashMap::new();
+        for completed_at in live.iter().chain(archived).filter_map(|t| t.completed_at) {
+            *completed_counts.entry(clock.date_of(completed_at)).or_insert(0) += 1;
+        }
+        Self {
+            name,
+            color,
+            open: live.iter().filter(|t| !t.completed && !t.deleted).count(),
+            overdue: live.iter().filter(|t| t.is_overdue(today)).count(),
+            completed: daily_series(start, &completed_counts),
+        }
+    }
+}
+
+/// A completed task's estimate next to the time it actually took
+#[derive(Debug, Clone, PartialEq)]
+pub struct EstimatePoint {
+    pub title: String,
+    pub estimate: Duration,
+    pub spent: Duration,
+}
+
+impl EstimatePoint {
+    /// How many times its estimate the task took: above 1 means it was underestimated
+    pub fn ratio(&self) -> f64 {
+        self.spent.num_minutes() as f64 / self.estimate.num_minutes().max(1) as f64
+    }
+}
+
+/// Time logged on a set of estimated tasks, and what they were estimated to take
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Effort {
+    pub spent: Duration,
+    pub estimate: Duration,
+}
+
+impl Effort {
+    pub fn is_over(&self) -> bool {
+        self.spent > self.estimate
+    }
+}
+
+/// When a set of open tasks would all be done, going by how many like them were completed a week lately
+#[derive(Debug, Clone, PartialEq)]
+pub struct Forecast {
+    pub open: usize,
+    /// Tasks completed per week over the last `FORECAST_WEEKS` weeks
+    pub per_week: f64,
+    /// None when nothing was completed in that time, so there's no pace to go by
+    pub cleared_by: Option<NaiveDate>,
+}
+
+impl Forecast {
+    fn new(open: usize, completed: usize, today: NaiveDate) -> Self {
+        let per_week = completed as f64 / FORECAST_WEEKS as f64;
+        let cleared_by = (completed > 0)
+            .then(|| today + Duration::days((open as f64 / per_week * 7.0).ceil() as i64));
+        Self { open, per_week, cleared_by }
+    }
+}
+
+impl Stats {
+    /// Compute everything from the stored tasks (any state) of the active context, plus the
+    /// archived tasks closed within the chart range, the all-time archive counts and the habits
+    pub fn compute(live: &[Todo], archived: &[Todo], archived_totals: ArchiveTotals, habits: &[Habit], workflow: &WorkflowConfig, clock: &Clock) -> Self {
+        let today = clock.today();
+        let start = today - Duration::days(HISTORY_DAYS);
+        let is_open = |t: &&Todo| !t.completed && !t.deleted;
+
+        let overdue_count = live.iter().filter(|t| t.is_overdue(today)).count();
+        let todo_count = live.iter().filter(is_open).count();
+        let done_count = live.iter().filter(|t| t.completed).count() + archived_totals.completed;
+        let deleted_count = live.iter().filter(|t| t.deleted).count() + archived_totals.deleted;
+
+        // How long the open tasks have been waiting
+        let ages: Vec<i64> = live.iter()
+            .filter(is_open)
+            .map(|t| (today - clock.date_of(t.created_at)).num_days().max(0))
+            .collect();
+        let average_backlog_age = mean(ages.iter().map(|&age| age as f64));
+        let oldest_backlog_age = ages.iter().max().copied();
+
+        let mut status_counts: Vec<(String, usize)> = workflow.open_statuses().iter()
+            .map(|status| (status.name.clone(), 0))
+            .collect();
+        for todo in live.iter().filter(is_open) {
+            status_counts[workflow.index_of(todo)].1 += 1;
+        }
+
+        // The chart series cover live and recently archived tasks alike
+        let todos: Vec<&Todo> = live.iter().chain(archived).collect();
+
+        // Count tasks created and completed per day
+        let mut created_counts: HashMap<NaiveDate, usize> = HashMap::new();
+        let mut completed_counts: HashMap<NaiveDate, usize> = HashMap::new();
+        for todo in &todos {
+            *created_counts.entry(clock.date_of(todo.created_at)).or_insert(0) += 1;
+            if let Some(completed_at) = todo.completed_at {
+                *completed_counts.entry(clock.date_of(completed_at)).or_insert(0) += 1;
+            }
+        }
+
+        // A task is overdue on a day if it was past due and not yet completed by then
+        // (deleted and someday tasks don't count, whether or not they've been archived)
+        let overdue = (0..=HISTORY_DAYS)
+            .map(|offset| {
+                let date = start + Duration::days(offset);
+                let count = todos.iter()
+                    .filter(|todo| !todo.deleted && !todo.someday)
+                    .filter(|todo| todo.due_date.is_some_and(|due_date| due_date < date))
+                    .filter(|todo| todo.completed_at.is_none_or(|at| clock.date_of(at) >= date))
+                    .count();
+                (offset as f64, count as f64)
+            })
+            .collect();
+
+        let mean_time_to_done = mean(todos.iter()
+            .filter(|t| t.completed)
+            .filter_map(|t| t.completed_at.map(|at| (at - t.created_at).num_minutes() as f64 / (24.0 * 60.0))));
+
+        // Charted tasks reach back further than the forecast pace, so they hold every recent completion
+        let pace_start = today - Duration::weeks(FORECAST_WEEKS);
+        let recently_completed: Vec<&&Todo> = todos.iter()
+            .filter(|t| t.completed_at.is_some_and(|at| clock.date_of(at) > pace_start))
+            .collect();
+        let forecast = Forecast::new(todo_count, recently_completed.len(), today);
+        let mut projects: Vec<&str> = live.iter()
+            .filter(is_open)
+            .filter_map(|t| t.project.as_deref())
+            .collect();
+        projects.sort();
+        projects.dedup();
+        let mut project_forecasts: Vec<(String, Forecast)> = projects.into_iter()
+            .map(|project| {
+                let in_project = |t: &Todo| t.project.as_deref() == Some(project);
+                let open = live.iter().filter(is_open).filter(|t| in_project(t)).count();
+                let completed = recently_completed.iter().filter(|t| in_project(t)).count();
+                (project.to_string(), Forecast::new(open, completed, today))
+            })
+            .collect();
+        // Soonest done first, then those with no pace to go by
+        project_forecasts.sort_by_key(|(_, forecast)| (forecast.cleared_by.is_none(), forecast.cleared_by));
+
+        // Only tasks with an estimate count, so unestimated work doesn't look over budget
+        let mut effort: BTreeMap<String, Effort> = BTreeMap::new();
+        for todo in live.iter().filter(|t| !t.deleted) {
+            if let (Some(project), Some(estimate)) = (&todo.project, todo.estimate()) {
+                let totals = effort.entry(project.clone()).or_default();
+                totals.spent += todo.time_spent();
+                totals.estimate += estimate;
+            }
+        }
+        let project_effort = effort.into_iter().collect();
+
+        // A task with no time logged says nothing about how good its estimate was
+        let mut estimate_accuracy: Vec<EstimatePoint> = todos.iter()
+            .filter(|t| t.completed && !t.deleted)
+            .filter_map(|t| t.estimate().map(|estimate| EstimatePoint {
+                title: t.title.clone(),
+                estimate,
+                spent: t.time_spent(),
+            }))
+            .filter(|point| point.spent > Duration::zero() && point.estimate > Duration::zero())
+            .collect();
+        estimate_accuracy.sort_by(|a, b| b.ratio().total_cmp(&a.ratio()));
+
+        // Habits aren't tied to a context, so every check-off counts
+        let mut activity_counts = completed_counts.clone();
+        for date in habits.iter().flat_map(|habit| &habit.done) {
+            *activity_counts.entry(*date).or_insert(0) += 1;
+        }
+        let activity = (0..=HISTORY_DAYS)
+            .map(|offset| activity_counts.get(&(start + Duration::days(offset))).copied().unwrap_or(0))
+            .collect();
+
+        Self {
+            overdue_count,
+            todo_count,
+            done_count,
+            deleted_count,
+            start,
+            created: daily_series(start, &created_counts),
+            overdue,
+            completed: daily_series(start, &completed_counts),
+            activity,
+            average_backlog_age,
+            oldest_backlog_age,
+            mean_time_to_done,
+            status_counts,
+            forecast,
+            project_forecasts,
+            project_effort,
+            estimate_accuracy,
+            sources: Vec::new(),
+        }
+    }
+
+    /// The typical (median) ratio of time taken to time estimated, None with nothing to compare
+    pub fn median_estimate_ratio(&self) -> Option<f64> {
+        let mut ratios: Vec<f64> = self.estimate_accuracy.iter().map(EstimatePoint::ratio).collect();
+        ratios.sort_by(f64::total_cmp);
+        let middle = ratios.len() / 2;
+        match ratios.len() {
+            0 => None,
+            len if len % 2 == 0 => Some((ratios[middle - 1] + ratios[middle]) / 2.0),
+            _ => Some(ratios[middle]),
+        }
+    }
+}
+
+/// (days since `start`, count) for each day from `start` to `HISTORY_DAYS` later
+fn daily_series(start: NaiveDate, counts: &HashMap<NaiveDate, usize>) -> Vec<(f64, f64)> {
+    (0..=HISTORY_DAYS)
+        .map(|offset| {
+            let count = counts.get(&(start + Duration::days(offset))).copied().unwrap_or(0);
+            (offset as f64, count as f64)
+        })
+        .collect()
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
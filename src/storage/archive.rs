@@ -0,0 +1,172 @@
+// Archive - Closed tasks moved out of todos.json into one file per month
+// Views read only the months they need, and a small summary keeps the all-time counts,
+// so startup and the Stats tab stay fast however long the history gets.
+
+use crate::clock::Clock;
+use crate::models::Todo;
+use super::FileStorage;
+use super::file_storage::write_atomically;
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use crate::error::{Result, WithPath};
+use std::fs;
+use std::path::PathBuf;
+
+/// Completed and deleted counts for one month, keyed by context ("" for tasks without one)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct MonthSummary {
+    completed: BTreeMap<String, usize>,
+    deleted: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ArchiveSummary {
+    /// Highest task ID ever archived, so new tasks never reuse one
+    max_id: usize,
+    /// Month ("2026-09") to counts
+    months: BTreeMap<String, MonthSummary>,
+}
+
+/// All-time counts of archived tasks
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ArchiveTotals {
+    pub completed: usize,
+    pub deleted: usize,
+}
+
+pub struct Archive {
+    dir: PathBuf,
+    summary: ArchiveSummary,
+}
+
+impl Archive {
+    pub fn open(dir: PathBuf) -> Self {
+        // No summary just means nothing has been archived yet
+        let summary = fs::read_to_string(dir.join("summary.json"))
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self { dir, summary }
+    }
+
+    pub fn get_default_path() -> PathBuf {
+        // Keep archives alongside todos.json: ~/.local/share/tdui/archive/
+        FileStorage::get_default_path().with_file_name("archive")
+    }
+
+    pub fn max_id(&self) -> usize {
+        self.summary.max_id
+    }
+
+    /// The month a closed task is filed under: when it was completed, or created if it never was
+    fn month_of(todo: &Todo, clock: &Clock) -> String {
+        let date = clock.date_of(todo.completed_at.unwrap_or(todo.created_at));
+        format!("{:04}-{:02}", date.year(), date.month())
+    }
+
+    fn month_path(&self, month: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", month))
+    }
+
+    fn load_month(&self, month: &str) -> Vec<Todo> {
+        fs::read_to_string(self.month_path(month))
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// A month file about to be added to: missing is empty, but one that doesn't parse is an
+    /// error rather than something to write over
+    fn read_month(&self, month: &str) -> Result<Vec<Todo>> {
+        let path = self.month_path(month);
+        match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).with_path(&path),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(err) => Err(err).with_path(&path),
+        }
+    }
+
+    /// Move completed and deleted tasks closed before their `cutoff` (None keeps the task) out of
+    /// storage and into the archive. Tasks leave storage only once every archive file is written,
+    /// so a failed write leaves them where they were.
+    pub fn archive_closed(
+        &mut self,
+        storage: &mut FileStorage,
+        cutoff: impl Fn(&Todo) -> Option<NaiveDate>,
+        clock: &Clock,
+    ) -> Result<usize> {
+        let closed: Vec<Todo> = storage.todos().iter()
+            .filter(|todo| {
+                (todo.completed || todo.deleted)
+                    && cutoff(todo).is_some_and(|cutoff| clock.date_of(todo.completed_at.unwrap_or(todo.created_at)) < cutoff)
+            })
+            .cloned()
+            .collect();
+        if closed.is_empty() {
+            return Ok(0);
+        }
+
+        let mut by_month: BTreeMap<String, Vec<Todo>> = BTreeMap::new();
+        for todo in &closed {
+            by_month.entry(Self::month_of(todo, clock)).or_default().push(todo.clone());
+        }
+
+        fs::create_dir_all(&self.dir).with_path(&self.dir)?;
+        let mut summary = self.summary.clone();
+        for (month, todos) in by_month {
+            let month_summary = summary.months.entry(month.clone()).or_default();
+            for todo in &todos {
+                let context = todo.context.clone().unwrap_or_default();
+                let counts = if todo.deleted { &mut month_summary.deleted } else { &mut month_summary.completed };
+                *counts.entry(context).or_insert(0) += 1;
+                summary.max_id = summary.max_id.max(todo.id);
+            }
+
+            // A task filed by an archive that failed before summary.json was written isn't filed twice
+            let mut month_todos = self.read_month(&month)?;
+            let filed: BTreeSet<usize> = month_todos.iter().map(|todo| todo.id).collect();
+            month_todos.extend(todos.into_iter().filter(|todo| !filed.contains(&todo.id)));
+            let path = self.month_path(&month);
+            write_atomically(&path, &serde_json::to_string(&month_todos).with_path(&path)?)?;
+        }
+        let path = self.dir.join("summary.json");
+        write_atomically(&path, &serde_json::to_string_pretty(&summary).with_path(&path)?)?;
+        self.summary = summary;
+
+        // The archive now holds these tasks, so drop them from todos.json right away
+        let ids: BTreeSet<usize> = closed.iter().map(|todo| todo.id).collect();
+        storage.remove_where(|todo| ids.contains(&todo.id));
+        storage.flush()?;
+        Ok(closed.len())
+    }
+
+    /// Archived tasks filed under any month from `from` to `to`, reading only those month files
+    pub fn load_range(&self, from: NaiveDate, to: NaiveDate) -> Vec<Todo> {
+        let first = format!("{:04}-{:02}", from.year(), from.month());
+        let last = format!("{:04}-{:02}", to.year(), to.month());
+        self.summary.months
+            .range(first..=last)
+            .flat_map(|(month, _)| self.load_month(month))
+            .collect()
+    }
+
+    /// Every archived task, reading every month file
+    pub fn load_all(&self) -> Vec<Todo> {
+        self.summary.months.keys().flat_map(|month| self.load_month(month)).collect()
+    }
+
+    /// All-time archived counts, optionally only for one context
+    pub fn totals(&self, context: Option<&str>) -> ArchiveTotals {
+        let count = |counts: &BTreeMap<String, usize>| -> usize {
+            match context {
+                Some(context) => counts.get(context).copied().unwrap_or(0),
+                None => counts.values().sum(),
+            }
+        };
+        self.summary.months.values().fold(ArchiveTotals::default(), |totals, month| ArchiveTotals {
+            completed: totals.completed + count(&month.completed),
+            deleted: totals.deleted + count(&month.deleted),
+        })
+    }
+}
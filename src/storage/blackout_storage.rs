@@ -0,0 +1,67 @@
+// Blackout storage - Days away live in blackouts.json next to todos.json
+// Like projects, the file is small and only changes from a popup, so each change is written straight away.
+
+use crate::models::Blackout;
+use super::FileStorage;
+use crate::error::{Result, WithPath};
+use chrono::NaiveDate;
+use std::fs;
+use std::path::PathBuf;
+
+pub struct BlackoutStorage {
+    file_path: PathBuf,
+    blackouts: Vec<Blackout>,
+}
+
+impl BlackoutStorage {
+    pub fn open(file_path: PathBuf) -> Self {
+        // A missing or unreadable file just means no days are blacked out
+        let blackouts = fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self { file_path, blackouts }
+    }
+
+    pub fn get_default_path() -> PathBuf {
+        // ~/.local/share/tdui/blackouts.json
+        FileStorage::get_default_path().with_file_name("blackouts.json")
+    }
+
+    pub fn blackouts(&self) -> &[Blackout] {
+        &self.blackouts
+    }
+
+    /// The blackout `date` falls in, if any
+    pub fn covering(&self, date: NaiveDate) -> Option<&Blackout> {
+        self.blackouts.iter().find(|blackout| blackout.covers(date))
+    }
+
+    /// Add a blackout, or replace `replacing` (one already stored) with it
+    pub fn upsert(&mut self, replacing: Option<&Blackout>, blackout: Blackout) -> Result<()> {
+        if let Some(old) = replacing {
+            self.blackouts.retain(|b| b != old);
+        }
+        self.blackouts.push(blackout);
+        self.blackouts.sort_by_key(|b| b.start);
+        self.save()
+    }
+
+    pub fn remove(&mut self, blackout: &Blackout) -> Result<()> {
+        self.blackouts.retain(|b| b != blackout);
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.file_path.parent() {
+            fs::create_dir_all(parent).with_path(parent)?;
+        }
+
+        // Write to a temporary file and rename it over the original, like todos.json
+        let temp_path = self.file_path.with_extension("json.tmp");
+        let json = serde_json::to_string_pretty(&self.blackouts).with_path(&self.file_path)?;
+        fs::write(&temp_path, json).with_path(&self.file_path)?;
+        fs::rename(&temp_path, &self.file_path).with_path(&self.file_path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+// Event bus - Passes each change to the stored tasks on to whoever subscribed (the web
+// dashboard's live updates): the same changes the journal records, as they're made. A subscriber
+// that has gone away is dropped at the next change.
+
+use super::journal::Entry;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+#[derive(Default)]
+pub struct EventBus {
+    subscribers: Vec<Sender<Entry>>,
+}
+
+impl EventBus {
+    /// Every change from now on
+    pub fn subscribe(&mut self) -> Receiver<Entry> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subscribers.is_empty()
+    }
+
+    pub fn publish(&mut self, entry: &Entry) {
+        self.subscribers.retain(|subscriber| subscriber.send(entry.clone()).is_ok());
+    }
+}
@@ -1,48 +1,639 @@
 // File storage - JSON-based persistence for todos
+// The whole task set is read once and kept in memory; edits mark it dirty and are
+// written back in one go shortly afterwards, so bursts of changes cost a single write.
+// Projects can be split out into their own files under projects/ (e.g. projects/work.json),
+// so one project can be synced to another machine without the rest.
+// The highest ID ever stored is kept in meta.json, so a task archived or purged from the end of
+// the list doesn't hand its ID to the next new task (links and history would point at the wrong one).
+// A file that doesn't parse is salvaged entry by entry rather than treated as empty, and nothing
+// is written over it until the user has chosen to keep what was recovered.
+// The TUI hands its writes to a background thread (see writer.rs); command line tools write directly.
+// It also logs each change to a journal as it's made (see journal.rs), which opening replays, so
+// a crash between saves loses nothing.
+// Other processes write the same files (`tdui <text>`, `tdui import`, a second TUI), so before
+// each save the files are checked against what this run last read or wrote, and tasks changed
+// elsewhere since are taken in rather than written over.
 
+use super::archive::Archive;
+use super::events::EventBus;
+use super::journal::{self, Entry, Journal};
+use super::writer::Writer;
+use crate::error::{Result, WithPath};
 use crate::models::Todo;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 use std::fs;
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant, SystemTime};
+
+/// How long unsaved edits may wait before they're written to disk
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// A project's own task file, naming the project so the file name doesn't have to
+#[derive(Serialize, Deserialize)]
+struct ProjectFile {
+    project: String,
+    todos: Vec<Todo>,
+}
+
+/// Bookkeeping saved next to todos.json
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct StorageMeta {
+    /// The highest task ID ever stored
+    last_id: usize,
+}
+
+/// Everything a save writes, copied out so the background writer can write it while the tasks
+/// go on changing
+pub struct Snapshot {
+    file_path: PathBuf,
+    todos: Vec<Todo>,
+    split_projects: BTreeSet<String>,
+    last_id: usize,
+}
+
+impl Snapshot {
+    pub fn write(&self) -> Result<FileStamps> {
+        write_files(&self.file_path, &self.todos, &self.split_projects, self.last_id)
+    }
+}
+
+/// When each task file was last modified and how long it was, to tell whether something else
+/// has written them since
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileStamps(Vec<(PathBuf, Option<SystemTime>, u64)>);
+
+impl FileStamps {
+    fn read(file_path: &Path) -> Self {
+        let main_file = file_path.exists().then(|| file_path.to_path_buf());
+        Self(main_file.into_iter().chain(project_file_paths(file_path))
+            .map(|path| {
+                let metadata = fs::metadata(&path).ok();
+                let modified = metadata.as_ref().and_then(|metadata| metadata.modified().ok());
+                let len = metadata.map(|metadata| metadata.len()).unwrap_or_default();
+                (path, modified, len)
+            })
+            .collect())
+    }
+}
+
+/// A task file that couldn't be read as it was, and what could be saved from it
+#[derive(Debug, Clone)]
+pub struct DamagedFile {
+    pub path: PathBuf,
+    /// Why the file couldn't be read
+    pub reason: String,
+    /// Tasks read from the entries that were still whole
+    pub recovered: usize,
+    /// Entries that looked like tasks but couldn't be read
+    pub skipped: usize,
+    /// Where the original is copied before the recovered tasks are saved over it
+    pub backup_path: PathBuf,
+}
 
 pub struct FileStorage {
     file_path: PathBuf,
+    todos: Vec<Todo>,
+    /// Projects whose tasks are kept in their own file; every project file found is one
+    split_projects: BTreeSet<String>,
+    /// When the oldest unsaved change was made, or None if memory matches the file
+    dirty_since: Option<Instant>,
+    /// Bumped on every change, so callers can tell when cached results are stale
+    revision: u64,
+    /// Files that were damaged when opened; while any are, nothing is saved
+    damaged: Vec<DamagedFile>,
+    /// The highest task ID ever stored, including tasks since archived or purged
+    last_id: usize,
+    /// Writes saves on its own thread, once `with_background_writer` has started it
+    writer: Option<Writer>,
+    /// The revision handed to the writer and not yet known to be on disk
+    writing: Option<u64>,
+    /// Whether the latest attempt to save failed
+    failing: bool,
+    /// A hash of each task as the files held it when this run last read or wrote them, and the
+    /// files' stamps at that point
+    on_disk: HashMap<usize, u64>,
+    stamps: FileStamps,
+    /// What `on_disk` becomes once the write handed to the background writer is done
+    sent: Option<HashMap<usize, u64>>,
+    /// Whether tasks were taken in from the files since `take_changed_elsewhere` was last asked
+    changed_elsewhere: bool,
+    /// Where each change is logged as it's made, once `with_journal` has turned it on
+    journal: Option<Journal>,
+    /// Where each change is sent as it's made, for those who subscribed
+    events: EventBus,
 }
 
 impl FileStorage {
-    pub fn new(file_path: PathBuf) -> Self {
-        Self { file_path }
+    /// Open the todo file, reading it once; later reads are served from memory
+    pub fn open(file_path: PathBuf) -> Self {
+        let mut storage = Self {
+            file_path,
+            todos: Vec::new(),
+            split_projects: BTreeSet::new(),
+            dirty_since: None,
+            revision: 0,
+            damaged: Vec::new(),
+            last_id: 0,
+            writer: None,
+            writing: None,
+            failing: false,
+            on_disk: HashMap::new(),
+            stamps: FileStamps::default(),
+            sent: None,
+            changed_elsewhere: false,
+            journal: None,
+            events: EventBus::default(),
+        };
+        storage.stamps = FileStamps::read(&storage.file_path);
+        (storage.todos, storage.split_projects, storage.damaged) = storage.load_salvaging();
+        storage.on_disk = task_hashes(&storage.todos);
+        storage.last_id = storage.todos.iter().map(|t| t.id).fold(storage.stored_last_id(), usize::max);
+
+        // Changes a run that ended without saving them left in the journal; the next save keeps them
+        let entries = Journal::read(&Journal::path_for(&storage.file_path));
+        if !entries.is_empty() {
+            for entry in entries {
+                storage.replay(entry);
+            }
+            storage.mark_dirty();
+        }
+        storage
     }
 
-    pub fn load_todos(&self) -> anyhow::Result<Vec<Todo>> {
-        // Check if file exists
-        if !self.file_path.exists() {
-            return Ok(Vec::new());
+    /// The highest task ID meta.json records; a missing or unreadable one only loses IDs no longer
+    /// in any file
+    fn stored_last_id(&self) -> usize {
+        fs::read_to_string(self.meta_path()).ok()
+            .and_then(|contents| serde_json::from_str::<StorageMeta>(&contents).ok())
+            .unwrap_or_default()
+            .last_id
+    }
+
+    /// Read the files again for changes made outside this run (another tdui, a sync, an editor);
+    /// returns true if the tasks on disk differ from those in memory. Nothing is read while there
+    /// are unsaved changes, so none are lost
+    pub fn reload(&mut self) -> Result<bool> {
+        if self.is_dirty() || !self.damaged.is_empty() {
+            return Ok(false);
+        }
+        let stamps = FileStamps::read(&self.file_path);
+        if stamps == self.stamps {
+            return Ok(false);
         }
+        let (todos, split_projects) = self.load()?;
+        self.stamps = stamps;
+        self.on_disk = task_hashes(&todos);
+        if split_projects == self.split_projects && fingerprint(&todos) == fingerprint(&self.todos) {
+            return Ok(false);
+        }
+        self.last_id = todos.iter().map(|t| t.id).fold(self.last_id.max(self.stored_last_id()), usize::max);
+        self.todos = todos;
+        self.split_projects = split_projects;
+        self.revision += 1;
+        Ok(true)
+    }
 
-        // Read file contents
-        let contents = fs::read_to_string(&self.file_path)?;
+    /// Take in what was saved elsewhere since this run last read or wrote the files: tasks added
+    /// there, tasks changed there but not here, and removals of tasks not changed here. Where both
+    /// changed a task, this run's copy is kept, as it's the one being saved. A task added there
+    /// under an ID this run also gave a new task is moved to a fresh ID
+    fn take_in_changes_elsewhere(&mut self) -> Result<()> {
+        let stamps = FileStamps::read(&self.file_path);
+        if stamps == self.stamps {
+            return Ok(());
+        }
+        let (theirs, _) = self.load()?;
+        self.last_id = self.last_id.max(self.stored_last_id());
+        let ours = task_hashes(&self.todos);
+        let mut taken = Vec::new();
+        for todo in &theirs {
+            let hash = task_hash(todo);
+            match (self.on_disk.get(&todo.id), ours.get(&todo.id)) {
+                // Unchanged there, or changed here and there alike
+                (Some(&base), _) if base == hash => {}
+                (_, Some(&mine)) if mine == hash => {}
+                // Changed there; taken unless it was changed or removed here too
+                (Some(&base), Some(&mine)) if mine == base => taken.push(todo.clone()),
+                (Some(_), _) => {}
+                // New there
+                (None, None) => taken.push(todo.clone()),
+                (None, Some(_)) => {
+                    let mut todo = todo.clone();
+                    self.last_id = self.last_id.max(todo.id) + 1;
+                    todo.id = self.last_id;
+                    taken.push(todo);
+                }
+            }
+        }
+        let theirs: BTreeSet<usize> = theirs.iter().map(|todo| todo.id).collect();
+        let removed: Vec<usize> = self.on_disk.iter()
+            .filter(|&(id, base)| !theirs.contains(id) && ours.get(id) == Some(base))
+            .map(|(&id, _)| id)
+            .collect();
 
-        // Deserialize JSON to Vec<Todo>
-        let todos: Vec<Todo> = serde_json::from_str(&contents)?;
+        if !taken.is_empty() || !removed.is_empty() {
+            if !removed.is_empty() {
+                self.todos.retain(|todo| !removed.contains(&todo.id));
+                self.record(|| Entry::Remove(removed));
+            }
+            for todo in taken {
+                self.record(|| Entry::Upsert(Box::new(todo.clone())));
+                self.replay(Entry::Upsert(Box::new(todo)));
+            }
+            self.revision += 1;
+            self.changed_elsewhere = true;
+        }
+        Ok(())
+    }
 
-        Ok(todos)
+    /// Whether tasks saved elsewhere were taken in since this was last asked
+    pub fn take_changed_elsewhere(&mut self) -> bool {
+        std::mem::take(&mut self.changed_elsewhere)
     }
 
-    pub fn save_todos(&self, todos: &[Todo]) -> anyhow::Result<()> {
-        // Create parent directory if it doesn't exist
-        if let Some(parent) = self.file_path.parent() {
-            fs::create_dir_all(parent)?;
+    fn replay(&mut self, entry: Entry) {
+        match entry {
+            Entry::Upsert(todo) => {
+                let todo = *todo;
+                self.last_id = self.last_id.max(todo.id);
+                match self.todos.iter_mut().find(|t| t.id == todo.id) {
+                    Some(existing) => *existing = todo,
+                    None => self.todos.push(todo),
+                }
+            }
+            Entry::Remove(ids) => self.todos.retain(|todo| !ids.contains(&todo.id)),
+            Entry::Split { project, split: true } => {
+                self.split_projects.insert(project);
+            }
+            Entry::Split { project, split: false } => {
+                self.split_projects.remove(&project);
+            }
         }
+    }
 
-        // Serialize Vec<Todo> to JSON with pretty printing
-        let json = serde_json::to_string_pretty(todos)?;
+    /// Log every change to the journal as it's made from now on
+    pub fn with_journal(mut self) -> Self {
+        self.journal = Some(Journal::new(Journal::path_for(&self.file_path)));
+        self
+    }
 
-        // Write to file
-        fs::write(&self.file_path, json)?;
+    /// Log a change, if the journal is on, and pass it to subscribers; a change that can't be logged
+    /// is still saved as usual
+    fn record(&mut self, entry: impl FnOnce() -> Entry) {
+        if self.journal.is_none() && self.events.is_empty() {
+            return;
+        }
+        let entry = entry();
+        if let Some(journal) = &mut self.journal {
+            let _ = journal.record(&entry);
+        }
+        self.events.publish(&entry);
+    }
 
+    /// Each change made from now on, as the journal records it
+    pub fn subscribe(&mut self) -> Receiver<Entry> {
+        self.events.subscribe()
+    }
+
+    /// Once everything is on disk, the journal has nothing left to add
+    fn clear_journal(&mut self) {
+        if self.is_dirty() {
+            return;
+        }
+        let _ = match &mut self.journal {
+            Some(journal) => journal.clear(),
+            // Left by a run that had it on
+            None => journal::remove(&Journal::path_for(&self.file_path)),
+        };
+    }
+
+    /// Save on a background thread from now on, so flushing doesn't wait for the disk
+    pub fn with_background_writer(mut self) -> Self {
+        self.writer = Some(Writer::start());
+        self
+    }
+
+    /// Read todos.json and every project file
+    pub fn load_todos(&self) -> Result<Vec<Todo>> {
+        Ok(self.load()?.0)
+    }
+
+    fn load(&self) -> Result<(Vec<Todo>, BTreeSet<String>)> {
+        // A missing todos.json just means no tasks outside project files
+        let mut todos: Vec<Todo> = if self.file_path.exists() {
+            let contents = fs::read_to_string(&self.file_path).with_path(&self.file_path)?;
+            serde_json::from_str(&contents).with_path(&self.file_path)?
+        } else {
+            Vec::new()
+        };
+
+        let mut split_projects = BTreeSet::new();
+        for path in self.project_file_paths() {
+            let contents = fs::read_to_string(&path).with_path(&path)?;
+            let file: ProjectFile = serde_json::from_str(&contents).with_path(&path)?;
+            todos.extend(file.todos);
+            split_projects.insert(file.project);
+        }
+        Ok((todos, split_projects))
+    }
+
+    /// Like `load`, but a file that can't be read is salvaged as far as possible and reported
+    /// instead of failing the whole load
+    fn load_salvaging(&self) -> (Vec<Todo>, BTreeSet<String>, Vec<DamagedFile>) {
+        let mut todos = Vec::new();
+        let mut split_projects = BTreeSet::new();
+        let mut damaged = Vec::new();
+        for path in self.file_paths() {
+            let is_project_file = path != self.file_path;
+            let parsed = fs::read_to_string(&path)
+                .map_err(|err| (String::new(), err.to_string()))
+                .and_then(|contents| {
+                    let parsed = if is_project_file {
+                        serde_json::from_str::<ProjectFile>(&contents).map(|file| (Some(file.project), file.todos))
+                    } else {
+                        serde_json::from_str::<Vec<Todo>>(&contents).map(|todos| (None, todos))
+                    };
+                    parsed.map_err(|err| (contents, err.to_string()))
+                });
+            match parsed {
+                Ok((project, file_todos)) => {
+                    split_projects.extend(project);
+                    todos.extend(file_todos);
+                }
+                Err((contents, reason)) => {
+                    let (recovered, skipped) = salvage_todos(&contents);
+                    // The project's name was in the damaged part, but its tasks still carry it
+                    if is_project_file {
+                        split_projects.extend(recovered.iter().filter_map(|todo| todo.project.clone()));
+                    }
+                    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+                    let mut backup_path = path.clone().into_os_string();
+                    backup_path.push(format!(".damaged-{}", stamp));
+                    damaged.push(DamagedFile {
+                        path,
+                        reason,
+                        recovered: recovered.len(),
+                        skipped,
+                        backup_path: PathBuf::from(backup_path),
+                    });
+                    todos.extend(recovered);
+                }
+            }
+        }
+        (todos, split_projects, damaged)
+    }
+
+    /// Files found damaged on opening that haven't been dealt with yet
+    pub fn damaged_files(&self) -> &[DamagedFile] {
+        &self.damaged
+    }
+
+    /// Copy each damaged file to its backup path, then save the recovered tasks in their place
+    pub fn keep_recovered(&mut self) -> Result<()> {
+        for file in &self.damaged {
+            fs::copy(&file.path, &file.backup_path).with_path(&file.backup_path)?;
+        }
+        self.damaged.clear();
+        self.mark_dirty();
+        self.flush()?;
         Ok(())
     }
 
+    fn meta_path(&self) -> PathBuf {
+        meta_path(&self.file_path)
+    }
+
+    /// The ID for a new task: one past any ever stored, so no ID is handed out twice
+    pub fn next_id(&self) -> usize {
+        self.last_id + 1
+    }
+
+    /// The ID for a new task that's free in the archive too, whose highest ID covers tasks
+    /// archived before storage kept track itself
+    pub fn next_free_id(&self, archive: &Archive) -> usize {
+        self.next_id().max(archive.max_id() + 1)
+    }
+
+    /// todos.json (if it exists) and every project file, in the order they're read
+    pub fn file_paths(&self) -> Vec<PathBuf> {
+        let main_file = self.file_path.exists().then(|| self.file_path.clone());
+        main_file.into_iter().chain(self.project_file_paths()).collect()
+    }
+
+    fn project_file_paths(&self) -> Vec<PathBuf> {
+        project_file_paths(&self.file_path)
+    }
+
+    /// Whether the project's tasks are kept in their own file
+    pub fn is_split(&self, project: &str) -> bool {
+        self.split_projects.contains(project)
+    }
+
+    /// Move a project's tasks into their own file, or back into todos.json
+    pub fn set_split(&mut self, project: &str, split: bool) {
+        let changed = if split {
+            self.split_projects.insert(project.to_string())
+        } else {
+            self.split_projects.remove(project)
+        };
+        if changed {
+            self.record(|| Entry::Split { project: project.to_string(), split });
+            self.mark_dirty();
+        }
+    }
+
+    /// Every stored task, in any state
+    pub fn todos(&self) -> &[Todo] {
+        &self.todos
+    }
+
+    /// Add a task, or replace the stored task with the same ID
+    pub fn upsert(&mut self, todo: Todo) {
+        self.record(|| Entry::Upsert(Box::new(todo.clone())));
+        match self.todos.iter_mut().find(|t| t.id == todo.id) {
+            Some(existing) => *existing = todo,
+            None => {
+                self.last_id = self.last_id.max(todo.id);
+                self.todos.push(todo);
+            }
+        }
+        self.mark_dirty();
+    }
+
+    /// Add tasks whose IDs aren't stored yet, without checking for an existing copy of each
+    pub fn append(&mut self, todos: Vec<Todo>) {
+        self.last_id = todos.iter().map(|t| t.id).fold(self.last_id, usize::max);
+        for todo in &todos {
+            self.record(|| Entry::Upsert(Box::new(todo.clone())));
+        }
+        self.todos.extend(todos);
+        self.mark_dirty();
+    }
+
+    /// Take out every task matching `predicate`, returning them
+    pub fn remove_where(&mut self, predicate: impl Fn(&Todo) -> bool) -> Vec<Todo> {
+        let (removed, kept): (Vec<Todo>, Vec<Todo>) = std::mem::take(&mut self.todos)
+            .into_iter()
+            .partition(|t| predicate(t));
+        self.todos = kept;
+        if !removed.is_empty() {
+            self.record(|| Entry::Remove(removed.iter().map(|todo| todo.id).collect()));
+            self.mark_dirty();
+        }
+        removed
+    }
+
+    /// Change the stored task with the given ID in place
+    pub fn update(&mut self, id: usize, change: impl FnOnce(&mut Todo)) {
+        if let Some(todo) = self.todos.iter_mut().find(|t| t.id == id) {
+            change(todo);
+            let todo = todo.clone();
+            self.record(|| Entry::Upsert(Box::new(todo)));
+            self.mark_dirty();
+        }
+    }
+
+    /// Whether there are changes not yet known to be on disk
+    pub fn is_dirty(&self) -> bool {
+        self.dirty_since.is_some() || self.writing.is_some()
+    }
+
+    /// Whether the latest attempt to save failed (the background writer keeps retrying it)
+    pub fn is_failing(&self) -> bool {
+        self.failing
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    fn mark_dirty(&mut self) {
+        self.revision += 1;
+        self.dirty_since.get_or_insert_with(Instant::now);
+    }
+
+    /// Write pending changes once the oldest is `SAVE_DELAY` old; returns true if anything was written.
+    /// With a background writer this only starts the write, and a later call reports how it went
+    pub fn flush_if_due(&mut self) -> Result<bool> {
+        let written = self.finished_writes()?;
+        match self.dirty_since {
+            Some(since) if since.elapsed() >= SAVE_DELAY => Ok(self.save()? || written),
+            _ => Ok(written),
+        }
+    }
+
+    /// Write pending changes now, waiting for the background writer if there is one; returns true
+    /// if anything was written
+    pub fn flush(&mut self) -> Result<bool> {
+        // A failure being retried is about to be tried once more below
+        let _ = self.finished_writes();
+        // A write under way has to land before the files are checked for changes made elsewhere
+        if !self.failing {
+            self.wait_for_writer()?;
+        }
+        let saved = self.save()?;
+        Ok(self.wait_for_writer()? || saved)
+    }
+
+    /// Block until the write handed to the background writer is done; true if there was one
+    fn wait_for_writer(&mut self) -> Result<bool> {
+        let (Some(writer), Some(revision)) = (&self.writer, self.writing) else {
+            return Ok(false);
+        };
+        match writer.wait_for(revision) {
+            Some(result) => {
+                self.failing = result.is_err();
+                self.written(result?);
+                Ok(true)
+            }
+            None => self.write_without_writer(),
+        }
+    }
+
+    /// The write handed to the background writer is on disk
+    fn written(&mut self, stamps: FileStamps) {
+        self.writing = None;
+        self.on_disk = self.sent.take().unwrap_or_default();
+        self.stamps = stamps;
+        self.clear_journal();
+    }
+
+    /// Hand the changes to the background writer, or write them here if there isn't one; returns
+    /// true if they're already on disk
+    fn save(&mut self) -> Result<bool> {
+        // Saving now would replace a damaged file with only what was recovered from it
+        if self.dirty_since.is_none() || !self.damaged.is_empty() {
+            return Ok(false);
+        }
+        // Until the write under way lands the files can't be told apart from changes made
+        // elsewhere; one that failed is replaced by this newer snapshot
+        if self.writing.is_some() && !self.failing {
+            return Ok(false);
+        }
+        self.take_in_changes_elsewhere()?;
+        let Some(writer) = &self.writer else {
+            let result = write_files(&self.file_path, &self.todos, &self.split_projects, self.last_id);
+            self.failing = result.is_err();
+            self.stamps = result?;
+            self.on_disk = task_hashes(&self.todos);
+            self.dirty_since = None;
+            self.clear_journal();
+            return Ok(true);
+        };
+        let snapshot = Snapshot {
+            file_path: self.file_path.clone(),
+            todos: self.todos.clone(),
+            split_projects: self.split_projects.clone(),
+            last_id: self.last_id,
+        };
+        if !writer.send(self.revision, snapshot) {
+            return self.write_without_writer();
+        }
+        self.sent = Some(task_hashes(&self.todos));
+        self.writing = Some(self.revision);
+        self.dirty_since = None;
+        Ok(false)
+    }
+
+    /// Take in how the background writer's attempts went: true once the changes handed to it are
+    /// on disk, or the error from its latest attempt
+    fn finished_writes(&mut self) -> Result<bool> {
+        let Some(writer) = &self.writer else {
+            return Ok(false);
+        };
+        let mut written = false;
+        let mut error = None;
+        for (revision, result) in writer.finished() {
+            self.failing = result.is_err();
+            match result {
+                Ok(stamps) if self.writing == Some(revision) => {
+                    self.written(stamps);
+                    written = true;
+                    error = None;
+                }
+                Ok(_) => error = None,
+                Err(err) => error = Some(err),
+            }
+        }
+        match error {
+            Some(err) => Err(err),
+            None => Ok(written),
+        }
+    }
+
+    /// The writer's thread has stopped, so write here from now on
+    fn write_without_writer(&mut self) -> Result<bool> {
+        self.writer = None;
+        self.writing = None;
+        self.dirty_since.get_or_insert_with(Instant::now);
+        self.save()
+    }
+
     pub fn get_default_path() -> PathBuf {
         // Get home directory
         let home = std::env::var("HOME")
@@ -57,3 +648,165 @@ impl FileStorage {
             .join("todos.json")
     }
 }
+
+/// Write the tasks to todos.json, the split projects' files and meta.json, returning the files'
+/// stamps once written
+fn write_files(file_path: &Path, todos: &[Todo], split_projects: &BTreeSet<String>, last_id: usize) -> Result<FileStamps> {
+    // Create parent directory if it doesn't exist
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent).with_path(parent)?;
+    }
+
+    let (split, rest): (Vec<&Todo>, Vec<&Todo>) = todos.iter()
+        .partition(|todo| todo.project.as_ref().is_some_and(|project| split_projects.contains(project)));
+    write_atomically(file_path, &serde_json::to_string_pretty(&rest).with_path(file_path)?)?;
+    let meta_path = meta_path(file_path);
+    let meta = StorageMeta { last_id: todos.iter().map(|t| t.id).fold(last_id, usize::max) };
+    write_atomically(&meta_path, &serde_json::to_string_pretty(&meta).with_path(&meta_path)?)?;
+
+    // One file per split project (even an empty one, so the split is remembered), and
+    // none left over for projects no longer split
+    let mut written = Vec::new();
+    if !split_projects.is_empty() {
+        fs::create_dir_all(projects_dir(file_path)).with_path(&projects_dir(file_path))?;
+    }
+    for project in split_projects {
+        let path = project_file_path(file_path, project, &written);
+        let file = ProjectFile {
+            project: project.clone(),
+            todos: split.iter().filter(|todo| todo.project.as_ref() == Some(project)).map(|&todo| todo.clone()).collect(),
+        };
+        write_atomically(&path, &serde_json::to_string_pretty(&file).with_path(&path)?)?;
+        written.push(path);
+    }
+    for path in project_file_paths(file_path) {
+        if !written.contains(&path) {
+            fs::remove_file(&path).with_path(&path)?;
+        }
+    }
+
+    Ok(FileStamps::read(file_path))
+}
+
+/// Each task as JSON, in ID order, so the same tasks compare equal whichever file they're in
+fn fingerprint(todos: &[Todo]) -> Vec<(usize, String)> {
+    let mut tasks: Vec<(usize, String)> = todos.iter()
+        .map(|todo| (todo.id, serde_json::to_string(todo).unwrap_or_default()))
+        .collect();
+    tasks.sort();
+    tasks
+}
+
+/// A hash of each task's JSON, by ID, to tell which tasks changed between two reads
+fn task_hashes(todos: &[Todo]) -> HashMap<usize, u64> {
+    todos.iter().map(|todo| (todo.id, task_hash(todo))).collect()
+}
+
+fn task_hash(todo: &Todo) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(todo).unwrap_or_default().hash(&mut hasher);
+    hasher.finish()
+}
+
+fn meta_path(file_path: &Path) -> PathBuf {
+    file_path.with_file_name("meta.json")
+}
+
+fn projects_dir(file_path: &Path) -> PathBuf {
+    file_path.with_file_name("projects")
+}
+
+fn project_file_paths(file_path: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(projects_dir(file_path)) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// projects/<name>.json with the name made filename-safe, numbered if another project took it
+fn project_file_path(file_path: &Path, project: &str, taken: &[PathBuf]) -> PathBuf {
+    let stem: String = project.chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    (1..)
+        .map(|n| match n {
+            1 => projects_dir(file_path).join(format!("{}.json", stem)),
+            n => projects_dir(file_path).join(format!("{}-{}.json", stem, n)),
+        })
+        .find(|path| !taken.contains(path))
+        .unwrap_or_default()
+}
+
+/// Write to a temporary file and rename it over the original, so a crash mid-write
+/// never leaves a truncated file behind
+pub(super) fn write_atomically(path: &Path, contents: &str) -> Result<()> {
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, contents).with_path(path)?;
+    fs::rename(&temp_path, path).with_path(path)?;
+    Ok(())
+}
+
+/// Pull every whole task out of a file that doesn't parse: each {...} that reads as a task is kept,
+/// and others are searched for tasks inside them (a project file wraps its tasks in an object).
+/// Returns the tasks and the number of broken entries left behind.
+fn salvage_todos(text: &str) -> (Vec<Todo>, usize) {
+    let mut todos = Vec::new();
+    let mut skipped = 0;
+    for object in json_objects(text) {
+        if let Ok(todo) = serde_json::from_str::<Todo>(object) {
+            todos.push(todo);
+            continue;
+        }
+        let (inner, inner_skipped) = salvage_todos(&object[1..]);
+        if inner.is_empty() && inner_skipped == 0 && object.contains("\"title\"") {
+            skipped += 1;
+        }
+        todos.extend(inner);
+        skipped += inner_skipped;
+    }
+    (todos, skipped)
+}
+
+/// The outermost {...} spans in `text`, skipping braces inside strings; one cut off by the end
+/// of the text runs to the end
+fn json_objects(text: &str) -> Vec<&str> {
+    let mut objects = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    let (mut in_string, mut escaped) = (false, false);
+    for (index, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => {
+                if depth == 0 {
+                    start = index;
+                }
+                depth += 1;
+            }
+            '}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    objects.push(&text[start..=index]);
+                }
+            }
+            _ => {}
+        }
+    }
+    if depth > 0 {
+        objects.push(&text[start..]);
+    }
+    objects
+}
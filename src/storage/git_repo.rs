@@ -0,0 +1,254 @@
+// Git repo - Keeps the data directory under version control when enabled in config
+// Changes are described as they happen ("complete: buy milk") and committed once they're
+// on disk, giving a history of every task; pull and push sync it with another machine.
+// Runs the `git` command line tool, so it uses the user's own git setup and credentials.
+
+use super::FileStorage;
+use crate::error::{Result, TduiError, WithPath};
+use crate::models::Todo;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Caches that are rebuilt locally and shouldn't be versioned
+const IGNORED: &str = "search_index.json\nvault.json\ncalendars/\n*.tmp\njournal.jsonl\n";
+
+/// How many of the latest commits are searched for earlier versions of a task
+const HISTORY_COMMITS: usize = 200;
+
+/// A task as it was saved in one commit
+#[derive(Debug, Clone)]
+pub struct TaskVersion {
+    pub committed_at: DateTime<Utc>,
+    /// The commit's first line, e.g. "edit: buy milk"
+    pub message: String,
+    pub todo: Todo,
+}
+
+pub struct GitRepo {
+    dir: PathBuf,
+    /// Descriptions of the changes since the last commit
+    pending: Vec<String>,
+}
+
+impl GitRepo {
+    /// Use the data directory's repo, creating it (and committing what's there) the first time
+    pub fn open(dir: PathBuf) -> Result<Self> {
+        let mut repo = Self { dir, pending: Vec::new() };
+        if !repo.dir.join(".git").exists() {
+            fs::create_dir_all(&repo.dir).with_path(&repo.dir)?;
+            repo.git(&["init", "--quiet"])?;
+            let ignore_path = repo.dir.join(".gitignore");
+            fs::write(&ignore_path, IGNORED).with_path(&ignore_path)?;
+            repo.record("start tracking tdui data".to_string());
+            repo.commit()?;
+        }
+        Ok(repo)
+    }
+
+    /// The repo in `dir` if there is one, without creating it
+    pub fn existing(dir: PathBuf) -> Option<Self> {
+        dir.join(".git").exists().then(|| Self { dir, pending: Vec::new() })
+    }
+
+    pub fn get_default_path() -> PathBuf {
+        // The directory holding todos.json: ~/.local/share/tdui/
+        FileStorage::get_default_path().parent().map(PathBuf::from).unwrap_or_default()
+    }
+
+    /// Remember a change to describe in the next commit
+    pub fn record(&mut self, message: String) {
+        self.pending.push(message);
+    }
+
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Commit everything in the data directory, described by the recorded changes; returns false
+    /// if there was nothing to commit
+    pub fn commit(&mut self) -> Result<bool> {
+        let changes = std::mem::take(&mut self.pending);
+        self.git(&["add", "--all"])?;
+        if self.git(&["status", "--porcelain"])?.trim().is_empty() {
+            return Ok(false);
+        }
+        let message = match changes.as_slice() {
+            [] => "update tdui data".to_string(),
+            [change] => change.clone(),
+            [first, rest @ ..] => format!("{} (and {} more)\n\n{}", first, rest.len(), changes.join("\n")),
+        };
+        self.git(&["commit", "--quiet", "--message", &message])?;
+        Ok(true)
+    }
+
+    /// Pull (rebasing local commits on top) and push to the branch's upstream
+    pub fn sync(&self) -> Result<()> {
+        self.git(&["pull", "--rebase", "--quiet"])?;
+        self.git(&["push", "--quiet"])?;
+        Ok(())
+    }
+
+    /// The lock a git command that didn't finish left behind; commits fail until it's removed
+    pub fn stale_lock(&self) -> Option<PathBuf> {
+        let path = self.dir.join(".git").join("index.lock");
+        path.exists().then_some(path)
+    }
+
+    /// Whether a pull stopped partway (on a conflict, say), which holds up the next sync
+    pub fn rebase_in_progress(&self) -> bool {
+        ["rebase-merge", "rebase-apply"].iter().any(|name| self.dir.join(".git").join(name).exists())
+    }
+
+    /// When the latest commit was made; None before the first one (git log fails on an empty repo)
+    pub fn last_commit(&self) -> Option<DateTime<Utc>> {
+        let log = self.git(&["log", "--max-count=1", "--format=%cI"]).ok()?;
+        DateTime::parse_from_rfc3339(log.trim()).ok().map(|at| at.with_timezone(&Utc))
+    }
+
+    /// The remote sync pulls from and pushes to, e.g. "origin"; None if there isn't one
+    pub fn remote(&self) -> Result<Option<String>> {
+        Ok(self.git(&["remote"])?.lines().next().map(str::to_string))
+    }
+
+    /// Contact `remote` without changing anything, failing rather than asking for credentials
+    pub fn check_remote(&self, remote: &str) -> Result<()> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.dir)
+            .args(["ls-remote", "--heads", remote])
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|err| TduiError::SyncFailed(format!("couldn't run git: {}", err)))?;
+        if !output.status.success() {
+            return Err(git_failure("ls-remote", &output.stderr));
+        }
+        Ok(())
+    }
+
+    /// Every committed version of task `id`, newest first, looking back over the latest
+    /// HISTORY_COMMITS commits to the task files
+    pub fn task_versions(&self, id: usize) -> Result<Vec<TaskVersion>> {
+        let log = self.git(&["log", &format!("--max-count={}", HISTORY_COMMITS), "--format=%H%x09%cI%x09%s", "--", "todos.json", "projects"])?;
+        let commits: Vec<(&str, DateTime<Utc>, &str)> = log.lines()
+            .filter_map(|line| {
+                let mut parts = line.splitn(3, '\t');
+                let hash = parts.next()?;
+                let committed_at = DateTime::parse_from_rfc3339(parts.next()?).ok()?.with_timezone(&Utc);
+                Some((hash, committed_at, parts.next().unwrap_or_default()))
+            })
+            .collect();
+
+        // The task may have been in todos.json or any project file that ever existed
+        let project_files = self.git(&["log", "--name-only", "--format=", "--", "projects"])?;
+        let mut files: Vec<&str> = project_files.lines().filter(|path| path.ends_with(".json")).collect();
+        files.sort();
+        files.dedup();
+        files.insert(0, "todos.json");
+
+        // One `cat-file` reads every file at every commit, rather than a git run for each
+        let requests: Vec<String> = commits.iter()
+            .flat_map(|(hash, _, _)| files.iter().map(move |file| format!("{}:{}", hash, file)))
+            .collect();
+        let contents = self.git_batch(&requests)?;
+
+        let mut versions = Vec::new();
+        for (commit, files) in commits.iter().zip(contents.chunks(files.len().max(1))) {
+            let found = files.iter().flatten().find_map(|content| find_task(content, id));
+            if let Some(todo) = found {
+                let (_, committed_at, message) = *commit;
+                versions.push(TaskVersion { committed_at, message: message.to_string(), todo });
+            }
+        }
+        Ok(versions)
+    }
+
+    /// Run a git command in the data directory, returning its output or its error message
+    fn git(&self, args: &[&str]) -> Result<String> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.dir)
+            .args(args)
+            .output()
+            .map_err(|err| TduiError::SyncFailed(format!("couldn't run git: {}", err)))?;
+        if !output.status.success() {
+            return Err(git_failure(args[0], &output.stderr));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// The contents of each "<commit>:<path>" in `objects`, None where the file didn't exist
+    fn git_batch(&self, objects: &[String]) -> Result<Vec<Option<Vec<u8>>>> {
+        let mut child = Command::new("git")
+            .arg("-C")
+            .arg(&self.dir)
+            .args(["cat-file", "--batch"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| TduiError::SyncFailed(format!("couldn't run git: {}", err)))?;
+        // Write from another thread so a full output pipe can't stall the input
+        let mut stdin = child.stdin.take();
+        let input = objects.join("\n") + "\n";
+        let writer = std::thread::spawn(move || stdin.as_mut().map(|stdin| stdin.write_all(input.as_bytes())));
+        let output = child.wait_with_output()
+            .map_err(|err| TduiError::SyncFailed(format!("couldn't run git: {}", err)))?;
+        let _ = writer.join();
+        if !output.status.success() {
+            return Err(git_failure("cat-file", &output.stderr));
+        }
+
+        // Each object is "<sha> <type> <size>\n<contents>\n", or "<name> missing\n"
+        let mut contents = Vec::new();
+        let mut rest = output.stdout.as_slice();
+        while let Some(end) = rest.iter().position(|&byte| byte == b'\n') {
+            let header = String::from_utf8_lossy(&rest[..end]).into_owned();
+            rest = &rest[end + 1..];
+            let size = header.rsplit(' ').next().and_then(|size| size.parse::<usize>().ok());
+            match size {
+                Some(size) if !header.ends_with(" missing") && size <= rest.len() => {
+                    contents.push(Some(rest[..size].to_vec()));
+                    rest = rest.get(size + 1..).unwrap_or_default();
+                }
+                _ => contents.push(None),
+            }
+        }
+        contents.resize(objects.len(), None);
+        Ok(contents)
+    }
+}
+
+/// The first line of git's error output, as a sync error
+fn git_failure(command: &str, stderr: &[u8]) -> TduiError {
+    let stderr = String::from_utf8_lossy(stderr);
+    let reason = stderr.lines().find(|line| !line.trim().is_empty()).unwrap_or("failed");
+    TduiError::SyncFailed(format!("git {}: {}", command, reason.trim()))
+}
+
+/// Task `id` from the contents of todos.json or a project file, if it's there and readable
+fn find_task(content: &[u8], id: usize) -> Option<Todo> {
+    let value: Value = serde_json::from_slice(content).ok()?;
+    let entries = match &value {
+        Value::Array(entries) => entries,
+        _ => value.get("todos")?.as_array()?,
+    };
+    let entry = entries.iter().find(|entry| entry.get("id").and_then(Value::as_u64) == Some(id as u64))?;
+    serde_json::from_value(entry.clone()).ok()
+}
+
+/// A commit message line for a task saved over `before` (None if it's new)
+pub fn describe_change(before: Option<&Todo>, after: &Todo) -> String {
+    let verb = match before {
+        None => "add",
+        Some(before) if before.completed && !after.completed => "reopen",
+        Some(before) if before.due_date != after.due_date => "reschedule",
+        Some(before) if before.status() != after.status() => "change status",
+        Some(_) => "edit",
+    };
+    format!("{}: {}", verb, after.title)
+}
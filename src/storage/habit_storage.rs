@@ -0,0 +1,71 @@
+// Habit storage - Habits live in their own habits.json next to todos.json
+// The file is small and changes one key press at a time, so every change is written straight away.
+
+use crate::models::Habit;
+use super::FileStorage;
+use crate::error::{Result, WithPath};
+use std::fs;
+use std::path::PathBuf;
+
+pub struct HabitStorage {
+    file_path: PathBuf,
+    habits: Vec<Habit>,
+    /// Bumped on every change, so callers can tell when cached results are stale
+    revision: u64,
+}
+
+impl HabitStorage {
+    pub fn open(file_path: PathBuf) -> Self {
+        // A missing or unreadable file just means no habits yet
+        let habits = fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self { file_path, habits, revision: 0 }
+    }
+
+    pub fn get_default_path() -> PathBuf {
+        // Keep habits alongside todos.json: ~/.local/share/tdui/habits.json
+        FileStorage::get_default_path().with_file_name("habits.json")
+    }
+
+    pub fn habits(&self) -> &[Habit] {
+        &self.habits
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn add(&mut self, habit: Habit) -> Result<()> {
+        self.habits.push(habit);
+        self.save()
+    }
+
+    pub fn remove(&mut self, id: usize) -> Result<()> {
+        self.habits.retain(|h| h.id != id);
+        self.save()
+    }
+
+    /// Change the habit with the given ID in place
+    pub fn update(&mut self, id: usize, change: impl FnOnce(&mut Habit)) -> Result<()> {
+        if let Some(habit) = self.habits.iter_mut().find(|h| h.id == id) {
+            change(habit);
+        }
+        self.save()
+    }
+
+    fn save(&mut self) -> Result<()> {
+        self.revision += 1;
+        if let Some(parent) = self.file_path.parent() {
+            fs::create_dir_all(parent).with_path(parent)?;
+        }
+
+        // Write to a temporary file and rename it over the original, like todos.json
+        let temp_path = self.file_path.with_extension("json.tmp");
+        let json = serde_json::to_string_pretty(&self.habits).with_path(&self.file_path)?;
+        fs::write(&temp_path, json).with_path(&self.file_path)?;
+        fs::rename(&temp_path, &self.file_path).with_path(&self.file_path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+// Journal - An append-only log of task changes beside todos.json, written the moment each change
+// is made, so a crash or a kill between saves loses nothing. Each line is one change as JSON.
+// Opening storage replays whatever a previous run left in it, and the journal is removed once the
+// task files hold everything it recorded. Replaying a change that was already saved does no harm.
+
+use crate::error::{Result, WithPath};
+use crate::models::Todo;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// One change to the stored tasks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Entry {
+    /// A task added, or changed to this
+    Upsert(Box<Todo>),
+    /// Tasks taken out, by ID
+    Remove(Vec<usize>),
+    /// A project's tasks moved into their own file, or back
+    Split { project: String, split: bool },
+}
+
+pub struct Journal {
+    path: PathBuf,
+    /// Opened on the first change after the journal was last removed
+    file: Option<File>,
+}
+
+impl Journal {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, file: None }
+    }
+
+    /// journal.jsonl, next to the task file at `file_path`
+    pub fn path_for(file_path: &Path) -> PathBuf {
+        file_path.with_file_name("journal.jsonl")
+    }
+
+    /// The changes recorded at `path`; a last line cut short by a crash is left out
+    pub fn read(path: &Path) -> Vec<Entry> {
+        let Ok(contents) = fs::read_to_string(path) else {
+            return Vec::new();
+        };
+        contents.lines()
+            .map_while(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+
+    /// Append a change and hand it to the OS straight away
+    pub fn record(&mut self, entry: &Entry) -> Result<()> {
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => self.file.insert(OpenOptions::new().create(true).append(true).open(&self.path).with_path(&self.path)?),
+        };
+        let mut line = serde_json::to_string(entry).with_path(&self.path)?;
+        line.push('\n');
+        file.write_all(line.as_bytes()).with_path(&self.path)
+    }
+
+    /// Remove the journal once everything in it has been saved
+    pub fn clear(&mut self) -> Result<()> {
+        self.file = None;
+        remove(&self.path)
+    }
+}
+
+/// Remove the journal at `path`, if there is one
+pub fn remove(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err).with_path(path),
+        _ => Ok(()),
+    }
+}
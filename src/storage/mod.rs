@@ -1,5 +1,21 @@
-// Storage module - Handles persistence of todos
+// Storage module - Handles persistence of todos, habits, projects, daily notes and days away, optionally versioned in git
 
+mod archive;
+mod blackout_storage;
+mod events;
 mod file_storage;
+mod git_repo;
+mod habit_storage;
+mod journal;
+mod note_storage;
+mod project_storage;
+mod writer;
 
-pub use file_storage::FileStorage;
+pub use archive::{Archive, ArchiveTotals};
+pub use blackout_storage::BlackoutStorage;
+pub use file_storage::{DamagedFile, FileStorage};
+pub use git_repo::{GitRepo, TaskVersion, describe_change};
+pub use habit_storage::HabitStorage;
+pub use journal::Entry;
+pub use note_storage::NoteStorage;
+pub use project_storage::ProjectStorage;
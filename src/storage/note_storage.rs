@@ -0,0 +1,61 @@
+// Note storage - Daily notes live in notes.json next to todos.json, one free-form note per day
+// Like habits, notes change one edit at a time, so every change is written straight away.
+
+use super::FileStorage;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use crate::error::{Result, WithPath};
+use std::fs;
+use std::path::PathBuf;
+
+pub struct NoteStorage {
+    file_path: PathBuf,
+    notes: BTreeMap<NaiveDate, String>,
+}
+
+impl NoteStorage {
+    pub fn open(file_path: PathBuf) -> Self {
+        // A missing or unreadable file just means no notes yet
+        let notes = fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self { file_path, notes }
+    }
+
+    pub fn get_default_path() -> PathBuf {
+        // Keep notes alongside todos.json: ~/.local/share/tdui/notes.json
+        FileStorage::get_default_path().with_file_name("notes.json")
+    }
+
+    pub fn notes(&self) -> &BTreeMap<NaiveDate, String> {
+        &self.notes
+    }
+
+    pub fn get(&self, date: NaiveDate) -> Option<&str> {
+        self.notes.get(&date).map(String::as_str)
+    }
+
+    /// Replace the note for `date`; a blank note removes it
+    pub fn set(&mut self, date: NaiveDate, text: String) -> Result<()> {
+        if text.trim().is_empty() {
+            self.notes.remove(&date);
+        } else {
+            self.notes.insert(date, text);
+        }
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.file_path.parent() {
+            fs::create_dir_all(parent).with_path(parent)?;
+        }
+
+        // Write to a temporary file and rename it over the original, like todos.json
+        let temp_path = self.file_path.with_extension("json.tmp");
+        let json = serde_json::to_string_pretty(&self.notes).with_path(&self.file_path)?;
+        fs::write(&temp_path, json).with_path(&self.file_path)?;
+        fs::rename(&temp_path, &self.file_path).with_path(&self.file_path)?;
+        Ok(())
+    }
+}
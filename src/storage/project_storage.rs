@@ -0,0 +1,64 @@
+// Project storage - Project settings live in their own projects.json next to todos.json
+// Like habits, the file is small and only changes from a popup, so each change is written straight away.
+
+use crate::models::Project;
+use super::FileStorage;
+use crate::error::{Result, WithPath};
+use std::fs;
+use std::path::PathBuf;
+
+pub struct ProjectStorage {
+    file_path: PathBuf,
+    projects: Vec<Project>,
+}
+
+impl ProjectStorage {
+    pub fn open(file_path: PathBuf) -> Self {
+        // A missing or unreadable file just means no project has settings yet
+        let projects = fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self { file_path, projects }
+    }
+
+    pub fn get_default_path() -> PathBuf {
+        // ~/.local/share/tdui/projects.json
+        FileStorage::get_default_path().with_file_name("projects.json")
+    }
+
+    pub fn projects(&self) -> &[Project] {
+        &self.projects
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Project> {
+        self.projects.iter().find(|project| project.name == name)
+    }
+
+    /// The configured color of the named project, if it has one
+    pub fn color_of(&self, name: Option<&str>) -> Option<&str> {
+        self.get(name?)?.color.as_deref()
+    }
+
+    /// Save a project's settings, replacing any with the same name
+    pub fn upsert(&mut self, project: Project) -> Result<()> {
+        match self.projects.iter_mut().find(|p| p.name == project.name) {
+            Some(existing) => *existing = project,
+            None => self.projects.push(project),
+        }
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.file_path.parent() {
+            fs::create_dir_all(parent).with_path(parent)?;
+        }
+
+        // Write to a temporary file and rename it over the original, like todos.json
+        let temp_path = self.file_path.with_extension("json.tmp");
+        let json = serde_json::to_string_pretty(&self.projects).with_path(&self.file_path)?;
+        fs::write(&temp_path, json).with_path(&self.file_path)?;
+        fs::rename(&temp_path, &self.file_path).with_path(&self.file_path)?;
+        Ok(())
+    }
+}
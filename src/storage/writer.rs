@@ -0,0 +1,84 @@
+// Background writer - Saves the task files on a thread of its own, so a slow disk or network
+// filesystem never holds up typing. Snapshots sent while a write is under way are merged (only the
+// newest is written), and a write that fails is tried again until it works or a newer snapshot
+// takes its place. The outcome of every attempt is sent back for the UI to report.
+
+use super::file_storage::{FileStamps, Snapshot};
+use crate::error::Result;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// How long a failed write waits before it's tried again
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+pub struct Writer {
+    /// Snapshots to write, each with the storage revision it was taken at
+    requests: Option<Sender<(u64, Snapshot)>>,
+    /// The revision and outcome of each write attempt, with the files' stamps once written
+    results: Receiver<(u64, Result<FileStamps>)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Writer {
+    pub fn start() -> Self {
+        let (requests, inbox) = mpsc::channel::<(u64, Snapshot)>();
+        let (outbox, results) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            let mut next = inbox.recv().ok();
+            while let Some((mut revision, mut snapshot)) = next.take() {
+                // Anything queued behind this snapshot is newer, so only the last one is worth writing
+                while let Ok(newer) = inbox.try_recv() {
+                    (revision, snapshot) = newer;
+                }
+                let result = snapshot.write();
+                let failed = result.is_err();
+                if outbox.send((revision, result)).is_err() {
+                    return;
+                }
+                next = if failed {
+                    match inbox.recv_timeout(RETRY_DELAY) {
+                        Ok(newer) => Some(newer),
+                        Err(RecvTimeoutError::Timeout) => Some((revision, snapshot)),
+                        Err(RecvTimeoutError::Disconnected) => None,
+                    }
+                } else {
+                    inbox.recv().ok()
+                };
+            }
+        });
+        Self { requests: Some(requests), results, thread: Some(thread) }
+    }
+
+    /// Queue a snapshot; false if the thread has stopped and it has to be written some other way
+    pub fn send(&self, revision: u64, snapshot: Snapshot) -> bool {
+        self.requests.as_ref().is_some_and(|requests| requests.send((revision, snapshot)).is_ok())
+    }
+
+    /// Outcomes of the attempts made since last asked, oldest first
+    pub fn finished(&self) -> Vec<(u64, Result<FileStamps>)> {
+        self.results.try_iter().collect()
+    }
+
+    /// Block until an attempt at `revision` (or a later one) is made, returning its outcome; None
+    /// if the thread has stopped
+    pub fn wait_for(&self, revision: u64) -> Option<Result<FileStamps>> {
+        loop {
+            match self.results.recv() {
+                Ok((written, result)) if written >= revision => return Some(result),
+                Ok(_) => {}
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+impl Drop for Writer {
+    /// Let a write under way finish before the program goes on (or exits)
+    fn drop(&mut self) {
+        self.requests.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
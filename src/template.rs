@@ -0,0 +1,68 @@
+// Template module - Variables like {{date}} and {{week}} in new tasks, and named task templates
+// Expanded once when a task is created (from quick-add, the new task popup or the inline input),
+// so "Weekly review {{week}}" saved as a template stamps out "Weekly review 2026-W42".
+
+use crate::config::TaskTemplate;
+use chrono::{Datelike, NaiveDate};
+use std::process::Command;
+
+/// The title and description of a new task: a "/name" title takes the named template's,
+/// with any typed description added below, then variables are expanded in both
+pub fn resolve(templates: &[TaskTemplate], title: &str, description: &str, today: NaiveDate) -> (String, String) {
+    let template = title.trim().strip_prefix('/')
+        .and_then(|name| templates.iter().find(|template| template.name.eq_ignore_ascii_case(name)));
+    let (title, description) = match template {
+        Some(template) if description.is_empty() => (template.title.clone(), template.description.clone()),
+        Some(template) => (template.title.clone(), format!("{}\n{}", template.description, description)),
+        None => (title.to_string(), description.to_string()),
+    };
+    (expand(&title, today), expand(&description, today))
+}
+
+/// Replace the known `{{variables}}` in `text`, leaving unknown ones as typed:
+/// date (2026-10-16), week (2026-W42), weekday (Friday), month (October 2026), year, clipboard
+pub fn expand(text: &str, today: NaiveDate) -> String {
+    let mut expanded = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start..].find("}}") else { break };
+        let name = rest[start + 2..start + length].trim();
+        expanded.push_str(&rest[..start]);
+        match value(name, today) {
+            Some(value) => expanded.push_str(&value),
+            None => expanded.push_str(&rest[start..start + length + 2]),
+        }
+        rest = &rest[start + length + 2..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+fn value(name: &str, today: NaiveDate) -> Option<String> {
+    let week = today.iso_week();
+    match name {
+        "date" => Some(today.format("%Y-%m-%d").to_string()),
+        "week" => Some(format!("{}-W{:02}", week.year(), week.week())),
+        "weekday" => Some(today.format("%A").to_string()),
+        "month" => Some(today.format("%B %Y").to_string()),
+        "year" => Some(today.year().to_string()),
+        // An unreadable clipboard expands to nothing rather than leaving the placeholder in
+        "clipboard" => Some(clipboard().unwrap_or_default()),
+        _ => None,
+    }
+}
+
+/// The clipboard's text, read with whichever of the platform's clipboard tools is installed
+fn clipboard() -> Option<String> {
+    let tools: &[(&str, &[&str])] = &[
+        ("pbpaste", &[]),
+        ("wl-paste", &["--no-newline"]),
+        ("xclip", &["-selection", "clipboard", "-out"]),
+        ("xsel", &["--clipboard", "--output"]),
+        ("powershell", &["-NoProfile", "-Command", "Get-Clipboard"]),
+    ];
+    tools.iter().find_map(|(program, args)| {
+        let output = Command::new(program).args(*args).output().ok()?;
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+    })
+}
@@ -0,0 +1,165 @@
+// Text buffer - The text of a multi-line editor (a task's description, a day's note), kept in a
+// rope so a pasted log thousands of lines long is as cheap to type after, scroll and draw as a
+// short note. Only the rows in view are wrapped each frame; where each finished line starts is
+// remembered, since typing only ever changes the last line.
+
+use ropey::Rope;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+
+#[derive(Debug, Clone, Default)]
+pub struct TextBuffer {
+    rope: Rope,
+    /// The width last wrapped at, and the row each line up to the last starts on at that width
+    /// (the first entry is 0); only lines before the last are in it
+    line_starts: RefCell<(u16, Vec<usize>)>,
+    /// How many lines before the last have been measured, and the longest of them
+    longest: Cell<(usize, usize)>,
+}
+
+impl TextBuffer {
+    pub fn new(text: &str) -> Self {
+        Self { rope: Rope::from_str(text), ..Self::default() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rope.len_chars() == 0
+    }
+
+    /// Type a character at the end
+    pub fn push(&mut self, c: char) {
+        self.rope.insert_char(self.rope.len_chars(), c);
+    }
+
+    /// Take the last character away
+    pub fn pop(&mut self) {
+        let len = self.rope.len_chars();
+        if len == 0 {
+            return;
+        }
+        // Taking a line break away unfinishes the line before it
+        if self.rope.char(len - 1) == '\n' {
+            self.line_starts.borrow_mut().1.truncate(self.rope.len_lines() - 1);
+            self.longest.set((0, 0));
+        }
+        self.rope.remove(len - 1..);
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Line `index`, without its line break; borrowed unless it straddles two of the rope's chunks
+    fn line(&self, index: usize) -> Cow<'_, str> {
+        let line = self.rope.line(index);
+        let len = line.len_chars();
+        if len > 0 && line.char(len - 1) == '\n' {
+            line.slice(..len - 1).into()
+        } else {
+            line.into()
+        }
+    }
+
+    fn last_line(&self) -> Cow<'_, str> {
+        self.line(self.rope.len_lines() - 1)
+    }
+
+    /// The row each line before the last starts on at `width`, plus the row the last starts on;
+    /// only lines not seen at this width before are wrapped
+    fn line_starts(&self, width: u16) -> std::cell::Ref<'_, (u16, Vec<usize>)> {
+        {
+            let mut cached = self.line_starts.borrow_mut();
+            let (cached_width, starts) = &mut *cached;
+            if *cached_width != width || starts.is_empty() {
+                *cached_width = width;
+                *starts = vec![0];
+            }
+            let finished = self.rope.len_lines() - 1;
+            starts.truncate(finished + 1);
+            for index in starts.len() - 1..finished {
+                let start = starts[index] + wrap_line(&self.line(index), width).count();
+                starts.push(start);
+            }
+        }
+        self.line_starts.borrow()
+    }
+
+    /// How many rows the text takes wrapped at `width`
+    pub fn rows(&self, width: u16) -> usize {
+        let last_start = *self.line_starts(width).1.last().unwrap_or(&0);
+        last_start + wrap_line(&self.last_line(), width).count()
+    }
+
+    /// (row, column) just after the end of the text wrapped at `width`
+    pub fn cursor(&self, width: u16) -> (usize, usize) {
+        let last_start = *self.line_starts(width).1.last().unwrap_or(&0);
+        let last_line = self.last_line();
+        let (rows, column) = wrap_line(&last_line, width)
+            .fold((0, 0), |(rows, _), row| (rows + 1, row.chars().count()));
+        let row = last_start + rows - 1;
+        // A full last row puts the cursor at the start of the next
+        if width > 0 && column >= width as usize { (row + 1, 0) } else { (row, column) }
+    }
+
+    /// Up to `count` rows from row `first` on, wrapped at `width`
+    pub fn rows_in_view(&self, width: u16, first: usize, count: usize) -> Vec<String> {
+        let starts = self.line_starts(width);
+        // The line holding row `first`: the last one starting at or before it
+        let mut index = starts.1.partition_point(|&start| start <= first).saturating_sub(1);
+        let mut row = starts.1.get(index).copied().unwrap_or(0);
+        drop(starts);
+        let mut rows = Vec::new();
+        while rows.len() < count && index < self.rope.len_lines() {
+            for text in wrap_line(&self.line(index), width) {
+                if row >= first && rows.len() < count {
+                    rows.push(text.to_string());
+                }
+                row += 1;
+            }
+            index += 1;
+        }
+        rows
+    }
+
+    /// Characters in the longest line
+    pub fn longest_line(&self) -> usize {
+        let finished = self.rope.len_lines() - 1;
+        let (counted, mut longest) = self.longest.get();
+        for index in counted..finished {
+            // Less its line break
+            longest = longest.max(self.rope.line(index).len_chars() - 1);
+        }
+        self.longest.set((finished, longest));
+        longest.max(self.rope.line(finished).len_chars())
+    }
+}
+
+impl fmt::Display for TextBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.rope, f)
+    }
+}
+
+/// `line` split into the rows an editor `width` wide shows: wrapped after the last space that
+/// fits, or mid-word when a word is wider than the editor
+pub fn wrap_line(line: &str, width: u16) -> impl Iterator<Item = &str> {
+    let width = width as usize;
+    let mut rest = Some(line);
+    std::iter::from_fn(move || {
+        let current = rest?;
+        // Where the row would end, if the rest doesn't fit on it
+        let Some((end, _)) = current.char_indices().nth(width).filter(|_| width > 0) else {
+            rest = None;
+            return Some(current);
+        };
+        let cut = if current[end..].starts_with(' ') {
+            // The space a full row stops at stays on it, out of sight
+            end + 1
+        } else {
+            current[..end].rfind(' ').map_or(end, |space| space + 1)
+        };
+        rest = Some(&current[cut..]);
+        Some(&current[..cut])
+    })
+}
@@ -0,0 +1,373 @@
+// Theme module - Semantic styles used by the UI
+// Rendering code asks the theme for a role (overdue, focused border, ...) instead of
+// hardcoding colors, so alternative palettes only need a new constructor here.
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols::Marker;
+use ratatui::widgets::BorderType;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeKind {
+    #[default]
+    Default,
+    HighContrast,
+    Deuteranopia,
+    Monochrome,
+}
+
+/// Icons shown next to colored state so it's readable without relying on color
+#[derive(Debug, Clone)]
+pub struct Icons {
+    pub overdue: &'static str,
+    pub due_today: &'static str,
+    pub done: &'static str,
+    pub pending: &'static str,
+    pub selected_button: &'static str,
+    pub expanded: &'static str,
+    pub collapsed: &'static str,
+    /// Tasks marked for a batch edit
+    pub marked: &'static str,
+    /// Between a waiting task and who it's waiting on
+    pub delegated: &'static str,
+    /// Before a task in a project with a color
+    pub project: &'static str,
+    /// Before a subtask
+    pub subtask: &'static str,
+    /// Streak grid cells for a habit period that was / wasn't checked off
+    pub habit_done: &'static str,
+    pub habit_missed: &'static str,
+    /// Activity heatmap cells, from no activity to the busiest days
+    pub heat: [&'static str; 5],
+}
+
+impl Default for Icons {
+    fn default() -> Self {
+        Self {
+            overdue: "!",
+            due_today: "⏰",
+            done: "✓",
+            pending: "○",
+            selected_button: "▶",
+            expanded: "▾",
+            collapsed: "▸",
+            marked: "●",
+            delegated: "→",
+            project: "■",
+            subtask: "↳",
+            habit_done: "■",
+            habit_missed: "·",
+            heat: ["·", "░", "▒", "▓", "█"],
+        }
+    }
+}
+
+impl Icons {
+    pub fn ascii() -> Self {
+        Self {
+            overdue: "!",
+            due_today: "*",
+            done: "x",
+            pending: "o",
+            selected_button: ">",
+            expanded: "v",
+            collapsed: ">",
+            marked: "+",
+            delegated: "->",
+            project: "#",
+            subtask: "-",
+            habit_done: "#",
+            habit_missed: ".",
+            heat: [".", ":", "+", "*", "#"],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub focused_border: Style,
+    pub focused_border_type: BorderType,
+    pub border: Style,
+    pub accent: Style,
+    pub tab: Style,
+    pub tab_selected: Style,
+    pub muted: Style,
+    pub dim: Style,
+    pub overdue: Style,
+    pub due_today: Style,
+    pub pending: Style,
+    pub success: Style,
+    /// Age suffix on tasks open longer than the aging threshold
+    pub aging: Style,
+    /// Age suffix on tasks open three times longer than the threshold
+    pub aging_old: Style,
+    pub input_active: Style,
+    /// Where a task being dragged with the mouse would land
+    pub drop_target: Style,
+    pub popup: Style,
+    pub yes_button: Style,
+    pub yes_button_selected: Style,
+    pub no_button: Style,
+    pub no_button_selected: Style,
+    pub calendar_due: Style,
+    pub calendar_overdue: Style,
+    pub calendar_today: Style,
+    pub calendar_selected: Style,
+    /// Days with events from subscribed calendars
+    pub calendar_event: Style,
+    /// Weekends and holidays
+    pub calendar_non_working: Style,
+    /// Days away: vacations and trips
+    pub calendar_blackout: Style,
+    /// Added on top of a day's other style when it has a daily note
+    pub calendar_note: Style,
+    pub chart_created: Style,
+    pub chart_overdue: Style,
+    pub chart_completed: Style,
+    /// Markers for the created/overdue/completed chart series
+    pub chart_markers: [Marker; 3],
+    pub icons: Icons,
+    /// Draw only ASCII: borders, icons, chart markers and counters
+    pub ascii: bool,
+    /// Whether the palette uses color at all; without it, colors from config (task statuses) are dropped
+    pub colored: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            focused_border: Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            focused_border_type: BorderType::Plain,
+            border: Style::default().fg(Color::DarkGray),
+            accent: Style::default().fg(Color::Cyan),
+            tab: Style::default().fg(Color::White),
+            tab_selected: Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            muted: Style::default().fg(Color::Gray),
+            dim: Style::default().fg(Color::DarkGray),
+            overdue: Style::default().fg(Color::Red),
+            due_today: Style::default().fg(Color::Yellow),
+            pending: Style::default().fg(Color::Yellow),
+            success: Style::default().fg(Color::Green),
+            aging: Style::default().fg(Color::DarkGray),
+            aging_old: Style::default().fg(Color::Red).add_modifier(Modifier::DIM),
+            input_active: Style::default().fg(Color::Yellow),
+            drop_target: Style::default().fg(Color::Yellow).add_modifier(Modifier::REVERSED),
+            popup: Style::default().bg(Color::Black),
+            yes_button: Style::default().fg(Color::Green),
+            yes_button_selected: Style::default().bg(Color::Green).fg(Color::Black).add_modifier(Modifier::BOLD),
+            no_button: Style::default().fg(Color::Red),
+            no_button_selected: Style::default().bg(Color::Red).fg(Color::Black).add_modifier(Modifier::BOLD),
+            calendar_due: Style::default().bg(Color::DarkGray).fg(Color::White),
+            calendar_overdue: Style::default().bg(Color::Red).fg(Color::White).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            calendar_today: Style::default().bg(Color::Cyan).fg(Color::Black).add_modifier(Modifier::BOLD),
+            calendar_selected: Style::default().bg(Color::Yellow).fg(Color::Black).add_modifier(Modifier::BOLD),
+            calendar_event: Style::default().fg(Color::Magenta).add_modifier(Modifier::ITALIC),
+            calendar_non_working: Style::default().fg(Color::DarkGray),
+            calendar_blackout: Style::default().fg(Color::Blue).add_modifier(Modifier::CROSSED_OUT),
+            calendar_note: Style::default().add_modifier(Modifier::UNDERLINED),
+            chart_created: Style::default().fg(Color::Yellow),
+            chart_overdue: Style::default().fg(Color::Red),
+            chart_completed: Style::default().fg(Color::Cyan),
+            chart_markers: [Marker::Braille, Marker::Braille, Marker::Braille],
+            icons: Icons::default(),
+            ascii: false,
+            colored: true,
+        }
+    }
+}
+
+impl Theme {
+    pub fn from_kind(kind: ThemeKind) -> Self {
+        match kind {
+            ThemeKind::Default => Theme::default(),
+            ThemeKind::HighContrast => Theme::high_contrast(),
+            ThemeKind::Deuteranopia => Theme::deuteranopia(),
+            ThemeKind::Monochrome => Theme::monochrome(),
+        }
+    }
+
+    /// Bright foregrounds on black with thick focus borders, for low-vision users and washed-out screens
+    pub fn high_contrast() -> Self {
+        let bold = Modifier::BOLD;
+
+        Self {
+            focused_border: Style::default().fg(Color::White).add_modifier(bold),
+            focused_border_type: BorderType::Thick,
+            border: Style::default().fg(Color::Gray),
+            accent: Style::default().fg(Color::LightCyan),
+            tab: Style::default().fg(Color::White),
+            tab_selected: Style::default().bg(Color::White).fg(Color::Black).add_modifier(bold),
+            muted: Style::default().fg(Color::White),
+            dim: Style::default().fg(Color::Gray),
+            overdue: Style::default().fg(Color::LightRed).add_modifier(bold),
+            due_today: Style::default().fg(Color::LightYellow).add_modifier(bold),
+            pending: Style::default().fg(Color::LightYellow),
+            success: Style::default().fg(Color::LightGreen),
+            aging: Style::default().fg(Color::Gray),
+            aging_old: Style::default().fg(Color::LightRed),
+            input_active: Style::default().fg(Color::LightYellow).add_modifier(Modifier::UNDERLINED),
+            drop_target: Style::default().fg(Color::LightYellow).add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            popup: Style::default().bg(Color::Black).fg(Color::White),
+            yes_button: Style::default().fg(Color::LightGreen),
+            yes_button_selected: Style::default().bg(Color::LightGreen).fg(Color::Black).add_modifier(bold),
+            no_button: Style::default().fg(Color::LightRed),
+            no_button_selected: Style::default().bg(Color::LightRed).fg(Color::Black).add_modifier(bold),
+            calendar_due: Style::default().bg(Color::Gray).fg(Color::Black),
+            calendar_overdue: Style::default().bg(Color::LightRed).fg(Color::Black).add_modifier(bold | Modifier::UNDERLINED),
+            calendar_today: Style::default().bg(Color::White).fg(Color::Black).add_modifier(bold),
+            calendar_selected: Style::default().bg(Color::LightYellow).fg(Color::Black).add_modifier(bold),
+            calendar_event: Style::default().fg(Color::LightMagenta).add_modifier(bold | Modifier::ITALIC),
+            calendar_non_working: Style::default().fg(Color::Gray),
+            calendar_blackout: Style::default().fg(Color::LightBlue).add_modifier(bold | Modifier::CROSSED_OUT),
+            calendar_note: Style::default().add_modifier(Modifier::UNDERLINED),
+            chart_created: Style::default().fg(Color::LightYellow),
+            chart_overdue: Style::default().fg(Color::LightRed),
+            chart_completed: Style::default().fg(Color::LightCyan),
+            chart_markers: [Marker::Braille, Marker::Dot, Marker::Block],
+            icons: Icons::default(),
+            ascii: false,
+            colored: true,
+        }
+    }
+
+    /// Okabe-Ito palette: blue/orange/yellow instead of red/green, safe for red-green color blindness
+    pub fn deuteranopia() -> Self {
+        let orange = Color::Rgb(230, 159, 0);
+        let vermillion = Color::Rgb(213, 94, 0);
+        let sky_blue = Color::Rgb(86, 180, 233);
+        let blue = Color::Rgb(0, 114, 178);
+        let yellow = Color::Rgb(240, 228, 66);
+        let bold = Modifier::BOLD;
+
+        Self {
+            focused_border: Style::default().fg(sky_blue).add_modifier(bold),
+            focused_border_type: BorderType::Thick,
+            border: Style::default().fg(Color::DarkGray),
+            accent: Style::default().fg(sky_blue),
+            tab: Style::default().fg(Color::White),
+            tab_selected: Style::default().fg(sky_blue).add_modifier(bold | Modifier::UNDERLINED),
+            muted: Style::default().fg(Color::Gray),
+            dim: Style::default().fg(Color::DarkGray),
+            overdue: Style::default().fg(vermillion).add_modifier(bold),
+            due_today: Style::default().fg(yellow),
+            pending: Style::default().fg(orange),
+            success: Style::default().fg(blue),
+            aging: Style::default().fg(Color::DarkGray),
+            aging_old: Style::default().fg(vermillion),
+            input_active: Style::default().fg(yellow),
+            drop_target: Style::default().fg(yellow).add_modifier(Modifier::REVERSED),
+            popup: Style::default().bg(Color::Black),
+            yes_button: Style::default().fg(sky_blue),
+            yes_button_selected: Style::default().bg(sky_blue).fg(Color::Black).add_modifier(bold),
+            no_button: Style::default().fg(orange),
+            no_button_selected: Style::default().bg(orange).fg(Color::Black).add_modifier(bold),
+            calendar_due: Style::default().bg(Color::DarkGray).fg(Color::White),
+            calendar_overdue: Style::default().bg(vermillion).fg(Color::White).add_modifier(bold | Modifier::UNDERLINED),
+            calendar_today: Style::default().bg(sky_blue).fg(Color::Black).add_modifier(bold),
+            calendar_selected: Style::default().bg(yellow).fg(Color::Black).add_modifier(bold),
+            calendar_event: Style::default().fg(blue).add_modifier(Modifier::ITALIC),
+            calendar_non_working: Style::default().fg(Color::DarkGray),
+            calendar_blackout: Style::default().fg(blue).add_modifier(Modifier::CROSSED_OUT),
+            calendar_note: Style::default().add_modifier(Modifier::UNDERLINED),
+            chart_created: Style::default().fg(yellow),
+            chart_overdue: Style::default().fg(vermillion),
+            chart_completed: Style::default().fg(sky_blue),
+            chart_markers: [Marker::Braille, Marker::Dot, Marker::Block],
+            icons: Icons::default(),
+            ascii: false,
+            colored: true,
+        }
+    }
+
+    /// Colorless theme that conveys state with bold/reverse/underline only
+    pub fn monochrome() -> Self {
+        let plain = Style::default();
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let reversed = Style::default().add_modifier(Modifier::REVERSED);
+
+        Self {
+            focused_border: bold,
+            focused_border_type: BorderType::Thick,
+            border: plain,
+            accent: plain,
+            tab: plain,
+            tab_selected: reversed.add_modifier(Modifier::BOLD),
+            muted: plain,
+            dim: Style::default().add_modifier(Modifier::DIM),
+            overdue: bold.add_modifier(Modifier::UNDERLINED),
+            due_today: bold,
+            pending: plain,
+            success: plain,
+            aging: Style::default().add_modifier(Modifier::DIM),
+            aging_old: Style::default().add_modifier(Modifier::DIM | Modifier::ITALIC),
+            input_active: Style::default().add_modifier(Modifier::UNDERLINED),
+            drop_target: Style::default().add_modifier(Modifier::REVERSED),
+            popup: plain,
+            yes_button: plain,
+            yes_button_selected: reversed.add_modifier(Modifier::BOLD),
+            no_button: plain,
+            no_button_selected: reversed.add_modifier(Modifier::BOLD),
+            calendar_due: Style::default().add_modifier(Modifier::UNDERLINED),
+            calendar_overdue: bold.add_modifier(Modifier::UNDERLINED),
+            calendar_today: reversed,
+            calendar_selected: reversed.add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            calendar_event: Style::default().add_modifier(Modifier::ITALIC),
+            calendar_non_working: Style::default().add_modifier(Modifier::DIM),
+            calendar_blackout: Style::default().add_modifier(Modifier::DIM | Modifier::CROSSED_OUT),
+            calendar_note: Style::default().add_modifier(Modifier::UNDERLINED),
+            chart_created: plain,
+            chart_overdue: plain,
+            chart_completed: plain,
+            // Without color the series are told apart by their markers
+            chart_markers: [Marker::Braille, Marker::Dot, Marker::Block],
+            icons: Icons::default(),
+            ascii: false,
+            colored: false,
+        }
+    }
+
+    /// Style for a label (a workflow status, a project) in the color configured for it, if any
+    pub fn label(&self, color: Option<&str>) -> Style {
+        match color.and_then(|color| color.parse::<Color>().ok()) {
+            Some(color) if self.colored => Style::default().fg(color),
+            _ => Style::default(),
+        }
+    }
+
+    /// Swap every non-ASCII glyph for a plain fallback, for terminals/fonts that mangle them
+    pub fn with_ascii(mut self) -> Self {
+        self.icons = Icons::ascii();
+        self.chart_markers = [Marker::Dot, Marker::Dot, Marker::Dot];
+        self.ascii = true;
+        self
+    }
+
+    /// Guess whether the terminal can draw Unicode from the locale and TERM
+    pub fn unicode_supported() -> bool {
+        // The Linux console can't draw Braille or most symbols even with a UTF-8 locale
+        if std::env::var("TERM").is_ok_and(|term| term == "linux") {
+            return false;
+        }
+
+        // The first of LC_ALL / LC_CTYPE / LANG that is set decides the character set
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty());
+
+        match locale {
+            Some(locale) => {
+                let locale = locale.to_lowercase();
+                locale.contains("utf-8") || locale.contains("utf8")
+            }
+            // Windows terminals rarely set a locale but handle Unicode fine
+            None => cfg!(windows),
+        }
+    }
+
+    /// True when the NO_COLOR convention (https://no-color.org) asks for colorless output
+    pub fn no_color_requested() -> bool {
+        std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+    }
+}
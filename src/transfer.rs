@@ -0,0 +1,104 @@
+// Transfer module - Moving or copying tasks to another project, or to another data folder
+// Within the data folder, moving only changes the project (storage puts the tasks in the right
+// file); copying adds new tasks. Another folder (one of the stats sources) keeps its own IDs, so
+// tasks going there are numbered from its next free ID, and subtasks go along with their parent.
+
+use crate::config::StatsSource;
+use crate::models::Todo;
+use crate::storage::{Archive, FileStorage};
+use chrono::Utc;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Where the transfer popup can send tasks
+#[derive(Debug, Clone, PartialEq)]
+pub enum Destination {
+    /// A project in this data folder; None takes tasks out of their project
+    Project(Option<String>),
+    /// A project that doesn't exist yet, named as typed
+    NewProject(String),
+    /// Another data folder, by its index in the stats sources
+    Folder(usize),
+}
+
+impl Destination {
+    pub fn label(&self, folders: &[StatsSource]) -> String {
+        match self {
+            Destination::Project(Some(name)) => format!("Project: {}", name),
+            Destination::Project(None) => "No project".to_string(),
+            Destination::NewProject(name) => format!("New project: {}", name),
+            Destination::Folder(index) => match folders.get(*index) {
+                Some(folder) => format!("Folder: {} ({})", folder.name, folder.path.display()),
+                None => "Folder: ?".to_string(),
+            },
+        }
+    }
+}
+
+/// `todos` plus all their subtasks, at any depth, found in `all`
+pub fn with_subtasks(todos: &[Todo], all: &[Todo]) -> Vec<Todo> {
+    let mut picked: Vec<Todo> = todos.to_vec();
+    let mut index = 0;
+    while index < picked.len() {
+        let id = picked[index].id;
+        for child in all.iter().filter(|todo| todo.parent == Some(id)) {
+            if !picked.iter().any(|todo| todo.id == child.id) {
+                picked.push(child.clone());
+            }
+        }
+        index += 1;
+    }
+    picked
+}
+
+/// The tasks renumbered from `first_id`, with subtasks pointing at their parent's new ID; a parent
+/// that isn't among them keeps its ID
+pub fn renumber(todos: &[Todo], first_id: usize) -> Vec<Todo> {
+    let ids: HashMap<usize, usize> = todos.iter().enumerate().map(|(offset, todo)| (todo.id, first_id + offset)).collect();
+    todos.iter()
+        .map(|todo| {
+            let mut todo = todo.clone();
+            todo.id = ids[&todo.id];
+            todo.parent = todo.parent.map(|parent| ids.get(&parent).copied().unwrap_or(parent));
+            todo
+        })
+        .collect()
+}
+
+/// A fresh copy of a task: created now, with no time logged, no import link and no place in a day
+pub fn fresh_copy(todo: &Todo) -> Todo {
+    let mut copy = todo.clone();
+    copy.created_at = Utc::now();
+    copy.updated_at = None;
+    copy.time_entries = Vec::new();
+    copy.source_id = None;
+    copy.rank = None;
+    copy
+}
+
+/// Add the tasks to the data folder at `dir` under IDs it has never used; returns how many
+pub fn add_to_folder(dir: &Path, todos: &[Todo]) -> anyhow::Result<usize> {
+    if !dir.is_dir() {
+        anyhow::bail!("{} isn't a folder", dir.display());
+    }
+    let mut storage = FileStorage::open(dir.join("todos.json"));
+    // Saving now would replace its damaged file with what could be recovered from it
+    if let Some(file) = storage.damaged_files().first() {
+        anyhow::bail!("{} can't be read ({}); open tdui on that folder to recover it first", file.path.display(), file.reason);
+    }
+    // A parent left behind means nothing over there
+    let ids: HashSet<usize> = todos.iter().map(|todo| todo.id).collect();
+    let todos: Vec<Todo> = todos.iter()
+        .map(|todo| {
+            let mut todo = todo.clone();
+            todo.parent = todo.parent.filter(|parent| ids.contains(parent));
+            todo
+        })
+        .collect();
+    let first_id = storage.next_free_id(&Archive::open(dir.join("archive")));
+    let todos = renumber(&todos, first_id);
+    let count = todos.len();
+    storage.append(todos);
+    storage.flush()?;
+    Ok(count)
+}
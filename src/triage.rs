@@ -0,0 +1,69 @@
+// Triage module - Collects open tasks nobody has touched for a while, so they get a decision
+// (keep, reschedule or delete) instead of silently sitting at the bottom of the list.
+// Someday tasks come up on a shorter cycle of their own, to be kept parked or made active.
+// The App asks about them in a popup at startup and applies the choices.
+
+use crate::clock::Clock;
+use crate::models::Todo;
+use chrono::NaiveDate;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TriageAction {
+    /// Leave the task as it is (counts as reviewed, so it isn't asked about again for a while)
+    Keep,
+    /// Take a someday task off the shelf, back onto the active list
+    Activate,
+    /// Set the due date (bringing a someday task back too)
+    Reschedule(NaiveDate),
+    Delete,
+}
+
+impl TriageAction {
+    pub fn label(&self) -> String {
+        match self {
+            TriageAction::Keep => "Keep".to_string(),
+            TriageAction::Activate => "Make active".to_string(),
+            TriageAction::Reschedule(date) => format!("Due {}", date.format("%a %Y-%m-%d")),
+            TriageAction::Delete => "Delete".to_string(),
+        }
+    }
+}
+
+/// One stale or someday task and what to do with it
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriageItem {
+    pub id: usize,
+    pub title: String,
+    /// Days since the task was last touched
+    pub idle_days: i64,
+    /// Parked for some day, up for its regular look rather than stale
+    pub someday: bool,
+    pub action: TriageAction,
+}
+
+/// Open tasks (not someday ones) untouched for at least `after_days`, longest idle first
+pub fn stale(todos: &[Todo], after_days: u32, clock: &Clock) -> Vec<TriageItem> {
+    idle(todos.iter().filter(|t| !t.someday), after_days, clock)
+}
+
+/// Someday tasks not looked at for at least `after_days`, longest idle first
+pub fn someday(todos: &[Todo], after_days: u32, clock: &Clock) -> Vec<TriageItem> {
+    idle(todos.iter().filter(|t| t.someday), after_days, clock)
+}
+
+fn idle<'a>(todos: impl Iterator<Item = &'a Todo>, after_days: u32, clock: &Clock) -> Vec<TriageItem> {
+    let today = clock.today();
+    let mut items: Vec<TriageItem> = todos
+        .filter(|t| !t.completed && !t.deleted)
+        .map(|t| TriageItem {
+            id: t.id,
+            title: t.title.clone(),
+            idle_days: (today - clock.date_of(t.last_touched())).num_days(),
+            someday: t.someday,
+            action: TriageAction::Keep,
+        })
+        .filter(|item| item.idle_days >= after_days as i64)
+        .collect();
+    items.sort_by_key(|item| std::cmp::Reverse(item.idle_days));
+    items
+}
@@ -4,33 +4,90 @@
 use ratatui::{
     Frame,
     layout::{Layout, Constraint, Direction, Rect, Alignment},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Clear, Tabs, calendar::{Monthly, CalendarEventStore}, Chart, Dataset, Axis, GraphType},
-    style::{Style, Color, Modifier},
+    widgets::{Block, BorderType, Borders, List, ListItem, ListState, Paragraph, Clear, Tabs, calendar::{Monthly, CalendarEventStore}, Chart, Dataset, Axis, GraphType},
+    style::{Style, Modifier},
+    symbols::{border, line},
     text::{Line, Span},
-    symbols,
 };
-use chrono::{Datelike, NaiveDate, Local, Duration};
+use chrono::{Datelike, NaiveDate, Duration, Timelike, Utc};
 use time::{Date, Month};
-use crate::app::{App, InputMode, Panel, Tab};
+use crate::app::{App, BatchField, BlackoutField, DetailField, DueBucket, InputMode, JumpTarget, ListFilter, MouseTarget, Panel, ProjectField, Tab};
+use crate::config::{SortMode, Swimlanes};
+use crate::diff::{self, DiffLine};
+use crate::models::{Habit, HabitFrequency, Recurrence, ReminderOffset, TimeEntry, Todo};
+use crate::search::TaskSource;
+use crate::session::SessionSummary;
+use crate::stats::{EstimatePoint, FORECAST_WEEKS, HISTORY_DAYS, SourceStats, Stats};
+use crate::text_buffer::TextBuffer;
+use crate::theme::Theme;
+use crate::triage::TriageAction;
+use crate::usage::UsageLog;
 use tui_big_text::{BigText, PixelSize};
+use std::collections::{HashMap, HashSet};
 
 /// Helper function to get border style based on whether a panel is focused
-fn get_border_style(is_focused: bool) -> Style {
+fn get_border_style(theme: &Theme, is_focused: bool) -> Style {
     if is_focused {
-        Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+        theme.focused_border
     } else {
-        Style::default().fg(Color::DarkGray)
+        theme.border
     }
 }
 
+/// Helper function to get border characters, so focus is visible without relying on color
+fn get_border_set(theme: &Theme, is_focused: bool) -> border::Set {
+    match (theme.ascii, is_focused) {
+        (true, true) => ASCII_FOCUSED_BORDER,
+        (true, false) => ASCII_BORDER,
+        (false, true) => BorderType::border_symbols(theme.focused_border_type),
+        (false, false) => BorderType::border_symbols(BorderType::Plain),
+    }
+}
+
+/// Helper function to create a block that respects the theme's border characters
+fn themed_block<'a>(theme: &Theme) -> Block<'a> {
+    Block::default().border_set(get_border_set(theme, false))
+}
+
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+const ASCII_FOCUSED_BORDER: border::Set = border::Set {
+    horizontal_top: "=",
+    horizontal_bottom: "=",
+    ..ASCII_BORDER
+};
+
 pub fn render(frame: &mut Frame, app: &App) {
     let size = frame.area();
+    // Filled in again below by whatever is drawn this time
+    app.mouse_targets.borrow_mut().clear();
+
+    // Quitting shows only the session summary
+    if let Some(summary) = &app.exit_summary {
+        render_exit_summary(frame, app, summary, size);
+        return;
+    }
+
+    // A focus session hides everything but its task
+    if app.focus.is_some() {
+        render_focus_view(frame, app, size);
+        return;
+    }
 
     // Split the screen into tabs, main area, and footer
     let main_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3),   // Tabs
+            Constraint::Length(if app.config.greeting { 4 } else { 3 }),   // Tabs, and the greeting
             Constraint::Min(0),      // Main content area
             Constraint::Length(1),   // Footer
         ])
@@ -41,12 +98,17 @@ pub fn render(frame: &mut Frame, app: &App) {
 
     // Render content based on selected tab
     match app.selected_tab {
-        Tab::Tasks => render_tasks_tab(frame, app, main_layout[1]),
+        Tab::Tasks | Tab::Workspace(_) => render_tasks_tab(frame, app, main_layout[1]),
         Tab::Stats => render_stats_tab(frame, app, main_layout[1]),
+        Tab::Habits => render_habits_tab(frame, app, main_layout[1]),
+        Tab::Board => render_board_tab(frame, app, main_layout[1]),
+        Tab::Month => render_month_tab(frame, app, main_layout[1]),
+        Tab::Year => render_year_tab(frame, app, main_layout[1]),
+        Tab::Done => render_done_tab(frame, app, main_layout[1]),
     }
 
     // Render footer
-    render_footer(frame, main_layout[2]);
+    render_footer(frame, app, main_layout[2]);
 
     // Render the new task panel if it's open
     if app.show_new_task_panel {
@@ -62,35 +124,298 @@ pub fn render(frame: &mut Frame, app: &App) {
     if app.show_delete_panel {
         render_delete_panel(frame, app);
     }
+
+    // Render the search panel if it's open
+    if app.show_search_panel {
+        render_search_panel(frame, app);
+    }
+
+    // Render the context picker if it's open
+    if app.show_context_panel {
+        render_context_panel(frame, app);
+    }
+
+    // Render the workspace picker if it's open
+    if app.show_workspace_panel {
+        render_workspace_panel(frame, app);
+    }
+
+    // Render the new habit panel if it's open
+    if app.show_habit_panel {
+        render_habit_panel(frame, app);
+    }
+
+    // Render the project settings if they're open
+    if app.show_project_panel {
+        render_project_panel(frame, app);
+    }
+
+    // Render the blackout popup if it's open
+    if app.show_blackout_panel {
+        render_blackout_panel(frame, app);
+    }
+
+    // Render the waiting-on popup if it's open
+    if app.show_waiting_panel {
+        render_waiting_panel(frame, app);
+    }
+
+    // Render the note editor if it's open
+    if app.show_note_panel {
+        render_note_panel(frame, app);
+    }
+
+    // Render the proposed plan if it's open
+    if app.show_plan_panel {
+        render_plan_panel(frame, app);
+    }
+
+    // Render the batch edit if it's open
+    if app.show_batch_panel {
+        render_batch_panel(frame, app);
+    }
+
+    // Render the move/copy popup if it's open
+    if app.show_transfer_panel {
+        render_transfer_panel(frame, app);
+    }
+
+    // Render the stale task triage if it's open
+    if app.show_triage_panel {
+        render_triage_panel(frame, app);
+    }
+
+    // Render the task history if it's open
+    if app.show_history_panel {
+        render_history_panel(frame, app);
+    }
+
+    // Render the escalated tasks popup if it's open
+    if app.show_attention_panel {
+        render_attention_panel(frame, app);
+    }
+
+    // Render the empty trash question if it's open
+    if app.show_empty_trash_panel {
+        render_empty_trash_panel(frame, app);
+    }
+
+    // Render an assistant's request for a change if one is waiting
+    if app.show_tool_call_panel {
+        render_tool_call_panel(frame, app);
+    }
+
+    // Render the "every subtask is done" question if it's open
+    if app.show_parent_done_panel {
+        render_parent_done_panel(frame, app);
+    }
+
+    // Render the discard confirmation if it's open
+    if app.show_discard_panel {
+        render_discard_panel(frame, app);
+    }
+
+    // Render the duplicate question if it's open
+    if app.show_duplicate_panel {
+        render_duplicate_panel(frame, app);
+    }
+
+    // Render the damaged file recovery if it's open
+    if app.show_recovery_panel {
+        render_recovery_panel(frame, app);
+    }
+
+    // Render the keybinding problems over everything else until a key is pressed
+    if app.show_keymap_problems {
+        render_keymap_problems(frame, app);
+    }
+}
+
+fn render_focus_view(frame: &mut Frame, app: &App, area: Rect) {
+    let block = themed_block(&app.theme)
+        .title("Focus")
+        .borders(Borders::ALL);
+    let inner_area = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(2),  // Title
+            Constraint::Length(1),  // Due date and time logged
+            Constraint::Length(10), // Countdown
+            Constraint::Min(0),     // Description
+            Constraint::Length(1),  // Urgent notice or instructions
+        ])
+        .split(inner_area);
+
+    let Some((session, todo)) = app.focus.as_ref().zip(app.focused_todo()) else {
+        return;
+    };
+
+    let title = Paragraph::new(Span::styled(todo.title.clone(), Style::default().add_modifier(Modifier::BOLD)))
+        .alignment(Alignment::Center);
+    frame.render_widget(title, chunks[0]);
+
+    let mut details = Vec::new();
+    if let Some(due_date) = todo.due_date {
+        details.push(format!("Due {}", due_date.format("%a %Y-%m-%d")));
+    }
+    if !todo.time_entries.is_empty() {
+        details.push(format!("{} logged before", TimeEntry::format_duration(todo.time_spent())));
+    }
+    let details = Paragraph::new(details.join("  "))
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(details, chunks[1]);
+
+    let remaining = session.remaining().num_seconds();
+    let countdown = format!("{:02}:{:02}", remaining / 60, remaining % 60);
+    // Screen readers and ASCII terminals get the countdown as plain text
+    if app.config.screen_reader || app.theme.ascii {
+        let countdown_text = Paragraph::new(format!("{} left", countdown))
+            .style(app.theme.accent)
+            .alignment(Alignment::Center);
+        frame.render_widget(countdown_text, chunks[2]);
+    } else {
+        let big_text = BigText::builder()
+            .pixel_size(PixelSize::Full)
+            .style(app.theme.accent)
+            .lines(vec![countdown.into()])
+            .centered()
+            .build();
+        frame.render_widget(big_text, chunks[2]);
+    }
+
+    let description = Paragraph::new(todo.description.split('\n').map(Line::from).collect::<Vec<_>>())
+        .wrap(ratatui::widgets::Wrap { trim: false });
+    frame.render_widget(description, chunks[3]);
+
+    // Urgent reminders still get through; everything else waits for the session to end
+    let footer = match &app.notice {
+        Some(notice) => Paragraph::new(Span::styled(notice.clone(), app.theme.due_today.add_modifier(Modifier::BOLD))),
+        None => Paragraph::new("Esc: Stop the session (the time so far is logged)").style(app.theme.muted),
+    };
+    frame.render_widget(footer.alignment(Alignment::Center), chunks[4]);
+}
+
+fn render_exit_summary(frame: &mut Frame, app: &App, summary: &SessionSummary, area: Rect) {
+    let popup_area = centered_rect(40, 40, area);
+    let block = themed_block(&app.theme)
+        .title("This session")
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+    let inner_area = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(4),     // Counts
+            Constraint::Length(1),  // Instructions
+        ])
+        .split(inner_area);
+
+    let overdue_style = if summary.still_overdue > 0 { app.theme.overdue } else { Style::default() };
+    let lines = vec![
+        Line::from(vec![Span::raw("Completed:     "), Span::styled(summary.completed.to_string(), app.theme.success)]),
+        Line::from(format!("Added:         {}", summary.added)),
+        Line::from(format!("Rescheduled:   {}", summary.rescheduled)),
+        Line::from(vec![Span::raw("Still overdue: "), Span::styled(summary.still_overdue.to_string(), overdue_style)]),
+    ];
+    frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+    let instructions = Paragraph::new("Press any key to quit")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[1]);
 }
 
 fn render_tabs(frame: &mut Frame, app: &App, area: Rect) {
-    let titles = vec!["Tasks", "Stats"];
-    let selected_index = match app.selected_tab {
-        Tab::Tasks => 0,
-        Tab::Stats => 1,
+    let tabs = app.tabs();
+    let titles: Vec<String> = tabs.iter().map(|&tab| app.tab_title(tab)).collect();
+    let selected_index = tabs.iter().position(|&tab| tab == app.selected_tab).unwrap_or(0);
+
+    // Show the active context on the right so it's clear the views are filtered
+    let context_title = match &app.config.active_context {
+        Some(context) => Line::from(Span::styled(
+            format!(" Context: {} ", context),
+            app.theme.pending.add_modifier(Modifier::BOLD),
+        )),
+        None => Line::from(" All contexts "),
     };
 
+    let block = themed_block(&app.theme)
+        .borders(Borders::ALL)
+        .title(context_title.alignment(Alignment::Right));
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1)])
+        .split(block.inner(area));
+    frame.render_widget(block, area);
+
     let tabs = Tabs::new(titles)
-        .block(Block::default().borders(Borders::ALL))
         .select(selected_index)
-        .style(Style::default().fg(Color::White))
-        .highlight_style(
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD)
-        );
+        .style(app.theme.tab)
+        .highlight_style(app.theme.tab_selected)
+        .divider(if app.theme.ascii { "|" } else { line::VERTICAL });
+    frame.render_widget(tabs, rows[0]);
+
+    if app.config.greeting {
+        frame.render_widget(Paragraph::new(greeting_line(app)), rows[1]);
+    }
+}
 
-    frame.render_widget(tabs, area);
+/// "Good morning. Friday 16 October — 3 due today, 1 overdue, streak 6 days"
+fn greeting_line(app: &App) -> Line<'static> {
+    let now = app.clock.now();
+    let today = app.clock.today();
+    let greeting = match now.hour() {
+        5..=11 => "Good morning",
+        12..=17 => "Good afternoon",
+        _ => "Good evening",
+    };
+    let open = || app.todos.iter().filter(|todo| !todo.completed && !todo.deleted && !todo.someday);
+    let due_today = open().filter(|todo| todo.due_date == Some(today)).count();
+    let overdue = open().filter(|todo| todo.is_overdue(today)).count();
+    let dash = if app.theme.ascii { "-" } else { "\u{2014}" };
+
+    let mut spans = vec![
+        Span::raw(format!(" {}. ", greeting)),
+        Span::styled(today.format("%A %-d %B").to_string(), Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(format!(" {} ", dash)),
+        Span::styled(format!("{} due today", due_today), if due_today > 0 { app.theme.due_today } else { app.theme.muted }),
+        Span::raw(", "),
+        Span::styled(format!("{} overdue", overdue), if overdue > 0 { app.theme.overdue } else { app.theme.muted }),
+    ];
+    match app.completion_streak() {
+        0 => {}
+        1 => spans.push(Span::raw(", streak 1 day")),
+        days => spans.push(Span::raw(format!(", streak {} days", days))),
+    }
+    Line::from(spans)
 }
 
 fn render_tasks_tab(frame: &mut Frame, app: &App, area: Rect) {
-    // Main layout: Split into two vertical columns (1/3 left, 2/3 right)
+    // A zoomed panel takes over the whole content area
+    if app.zoomed {
+        match app.focused_panel {
+            Panel::List => render_task_list(frame, app, area),
+            Panel::Calendar => render_calendar(frame, app, area),
+            Panel::Task => render_task_details(frame, app, area),
+        }
+        return;
+    }
+
+    // Main layout: Split into two vertical columns (1/3 left, 2/3 right; half each with a split view)
+    let list_percentage = if app.split_view.is_some() { 50 } else { 33 };
     let main_columns = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Percentage(33),  // Left column (1/3)
-            Constraint::Percentage(67),  // Right column (2/3)
+            Constraint::Percentage(list_percentage),        // Left column
+            Constraint::Percentage(100 - list_percentage),  // Right column
         ])
         .split(area);
 
@@ -103,44 +428,143 @@ fn render_tasks_tab(frame: &mut Frame, app: &App, area: Rect) {
         ])
         .split(main_columns[1]);
 
-    // Create the task list widget
-    let today = Local::now().date_naive();
-    let task_items: Vec<ListItem> = app.todos
-        .iter()
-        .enumerate()
-        .map(|(i, todo)| {
-            let content = format!("{}. {}", i + 1, todo.display_string());
+    // Render the widgets
+    render_task_list(frame, app, main_columns[0]);
+    render_calendar(frame, app, right_sections[0]);
+    if app.focused_panel == Panel::Calendar {
+        render_day_note(frame, app, right_sections[1]);
+    } else {
+        render_task_details(frame, app, right_sections[1]);
+    }
+}
 
-            // Determine task color based on due date
-            if let Some(due_date) = todo.due_date {
-                if !todo.completed {
-                    if due_date < today {
-                        // Overdue tasks in red
-                        ListItem::new(content).style(Style::default().fg(Color::Red))
-                    } else if due_date == today {
-                        // Tasks due today in yellow
-                        ListItem::new(content).style(Style::default().fg(Color::Yellow))
-                    } else {
-                        // Future tasks in default color
-                        ListItem::new(content)
-                    }
-                } else {
-                    // Completed tasks in default color
-                    ListItem::new(content)
-                }
-            } else {
-                // No due date in default color
-                ListItem::new(content)
+fn render_task_list(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(split) = &app.split_view else {
+        let indices = app.filtered_todo_indices(&ListFilter::All);
+        let is_focused = app.focused_panel == Panel::List;
+        let title = match app.config.sort {
+            SortMode::Due => "List".to_string(),
+            SortMode::Urgency => "List (by urgency)".to_string(),
+        };
+        render_list_pane(frame, app, area, title, &indices, app.selected_todo_index, is_focused, true);
+        return;
+    };
+
+    // Two filtered lists side by side
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(50),
+            Constraint::Percentage(50),
+        ])
+        .split(area);
+
+    for pane in 0..2 {
+        let indices = app.filtered_todo_indices(&split.filters[pane]);
+        // The focused pane follows the live selection, the other one shows what it remembered
+        let selected = if pane == split.active_pane {
+            app.selected_todo_index
+        } else {
+            split.selected_ids[pane].and_then(|id| app.todos.iter().position(|t| t.id == id))
+        };
+        let is_focused = app.focused_panel == Panel::List && pane == split.active_pane;
+        let title = match app.config.sort {
+            SortMode::Due => format!("List: {}", split.filters[pane].label()),
+            SortMode::Urgency => format!("List: {} (by urgency)", split.filters[pane].label()),
+        };
+        render_list_pane(frame, app, panes[pane], title, &indices, selected, is_focused, pane == split.active_pane);
+    }
+}
+
+/// Draw a list of tasks (`indices` into `app.todos`), highlighting the `selected` task; the
+/// `active` pane is the one the cursor and jump hints work in
+#[allow(clippy::too_many_arguments)]
+fn render_list_pane(
+    frame: &mut Frame,
+    app: &App,
+    area: Rect,
+    title: String,
+    indices: &[usize],
+    selected: Option<usize>,
+    is_focused: bool,
+    active: bool,
+) {
+    let today = app.clock.today();
+    let mut task_items: Vec<ListItem> = Vec::new();
+    let mut selected_row = None;
+    // (row, index into `app.todos`) of each task drawn
+    let mut task_rows = Vec::new();
+    let mut number = 0;
+    let mut current_bucket = None;
+    let folded_away = app.folded_away(indices);
+    let tree = if app.config.tree_view { tree_prefixes(app, indices, &folded_away) } else { Default::default() };
+
+    for &i in indices {
+        let todo = &app.todos[i];
+        if folded_away.contains(&todo.id) {
+            continue;
+        }
+
+        // Grouped lists get a header row whenever a new due bucket starts
+        if app.config.group_by_due {
+            let bucket = app.due_bucket(todo);
+            if current_bucket != Some(bucket) {
+                current_bucket = Some(bucket);
+                task_items.push(group_header(app, indices, bucket));
             }
-        })
-        .collect();
+            if app.collapsed_buckets.contains(&bucket) {
+                continue;
+            }
+        }
+
+        number += 1;
+        if selected == Some(i) {
+            selected_row = Some(task_items.len());
+        }
+        task_rows.push((task_items.len(), i));
+        let prefix = tree.get(&i).map_or("", String::as_str);
+        let item = task_list_item(app, todo, number, prefix, today);
+        let is_drop_target = active && app.drag.is_some_and(|drag| drag.over == Some(MouseTarget::ListRow(i)));
+        task_items.push(if is_drop_target { item.style(app.theme.drop_target) } else { item });
+    }
+
+    // The inline new-task input goes right below the selection and takes over the highlight
+    let inline_row = (is_focused && app.show_inline_task).then(|| {
+        let row = selected_row.map_or(task_items.len(), |row| row + 1);
+        task_items.insert(row, ListItem::new(format!("+ {}", app.inline_task_title)).style(app.theme.input_active));
+        selected_row = Some(row);
+        row
+    });
+
+    // Tasks finished today sit in a collapsible section at the bottom
+    if app.shows_done_today() {
+        let done_today = app.done_today_todos();
+        let arrow = if app.done_today_expanded { app.theme.icons.expanded } else { app.theme.icons.collapsed };
+        if app.done_today_selected {
+            selected_row = Some(task_items.len());
+        }
+        task_items.push(ListItem::new(format!("{} Done today ({})", arrow, done_today.len()))
+            .style(app.theme.success.add_modifier(Modifier::BOLD)));
+
+        if app.done_today_expanded {
+            for todo in done_today {
+                task_items.push(ListItem::new(format!("  {} {}", app.theme.icons.done, todo.title))
+                    .style(app.theme.dim));
+            }
+        }
+    }
+
+    // Count the tasks marked for a batch edit
+    let marked = app.marked_todos().len();
+    let title = if marked > 0 { format!("{} ({} marked)", title, marked) } else { title };
 
-    let list_border_style = get_border_style(app.focused_panel == Panel::List);
+    let list_border_style = get_border_style(&app.theme, is_focused);
     let task_list = List::new(task_items)
-        .block(Block::default()
-            .title("List")
+        .block(themed_block(&app.theme)
+            .title(title)
             .borders(Borders::ALL)
-            .border_style(list_border_style))
+            .border_style(list_border_style)
+            .border_set(get_border_set(&app.theme, is_focused)))
         .style(Style::default())
         .highlight_style(
             Style::default()
@@ -150,44 +574,220 @@ fn render_tasks_tab(frame: &mut Frame, app: &App, area: Rect) {
 
     // Create list state for selection
     let mut list_state = ListState::default();
-    list_state.select(app.selected_todo_index);
+    list_state.select(selected_row);
 
-    // Render the widgets
-    frame.render_stateful_widget(task_list, main_columns[0], &mut list_state);
-    render_calendar(frame, app, right_sections[0]);
-    render_task_details(frame, app, right_sections[1]);
-}
+    frame.render_stateful_widget(task_list, area, &mut list_state);
 
-fn render_stats_tab(frame: &mut Frame, app: &App, area: Rect) {
-    let today = Local::now().date_naive();
+    if let Some(row) = inline_row {
+        // Past the border, the ">> " highlight symbol and "+ "
+        let cursor_x = area.x + 6 + app.inline_task_title.len() as u16;
+        let cursor_y = area.y + 1 + (row - list_state.offset()) as u16;
+        if cursor_x < area.x + area.width - 1 {
+            frame.set_cursor_position((cursor_x, cursor_y));
+        }
+    }
 
-    // Load all todos including completed and deleted ones
-    let all_todos = app.get_all_todos();
+    // Only the active pane's tasks can be picked up, as that's where the selection is
+    if active && app.config.mouse {
+        let height = area.height.saturating_sub(2) as usize;
+        let mut targets = app.mouse_targets.borrow_mut();
+        for &(row, index) in &task_rows {
+            if let Some(screen_row) = row.checked_sub(list_state.offset()).filter(|&row| row < height) {
+                let row_area = Rect::new(area.x + 1, area.y + 1 + screen_row as u16, area.width.saturating_sub(2), 1);
+                targets.push((row_area, MouseTarget::ListRow(index)));
+            }
+        }
+    }
 
-    // Calculate statistics
-    let overdue_count = app.todos.iter()
-        .filter(|t| {
-            if let Some(due_date) = t.due_date {
-                due_date < today && !t.completed
-            } else {
-                false
+    // Jump hint labels go over the highlight symbol's column of each task on screen
+    if active && app.input_mode == InputMode::JumpHints {
+        let hints = app.jump_hints();
+        let height = area.height.saturating_sub(2) as usize;
+        for (row, index) in task_rows {
+            let Some(screen_row) = row.checked_sub(list_state.offset()).filter(|&row| row < height) else {
+                continue;
+            };
+            if let Some((label, _)) = hints.iter().find(|(_, target)| *target == JumpTarget::Task(index)) {
+                render_hint(frame, app, label, area.x + 1, area.y + 1 + screen_row as u16);
             }
-        })
-        .count();
+        }
+    }
+}
+
+/// Guides joining each shown task at `indices` (in tree order) to its parent, plus an arrow on
+/// tasks with subtasks saying whether they're folded, by index into `app.todos`
+fn tree_prefixes(app: &App, indices: &[usize], folded_away: &HashSet<usize>) -> HashMap<usize, String> {
+    let shown: Vec<usize> = indices.iter().copied().filter(|&i| !folded_away.contains(&app.todos[i].id)).collect();
+    let depths = app.tree_depths(&shown);
+    let depth = |i: usize| depths[&app.todos[i].id];
+    let parents: HashSet<usize> = indices.iter().filter_map(|&i| app.todos[i].parent).collect();
+    let (branch, last, through) = if app.theme.ascii {
+        ("|- ", "`- ", "|  ")
+    } else {
+        ("\u{251c}\u{2500} ", "\u{2514}\u{2500} ", "\u{2502}  ")
+    };
 
-    // Count all pending (not completed, not deleted) tasks
-    // Note: app.todos is already filtered to exclude completed and deleted tasks
-    let todo_count = app.todos.len();
+    // Whether another task at the same level follows under the same parent, found from the bottom up
+    let mut has_next = vec![false; shown.len()];
+    let mut seen_at_depth: Vec<bool> = Vec::new();
+    for (row, &i) in shown.iter().enumerate().rev() {
+        let d = depth(i);
+        seen_at_depth.resize(d + 1, false);
+        has_next[row] = seen_at_depth[d];
+        seen_at_depth[d] = true;
+    }
 
-    // Count completed tasks
-    let done_count = all_todos.iter()
-        .filter(|t| t.completed)
-        .count();
+    let mut prefixes = HashMap::new();
+    // For each level above the current task, whether its line carries on past it
+    let mut continues: Vec<bool> = Vec::new();
+    for (row, &i) in shown.iter().enumerate() {
+        let todo = &app.todos[i];
+        let d = depth(i);
+        continues.truncate(d.saturating_sub(1));
+        let mut prefix: String = continues.iter().map(|&more| if more { through } else { "   " }).collect();
+        if d > 0 {
+            prefix.push_str(if has_next[row] { branch } else { last });
+            continues.push(has_next[row]);
+        }
+        if parents.contains(&todo.id) {
+            let arrow = if app.config.tree_folded.contains(&todo.id) { app.theme.icons.collapsed } else { app.theme.icons.expanded };
+            prefix.push_str(arrow);
+            prefix.push(' ');
+        } else if d == 0 && !parents.is_empty() {
+            // Line titles up with those after an arrow
+            prefix.push_str("  ");
+        }
+        prefixes.insert(i, prefix);
+    }
+    prefixes
+}
+
+/// One jump hint label at (x, y): hidden once a typed first letter rules it out, otherwise
+/// down to the letter still to type
+fn render_hint(frame: &mut Frame, app: &App, label: &str, x: u16, y: u16) {
+    let Some(rest) = label.strip_prefix(app.jump_hint_input.as_str()) else {
+        return;
+    };
+    let style = app.theme.accent.add_modifier(Modifier::BOLD | Modifier::REVERSED);
+    let hint = Line::from(vec![
+        Span::raw(" ".repeat(app.jump_hint_input.len())),
+        Span::styled(rest.to_string(), style),
+    ]);
+    let width = label.len() as u16;
+    if frame.area().width >= x + width {
+        frame.render_widget(Paragraph::new(hint), Rect::new(x, y, width, 1));
+    }
+}
 
-    // Count deleted tasks
-    let deleted_count = all_todos.iter()
-        .filter(|t| t.deleted)
+/// Non-selectable section header for a due bucket, e.g. "▾ Today (3)"
+fn group_header(app: &App, indices: &[usize], bucket: DueBucket) -> ListItem<'static> {
+    let count = indices.iter()
+        .filter(|&&i| app.due_bucket(&app.todos[i]) == bucket)
         .count();
+    let arrow = if app.collapsed_buckets.contains(&bucket) {
+        app.theme.icons.collapsed
+    } else {
+        app.theme.icons.expanded
+    };
+    ListItem::new(format!("{} {} ({})", arrow, bucket.label(&app.config.due_groups), count))
+        .style(app.theme.accent.add_modifier(Modifier::BOLD))
+}
+
+/// One task's row in the list; `tree_prefix` is its guides and fold arrow in the tree
+fn task_list_item(app: &App, todo: &Todo, number: usize, tree_prefix: &str, today: NaiveDate) -> ListItem<'static> {
+    let mut content = format!("{}. {}", number, todo.display_string());
+    // Subtasks are marked (the tree's guides do that there), and their parents show how far along they are
+    if todo.parent.is_some() && !app.config.tree_view {
+        content = format!("{} {}", app.theme.icons.subtask, content);
+    }
+    if let Some((done, total)) = app.subtask_progress(todo.id) {
+        content.push_str(&format!(" [{}/{}]", done, total));
+    }
+
+    // Determine task color based on due date, with an icon so state doesn't rely on color alone
+    let (text, style) = match todo.due_date {
+        // Tasks overdue past the escalation threshold bold and blinking too
+        Some(_) if app.is_escalated(todo) => {
+            (format!("{} {}", app.theme.icons.overdue, content), app.theme.overdue.add_modifier(Modifier::BOLD | Modifier::SLOW_BLINK))
+        }
+        // Overdue tasks in red
+        Some(_) if todo.is_overdue(today) => {
+            (format!("{} {}", app.theme.icons.overdue, content), app.theme.overdue)
+        }
+        // Tasks due today in yellow
+        Some(due_date) if !todo.completed && due_date == today => {
+            (format!("{} {}", app.theme.icons.due_today, content), app.theme.due_today)
+        }
+        // Future, completed and undated tasks in default color
+        _ => (content, Style::default()),
+    };
+
+    let mut spans = vec![Span::styled(tree_prefix.to_string(), app.theme.muted)];
+    if app.marked.contains(&todo.id) {
+        spans.push(Span::styled(format!("{} ", app.theme.icons.marked), app.theme.accent.add_modifier(Modifier::BOLD)));
+    }
+    if let Some(color) = app.project_color(todo.project.as_deref()) {
+        spans.push(Span::styled(format!("{} ", app.theme.icons.project), app.theme.label(Some(color))));
+    }
+    spans.push(Span::raw(text));
+    spans.extend(status_suffix(app, todo));
+    spans.extend(waiting_suffix(app, todo));
+    spans.extend(age_suffix(app, todo));
+    ListItem::new(Line::from(spans)).style(style)
+}
+
+/// " [In Progress]" after an open task past the workflow's first status
+fn status_suffix(app: &App, todo: &Todo) -> Option<Span<'static>> {
+    let workflow = &app.config.workflow;
+    let index = workflow.index_of(todo);
+    if index == 0 || todo.completed {
+        return None;
+    }
+    let status = &workflow.statuses[index];
+    Some(Span::styled(format!(" [{}]", status.name), app.theme.label(status.color.as_deref())))
+}
+
+/// " → Alice" after a task waiting on someone, flagged once it's due a ping
+fn waiting_suffix(app: &App, todo: &Todo) -> Option<Span<'static>> {
+    if !todo.is_waiting() || todo.completed {
+        return None;
+    }
+    let who = todo.delegated_to.as_deref().unwrap_or("someone");
+    if todo.needs_ping(Utc::now(), app.config.waiting.nag_after_days) {
+        Some(Span::styled(format!(" {} {} (ping due)", app.theme.icons.delegated, who), app.theme.due_today))
+    } else {
+        Some(Span::styled(format!(" {} {}", app.theme.icons.delegated, who), app.theme.muted))
+    }
+}
+
+/// " (14d)" after an open task once it's older than the aging threshold, more prominent past three times it
+fn age_suffix(app: &App, todo: &Todo) -> Option<Span<'static>> {
+    let threshold = app.config.aging.after_days as i64;
+    if threshold == 0 || todo.completed || todo.deleted {
+        return None;
+    }
+    let age = app.age_days(todo);
+    if age < threshold {
+        return None;
+    }
+    let style = if age >= threshold * 3 { app.theme.aging_old } else { app.theme.aging };
+    Some(Span::styled(format!(" ({}d)", age), style))
+}
+
+fn render_stats_tab(frame: &mut Frame, app: &App, area: Rect) {
+    if app.show_usage && let Some(usage) = app.usage() {
+        render_usage_view(frame, app, usage, area);
+        return;
+    }
+
+    // Stats are computed by the App between frames; there's nothing to show until then
+    let Some(stats) = app.stats() else {
+        return;
+    };
+    if app.show_estimates {
+        render_estimates_view(frame, app, stats, area);
+        return;
+    }
 
     // Divide into three equal rows
     let rows = Layout::default()
@@ -211,19 +811,21 @@ fn render_stats_tab(frame: &mut Frame, app: &App, area: Rect) {
         .split(rows[0]);
 
     // Render the four top panels
-    let panel_titles = ["Overdue", "ToDo", "Done", "Deleted"];
+    let overdue_title = format!("{} Overdue", app.theme.icons.overdue);
+    let done_title = format!("{} Done", app.theme.icons.done);
+    let panel_titles = [overdue_title.as_str(), "ToDo", done_title.as_str(), "Deleted"];
     let panel_counts = [
-        overdue_count,
-        todo_count,
-        done_count,
-        deleted_count,
+        stats.overdue_count,
+        stats.todo_count,
+        stats.done_count,
+        stats.deleted_count,
     ];
 
     for (i, panel_area) in top_panels.iter().enumerate() {
-        let block = Block::default()
+        let block = themed_block(&app.theme)
             .title(panel_titles[i])
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan));
+            .border_style(app.theme.accent);
 
         let inner = block.inner(*panel_area);
         frame.render_widget(block, *panel_area);
@@ -231,15 +833,24 @@ fn render_stats_tab(frame: &mut Frame, app: &App, area: Rect) {
         // Determine color based on panel type and value
         let text_style = if i == 0 && panel_counts[i] > 0 {
             // Overdue panel with count > 0: make it red
-            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            app.theme.overdue.add_modifier(Modifier::BOLD)
         } else if i == 1 {
             // ToDo panel: make it yellow
-            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            app.theme.pending.add_modifier(Modifier::BOLD)
         } else {
             // Default: cyan
-            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+            app.theme.accent.add_modifier(Modifier::BOLD)
         };
 
+        // Screen readers and ASCII terminals get the count as plain text
+        if app.config.screen_reader || app.theme.ascii {
+            let count_text = Paragraph::new(format!("{}: {}", panel_titles[i], panel_counts[i]))
+                .style(text_style)
+                .alignment(Alignment::Center);
+            frame.render_widget(count_text, inner);
+            continue;
+        }
+
         // Display count as big text
         let big_text = BigText::builder()
             .pixel_size(PixelSize::Full)
@@ -261,102 +872,910 @@ fn render_stats_tab(frame: &mut Frame, app: &App, area: Rect) {
         frame.render_widget(big_text, vertical_layout[1]);
     }
 
-    // Render middle row - New Tasks chart
-    let middle_block = Block::default()
-        .title("New Tasks")
+    // Render middle row - New Tasks chart, or completions per source when they're all merged
+    let all_sources_key = app.keymap.label("all_sources").unwrap_or_default();
+    let middle_title = if app.stats_all_sources {
+        format!("Completed by source - {}: this context only", all_sources_key)
+    } else {
+        format!("New Tasks - {}: all sources", all_sources_key)
+    };
+    let middle_block = themed_block(&app.theme)
+        .title(middle_title)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(app.theme.accent);
 
     let middle_inner = middle_block.inner(rows[1]);
     frame.render_widget(middle_block, rows[1]);
 
-    // Calculate tasks created per day for the last 3 months
-    let today = Local::now().date_naive();
-    let three_months_ago = today - Duration::days(90);
-
-    // Create a map of date -> count for tasks created
-    let mut task_counts = std::collections::HashMap::new();
-    for todo in &all_todos {
-        let created_date = todo.created_at.date_naive();
-        if created_date >= three_months_ago && created_date <= today {
-            *task_counts.entry(created_date).or_insert(0) += 1;
-        }
-    }
-
-    // Create data points for tasks created (convert to f64 for chart)
-    let mut data: Vec<(f64, f64)> = Vec::new();
-    for day_offset in 0..=90 {
-        let date = three_months_ago + Duration::days(day_offset);
-        let count = task_counts.get(&date).copied().unwrap_or(0);
-        data.push((day_offset as f64, count as f64));
-    }
-
-    // Calculate overdue tasks per day
-    let mut overdue_data: Vec<(f64, f64)> = Vec::new();
-    for day_offset in 0..=90 {
-        let date = three_months_ago + Duration::days(day_offset);
-        let overdue_on_this_day = all_todos.iter()
-            .filter(|todo| {
-                if let Some(due_date) = todo.due_date {
-                    // Task is past due on this date
-                    let is_past_due = due_date < date;
-
-                    // Task is not completed, or completed after this date
-                    let not_completed_yet = if let Some(completed_at) = todo.completed_at {
-                        completed_at.date_naive() >= date
-                    } else {
-                        true
-                    };
-
-                    is_past_due && not_completed_yet
-                } else {
-                    false
-                }
-            })
-            .count();
-        overdue_data.push((day_offset as f64, overdue_on_this_day as f64));
+    if app.stats_all_sources {
+        render_sources_chart(frame, app, middle_inner, &stats.sources);
+    } else if app.config.screen_reader {
+        // Screen readers get a plain-text summary of the series instead of the Braille chart
+        let summary = Paragraph::new(chart_summary(stats.start, &stats.created, &stats.overdue, &stats.completed))
+            .wrap(ratatui::widgets::Wrap { trim: true });
+        frame.render_widget(summary, middle_inner);
+    } else {
+        render_new_tasks_chart(frame, app, middle_inner, &stats.created, &stats.overdue, &stats.completed);
     }
 
-    // Calculate tasks completed per day
-    let mut completed_counts = std::collections::HashMap::new();
-    for todo in &all_todos {
-        if let Some(completed_at) = todo.completed_at {
-            let completed_date = completed_at.date_naive();
-            if completed_date >= three_months_ago && completed_date <= today {
-                *completed_counts.entry(completed_date).or_insert(0) += 1;
-            }
+    // Divide the bottom row between the backlog, its forecast and the activity heatmap
+    let bottom_panels = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(33),  // Backlog age and statuses
+            Constraint::Percentage(33),  // Forecast
+            Constraint::Percentage(34),  // Activity
+        ])
+        .split(rows[2]);
+
+    // Render bottom row
+    let bottom_title = match app.keymap.label("estimates").filter(|_| !stats.estimate_accuracy.is_empty()) {
+        Some(key) => format!("Backlog - {}: estimates vs actual", key),
+        None => "Backlog".to_string(),
+    };
+    let bottom_block = themed_block(&app.theme)
+        .title(bottom_title)
+        .borders(Borders::ALL)
+        .border_style(app.theme.accent);
+
+    let bottom_inner = bottom_block.inner(bottom_panels[0]);
+    frame.render_widget(bottom_block, bottom_panels[0]);
+
+    let days = |value: Option<f64>| value.map_or("-".to_string(), |days| format!("{:.1} days", days));
+    let label_style = Style::default().add_modifier(Modifier::BOLD);
+    let status_counts = stats.status_counts.iter()
+        .map(|(name, count)| format!("{} {}", name, count))
+        .collect::<Vec<_>>()
+        .join("  ");
+    let mut age_lines = vec![
+        Line::from(vec![
+            Span::styled("Average backlog age: ", label_style),
+            Span::raw(days(stats.average_backlog_age)),
+        ]),
+        Line::from(vec![
+            Span::styled("Oldest open task: ", label_style),
+            Span::raw(stats.oldest_backlog_age.map_or("-".to_string(), |age| format!("{} days", age))),
+        ]),
+        Line::from(vec![
+            Span::styled("Mean time to Done: ", label_style),
+            Span::raw(days(stats.mean_time_to_done)),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Open by status: ", label_style),
+            Span::raw(status_counts),
+        ]),
+    ];
+    // Estimated projects: time logged against the estimate, red once over
+    if !stats.project_effort.is_empty() {
+        let mut spans = vec![Span::styled("Effort: ", label_style)];
+        for (project, effort) in &stats.project_effort {
+            let style = if effort.is_over() { app.theme.overdue } else { Style::default() };
+            spans.push(Span::styled(project.clone(), app.theme.label(app.project_color(Some(project)))));
+            spans.push(Span::styled(
+                format!(" {} of {}  ", TimeEntry::format_duration(effort.spent), TimeEntry::format_duration(effort.estimate)),
+                style,
+            ));
         }
+        age_lines.push(Line::from(spans));
     }
+    let bottom_text = Paragraph::new(age_lines)
+        .alignment(Alignment::Center)
+        .wrap(ratatui::widgets::Wrap { trim: true });
+    frame.render_widget(bottom_text, bottom_inner.inner(ratatui::layout::Margin::new(1, 1)));
 
-    // Create data points for tasks completed
-    let mut completed_data: Vec<(f64, f64)> = Vec::new();
-    for day_offset in 0..=90 {
-        let date = three_months_ago + Duration::days(day_offset);
-        let count = completed_counts.get(&date).copied().unwrap_or(0);
-        completed_data.push((day_offset as f64, count as f64));
-    }
+    render_forecast(frame, app, stats, bottom_panels[1]);
+    render_activity_heatmap(frame, app, stats, bottom_panels[2]);
+}
 
-    // Create the datasets
-    let created_dataset = Dataset::default()
-        .name("Tasks Created")
-        .marker(symbols::Marker::Braille)
-        .graph_type(GraphType::Line)
-        .style(Style::default().fg(Color::Yellow))
-        .data(&data);
+/// When the backlog, and each project's open tasks, would be done at the recent pace
+fn render_forecast(frame: &mut Frame, app: &App, stats: &Stats, area: Rect) {
+    let block = themed_block(&app.theme)
+        .title("Forecast")
+        .borders(Borders::ALL)
+        .border_style(app.theme.accent);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
 
-    let overdue_dataset = Dataset::default()
-        .name("Overdue Tasks")
-        .marker(symbols::Marker::Braille)
-        .graph_type(GraphType::Line)
-        .style(Style::default().fg(Color::Red))
-        .data(&overdue_data);
+    let label_style = Style::default().add_modifier(Modifier::BOLD);
+    let forecast = &stats.forecast;
+    let mut lines = vec![match forecast.cleared_by {
+        _ if forecast.open == 0 => Line::from(Span::styled("Backlog clear", app.theme.success)),
+        Some(date) => Line::from(vec![
+            Span::styled("Backlog cleared by: ", label_style),
+            Span::raw(date.format("%a %Y-%m-%d").to_string()),
+        ]),
+        None => Line::from(vec![
+            Span::styled("Backlog cleared by: ", label_style),
+            Span::raw("-"),
+        ]),
+    }];
+    lines.push(Line::from(Span::styled(
+        format!("{} open, {:.1} done a week over the last {} weeks", forecast.open, forecast.per_week, FORECAST_WEEKS),
+        app.theme.muted,
+    )));
+
+    if !stats.project_forecasts.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("By project:", label_style)));
+        for (project, forecast) in &stats.project_forecasts {
+            let date = forecast.cleared_by.map_or("-".to_string(), |date| date.format("%a %m-%d").to_string());
+            lines.push(Line::from(vec![
+                Span::styled(format!("{} ", project), app.theme.label(app.project_color(Some(project)))),
+                Span::raw(date),
+                Span::styled(format!("  {} open, {:.1}/week", forecast.open, forecast.per_week), app.theme.muted),
+            ]));
+        }
+    }
+    let text = Paragraph::new(lines).wrap(ratatui::widgets::Wrap { trim: true });
+    frame.render_widget(text, inner.inner(ratatui::layout::Margin::new(1, 1)));
+}
 
-    let completed_dataset = Dataset::default()
-        .name("Tasks Completed")
-        .marker(symbols::Marker::Braille)
-        .graph_type(GraphType::Line)
-        .style(Style::default().fg(Color::Cyan))
-        .data(&completed_data);
+/// The Estimates view of the Stats tab: time taken against time estimated for completed tasks,
+/// as a scatter chart (points above the diagonal took longer than estimated) and a list
+fn render_estimates_view(frame: &mut Frame, app: &App, stats: &Stats, area: Rect) {
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(50),  // Scatter chart
+            Constraint::Percentage(50),  // Summary and tasks
+        ])
+        .split(area);
+    let points = &stats.estimate_accuracy;
+    let hours = |duration: Duration| duration.num_minutes() as f64 / 60.0;
+
+    let chart_block = themed_block(&app.theme)
+        .title(format!("Estimated vs actual hours - {}: back to stats", app.keymap.label("estimates").unwrap_or_default()))
+        .borders(Borders::ALL)
+        .border_style(app.theme.accent);
+    let chart_inner = chart_block.inner(columns[0]);
+    frame.render_widget(chart_block, columns[0]);
+
+    let (over, within): (Vec<&EstimatePoint>, Vec<&EstimatePoint>) = points.iter().partition(|point| point.spent > point.estimate);
+    let to_xy = |points: &[&EstimatePoint]| -> Vec<(f64, f64)> {
+        points.iter().map(|point| (hours(point.estimate), hours(point.spent))).collect()
+    };
+    let (over_data, within_data) = (to_xy(&over), to_xy(&within));
+    let max = points.iter()
+        .map(|point| hours(point.estimate).max(hours(point.spent)))
+        .fold(1.0, f64::max)
+        .ceil();
+    let diagonal = [(0.0, 0.0), (max, max)];
+
+    if points.is_empty() {
+        let empty = Paragraph::new("No completed task has both an estimate and time logged yet")
+            .style(app.theme.muted)
+            .alignment(Alignment::Center)
+            .wrap(ratatui::widgets::Wrap { trim: true });
+        frame.render_widget(empty, chart_inner.inner(ratatui::layout::Margin::new(1, 1)));
+    } else if app.config.screen_reader {
+        let summary = format!(
+            "{} of {} tasks took longer than estimated, {} took as long or less.",
+            over.len(), points.len(), within.len(),
+        );
+        frame.render_widget(Paragraph::new(summary).wrap(ratatui::widgets::Wrap { trim: true }), chart_inner);
+    } else {
+        let label = |value: f64| Span::styled(format!("{:.0}h", value), app.theme.muted);
+        let chart = Chart::new(vec![
+            Dataset::default()
+                .name("As estimated")
+                .marker(app.theme.chart_markers[0])
+                .graph_type(GraphType::Line)
+                .style(app.theme.dim)
+                .data(&diagonal),
+            Dataset::default()
+                .name("Within estimate")
+                .marker(app.theme.chart_markers[2])
+                .graph_type(GraphType::Scatter)
+                .style(app.theme.chart_completed)
+                .data(&within_data),
+            Dataset::default()
+                .name("Over estimate")
+                .marker(app.theme.chart_markers[1])
+                .graph_type(GraphType::Scatter)
+                .style(app.theme.chart_overdue)
+                .data(&over_data),
+        ])
+        .x_axis(Axis::default().title("Estimated").style(app.theme.muted).bounds([0.0, max]).labels(vec![label(0.0), label(max)]))
+        .y_axis(Axis::default().title("Actual").style(app.theme.muted).bounds([0.0, max]).labels(vec![label(0.0), label(max)]));
+        frame.render_widget(chart, chart_inner);
+    }
+
+    // The overall bias first, then each task, the worst underestimates at the top
+    let list_block = themed_block(&app.theme)
+        .title(format!("Completed tasks with estimates ({})", points.len()))
+        .borders(Borders::ALL)
+        .border_style(app.theme.accent);
+    let list_inner = list_block.inner(columns[1]);
+    frame.render_widget(list_block, columns[1]);
+
+    let label_style = Style::default().add_modifier(Modifier::BOLD);
+    let estimated: Duration = points.iter().map(|point| point.estimate).sum();
+    let spent: Duration = points.iter().map(|point| point.spent).sum();
+    let mut lines = Vec::new();
+    if let Some(ratio) = stats.median_estimate_ratio() {
+        let verdict = match ratio {
+            ratio if ratio > 1.05 => format!("Tasks typically take {:.1}x their estimate", ratio),
+            ratio if ratio < 0.95 => format!("Tasks typically take {:.0}% of their estimate", ratio * 100.0),
+            _ => "Tasks typically take about as long as estimated".to_string(),
+        };
+        let style = if ratio > 1.05 { app.theme.overdue } else { app.theme.success };
+        lines.push(Line::from(Span::styled(verdict, style.add_modifier(Modifier::BOLD))));
+        lines.push(Line::from(vec![
+            Span::styled("In total: ", label_style),
+            Span::raw(format!("{} taken of {} estimated", TimeEntry::format_duration(spent), TimeEntry::format_duration(estimated))),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("Over estimate: ", label_style),
+            Span::raw(format!("{} of {} tasks", over.len(), points.len())),
+        ]));
+        lines.push(Line::from(""));
+    }
+    for point in points {
+        let style = if point.spent > point.estimate { app.theme.overdue } else { app.theme.success };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:>5.1}x ", point.ratio()), style),
+            Span::raw(format!("{} ", point.title)),
+            Span::styled(
+                format!("{} of {}", TimeEntry::format_duration(point.spent), TimeEntry::format_duration(point.estimate)),
+                app.theme.muted,
+            ),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(lines), list_inner.inner(ratatui::layout::Margin::new(1, 0)));
+}
+
+/// The Usage view of the Stats tab: actions per day and which actions get used, from usage.json
+fn render_usage_view(frame: &mut Frame, app: &App, usage: &UsageLog, area: Rect) {
+    let today = app.clock.today();
+    let start = today - Duration::days(HISTORY_DAYS - 1);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(50),  // Actions per day
+            Constraint::Percentage(50),  // Actions by name
+        ])
+        .split(area);
+    let bar = |count: usize, most: usize, width: usize| app.theme.icons.heat[4].repeat(count * width / most.max(1));
+
+    // Newest day first, as many as fit
+    let days_block = themed_block(&app.theme)
+        .title(format!("Actions per day - {}: back to stats", app.keymap.label("usage").unwrap_or_default()))
+        .borders(Borders::ALL)
+        .border_style(app.theme.accent);
+    let days_inner = days_block.inner(columns[0]);
+    frame.render_widget(days_block, columns[0]);
+
+    let daily = usage.daily_totals(start, today);
+    let total: usize = daily.iter().map(|&(_, count)| count).sum();
+    let active_days = daily.iter().filter(|&&(_, count)| count > 0).count();
+    let busiest = daily.iter().map(|&(_, count)| count).max().unwrap_or(0);
+    let bar_width = (days_inner.width as usize).saturating_sub(22);
+    let mut day_lines = vec![
+        Line::from(Span::styled(
+            format!("{} actions on {} of the last {} days", total, active_days, daily.len()),
+            app.theme.muted,
+        )),
+        Line::from(""),
+    ];
+    for &(date, count) in daily.iter().rev() {
+        let style = if count == 0 { app.theme.dim } else { app.theme.success };
+        day_lines.push(Line::from(vec![
+            Span::styled(format!("{} ", date.format("%a %m-%d")), app.theme.muted),
+            Span::styled(format!("{:>5} ", count), style),
+            Span::styled(bar(count, busiest, bar_width), style),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(day_lines), days_inner.inner(ratatui::layout::Margin::new(1, 0)));
+
+    // Most used first, then the actions never used in the period
+    let actions_block = themed_block(&app.theme)
+        .title(format!("Actions used - last {} days", HISTORY_DAYS))
+        .borders(Borders::ALL)
+        .border_style(app.theme.accent);
+    let actions_inner = actions_block.inner(columns[1]);
+    frame.render_widget(actions_block, columns[1]);
+
+    let totals = usage.action_totals(start);
+    let most_used = totals.first().map_or(0, |(_, count)| *count);
+    let bar_width = (actions_inner.width as usize).saturating_sub(30);
+    let mut action_lines: Vec<Line> = totals.iter()
+        .map(|(action, count)| Line::from(vec![
+            Span::styled(format!("{:<18} ", action), app.theme.accent),
+            Span::raw(format!("{:>5} ", count)),
+            Span::styled(bar(*count, most_used, bar_width), app.theme.success),
+        ]))
+        .collect();
+    let unused: Vec<&str> = crate::keymap::Keymap::actions()
+        .filter(|action| !totals.iter().any(|(used, _)| used == action))
+        .collect();
+    if totals.is_empty() {
+        action_lines.push(Line::from(Span::styled("Nothing recorded yet", app.theme.muted)));
+    }
+    if !unused.is_empty() {
+        action_lines.push(Line::from(""));
+        action_lines.push(Line::from(Span::styled("Not used:", Style::default().add_modifier(Modifier::BOLD))));
+        action_lines.push(Line::from(Span::styled(unused.join(", "), app.theme.muted)));
+    }
+    let actions_text = Paragraph::new(action_lines).wrap(ratatui::widgets::Wrap { trim: true });
+    frame.render_widget(actions_text, actions_inner.inner(ratatui::layout::Margin::new(1, 0)));
+}
+
+/// Tasks completed plus habits checked off per day, as a week-by-week grid (Monday at the top)
+fn render_activity_heatmap(frame: &mut Frame, app: &App, stats: &Stats, area: Rect) {
+    let title = match app.keymap.label("usage").filter(|_| app.usage().is_some()) {
+        Some(key) => format!("Activity - {}: your usage", key),
+        None => "Activity".to_string(),
+    };
+    let block = themed_block(&app.theme)
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(app.theme.accent);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    // Screen readers get the busiest day and how many days had any activity
+    if app.config.screen_reader {
+        let active_days = stats.activity.iter().filter(|&&count| count > 0).count();
+        let busiest = stats.activity.iter().enumerate().max_by_key(|&(offset, count)| (count, offset));
+        let mut text = format!("Active on {} of the last {} days.", active_days, stats.activity.len());
+        if let Some((offset, &count)) = busiest.filter(|&(_, &count)| count > 0) {
+            let date = stats.start + Duration::days(offset as i64);
+            text.push_str(&format!(" Busiest day: {} with {}.", date.format("%Y-%m-%d"), count));
+        }
+        frame.render_widget(Paragraph::new(text).wrap(ratatui::widgets::Wrap { trim: true }), inner);
+        return;
+    }
+
+    // Scale the shades to the busiest day, so a quiet stretch still shows some contrast
+    let busiest = stats.activity.iter().copied().max().unwrap_or(0).max(1);
+    let level = |count: usize| -> usize {
+        if count == 0 { 0 } else { 1 + (count - 1) * 4 / busiest }
+    };
+
+    // Columns are weeks, starting from the Monday on or before the first day
+    let first_monday = stats.start - Duration::days(stats.start.weekday().num_days_from_monday() as i64);
+    let weeks = ((stats.start - first_monday).num_days() + stats.activity.len() as i64 + 6) / 7;
+    let labels = ["Mo", "  ", "We", "  ", "Fr", "  ", "Su"];
+    let lines: Vec<Line> = (0..7)
+        .map(|weekday| {
+            let mut spans = vec![Span::styled(format!("{} ", labels[weekday]), app.theme.dim)];
+            for week in 0..weeks {
+                let date = first_monday + Duration::weeks(week) + Duration::days(weekday as i64);
+                // Days before the first or after today stay blank
+                let count = usize::try_from((date - stats.start).num_days()).ok()
+                    .and_then(|offset| stats.activity.get(offset));
+                match count {
+                    Some(&count) => {
+                        let heat = level(count);
+                        let style = if heat == 0 { app.theme.dim } else { app.theme.success };
+                        spans.push(Span::styled(format!("{} ", app.theme.icons.heat[heat]), style));
+                    }
+                    None => spans.push(Span::raw("  ")),
+                }
+            }
+            Line::from(spans)
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn render_habits_tab(frame: &mut Frame, app: &App, area: Rect) {
+    let block = themed_block(&app.theme)
+        .title("Habits")
+        .borders(Borders::ALL)
+        .border_style(app.theme.focused_border)
+        .border_set(get_border_set(&app.theme, true));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),     // Habits
+            Constraint::Length(1),  // Instructions
+        ])
+        .split(inner);
+
+    let instructions = Paragraph::new("Up/Down: Select | Space: Check off | +: Add habit | -: Delete habit")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[1]);
+
+    if app.habits().is_empty() {
+        let empty = Paragraph::new("No habits yet. Press + to add one.")
+            .style(app.theme.muted)
+            .alignment(Alignment::Center);
+        frame.render_widget(empty, chunks[0]);
+        return;
+    }
+
+    // Name, frequency and streak take a fixed width; the grid fills the rest, newest period last
+    let today = app.clock.today();
+    let fixed_width = 3 + 24 + 8 + 12;
+    let periods = (chunks[0].width.saturating_sub(fixed_width) / 2).clamp(1, 60) as i64;
+    let items: Vec<ListItem> = app.habits()
+        .iter()
+        .map(|habit| ListItem::new(habit_line(app, habit, today, periods)))
+        .collect();
+
+    let habit_list = List::new(items)
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(app.selected_habit_index));
+    frame.render_stateful_widget(habit_list, chunks[0], &mut list_state);
+}
+
+/// Completed tasks, most recently done first: when each was done, and when it was due
+fn render_done_tab(frame: &mut Frame, app: &App, area: Rect) {
+    let block = themed_block(&app.theme)
+        .title(app.tab_title(Tab::Done))
+        .borders(Borders::ALL)
+        .border_style(app.theme.focused_border)
+        .border_set(get_border_set(&app.theme, true));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),     // Completed tasks
+            Constraint::Length(1),  // Instructions
+        ])
+        .split(inner);
+
+    let instructions = Paragraph::new("Up/Down: Select | r: Reopen | <: Done a day earlier | >: Done a day later")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[1]);
+
+    let done = app.done_tasks();
+    if done.is_empty() {
+        let empty = Paragraph::new("Nothing done yet.")
+            .style(app.theme.muted)
+            .alignment(Alignment::Center);
+        frame.render_widget(empty, chunks[0]);
+        return;
+    }
+
+    let items: Vec<ListItem> = done
+        .iter()
+        .map(|todo| {
+            let done_at = todo.completed_at.map(|at| app.clock.local(at).format("%a %Y-%m-%d %H:%M").to_string()).unwrap_or_default();
+            let mut spans = vec![
+                Span::styled(format!("{:<20}  ", done_at), app.theme.success),
+                Span::styled(format!("#{:<5} ", todo.id), app.theme.dim),
+                Span::raw(todo.title.clone()),
+            ];
+            if let Some(project) = &todo.project {
+                spans.push(Span::styled(format!("  {}", project), app.theme.label(app.project_color(Some(project)))));
+            }
+            if let Some(due_date) = todo.due_date {
+                spans.push(Span::styled(format!("  (due {})", due_date.format("%Y-%m-%d")), app.theme.dim));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let done_list = List::new(items)
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(app.selected_done_index.min(done.len() - 1)));
+    frame.render_stateful_widget(done_list, chunks[0], &mut list_state);
+}
+
+fn render_board_tab(frame: &mut Frame, app: &App, area: Rect) {
+    let board = app.board();
+    let block = themed_block(&app.theme)
+        .title(app.tab_title(Tab::Board))
+        .borders(Borders::ALL)
+        .border_style(app.theme.focused_border)
+        .border_set(get_border_set(&app.theme, true));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),     // Columns
+            Constraint::Length(1),  // Instructions
+        ])
+        .split(inner);
+
+    let key = |action: &str| app.keymap.label(action).unwrap_or_default();
+    let instructions = Paragraph::new(format!(
+        "Arrows: Move | {}/{}: Next/previous status | Enter: Edit | {}: Done",
+        key("next_status"), key("previous_status"), key("done"),
+    ))
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[1]);
+
+    let column_areas = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, board.columns.len() as u32); board.columns.len()])
+        .split(chunks[0]);
+
+    // Every column gives a lane the same height, so the lanes line up across the board
+    let today = app.clock.today();
+    let selected = app.selected_todo_index;
+    let heading = usize::from(board.swimlanes != Swimlanes::None);
+    let heights: Vec<usize> = board.lanes.iter()
+        .map(|lane| heading + lane.cells.iter().map(Vec::len).max().unwrap_or(0))
+        .collect();
+    let selected_line = selected.and_then(|index| board.position_of(index))
+        .map(|(lane, _, row)| heights[..lane].iter().sum::<usize>() + heading + row);
+    let visible = column_areas[0].height.saturating_sub(2) as usize;
+    let scroll = selected_line.map_or(0, |line| (line + 1).saturating_sub(visible));
+
+    let statuses = app.config.workflow.open_statuses();
+    for (column, column_area) in column_areas.iter().enumerate() {
+        let count: usize = board.lanes.iter().map(|lane| lane.cells[column].len()).sum();
+        let title = Line::from(Span::styled(
+            format!("{} ({})", board.columns[column], count),
+            app.theme.label(statuses[column].color.as_deref()),
+        ));
+        let is_drop_target = app.drag.is_some_and(|drag| drag.over == Some(MouseTarget::BoardColumn(column)));
+        let column_block = themed_block(&app.theme)
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(if is_drop_target { app.theme.drop_target } else { app.theme.border });
+        let column_inner = column_block.inner(*column_area);
+        let width = column_inner.width as usize;
+        if app.config.mouse {
+            app.mouse_targets.borrow_mut().push((*column_area, MouseTarget::BoardColumn(column)));
+        }
+
+        let mut lines: Vec<Line> = Vec::new();
+        for (lane, height) in board.lanes.iter().zip(&heights) {
+            if let Some(lane_title) = board.lane_title(lane) {
+                // Project lanes take the project's color
+                let color = match board.swimlanes {
+                    Swimlanes::Project => app.project_color(lane.title.as_deref()),
+                    _ => None,
+                };
+                let style = match color {
+                    Some(_) => app.theme.label(color),
+                    None => app.theme.accent,
+                };
+                let style = style.add_modifier(Modifier::BOLD);
+                lines.push(Line::from(Span::styled(truncate(lane_title, width), style)));
+            }
+            let cell = &lane.cells[column];
+            for &index in cell {
+                let screen_row = lines.len().checked_sub(scroll).filter(|&row| row < visible);
+                if let Some(row) = screen_row.filter(|_| app.config.mouse) {
+                    let card_area = Rect::new(column_inner.x, column_inner.y + row as u16, column_inner.width, 1);
+                    app.mouse_targets.borrow_mut().push((card_area, MouseTarget::BoardCard(index)));
+                }
+                lines.push(board_card(app, &app.todos[index], width, selected == Some(index), today));
+            }
+            lines.extend((cell.len() + heading..*height).map(|_| Line::default()));
+        }
+        let paragraph = Paragraph::new(lines)
+            .block(column_block)
+            .scroll((scroll as u16, 0));
+        frame.render_widget(paragraph, *column_area);
+    }
+}
+
+/// The selected day's month as a grid of day cells, each listing the tasks due that day
+fn render_month_tab(frame: &mut Frame, app: &App, area: Rect) {
+    let today = app.clock.today();
+    let selected = app.selected_calendar_date.unwrap_or(today);
+    let first = selected.with_day(1).unwrap_or(selected);
+    let block = themed_block(&app.theme)
+        .title(format!("{} - {}", app.tab_title(Tab::Month), first.format("%B %Y")))
+        .borders(Borders::ALL)
+        .border_style(app.theme.focused_border)
+        .border_set(get_border_set(&app.theme, true));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),  // Weekday names
+            Constraint::Min(1),     // Weeks
+            Constraint::Length(1),  // Instructions
+        ])
+        .split(inner);
+
+    let instructions = Paragraph::new(format!(
+        "Arrows: Move | PgUp/PgDn: Month | Enter: Day's tasks | {}: New task on that day",
+        app.keymap.label("new_task").unwrap_or_default(),
+    ))
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[2]);
+
+    // Weeks start on Sunday, as in the calendar panel
+    let start = first - Duration::days(first.weekday().num_days_from_sunday() as i64);
+    let weeks = ((first.weekday().num_days_from_sunday() + get_next_month(first).pred_opt().unwrap_or(first).day()) as usize).div_ceil(7);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, 7); 7]);
+    let header_cells = columns.split(chunks[0]);
+    for (day, cell) in header_cells.iter().enumerate() {
+        let name = (start + Duration::days(day as i64)).format("%a").to_string();
+        frame.render_widget(Paragraph::new(name).style(app.theme.accent).alignment(Alignment::Center), *cell);
+    }
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Ratio(1, weeks as u32); weeks])
+        .split(chunks[1]);
+    let loads = app.day_loads();
+    for (week, row) in rows.iter().enumerate() {
+        for (day, cell) in columns.split(*row).iter().enumerate() {
+            let date = start + Duration::days((week * 7 + day) as i64);
+            let load = loads.get(&date).copied().unwrap_or(0.0);
+            render_month_day(frame, app, *cell, date, load, date.month() == first.month(), date == selected);
+        }
+    }
+}
+
+/// One day of the Month tab: its number (in red, with its load, when more is due than the day
+/// holds), then as many of its tasks as fit, cut to the cell's width
+fn render_month_day(frame: &mut Frame, app: &App, area: Rect, date: NaiveDate, load: f64, in_month: bool, is_selected: bool) {
+    let today = app.clock.today();
+    let over_capacity = in_month && load > app.day_capacity();
+    let number_style = if over_capacity {
+        app.theme.calendar_overdue
+    } else if date == today {
+        app.theme.calendar_today
+    } else if !in_month {
+        app.theme.dim
+    } else if app.blackout_on(date).is_some() {
+        app.theme.calendar_blackout
+    } else if !app.is_working_day(date) {
+        app.theme.calendar_non_working
+    } else {
+        Style::default()
+    };
+    let number = if over_capacity {
+        format!("{:>2} {}", date.day(), app.describe_load(load))
+    } else {
+        format!("{:>2}", date.day())
+    };
+    let block = themed_block(&app.theme)
+        .title(Span::styled(number, number_style))
+        .borders(Borders::ALL)
+        .border_style(if is_selected { app.theme.focused_border } else { app.theme.border })
+        .border_set(get_border_set(&app.theme, is_selected));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    if !in_month {
+        return;
+    }
+
+    let width = inner.width as usize;
+    let room = inner.height as usize;
+    let due = app.tasks_due_on(date);
+    // Keep the last line for how many didn't fit
+    let shown = if due.len() > room { room.saturating_sub(1) } else { due.len() };
+    let mut lines: Vec<Line> = due.iter().take(shown).map(|todo| {
+        let style = if todo.is_overdue(today) { app.theme.overdue } else { Style::default() };
+        let text = match todo.due_time {
+            Some(time) => format!("{} {}", time.format("%H:%M"), todo.title),
+            None => todo.title.clone(),
+        };
+        Line::from(Span::styled(truncate(&text, width), style))
+    }).collect();
+    if shown < due.len() {
+        lines.push(Line::from(Span::styled(truncate(&format!("+{} more", due.len() - shown), width), app.theme.muted)));
+    }
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// The selected day's year as twelve small months, each day shaded by how many tasks are due
+fn render_year_tab(frame: &mut Frame, app: &App, area: Rect) {
+    let today = app.clock.today();
+    let selected = app.selected_calendar_date.unwrap_or(today);
+    let year = selected.year();
+    let block = themed_block(&app.theme)
+        .title(format!("{} - {}", app.tab_title(Tab::Year), year))
+        .borders(Borders::ALL)
+        .border_style(app.theme.focused_border)
+        .border_set(get_border_set(&app.theme, true));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),     // Months
+            Constraint::Length(1),  // Selected day
+            Constraint::Length(1),  // Instructions
+        ])
+        .split(inner);
+
+    let instructions = Paragraph::new("Arrows: Move | PgUp/PgDn: Month | Enter: Show in calendar")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[2]);
+
+    let counts = app.due_counts();
+    let in_year: Vec<(NaiveDate, usize)> = counts.iter()
+        .filter(|(date, _)| date.year() == year)
+        .map(|(&date, &count)| (date, count))
+        .collect();
+    let busiest = in_year.iter().max_by_key(|&&(date, count)| (count, std::cmp::Reverse(date))).copied();
+    let due_on = |date: NaiveDate| counts.get(&date).copied().unwrap_or(0);
+    let plural = |count: usize| if count == 1 { "task" } else { "tasks" };
+
+    let mut summary = format!("{}: {} {} due", selected.format("%a %Y-%m-%d"), due_on(selected), plural(due_on(selected)));
+    if let Some((date, count)) = busiest {
+        summary.push_str(&format!(" | Busiest day: {} ({})", date.format("%a %Y-%m-%d"), count));
+    }
+    frame.render_widget(Paragraph::new(summary).alignment(Alignment::Center), chunks[1]);
+
+    // Screen readers get the busiest days in order instead of the shading
+    if app.config.screen_reader {
+        let mut days = in_year.clone();
+        days.sort_by_key(|&(date, count)| (std::cmp::Reverse(count), date));
+        let mut text = format!("{} tasks due in {}.", in_year.iter().map(|(_, count)| count).sum::<usize>(), year);
+        if !days.is_empty() {
+            let busiest: Vec<String> = days.iter().take(5)
+                .map(|(date, count)| format!("{} ({})", date.format("%a %Y-%m-%d"), count))
+                .collect();
+            text.push_str(&format!(" Busiest days: {}.", busiest.join(", ")));
+        }
+        frame.render_widget(Paragraph::new(text).wrap(ratatui::widgets::Wrap { trim: true }), chunks[0]);
+        return;
+    }
+
+    // Scale the shades to the busiest day, as the activity heatmap does
+    let most = busiest.map_or(1, |(_, count)| count);
+    let level = |count: usize| -> usize {
+        if count == 0 { 0 } else { 1 + (count - 1) * 4 / most }
+    };
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Ratio(1, 3); 3])
+        .split(chunks[0]);
+    for (row, row_area) in rows.iter().enumerate() {
+        let month_areas = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, 4); 4])
+            .split(*row_area);
+        for (column, month_area) in month_areas.iter().enumerate() {
+            let Some(first) = NaiveDate::from_ymd_opt(year, (row * 4 + column + 1) as u32, 1) else {
+                continue;
+            };
+            let mut lines = vec![
+                Line::from(Span::styled(first.format("%B").to_string(), app.theme.accent.add_modifier(Modifier::BOLD))),
+                Line::from(Span::styled("Su Mo Tu We Th Fr Sa", app.theme.dim)),
+            ];
+            // Weeks start on Sunday, as in the calendar panel
+            let mut spans: Vec<Span> = vec![Span::raw("   "); first.weekday().num_days_from_sunday() as usize];
+            let mut date = first;
+            while date.month() == first.month() {
+                let heat = level(due_on(date));
+                let text = if date == selected {
+                    format!("{:>2}", date.day())
+                } else {
+                    app.theme.icons.heat[heat].repeat(2)
+                };
+                let style = if date == selected {
+                    app.theme.calendar_selected
+                } else if date == today {
+                    app.theme.calendar_today
+                } else if app.blackout_on(date).is_some() {
+                    app.theme.calendar_blackout
+                } else if heat == 0 {
+                    app.theme.dim
+                } else {
+                    app.theme.accent
+                };
+                spans.push(Span::styled(text, style));
+                spans.push(Span::raw(" "));
+                if date.weekday() == chrono::Weekday::Sat {
+                    lines.push(Line::from(std::mem::take(&mut spans)));
+                }
+                date += Duration::days(1);
+            }
+            if !spans.is_empty() {
+                lines.push(Line::from(spans));
+            }
+            // Centered as a whole, so the days stay in their weekday columns
+            let width = month_area.width.min(20);
+            let grid = Rect::new(month_area.x + (month_area.width - width) / 2, month_area.y, width, month_area.height);
+            frame.render_widget(Paragraph::new(lines), grid);
+        }
+    }
+}
+
+/// A task on the board: its title, cut to fit, colored by due date like the list
+fn board_card(app: &App, todo: &Todo, width: usize, is_selected: bool, today: NaiveDate) -> Line<'static> {
+    let (icon, style) = match todo.due_date {
+        Some(_) if todo.is_overdue(today) => (Some(app.theme.icons.overdue), app.theme.overdue),
+        Some(due_date) if due_date == today => (Some(app.theme.icons.due_today), app.theme.due_today),
+        _ => (None, Style::default()),
+    };
+    let prefix = if is_selected { ">> " } else { "   " };
+    let text = match icon {
+        Some(icon) => format!("{} {}", icon, todo.title),
+        None => todo.title.clone(),
+    };
+    let style = if is_selected { style.add_modifier(Modifier::BOLD) } else { style };
+    Line::from(Span::styled(format!("{}{}", prefix, truncate(&text, width.saturating_sub(prefix.len()))), style))
+}
+
+/// One habit row: name, frequency, current streak and the last `periods` days or weeks
+fn habit_line<'a>(app: &App, habit: &Habit, today: NaiveDate, periods: i64) -> Line<'a> {
+    let streak = habit.streak(today);
+    let unit = match (habit.frequency, streak) {
+        (HabitFrequency::Daily, 1) => "day",
+        (HabitFrequency::Daily, _) => "days",
+        (HabitFrequency::Weekly, 1) => "week",
+        (HabitFrequency::Weekly, _) => "weeks",
+    };
+    let name_style = if habit.is_done(today) { app.theme.success } else { Style::default() };
+    let mut spans = vec![
+        Span::styled(format!("{:<24}", truncate(&habit.name, 23)), name_style),
+        Span::styled(format!("{:<8}", habit.frequency.label()), app.theme.muted),
+        Span::styled(format!("{:>3} {:<6}  ", streak, unit), app.theme.accent),
+    ];
+
+    let current = habit.period_start(today);
+    let first_period = habit.period_start(habit.created);
+    for back in (0..periods).rev() {
+        let period = current - habit.frequency.period() * back as i32;
+        if period < first_period {
+            spans.push(Span::raw("  "));
+        } else if habit.is_done(period) {
+            spans.push(Span::styled(format!("{} ", app.theme.icons.habit_done), app.theme.success));
+        } else {
+            spans.push(Span::styled(format!("{} ", app.theme.icons.habit_missed), app.theme.dim));
+        }
+    }
+    Line::from(spans)
+}
+
+/// `text` cut to at most `max_chars` characters, marking the cut with "~"
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    format!("{}~", kept)
+}
+
+fn render_new_tasks_chart(
+    frame: &mut Frame,
+    app: &App,
+    area: Rect,
+    data: &[(f64, f64)],
+    overdue_data: &[(f64, f64)],
+    completed_data: &[(f64, f64)],
+) {
+    // Create the datasets
+    let created_dataset = Dataset::default()
+        .name("Tasks Created")
+        .marker(app.theme.chart_markers[0])
+        .graph_type(GraphType::Line)
+        .style(app.theme.chart_created)
+        .data(data);
+
+    let overdue_dataset = Dataset::default()
+        .name("Overdue Tasks")
+        .marker(app.theme.chart_markers[1])
+        .graph_type(GraphType::Line)
+        .style(app.theme.chart_overdue)
+        .data(overdue_data);
+
+    let completed_dataset = Dataset::default()
+        .name("Tasks Completed")
+        .marker(app.theme.chart_markers[2])
+        .graph_type(GraphType::Line)
+        .style(app.theme.chart_completed)
+        .data(completed_data);
 
     // Calculate max y value across all datasets
     let max_y = data.iter()
@@ -370,40 +1789,112 @@ fn render_stats_tab(frame: &mut Frame, app: &App, area: Rect) {
         .x_axis(
             Axis::default()
                 .title("Days ago")
-                .style(Style::default().fg(Color::Gray))
-                .bounds([0.0, 90.0])
+                .style(app.theme.muted)
+                .bounds([0.0, HISTORY_DAYS as f64])
         )
         .y_axis(
             Axis::default()
                 .title("Count")
-                .style(Style::default().fg(Color::Gray))
+                .style(app.theme.muted)
                 .bounds([0.0, max_y + 1.0])
         );
 
-    frame.render_widget(chart, middle_inner);
+    frame.render_widget(chart, area);
+}
 
-    // Render bottom row
-    let bottom_block = Block::default()
-        .title("Mean time to Done")
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+/// One line per data folder of the tasks it completed each day, in its configured color or else
+/// one of the chart colors
+fn render_sources_chart(frame: &mut Frame, app: &App, area: Rect, sources: &[SourceStats]) {
+    let palette = [app.theme.chart_completed, app.theme.chart_created, app.theme.chart_overdue, app.theme.accent];
+    let style_of = |index: usize, source: &SourceStats| match source.color {
+        Some(_) => app.theme.label(source.color.as_deref()),
+        None => palette[index % palette.len()],
+    };
+    let names: Vec<String> = sources.iter()
+        .map(|source| format!("{} ({} open, {} overdue)", source.name, source.open, source.overdue))
+        .collect();
+
+    if app.config.screen_reader {
+        let lines: Vec<Line> = sources.iter().zip(&names)
+            .map(|(source, name)| {
+                let completed: usize = source.completed.iter().map(|(_, y)| *y as usize).sum();
+                Line::from(format!("{}: {} completed in the last 90 days.", name, completed))
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines).wrap(ratatui::widgets::Wrap { trim: true }), area);
+        return;
+    }
 
-    let bottom_inner = bottom_block.inner(rows[2]);
-    frame.render_widget(bottom_block, rows[2]);
+    let datasets: Vec<Dataset> = sources.iter().zip(&names).enumerate()
+        .map(|(index, (source, name))| Dataset::default()
+            .name(name.as_str())
+            .marker(app.theme.chart_markers[index % app.theme.chart_markers.len()])
+            .graph_type(GraphType::Line)
+            .style(style_of(index, source))
+            .data(&source.completed))
+        .collect();
+    let max_y = sources.iter()
+        .flat_map(|source| &source.completed)
+        .map(|(_, y)| *y)
+        .fold(0.0, f64::max);
 
-    let bottom_text = Paragraph::new("Bottom content")
-        .style(Style::default().fg(Color::Gray))
-        .alignment(Alignment::Center);
-    frame.render_widget(bottom_text, bottom_inner);
+    let chart = Chart::new(datasets)
+        .x_axis(
+            Axis::default()
+                .title("Days ago")
+                .style(app.theme.muted)
+                .bounds([0.0, HISTORY_DAYS as f64])
+        )
+        .y_axis(
+            Axis::default()
+                .title("Completed")
+                .style(app.theme.muted)
+                .bounds([0.0, max_y + 1.0])
+        )
+        // The legend is the only place the sources are named, so keep it even when it's tall
+        .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 1)));
+    frame.render_widget(chart, area);
+}
+
+/// Describe the 90-day chart series in sentences for screen readers
+fn chart_summary(start: NaiveDate, data: &[(f64, f64)], overdue_data: &[(f64, f64)], completed_data: &[(f64, f64)]) -> Vec<Line<'static>> {
+    let total = |series: &[(f64, f64)]| series.iter().map(|(_, y)| *y as usize).sum::<usize>();
+    let last_week = |series: &[(f64, f64)]| series.iter().rev().take(7).map(|(_, y)| *y as usize).sum::<usize>();
+
+    let current_overdue = overdue_data.last().map(|(_, y)| *y as usize).unwrap_or(0);
+    let peak_overdue = overdue_data.iter()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .filter(|(_, y)| *y > 0.0)
+        .map(|(x, y)| (start + Duration::days(*x as i64), *y as usize));
+
+    let mut lines = vec![
+        Line::from(format!(
+            "Last 90 days: {} tasks created, {} tasks completed.",
+            total(data), total(completed_data)
+        )),
+        Line::from(format!(
+            "Last 7 days: {} tasks created, {} tasks completed.",
+            last_week(data), last_week(completed_data)
+        )),
+    ];
+    lines.push(Line::from(match peak_overdue {
+        Some((date, count)) => format!(
+            "Overdue today: {}. Peak overdue: {} on {}.",
+            current_overdue, count, date.format("%Y-%m-%d")
+        ),
+        None => "No overdue tasks in the last 90 days.".to_string(),
+    }));
+    lines
 }
 
 fn render_calendar(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     // Create the outer block for the calendar panel
-    let calendar_border_style = get_border_style(app.focused_panel == Panel::Calendar);
-    let block = Block::default()
-        .title("Calendar")
+    let calendar_border_style = get_border_style(&app.theme, app.focused_panel == Panel::Calendar);
+    let block = themed_block(&app.theme)
+        .title(calendar_title(app))
         .borders(Borders::ALL)
-        .border_style(calendar_border_style);
+        .border_style(calendar_border_style)
+        .border_set(get_border_set(&app.theme, app.focused_panel == Panel::Calendar));
 
     let inner_area = block.inner(area);
     frame.render_widget(block, area);
@@ -426,37 +1917,73 @@ fn render_calendar(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     // Create event store and add all due dates with muted highlight
     let mut events = CalendarEventStore::default();
 
-    let today_naive = Local::now().date_naive();
+    let today_naive = app.clock.today();
+
+    // Shade weekends, holidays and days away across the three visible months
+    let mut date = prev_month_date;
+    while date < get_next_month(next_month_date) {
+        if app.blackout_on(date).is_some() {
+            events.add(chrono_to_time_date(date), app.theme.calendar_blackout);
+        } else if !app.is_working_day(date) {
+            events.add(chrono_to_time_date(date), app.theme.calendar_non_working);
+        }
+        date += chrono::Duration::days(1);
+    }
+
+    // Then days with subscribed calendar events (holiday calendars are already shaded),
+    // so task due dates draw over them
+    let holiday_calendars = &app.config.workdays.holiday_calendars;
+    for (_, feed_events) in app.calendar_feeds.feeds().filter(|(name, _)| !holiday_calendars.iter().any(|holiday| holiday == name)) {
+        for event in feed_events {
+            let mut date = event.start;
+            while date <= event.end {
+                events.add(chrono_to_time_date(date), app.theme.calendar_event);
+                date += chrono::Duration::days(1);
+            }
+        }
+    }
 
     // Add all due dates from todos
     for todo in &app.todos {
         if let Some(due_date) = todo.due_date {
             let due_date_time = chrono_to_time_date(due_date);
 
-            // Check if task is overdue (due date is before today and not completed)
-            let is_overdue = due_date < today_naive && !todo.completed;
+            // Check if task is overdue (due date is before today and not completed or parked for someday)
+            let is_overdue = todo.is_overdue(today_naive);
 
-            // Style overdue tasks in red, normal due dates in dark gray
+            // Style overdue tasks in red, normal due dates in dark gray (in their project's color if it has one)
             let style = if is_overdue {
-                Style::default().bg(Color::Red).fg(Color::White).add_modifier(Modifier::BOLD)
+                app.theme.calendar_overdue
             } else {
-                Style::default().bg(Color::DarkGray).fg(Color::White)
+                app.theme.calendar_due.patch(app.theme.label(app.project_color(todo.project.as_deref())))
             };
 
             events.add(due_date_time, style);
         }
     }
 
+    // Days with more due than they hold are flagged like overdue ones
+    for date in app.over_capacity_days() {
+        events.add(chrono_to_time_date(date), app.theme.calendar_overdue);
+    }
+
     // Add today's date to highlight it (this will override due dates if today has a task)
     let today = chrono_to_time_date(today_naive);
-    events.add(today, Style::default().bg(Color::Cyan).fg(Color::Black).add_modifier(Modifier::BOLD));
+    events.add(today, app.theme.calendar_today);
 
     // Add selected calendar date (this will override today and due dates when calendar is focused)
-    if app.focused_panel == Panel::Calendar {
-        if let Some(selected_date) = app.selected_calendar_date {
-            let selected_date_time = chrono_to_time_date(selected_date);
-            events.add(selected_date_time, Style::default().bg(Color::Yellow).fg(Color::Black).add_modifier(Modifier::BOLD));
-        }
+    if app.focused_panel == Panel::Calendar
+        && let Some(selected_date) = app.selected_calendar_date
+    {
+        let selected_date_time = chrono_to_time_date(selected_date);
+        events.add(selected_date_time, app.theme.calendar_selected);
+    }
+
+    // Underline days with a note, on top of whatever else marks them
+    for date in app.note_dates() {
+        let date = chrono_to_time_date(date);
+        let style = events.0.get(&date).copied().unwrap_or_default();
+        events.add(date, style.patch(app.theme.calendar_note));
     }
 
     // Create monthly calendar widgets
@@ -467,7 +1994,7 @@ fn render_calendar(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let current_calendar = Monthly::new(current_date, events.clone())
         .show_month_header(Style::default().add_modifier(Modifier::BOLD))
         .show_weekdays_header(Style::default())
-        .show_surrounding(Style::default().fg(Color::DarkGray));
+        .show_surrounding(app.theme.dim);
 
     let next_calendar = Monthly::new(chrono_to_time_date(next_month_date), events)
         .show_month_header(Style::default())
@@ -477,19 +2004,61 @@ fn render_calendar(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     frame.render_widget(prev_calendar, calendar_columns[0]);
     frame.render_widget(current_calendar, calendar_columns[1]);
     frame.render_widget(next_calendar, calendar_columns[2]);
-}
 
-fn chrono_to_time_date(date: NaiveDate) -> Date {
-    let year = date.year();
-    let month = Month::try_from(date.month() as u8).unwrap();
-    let day = date.day() as u8;
-    Date::from_calendar_date(year, month, day).unwrap()
+    if app.input_mode == InputMode::JumpHints {
+        render_calendar_hints(frame, app, &calendar_columns, prev_month_date);
+    }
 }
 
-fn get_previous_month(date: NaiveDate) -> NaiveDate {
-    let year = date.year();
-    let month = date.month();
-
+/// Jump hint labels over the day numbers of the three months, laid out as the Monthly widget
+/// draws them: month and weekday header rows, then one row per week, three columns per day from Sunday
+fn render_calendar_hints(frame: &mut Frame, app: &App, columns: &[Rect], first_month: NaiveDate) {
+    let mut month = first_month;
+    for column in columns {
+        let offset = month.weekday().num_days_from_sunday();
+        for (label, target) in app.jump_hints() {
+            let JumpTarget::Day(date) = target else { continue };
+            if date.year() != month.year() || date.month() != month.month() {
+                continue;
+            }
+            let cell = offset + date.day0();
+            let (x, y) = (column.x + (cell % 7) as u16 * 3 + 1, column.y + 2 + (cell / 7) as u16);
+            if x + 2 <= column.x + column.width && y < column.y + column.height {
+                render_hint(frame, app, &label, x, y);
+            }
+        }
+        month = get_next_month(month);
+    }
+}
+
+/// "Calendar", plus the subscribed events on the selected day while the panel is focused
+fn calendar_title(app: &App) -> String {
+    let selected = app.selected_calendar_date.filter(|_| app.focused_panel == Panel::Calendar);
+    let events = selected.map(|date| app.calendar_feeds.events_on(date)).unwrap_or_default();
+    let blackout = selected.and_then(|date| app.blackout_on(date));
+    if events.is_empty() && blackout.is_none() {
+        return "Calendar".to_string();
+    }
+    let mut summaries: Vec<String> = blackout.map(|blackout| format!("Away: {}", blackout.describe())).into_iter().collect();
+    summaries.extend(events.iter()
+        .map(|event| match event.time {
+            Some(time) => format!("{} {}", time.format("%H:%M"), event.summary),
+            None => event.summary.clone(),
+        }));
+    format!("Calendar - {}", summaries.join(", "))
+}
+
+fn chrono_to_time_date(date: NaiveDate) -> Date {
+    let year = date.year();
+    let month = Month::try_from(date.month() as u8).unwrap();
+    let day = date.day() as u8;
+    Date::from_calendar_date(year, month, day).unwrap()
+}
+
+pub fn get_previous_month(date: NaiveDate) -> NaiveDate {
+    let year = date.year();
+    let month = date.month();
+
     if month == 1 {
         NaiveDate::from_ymd_opt(year - 1, 12, 1).unwrap()
     } else {
@@ -497,7 +2066,7 @@ fn get_previous_month(date: NaiveDate) -> NaiveDate {
     }
 }
 
-fn get_next_month(date: NaiveDate) -> NaiveDate {
+pub fn get_next_month(date: NaiveDate) -> NaiveDate {
     let year = date.year();
     let month = date.month();
 
@@ -508,19 +2077,58 @@ fn get_next_month(date: NaiveDate) -> NaiveDate {
     }
 }
 
+/// The note for the day selected in the calendar, shown in place of the task details
+fn render_day_note(frame: &mut Frame, app: &App, area: Rect) {
+    let date = app.selected_calendar_date.unwrap_or_else(|| app.clock.today());
+    let block = themed_block(&app.theme)
+        .title(format!("Note - {}", date.format("%a %Y-%m-%d")))
+        .borders(Borders::ALL)
+        .border_style(get_border_style(&app.theme, false))
+        .border_set(get_border_set(&app.theme, false));
+
+    let inner_area = block.inner(area);
+    frame.render_widget(block, area);
+
+    let content = match app.note_on(date) {
+        Some(text) => Paragraph::new(text.lines().map(Line::from).collect::<Vec<_>>())
+            .wrap(ratatui::widgets::Wrap { trim: false }),
+        None => Paragraph::new("No note for this day - press n to write one")
+            .style(app.theme.dim)
+            .alignment(Alignment::Center),
+    };
+    frame.render_widget(content, inner_area.inner(ratatui::layout::Margin::new(1, 1)));
+}
+
 fn render_task_details(frame: &mut Frame, app: &App, area: Rect) {
-    let task_border_style = get_border_style(app.focused_panel == Panel::Task);
+    let task_border_style = get_border_style(&app.theme, app.focused_panel == Panel::Task);
 
     // Get the selected task
     let selected_task = app.selected_todo_index
         .and_then(|index| app.todos.get(index));
 
     if let Some(task) = selected_task {
-        // Create the block
-        let block = Block::default()
-            .title("Task")
+        // The description's layout only changes with the tasks or the panel's width; the panel's
+        // border and the layout's margin take 4 columns
+        let key = (task.id, app.tasks_revision(), area.width.saturating_sub(4));
+        let layout = app.description_layout.borrow_mut().take()
+            .filter(|layout| layout.key == key)
+            .unwrap_or_else(|| DescriptionLayout::new(app, task, key));
+
+        // Create the block, saying where the selected link goes while the panel is focused
+        let editing = app.editing_field.filter(|_| app.input_mode == InputMode::EditingField);
+        let title = match layout.link(&task.description, app.selected_link) {
+            _ if editing.is_some() => "Task - Enter: save  Tab: save, next field  Esc: cancel".to_string(),
+            Some(id) if app.focused_panel == Panel::Task => {
+                let arrows = if app.theme.ascii { "left/right" } else { "←/→" };
+                format!("Task - Enter: open #{}  {}: other links", id, arrows)
+            }
+            _ => "Task".to_string(),
+        };
+        let block = themed_block(&app.theme)
+            .title(title)
             .borders(Borders::ALL)
-            .border_style(task_border_style);
+            .border_style(task_border_style)
+            .border_set(get_border_set(&app.theme, app.focused_panel == Panel::Task));
 
         let inner_area = block.inner(area);
         frame.render_widget(block, area);
@@ -532,39 +2140,112 @@ fn render_task_details(frame: &mut Frame, app: &App, area: Rect) {
             .constraints([
                 Constraint::Length(3),  // Title
                 Constraint::Min(5),     // Description
-                Constraint::Length(3),  // Due date
+                Constraint::Length(2),  // Due date
+                Constraint::Length(1),  // Context
+                Constraint::Length(1),  // Project, priority and tags
                 Constraint::Length(2),  // Created
+                Constraint::Length(if task.estimate_minutes.is_some() { 2 } else { 0 }),  // Effort
                 Constraint::Length(2),  // Status
             ])
             .split(inner_area);
 
-        // Title
+        // The field being edited in place shows what's typed, with the cursor after it
+        let field_input = Span::styled(app.field_input.as_str(), app.theme.input_active);
+        let mut cursor = None;
+
+        // Title, and the web page it's about
         let title_line = Line::from(vec![
             Span::styled("Title: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(&task.title),
+            if editing == Some(DetailField::Title) { field_input.clone() } else { Span::raw(&task.title) },
         ]);
-        let title_widget = Paragraph::new(title_line);
+        if editing == Some(DetailField::Title) {
+            cursor = Some((chunks[0], title_line.width()));
+        }
+        let mut title_lines = vec![title_line];
+        if let Some(url) = &task.url {
+            title_lines.push(Line::from(vec![
+                Span::styled("Link: ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::styled(url.as_str(), app.theme.accent.add_modifier(Modifier::UNDERLINED)),
+            ]));
+        }
+        let title_widget = Paragraph::new(title_lines);
         frame.render_widget(title_widget, chunks[0]);
 
-        // Description
-        let mut description_lines = vec![
-            Line::from(Span::styled("Description:", Style::default().add_modifier(Modifier::BOLD))),
-        ];
-        // Split description by newlines and create a Line for each
-        for line in task.description.split('\n') {
-            description_lines.push(Line::from(Span::raw(line)));
+        // Description: only the lines in view are built, starting from the one holding the first
+        // row shown; scrolling counts rows as wrapped, and stops with the last one in view
+        let height = chunks[1].height as usize;
+        let rows = 1 + layout.rows();
+        app.description_rows.set((rows as u16, chunks[1].height));
+        let scroll = (app.task_description_scroll as usize).min(rows.saturating_sub(height));
+        let mut description_lines = Vec::new();
+        let mut skip = 0;
+        if scroll == 0 {
+            description_lines.push(Line::from(Span::styled("Description:", Style::default().add_modifier(Modifier::BOLD))));
+        }
+        let first_row = scroll.saturating_sub(1);
+        let first = layout.lines.partition_point(|line| line.row <= first_row).saturating_sub(1);
+        let mut shown_rows = 0;
+        for (index, start) in layout.lines.iter().enumerate().skip(first) {
+            if shown_rows >= height + skip {
+                break;
+            }
+            if index == first {
+                skip = first_row.saturating_sub(start.row);
+            }
+            let end = layout.lines.get(index + 1).map_or(task.description.len(), |next| next.byte - 1);
+            let line = &task.description[start.byte..end];
+            let mut spans = Vec::new();
+            let mut rest = 0;
+            for (link_index, (range, id)) in (start.links..).zip(Todo::task_links(line)) {
+                spans.push(Span::raw(&line[rest..range.start]));
+                let mut style = if app.all_todos().iter().any(|t| t.id == id) { app.theme.accent.add_modifier(Modifier::UNDERLINED) } else { app.theme.muted };
+                if app.focused_panel == Panel::Task && link_index == app.selected_link {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                spans.push(Span::styled(&line[range.clone()], style));
+                rest = range.end;
+            }
+            spans.push(Span::raw(&line[rest..]));
+            description_lines.push(Line::from(spans));
+            shown_rows += layout.lines.get(index + 1).map_or(layout.description_rows, |next| next.row) - start.row;
+        }
+        // Tasks whose descriptions link here, once the description's end is in view
+        if shown_rows < height + skip && !layout.back_references.is_empty() {
+            description_lines.push(Line::from(""));
+            description_lines.push(Line::from(Span::styled("Linked from:", Style::default().add_modifier(Modifier::BOLD))));
+            for (id, title) in &layout.back_references {
+                description_lines.push(Line::from(vec![
+                    Span::styled(format!("#{}", id), app.theme.accent),
+                    Span::raw(format!(" {}", title)),
+                ]));
+            }
         }
         let description_widget = Paragraph::new(description_lines)
             .wrap(ratatui::widgets::Wrap { trim: false })
-            .scroll((app.task_description_scroll, 0));
+            .scroll((skip as u16, 0));
         frame.render_widget(description_widget, chunks[1]);
+        *app.description_layout.borrow_mut() = Some(layout);
 
-        // Due date
-        let due_date_line = if let Some(due_date) = task.due_date {
-            Line::from(vec![
+        // Due date, flagged when overdue or due today
+        let today = app.clock.today();
+        let due_date_line = if editing == Some(DetailField::DueDate) {
+            let line = Line::from(vec![
                 Span::styled("Due Date: ", Style::default().add_modifier(Modifier::BOLD)),
-                Span::raw(due_date.format("%Y-%m-%d").to_string()),
-            ])
+                field_input.clone(),
+            ]);
+            cursor = Some((chunks[2], line.width()));
+            line
+        } else if let Some(due_date) = task.due_date {
+            let mut spans = vec![
+                Span::styled("Due Date: ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(task.due_label(due_date)),
+            ];
+            if task.is_overdue(today) {
+                spans.push(Span::styled(format!("  {} Overdue", app.theme.icons.overdue), app.theme.overdue));
+            } else if !task.completed && due_date == today {
+                spans.push(Span::styled(format!("  {} Due today", app.theme.icons.due_today), app.theme.due_today));
+            }
+            Line::from(spans)
         } else {
             Line::from(vec![
                 Span::styled("Due Date: ", Style::default().add_modifier(Modifier::BOLD)),
@@ -574,24 +2255,90 @@ fn render_task_details(frame: &mut Frame, app: &App, area: Rect) {
         let due_date_widget = Paragraph::new(due_date_line);
         frame.render_widget(due_date_widget, chunks[2]);
 
-        // Created date
-        let created_line = Line::from(vec![
-            Span::styled("Created: ", Style::default().fg(Color::Gray).add_modifier(Modifier::BOLD)),
-            Span::styled(task.created_at.format("%Y-%m-%d %H:%M").to_string(), Style::default().fg(Color::Gray)),
-        ]);
+        // Context and reminders
+        let reminders = match &task.reminders {
+            Some(offsets) if offsets.is_empty() => "None".to_string(),
+            Some(offsets) => ReminderOffset::format_list(offsets),
+            None => format!("{} (default)", ReminderOffset::format_list(&app.config.reminders.default_offsets)),
+        };
+        let mut context_spans = vec![
+            Span::styled("Context: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(task.context.as_deref().unwrap_or("None")),
+            Span::styled("  Reminders: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(reminders),
+        ];
+        if let Some(rule) = &task.recurrence {
+            context_spans.push(Span::styled("  Repeats: ", Style::default().add_modifier(Modifier::BOLD)));
+            context_spans.push(Span::raw(rule.describe()));
+        }
+        let context_line = Line::from(context_spans);
+        frame.render_widget(Paragraph::new(context_line), chunks[3]);
+
+        // Project, priority and tags
+        let tags = task.tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" ");
+        let mut project_spans = vec![
+            Span::styled("Project: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(
+                task.project.clone().unwrap_or_else(|| "None".to_string()),
+                app.theme.label(app.project_color(task.project.as_deref())),
+            ),
+            Span::styled("  Priority: ", Style::default().add_modifier(Modifier::BOLD)),
+        ];
+        if editing == Some(DetailField::Priority) {
+            project_spans.push(field_input.clone());
+            cursor = Some((chunks[4], Line::from(project_spans.clone()).width()));
+        } else {
+            project_spans.push(Span::raw(task.priority.map_or("None", |p| p.label())));
+        }
+        project_spans.push(Span::styled("  Tags: ", Style::default().add_modifier(Modifier::BOLD)));
+        if editing == Some(DetailField::Tags) {
+            project_spans.push(field_input.clone());
+            cursor = Some((chunks[4], Line::from(project_spans.clone()).width()));
+        } else {
+            project_spans.push(Span::raw(if tags.is_empty() { "None".to_string() } else { tags }));
+        }
+        frame.render_widget(Paragraph::new(Line::from(project_spans)), chunks[4]);
+        if let Some((chunk, offset)) = cursor
+            && (offset as u16) < chunk.width
+        {
+            frame.set_cursor_position((chunk.x + offset as u16, chunk.y));
+        }
+
+        // Created date, and time logged by focus sessions
+        let mut created_spans = vec![
+            Span::styled("Created: ", app.theme.muted.add_modifier(Modifier::BOLD)),
+            Span::styled(app.clock.local(task.created_at).format("%Y-%m-%d %H:%M").to_string(), app.theme.muted),
+        ];
+        if !task.time_entries.is_empty() {
+            created_spans.push(Span::styled("  Time logged: ", app.theme.muted.add_modifier(Modifier::BOLD)));
+            created_spans.push(Span::styled(TimeEntry::format_duration(task.time_spent()), app.theme.muted));
+        }
+        // What the urgency order goes by, part by part
+        if !task.completed && !task.deleted {
+            let urgency = app.urgency_of(task);
+            created_spans.push(Span::styled("  Urgency: ", app.theme.muted.add_modifier(Modifier::BOLD)));
+            let parts = if urgency.parts.is_empty() { String::new() } else { format!(" ({})", urgency.describe()) };
+            created_spans.push(Span::styled(format!("{:.1}{}", urgency.total, parts), app.theme.muted));
+        }
+        let created_line = Line::from(created_spans);
         let created_widget = Paragraph::new(created_line);
-        frame.render_widget(created_widget, chunks[3]);
+        frame.render_widget(created_widget, chunks[5]);
+
+        // Time logged against the estimate, as a bar that turns red once over budget
+        if let Some(estimate) = task.estimate() {
+            frame.render_widget(Paragraph::new(effort_gauge(app, task.time_spent(), estimate)), chunks[6]);
+        }
 
         // Status
         let (status_label_style, status_value_style) = if task.completed {
             (
-                Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
-                Style::default().fg(Color::Green)
+                app.theme.success.add_modifier(Modifier::BOLD),
+                app.theme.success
             )
         } else {
             (
-                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
-                Style::default().fg(Color::Yellow)
+                app.theme.pending.add_modifier(Modifier::BOLD),
+                app.theme.pending
             )
         };
 
@@ -600,58 +2347,148 @@ fn render_task_details(frame: &mut Frame, app: &App, area: Rect) {
                 Line::from(vec![
                     Span::styled("Status: ", status_label_style),
                     Span::styled(
-                        format!("✓ Completed on {}", completed_at.format("%Y-%m-%d %H:%M")),
+                        format!("{} Completed on {}", app.theme.icons.done, app.clock.local(completed_at).format("%Y-%m-%d %H:%M")),
                         status_value_style
                     ),
                 ])
             } else {
                 Line::from(vec![
                     Span::styled("Status: ", status_label_style),
-                    Span::styled("✓ Completed", status_value_style),
+                    Span::styled(format!("{} Completed", app.theme.icons.done), status_value_style),
                 ])
             }
         } else {
-            Line::from(vec![
+            // Open tasks show their workflow status, in its configured color
+            let status = app.config.workflow.status_of(task);
+            let value_style = match &status.color {
+                Some(color) => app.theme.label(Some(color)),
+                None => status_value_style,
+            };
+            let mut spans = vec![
                 Span::styled("Status: ", status_label_style),
-                Span::styled("○ Pending", status_value_style),
-            ])
+                Span::styled(format!("{} {}", app.theme.icons.pending, status.name), value_style),
+            ];
+            if let Some((done, total)) = app.subtask_progress(task.id) {
+                spans.push(Span::styled("  Subtasks: ", Style::default().add_modifier(Modifier::BOLD)));
+                spans.push(Span::raw(format!("{}/{} done", done, total)));
+            }
+            if let Some(parent) = task.parent.and_then(|id| app.all_todos().iter().find(|t| t.id == id)) {
+                spans.push(Span::styled("  Subtask of: ", Style::default().add_modifier(Modifier::BOLD)));
+                spans.push(Span::raw(format!("#{} {}", parent.id, parent.title)));
+            }
+            Line::from(spans)
         };
         let status_widget = Paragraph::new(status_line);
-        frame.render_widget(status_widget, chunks[4]);
+        frame.render_widget(status_widget, chunks[7]);
     } else {
         // No task selected - show empty panel
-        let block = Block::default()
+        let block = themed_block(&app.theme)
             .title("Task")
             .borders(Borders::ALL)
-            .border_style(task_border_style);
+            .border_style(task_border_style)
+            .border_set(get_border_set(&app.theme, app.focused_panel == Panel::Task));
 
         let inner_area = block.inner(area);
         frame.render_widget(block, area);
 
         let empty_text = Paragraph::new("No task selected")
-            .style(Style::default().fg(Color::DarkGray))
+            .style(app.theme.dim)
             .alignment(Alignment::Center);
         frame.render_widget(empty_text, inner_area);
     }
 }
 
+/// Where each line of the selected task's description goes in the details once wrapped, and the
+/// tasks linking to it, so a long description (a pasted log, say) isn't wrapped and scanned whole
+/// every frame
+pub struct DescriptionLayout {
+    /// The task's ID, the tasks' revision and the width it was worked out for
+    key: (usize, u64, u16),
+    lines: Vec<DescriptionLine>,
+    /// Rows the description takes wrapped
+    description_rows: usize,
+    /// ID and title of each task whose description links here
+    back_references: Vec<(usize, String)>,
+    /// Rows the tasks linking here take, with the blank line and heading above them
+    back_reference_rows: usize,
+}
+
+struct DescriptionLine {
+    /// Where it starts in the description
+    byte: usize,
+    /// The row it starts on, counting from the description's first
+    row: usize,
+    /// How many `#<id>` links come before it
+    links: usize,
+}
+
+impl DescriptionLayout {
+    fn new(app: &App, task: &Todo, key: (usize, u64, u16)) -> Self {
+        let wrapped_rows = |line: Line| Paragraph::new(line).wrap(ratatui::widgets::Wrap { trim: false }).line_count(key.2).max(1);
+        let mut lines = Vec::new();
+        let (mut byte, mut row, mut links) = (0, 0, 0);
+        for line in task.description.split('\n') {
+            lines.push(DescriptionLine { byte, row, links });
+            byte += line.len() + 1;
+            // No character takes more columns than it has bytes, so a short line fits on one row
+            row += if line.len() <= key.2 as usize { 1 } else { wrapped_rows(Line::from(line)) };
+            links += Todo::task_links(line).len();
+        }
+        let back_references: Vec<(usize, String)> = app.back_references(task.id).iter()
+            .map(|todo| (todo.id, todo.title.clone()))
+            .collect();
+        let back_reference_rows = match back_references.len() {
+            0 => 0,
+            _ => 2 + back_references.iter().map(|(id, title)| wrapped_rows(Line::from(format!("#{} {}", id, title)))).sum::<usize>(),
+        };
+        Self { key, lines, description_rows: row, back_references, back_reference_rows }
+    }
+
+    /// Rows the description and the tasks linking here take, not counting the label
+    fn rows(&self) -> usize {
+        self.description_rows + self.back_reference_rows
+    }
+
+    /// The task the description's `index`th link goes to
+    fn link(&self, description: &str, index: usize) -> Option<usize> {
+        let line = self.lines.partition_point(|line| line.links <= index).checked_sub(1)?;
+        let end = self.lines.get(line + 1).map_or(description.len(), |next| next.byte - 1);
+        let text = &description[self.lines[line].byte..end];
+        Todo::task_links(text).get(index - self.lines[line].links).map(|(_, id)| *id)
+    }
+}
+
 fn render_new_task_panel(frame: &mut Frame, app: &App) {
-    // Create a centered rectangle for the popup
-    let popup_area = centered_rect(60, 70, frame.area());
+    // The date being typed counts as soon as it's a whole date
+    let due_date = if app.input_mode == InputMode::EditingDate {
+        NaiveDate::parse_from_str(&app.date_input_buffer, "%Y-%m-%d").ok()
+    } else {
+        app.new_task_due_date
+    };
+    let same_day = due_date.map(|date| app.tasks_due_on(date)).unwrap_or_default();
+    const SAME_DAY_SHOWN: usize = 3;
+    let same_day_height = match same_day.len() {
+        0 => 0,
+        count => 2 + count.min(SAME_DAY_SHOWN + 1) as u16,
+    };
+
+    let popup_area = task_editor_rect(app, frame.area(), same_day_height);
 
     // Clear the area behind the popup
     frame.render_widget(Clear, popup_area);
 
     // Create the main popup block
     let title = if app.editing_todo_id.is_some() {
-        "Edit Task"
+        "Edit Task".to_string()
+    } else if let Some(parent) = app.new_task_parent {
+        format!("New Subtask of #{}", parent)
     } else {
-        "New Task"
+        "New Task".to_string()
     };
-    let popup_block = Block::default()
+    let popup_block = themed_block(&app.theme)
         .title(title)
         .borders(Borders::ALL)
-        .style(Style::default().bg(Color::Black));
+        .style(app.theme.popup);
 
     // Get the inner area before rendering
     let inner_area = popup_block.inner(popup_area);
@@ -663,15 +2500,21 @@ fn render_new_task_panel(frame: &mut Frame, app: &App) {
         .margin(1)
         .constraints([
             Constraint::Length(3),  // Title field
-            Constraint::Min(10),    // Description field (flexible, at least 10 lines)
-            Constraint::Length(3),  // Date field
+            Constraint::Min(3),     // Description field (takes the rows the other fields leave)
+            Constraint::Length(2),  // Date field
+            Constraint::Length(2),  // Time field
+            Constraint::Length(same_day_height),  // Tasks already due that day
+            Constraint::Length(2),  // Context field
+            Constraint::Length(2),  // Reminders field
+            Constraint::Length(2),  // Repeat field
+            Constraint::Length(2),  // Estimate field
             Constraint::Length(2),  // Instructions
         ])
         .split(inner_area);
 
     // Title field
     let title_style = if app.input_mode == InputMode::EditingTitle {
-        Style::default().fg(Color::Yellow)
+        app.theme.input_active
     } else {
         Style::default()
     };
@@ -682,27 +2525,19 @@ fn render_new_task_panel(frame: &mut Frame, app: &App) {
     frame.render_widget(title_para, chunks[0]);
 
     // Description field
-    let description_style = if app.input_mode == InputMode::EditingDescription {
-        Style::default().fg(Color::Yellow)
-    } else {
-        Style::default()
-    };
-
-    let mut description_lines = vec![
-        Line::from(Span::styled("Description:", description_style.add_modifier(Modifier::BOLD))),
-    ];
-    // Split description by newlines and create a Line for each
-    for line in app.new_task_description.split('\n') {
-        description_lines.push(Line::from(Span::styled(line, description_style)));
-    }
-    let description_para = Paragraph::new(description_lines)
-        .wrap(ratatui::widgets::Wrap { trim: false })
-        .scroll((app.edit_description_scroll, 0));
-    frame.render_widget(description_para, chunks[1]);
+    render_multiline_field(
+        frame,
+        app,
+        chunks[1],
+        "Description:",
+        &app.new_task_description,
+        app.edit_description_scroll,
+        app.input_mode == InputMode::EditingDescription,
+    );
 
     // Date field
     let date_style = if app.input_mode == InputMode::EditingDate {
-        Style::default().fg(Color::Yellow)
+        app.theme.input_active
     } else {
         Style::default()
     };
@@ -713,19 +2548,109 @@ fn render_new_task_panel(frame: &mut Frame, app: &App) {
         format!("Due Date (YYYY-MM-DD): {}",
             app.new_task_due_date
                 .map(|d| d.format("%Y-%m-%d").to_string())
-                .unwrap_or_else(|| "".to_string()))
+                .unwrap_or_default())
     };
     let date_para = Paragraph::new(date_text)
         .style(date_style);
     frame.render_widget(date_para, chunks[2]);
 
+    // Time field
+    let time_style = if app.input_mode == InputMode::EditingTime {
+        app.theme.input_active
+    } else {
+        Style::default()
+    };
+    let mut time_spans = vec![Span::raw(format!("Due Time (HH:MM): {}", app.new_task_time))];
+    if app.new_task_time.is_empty() && app.input_mode != InputMode::EditingTime {
+        let fallback = format!("optional, reminders use {}", app.config.reminders.due_time.format("%H:%M"));
+        time_spans.push(Span::styled(fallback, app.theme.muted));
+    }
+    let time_para = Paragraph::new(Line::from(time_spans))
+        .style(time_style);
+    frame.render_widget(time_para, chunks[3]);
+
+    // Tasks already due that day, so deadlines don't pile up unnoticed
+    if !same_day.is_empty() {
+        let mut lines = vec![Line::from(Span::styled(
+            format!("Also due that day ({}):", same_day.len()),
+            app.theme.due_today,
+        ))];
+        for todo in same_day.iter().take(SAME_DAY_SHOWN) {
+            let time = todo.due_time.map(|time| time.format("%H:%M").to_string()).unwrap_or_else(|| "--:--".to_string());
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {} ", time), app.theme.muted),
+                Span::raw(todo.title.clone()),
+            ]));
+        }
+        if same_day.len() > SAME_DAY_SHOWN {
+            lines.push(Line::from(Span::styled(
+                format!("  and {} more", same_day.len() - SAME_DAY_SHOWN),
+                app.theme.muted,
+            )));
+        }
+        frame.render_widget(Paragraph::new(lines), chunks[4]);
+    }
+
+    // Context field
+    let context_style = if app.input_mode == InputMode::EditingContext {
+        app.theme.input_active
+    } else {
+        Style::default()
+    };
+    let context_para = Paragraph::new(format!("Context (@name): {}", app.new_task_context))
+        .style(context_style);
+    frame.render_widget(context_para, chunks[5]);
+
+    // Reminders field
+    let reminders_style = if app.input_mode == InputMode::EditingReminders {
+        app.theme.input_active
+    } else {
+        Style::default()
+    };
+    let default_offsets = ReminderOffset::format_list(&app.config.reminders.default_offsets);
+    let reminders_text = if app.new_task_reminders.is_empty() && app.input_mode != InputMode::EditingReminders {
+        format!("Reminders (1d, 2h, none): default ({})", default_offsets)
+    } else {
+        format!("Reminders (1d, 2h, none): {}", app.new_task_reminders)
+    };
+    let reminders_para = Paragraph::new(reminders_text)
+        .style(reminders_style);
+    frame.render_widget(reminders_para, chunks[6]);
+
+    // Repeat field, as an RRULE; once valid, shown in words beside it
+    let recurrence_style = if app.input_mode == InputMode::EditingRecurrence {
+        app.theme.input_active
+    } else {
+        Style::default()
+    };
+    let mut recurrence_spans = vec![Span::raw(format!("Repeat (RRULE): {}", app.new_task_recurrence))];
+    if let Ok(rule) = Recurrence::parse(&app.new_task_recurrence) {
+        recurrence_spans.push(Span::styled(format!("  ({})", rule.describe()), app.theme.muted));
+    } else if app.new_task_recurrence.is_empty() && app.input_mode != InputMode::EditingRecurrence {
+        recurrence_spans.push(Span::styled("none, e.g. FREQ=WEEKLY;BYDAY=MO,TH", app.theme.muted));
+    }
+    let recurrence_para = Paragraph::new(Line::from(recurrence_spans))
+        .style(recurrence_style);
+    frame.render_widget(recurrence_para, chunks[7]);
+
+    // Estimate field
+    let estimate_style = if app.input_mode == InputMode::EditingEstimate {
+        app.theme.input_active
+    } else {
+        Style::default()
+    };
+    let estimate_para = Paragraph::new(format!("Estimate (45m, 1h30m): {}", app.new_task_estimate))
+        .style(estimate_style);
+    frame.render_widget(estimate_para, chunks[8]);
+
     // Instructions
     let instructions = Paragraph::new(
-        "Tab: Switch | Enter: Save | Alt+Enter: New line | Ctrl+U/D or PgUp/Dn: Scroll desc | Esc: Cancel"
+        "Tab: Switch | Enter: Save | Alt+Enter: New line | Ctrl+U/D or PgUp/Dn: Scroll desc | Ctrl+±: Resize | Esc: Cancel"
     )
-    .style(Style::default().fg(Color::Gray))
-    .alignment(Alignment::Center);
-    frame.render_widget(instructions, chunks[3]);
+    .style(app.theme.muted)
+    .alignment(Alignment::Center)
+    .wrap(ratatui::widgets::Wrap { trim: true });
+    frame.render_widget(instructions, chunks[9]);
 
     // Set cursor position based on which field is being edited
     match app.input_mode {
@@ -736,22 +2661,6 @@ fn render_new_task_panel(frame: &mut Frame, app: &App) {
                 frame.set_cursor_position((cursor_x, cursor_y));
             }
         }
-        InputMode::EditingDescription => {
-            // Calculate cursor position for description (accounting for newlines and scroll)
-            // Use split('\n') instead of lines() to properly handle trailing newlines
-            let lines: Vec<&str> = app.new_task_description.split('\n').collect();
-            let line_count = lines.len();
-            let last_line = lines.last().map(|s| s.len()).unwrap_or(0);
-
-            let cursor_x = chunks[1].x + last_line as u16;
-            // Adjust cursor Y position for scroll offset
-            let cursor_y_absolute = chunks[1].y + 1 + (line_count - 1) as u16; // +1 for "Description:" line
-            let cursor_y = cursor_y_absolute.saturating_sub(app.edit_description_scroll);
-
-            if cursor_x < chunks[1].x + chunks[1].width && cursor_y >= chunks[1].y && cursor_y < chunks[1].y + chunks[1].height {
-                frame.set_cursor_position((cursor_x, cursor_y));
-            }
-        }
         InputMode::EditingDate => {
             let cursor_x = chunks[2].x + 22 + app.date_input_buffer.len() as u16; // "Due Date (YYYY-MM-DD): " is 22 chars
             let cursor_y = chunks[2].y;
@@ -759,10 +2668,179 @@ fn render_new_task_panel(frame: &mut Frame, app: &App) {
                 frame.set_cursor_position((cursor_x, cursor_y));
             }
         }
+        InputMode::EditingTime => {
+            let cursor_x = chunks[3].x + 18 + app.new_task_time.len() as u16; // "Due Time (HH:MM): " is 18 chars
+            let cursor_y = chunks[3].y;
+            if cursor_x < chunks[3].x + chunks[3].width {
+                frame.set_cursor_position((cursor_x, cursor_y));
+            }
+        }
+        InputMode::EditingContext => {
+            let cursor_x = chunks[5].x + 17 + app.new_task_context.len() as u16; // "Context (@name): " is 17 chars
+            let cursor_y = chunks[5].y;
+            if cursor_x < chunks[5].x + chunks[5].width {
+                frame.set_cursor_position((cursor_x, cursor_y));
+            }
+        }
+        InputMode::EditingReminders => {
+            let cursor_x = chunks[6].x + 26 + app.new_task_reminders.len() as u16; // "Reminders (1d, 2h, none): " is 26 chars
+            let cursor_y = chunks[6].y;
+            if cursor_x < chunks[6].x + chunks[6].width {
+                frame.set_cursor_position((cursor_x, cursor_y));
+            }
+        }
+        InputMode::EditingRecurrence => {
+            let cursor_x = chunks[7].x + 16 + app.new_task_recurrence.len() as u16; // "Repeat (RRULE): " is 16 chars
+            let cursor_y = chunks[7].y;
+            if cursor_x < chunks[7].x + chunks[7].width {
+                frame.set_cursor_position((cursor_x, cursor_y));
+            }
+        }
+        InputMode::EditingEstimate => {
+            let cursor_x = chunks[8].x + 23 + app.new_task_estimate.len() as u16; // "Estimate (45m, 1h30m): " is 23 chars
+            let cursor_y = chunks[8].y;
+            if cursor_x < chunks[8].x + chunks[8].width {
+                frame.set_cursor_position((cursor_x, cursor_y));
+            }
+        }
         _ => {}
     }
 }
 
+/// "Effort: █████····· 1h 05m of 2h 00m", with the bar full and red once the time logged
+/// is past the estimate
+fn effort_gauge(app: &App, spent: Duration, estimate: Duration) -> Line<'static> {
+    const WIDTH: i64 = 20;
+    let over = spent > estimate;
+    let filled = if estimate.num_minutes() > 0 {
+        (spent.num_minutes() * WIDTH / estimate.num_minutes()).min(WIDTH)
+    } else {
+        WIDTH
+    } as usize;
+    let style = if over { app.theme.overdue } else { app.theme.success };
+    let mut spans = vec![
+        Span::styled("Effort: ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::styled(app.theme.icons.heat[4].repeat(filled), style),
+        Span::styled(app.theme.icons.heat[0].repeat(WIDTH as usize - filled), app.theme.dim),
+        Span::raw(format!(" {} of {}", TimeEntry::format_duration(spent), TimeEntry::format_duration(estimate))),
+    ];
+    if over {
+        spans.push(Span::styled(format!(" (over by {})", TimeEntry::format_duration(spent - estimate)), style));
+    }
+    Line::from(spans)
+}
+
+/// A labelled multi-line text area (task description, daily note), with the cursor at the end
+/// of the text while it's being edited
+fn render_multiline_field(frame: &mut Frame, app: &App, area: Rect, label: &str, text: &TextBuffer, scroll: u16, active: bool) {
+    let style = if active {
+        app.theme.input_active
+    } else {
+        Style::default()
+    };
+
+    // The text is wrapped here rather than by the Paragraph, so scrolling and the cursor count the
+    // same rows that are drawn; only the rows in view are, below the label while it's scrolled into view
+    app.editor_size.set((area.width, area.height));
+    let mut lines = Vec::new();
+    if scroll == 0 {
+        lines.push(Line::from(Span::styled(label.to_string(), style.add_modifier(Modifier::BOLD))));
+    }
+    let first = (scroll as usize).saturating_sub(1);
+    for row in text.rows_in_view(area.width, first, (area.height as usize).saturating_sub(lines.len())) {
+        lines.push(Line::from(Span::styled(row, style)));
+    }
+    frame.render_widget(Paragraph::new(lines), area);
+
+    if active {
+        // The cursor sits at the end of the text, below the label line and moved up by the scroll
+        let (row, column) = text.cursor(area.width);
+        let cursor_x = area.x + column as u16;
+        let cursor_y = (area.y + 1 + row as u16).saturating_sub(scroll);
+
+        if cursor_x < area.x + area.width && cursor_y >= area.y && cursor_y < area.y + area.height {
+            frame.set_cursor_position((cursor_x, cursor_y));
+        }
+    }
+}
+
+/// Where the task editor goes in `area`: the size set with Ctrl++ and Ctrl+- if there is one,
+/// otherwise just big enough for the title and description, either way between EDITOR_MIN and
+/// nearly the whole terminal
+fn task_editor_rect(app: &App, area: Rect, same_day_height: u16) -> Rect {
+    /// Room for every field, its hints and three rows of description
+    const EDITOR_MIN: (u16, u16) = (64, 24);
+    // Borders and the layout's margin, both sides
+    const FRAME: u16 = 4;
+    let percent_of = |length: u16, percent: u16| (length as u32 * percent as u32 / 100) as u16;
+    let (max_width, max_height) = (percent_of(area.width, 95), percent_of(area.height, 95));
+
+    let (width, height) = match app.config.editor_size {
+        Some(size) => (percent_of(area.width, size.width), percent_of(area.height, size.height)),
+        None => {
+            let title = Line::from(format!("Title: {}", app.new_task_title)).width();
+            let longest = app.new_task_description.longest_line().max(title);
+            // A column spare for the cursor
+            let width = (longest as u16).saturating_add(1 + FRAME).clamp(EDITOR_MIN.0, max_width.max(EDITOR_MIN.0));
+            // The label, the wrapped text and a row to type on
+            let description = 2 + app.new_task_description.rows(width.saturating_sub(FRAME)) as u16;
+            let fields = 3 + 6 * 2 + same_day_height + 2;
+            (width, fields + description.max(3) + FRAME)
+        }
+    };
+    let width = width.max(EDITOR_MIN.0).min(max_width);
+    let height = height.max(EDITOR_MIN.1).min(max_height);
+
+    // Ctrl++ and Ctrl+- step from what's on screen, which is also what a tiny terminal shrinks it to
+    let percent = |length: u16, of: u16| ((length as u32 * 100 + of as u32 / 2) / of.max(1) as u32) as u16;
+    app.task_editor_percent.set((percent(width, area.width), percent(height, area.height)));
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+fn render_note_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(60, 60, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    let title = match app.editing_note_date {
+        Some(date) => format!("Note - {}", date.format("%a %Y-%m-%d")),
+        None => "Note".to_string(),
+    };
+    let popup_block = themed_block(&app.theme)
+        .title(title)
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(10),    // Note text
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    render_multiline_field(frame, app, chunks[0], "Note:", &app.note_text, app.note_scroll, true);
+
+    let instructions = Paragraph::new(
+        "Enter: Save | Alt+Enter: New line | Ctrl+U/D or PgUp/Dn: Scroll | Esc: Cancel"
+    )
+    .style(app.theme.muted)
+    .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[1]);
+}
+
 fn render_done_panel(frame: &mut Frame, app: &App) {
     // Create a centered rectangle for the popup
     let popup_area = centered_rect(60, 50, frame.area());
@@ -771,98 +2849,69 @@ fn render_done_panel(frame: &mut Frame, app: &App) {
     frame.render_widget(Clear, popup_area);
 
     // Create the main popup block
-    let popup_block = Block::default()
+    let popup_block = themed_block(&app.theme)
         .title("Done?")
         .borders(Borders::ALL)
-        .style(Style::default().bg(Color::Black));
+        .style(app.theme.popup);
 
     // Get the inner area before rendering
     let inner_area = popup_block.inner(popup_area);
     frame.render_widget(popup_block, popup_area);
 
     // Get the task to display
-    if let Some(completing_id) = app.completing_todo_id {
-        if let Some(task) = app.todos.iter().find(|t| t.id == completing_id) {
-            // Split the popup into sections
-            let chunks = Layout::default()
-                .direction(Direction::Vertical)
-                .margin(1)
-                .constraints([
-                    Constraint::Length(3),  // Title field
-                    Constraint::Min(5),     // Description field
-                    Constraint::Length(3),  // Date field
-                    Constraint::Length(3),  // Buttons
-                    Constraint::Length(2),  // Instructions
-                ])
-                .split(inner_area);
-
-            // Title (read-only)
-            let title_text = format!("Title: {}", task.title);
-            let title_para = Paragraph::new(title_text)
-                .style(Style::default().add_modifier(Modifier::BOLD));
-            frame.render_widget(title_para, chunks[0]);
+    if let Some(completing_id) = app.completing_todo_id
+        && let Some(task) = app.todos.iter().find(|t| t.id == completing_id)
+    {
+        // Split the popup into sections
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([
+                Constraint::Length(3),  // Title field
+                Constraint::Min(5),     // Description field
+                Constraint::Length(3),  // Date field
+                Constraint::Length(4),  // Buttons and checkbox
+                Constraint::Length(2),  // Instructions
+            ])
+            .split(inner_area);
 
-            // Description (read-only)
-            let mut description_lines = vec![
-                Line::from(Span::styled("Description:", Style::default().add_modifier(Modifier::BOLD))),
-            ];
-            // Split description by newlines and create a Line for each
-            for line in task.description.split('\n') {
-                description_lines.push(Line::from(line.to_string()));
-            }
-            let description_para = Paragraph::new(description_lines)
-                .wrap(ratatui::widgets::Wrap { trim: false });
-            frame.render_widget(description_para, chunks[1]);
+        // Title (read-only)
+        let title_text = format!("Title: {}", task.title);
+        let title_para = Paragraph::new(title_text)
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        frame.render_widget(title_para, chunks[0]);
 
-            // Due date (read-only)
-            let date_text = if let Some(due_date) = task.due_date {
-                format!("Due Date: {}", due_date.format("%Y-%m-%d"))
-            } else {
-                "Due Date: Not set".to_string()
-            };
-            let date_para = Paragraph::new(date_text);
-            frame.render_widget(date_para, chunks[2]);
-
-            // Buttons
-            let button_area = chunks[3];
-            let button_chunks = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints([
-                    Constraint::Percentage(50),
-                    Constraint::Percentage(50),
-                ])
-                .split(button_area);
+        // Description (read-only)
+        let mut description_lines = vec![
+            Line::from(Span::styled("Description:", Style::default().add_modifier(Modifier::BOLD))),
+        ];
+        // Split description by newlines and create a Line for each
+        for line in task.description.split('\n') {
+            description_lines.push(Line::from(line.to_string()));
+        }
+        let description_para = Paragraph::new(description_lines)
+            .wrap(ratatui::widgets::Wrap { trim: false });
+        frame.render_widget(description_para, chunks[1]);
 
-            // Yes button
-            let yes_style = if app.done_panel_yes_selected {
-                Style::default().bg(Color::Green).fg(Color::Black).add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(Color::Green)
-            };
-            let yes_button = Paragraph::new("[ Yes ]")
-                .style(yes_style)
-                .alignment(Alignment::Center);
-            frame.render_widget(yes_button, button_chunks[0]);
+        // Due date (read-only)
+        let date_text = if let Some(due_date) = task.due_date {
+            format!("Due Date: {}", task.due_label(due_date))
+        } else {
+            "Due Date: Not set".to_string()
+        };
+        let date_para = Paragraph::new(date_text);
+        frame.render_widget(date_para, chunks[2]);
 
-            // No button
-            let no_style = if !app.done_panel_yes_selected {
-                Style::default().bg(Color::Red).fg(Color::Black).add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(Color::Red)
-            };
-            let no_button = Paragraph::new("[ No ]")
-                .style(no_style)
-                .alignment(Alignment::Center);
-            frame.render_widget(no_button, button_chunks[1]);
+        // Buttons and the "don't ask again" checkbox
+        render_confirm_buttons(frame, app, chunks[3], app.done_panel_yes_selected);
 
-            // Instructions
-            let instructions = Paragraph::new(
-                "Tab/Left/Right: Switch buttons | Enter: Confirm | Esc: Cancel"
-            )
-            .style(Style::default().fg(Color::Gray))
-            .alignment(Alignment::Center);
-            frame.render_widget(instructions, chunks[4]);
-        }
+        // Instructions
+        let instructions = Paragraph::new(
+            "Tab: Switch | Space: Don't ask again | Enter: Confirm | Esc: Cancel"
+        )
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+        frame.render_widget(instructions, chunks[4]);
     }
 }
 
@@ -874,110 +2923,1340 @@ fn render_delete_panel(frame: &mut Frame, app: &App) {
     frame.render_widget(Clear, popup_area);
 
     // Create the main popup block
-    let popup_block = Block::default()
+    let popup_block = themed_block(&app.theme)
         .title("Delete?")
         .borders(Borders::ALL)
-        .style(Style::default().bg(Color::Black));
+        .style(app.theme.popup);
 
     // Get the inner area before rendering
     let inner_area = popup_block.inner(popup_area);
     frame.render_widget(popup_block, popup_area);
 
-    // Get the task to display
-    if let Some(deleting_id) = app.deleting_todo_id {
-        if let Some(task) = app.todos.iter().find(|t| t.id == deleting_id) {
-            // Split the popup into sections
-            let chunks = Layout::default()
-                .direction(Direction::Vertical)
-                .margin(1)
-                .constraints([
-                    Constraint::Length(3),  // Title field
-                    Constraint::Min(3),     // Description field
-                    Constraint::Length(3),  // Buttons
-                    Constraint::Length(2),  // Instructions
-                ])
-                .split(inner_area);
-
-            // Title (read-only)
-            let title_text = format!("Title: {}", task.title);
-            let title_para = Paragraph::new(title_text)
-                .style(Style::default().add_modifier(Modifier::BOLD));
-            frame.render_widget(title_para, chunks[0]);
+    // Get the task (or habit) to display
+    let subject = match app.deleting_habit_id {
+        Some(habit_id) => app.habits().iter().find(|h| h.id == habit_id).map(|habit| (
+            format!("Habit: {}", habit.name),
+            format!("{}, checked off {} times", habit.frequency.label(), habit.done.len()),
+        )),
+        None => app.deleting_todo_id
+            .and_then(|deleting_id| app.todos.iter().find(|t| t.id == deleting_id))
+            .map(|task| (format!("Title: {}", task.title), task.description.clone())),
+    };
+    if let Some((title_text, description)) = subject {
+        // Split the popup into sections
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([
+                Constraint::Length(3),  // Title field
+                Constraint::Min(3),     // Description field
+                Constraint::Length(4),  // Buttons and checkbox
+                Constraint::Length(2),  // Instructions
+            ])
+            .split(inner_area);
 
-            // Description (read-only)
-            let mut description_lines = vec![
-                Line::from(Span::styled("Description:", Style::default().add_modifier(Modifier::BOLD))),
-            ];
-            // Split description by newlines and create a Line for each
-            for line in task.description.split('\n') {
-                description_lines.push(Line::from(line.to_string()));
-            }
-            let description_para = Paragraph::new(description_lines)
-                .wrap(ratatui::widgets::Wrap { trim: false });
-            frame.render_widget(description_para, chunks[1]);
-
-            // Buttons
-            let button_area = chunks[2];
-            let button_chunks = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints([
-                    Constraint::Percentage(50),
-                    Constraint::Percentage(50),
-                ])
-                .split(button_area);
+        // Title (read-only)
+        let title_para = Paragraph::new(title_text)
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        frame.render_widget(title_para, chunks[0]);
 
-            // Yes button
-            let yes_style = if app.delete_panel_yes_selected {
-                Style::default().bg(Color::Green).fg(Color::Black).add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(Color::Green)
-            };
-            let yes_button = Paragraph::new("[ Yes ]")
-                .style(yes_style)
-                .alignment(Alignment::Center);
-            frame.render_widget(yes_button, button_chunks[0]);
+        // Description (read-only)
+        let mut description_lines = vec![
+            Line::from(Span::styled("Description:", Style::default().add_modifier(Modifier::BOLD))),
+        ];
+        // Split description by newlines and create a Line for each
+        for line in description.split('\n') {
+            description_lines.push(Line::from(line.to_string()));
+        }
+        let description_para = Paragraph::new(description_lines)
+            .wrap(ratatui::widgets::Wrap { trim: false });
+        frame.render_widget(description_para, chunks[1]);
 
-            // No button
-            let no_style = if !app.delete_panel_yes_selected {
-                Style::default().bg(Color::Red).fg(Color::Black).add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(Color::Red)
-            };
-            let no_button = Paragraph::new("[ No ]")
-                .style(no_style)
-                .alignment(Alignment::Center);
-            frame.render_widget(no_button, button_chunks[1]);
+        // Buttons and the "don't ask again" checkbox
+        render_confirm_buttons(frame, app, chunks[2], app.delete_panel_yes_selected);
 
-            // Instructions
-            let instructions = Paragraph::new(
-                "Tab/Left/Right: Switch buttons | Enter: Confirm | Esc: Cancel"
-            )
-            .style(Style::default().fg(Color::Gray))
-            .alignment(Alignment::Center);
-            frame.render_widget(instructions, chunks[3]);
-        }
+        // Instructions
+        let instructions = Paragraph::new(
+            "Tab: Switch | Space: Don't ask again | Enter: Confirm | Esc: Cancel"
+        )
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+        frame.render_widget(instructions, chunks[3]);
     }
 }
 
-fn render_footer(frame: &mut Frame, area: Rect) {
-    let footer_text = Line::from(vec![
-        Span::styled(" + ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-        Span::raw(": new  "),
-        Span::styled("d ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-        Span::raw(": done  "),
-        Span::styled("- ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-        Span::raw(": delete  "),
-        Span::styled("tab ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-        Span::raw(": panels  "),
-        Span::styled("t ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-        Span::raw(": today  "),
-        Span::styled("shift+←/→ ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-        Span::raw(": tabs"),
-    ]);
+fn render_discard_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(50, 30, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    // Create the main popup block
+    let popup_block = themed_block(&app.theme)
+        .title("Discard changes?")
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    // Split the popup into sections
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(1),     // Message
+            Constraint::Length(4),  // Buttons and checkbox
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    let message = Paragraph::new("Close the editor without saving?")
+        .alignment(Alignment::Center);
+    frame.render_widget(message, chunks[0]);
+
+    // Buttons and the "don't ask again" checkbox
+    render_confirm_buttons(frame, app, chunks[1], app.discard_panel_yes_selected);
+
+    // Instructions
+    let instructions = Paragraph::new("Space: Don't ask again | Enter: Confirm | Esc: Back")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[2]);
+}
+
+fn render_duplicate_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(50, 40, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    // Create the main popup block
+    let popup_block = themed_block(&app.theme)
+        .title("Already open?")
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    // Split the popup into sections
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(1),     // Message
+            Constraint::Length(4),  // Buttons and checkbox
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    let existing = app.duplicate_of.and_then(|id| app.all_todos().iter().find(|todo| todo.id == id));
+    let mut lines = vec![Line::from("A task like this one is already open:")];
+    if let Some(todo) = existing {
+        lines.push(Line::from(Span::styled(format!("#{} {}", todo.id, todo.title), Style::default().add_modifier(Modifier::BOLD))));
+    }
+    lines.push(Line::from("Open it instead?"));
+    let message = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .wrap(ratatui::widgets::Wrap { trim: true });
+    frame.render_widget(message, chunks[0]);
+
+    // Buttons and the "don't ask again" checkbox; No adds the new task anyway
+    render_confirm_buttons(frame, app, chunks[1], app.duplicate_panel_yes_selected);
+
+    // Instructions
+    let instructions = Paragraph::new("Space: Don't ask again | Enter: Confirm | Esc: Back")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[2]);
+}
+
+fn render_empty_trash_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(50, 30, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    // Create the main popup block
+    let popup_block = themed_block(&app.theme)
+        .title("Empty trash")
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(1),     // Question
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    let count = app.trash_count();
+    let question = Paragraph::new(format!(
+        "Remove {} deleted task{} for good? They won't show up in search again.",
+        count, if count == 1 { "" } else { "s" },
+    ))
+        .alignment(Alignment::Center)
+        .wrap(ratatui::widgets::Wrap { trim: true });
+    frame.render_widget(question, chunks[0]);
+
+    // Instructions
+    let instructions = Paragraph::new("y: Empty trash | n: Keep them")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[1]);
+}
+
+fn render_tool_call_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(50, 30, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    // Create the main popup block
+    let popup_block = themed_block(&app.theme)
+        .title("An assistant wants to change your tasks")
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(1),     // Question
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    let mut question = format!("{}?", app.tool_call_question().unwrap_or_default());
+    let waiting = app.tool_calls_waiting();
+    if waiting > 0 {
+        question.push_str(&format!("\n\n{} more waiting", waiting));
+    }
+    let question = Paragraph::new(question)
+        .alignment(Alignment::Center)
+        .wrap(ratatui::widgets::Wrap { trim: true });
+    frame.render_widget(question, chunks[0]);
+
+    // Instructions
+    let instructions = Paragraph::new("y: Allow | n: Decline")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[1]);
+}
+
+fn render_parent_done_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(50, 30, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    // Create the main popup block
+    let popup_block = themed_block(&app.theme)
+        .title("Every subtask is done")
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(1),     // Question
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    let title = app.parent_done_id
+        .and_then(|id| app.all_todos().iter().find(|t| t.id == id))
+        .map(|todo| todo.title.clone())
+        .unwrap_or_default();
+    let question = Paragraph::new(format!("Mark \"{}\" done too?", title))
+        .alignment(Alignment::Center)
+        .wrap(ratatui::widgets::Wrap { trim: true });
+    frame.render_widget(question, chunks[0]);
+
+    // Instructions
+    let instructions = Paragraph::new("y: Yes | n: Not yet | a: Always for this task | o: Never for this task")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center)
+        .wrap(ratatui::widgets::Wrap { trim: true });
+    frame.render_widget(instructions, chunks[1]);
+}
+
+fn render_search_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(60, 70, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    // Create the main popup block
+    let popup_block = themed_block(&app.theme)
+        .title("Search")
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    // Split the popup into sections
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(2),  // Query field
+            Constraint::Min(3),     // Results
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    // Query field
+    let query_text = format!("Search: {}", app.search_query);
+    let query_para = Paragraph::new(query_text)
+        .style(app.theme.input_active);
+    frame.render_widget(query_para, chunks[0]);
+
+    // Results, colored by where the task lives
+    let result_items: Vec<ListItem> = app.search_results
+        .iter()
+        .map(|hit| {
+            let style = match hit.source {
+                TaskSource::Active => Style::default(),
+                TaskSource::Note => app.theme.accent,
+                TaskSource::Completed | TaskSource::Archived => app.theme.success,
+                TaskSource::Deleted => app.theme.dim,
+                TaskSource::Someday => app.theme.muted,
+            };
+            let label = match hit.source {
+                TaskSource::Completed => format!("{} {}", app.theme.icons.done, hit.source.label()),
+                _ => hit.source.label().to_string(),
+            };
+            let content = if hit.source == TaskSource::Note
+                && let Some(date) = hit.due_date
+            {
+                format!("[{}] {} ({})", label, hit.title, date.format("%a %Y-%m-%d"))
+            } else if let Some(due_date) = hit.due_date {
+                format!("[{}] {} (Due: {})", label, hit.title, due_date.format("%Y-%m-%d"))
+            } else {
+                format!("[{}] {}", label, hit.title)
+            };
+            ListItem::new(content).style(style)
+        })
+        .collect();
+
+    let results_title = if app.search_query.is_empty() {
+        "Results".to_string()
+    } else {
+        format!("Results ({})", app.search_results.len())
+    };
+    let results_list = List::new(result_items)
+        .block(themed_block(&app.theme)
+            .title(results_title)
+            .borders(Borders::TOP))
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+
+    let mut results_state = ListState::default();
+    results_state.select(app.selected_search_index);
+    frame.render_stateful_widget(results_list, chunks[1], &mut results_state);
+
+    // Instructions
+    let instructions = Paragraph::new(
+        "Type to search | Up/Down: Select | Enter: Go to task or note | Esc: Close"
+    )
+    .style(app.theme.muted)
+    .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[2]);
+
+    // Keep the cursor at the end of the query
+    let cursor_x = chunks[0].x + 8 + app.search_query.len() as u16; // "Search: " is 8 chars
+    if cursor_x < chunks[0].x + chunks[0].width {
+        frame.set_cursor_position((cursor_x, chunks[0].y));
+    }
+}
+
+fn render_context_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(40, 50, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    // Create the main popup block
+    let popup_block = themed_block(&app.theme)
+        .title("Context")
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(3),     // Context choices
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    // "All contexts" always comes first
+    let mut items = vec![ListItem::new("All contexts")];
+    for context in &app.context_choices {
+        let item = if app.config.active_context.as_ref() == Some(context) {
+            ListItem::new(format!("{} (active)", context)).style(app.theme.pending)
+        } else {
+            ListItem::new(context.as_str())
+        };
+        items.push(item);
+    }
+
+    let context_list = List::new(items)
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(app.selected_context_index));
+    frame.render_stateful_widget(context_list, chunks[0], &mut list_state);
+
+    // Instructions
+    let instructions = Paragraph::new("Up/Down: Select | Enter: Apply | Esc: Cancel")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[1]);
+}
+
+fn render_workspace_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(40, 50, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    // Create the main popup block
+    let popup_block = themed_block(&app.theme)
+        .title("Open Workspace")
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(3),     // Filter choices
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    let items: Vec<ListItem> = app.workspace_choices
+        .iter()
+        .map(|filter| ListItem::new(filter.label()))
+        .collect();
+
+    let choice_list = List::new(items)
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(app.selected_workspace_index));
+    frame.render_stateful_widget(choice_list, chunks[0], &mut list_state);
+
+    // Instructions
+    let instructions = Paragraph::new("Up/Down: Select | Enter: Open | Esc: Cancel")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[1]);
+}
+
+fn render_habit_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(40, 30, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    // Create the main popup block
+    let popup_block = themed_block(&app.theme)
+        .title("New Habit")
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(2),  // Name field
+            Constraint::Length(2),  // Frequency
+            Constraint::Min(0),
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    let name = Paragraph::new(format!("Name: {}", app.new_habit_name)).style(app.theme.input_active);
+    frame.render_widget(name, chunks[0]);
+
+    let frequency = Paragraph::new(format!("Frequency: {}", app.new_habit_frequency.label()));
+    frame.render_widget(frequency, chunks[1]);
+
+    // Instructions
+    let instructions = Paragraph::new("Tab: Daily/Weekly | Enter: Save | Esc: Cancel")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[3]);
+
+    let cursor_x = chunks[0].x + 6 + app.new_habit_name.len() as u16; // "Name: " is 6 chars
+    if cursor_x < chunks[0].x + chunks[0].width {
+        frame.set_cursor_position((cursor_x, chunks[0].y));
+    }
+}
+
+fn render_project_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(50, 40, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    // Create the main popup block
+    let popup_block = themed_block(&app.theme)
+        .title(format!("Project: {}", app.project_panel_name))
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(2),  // Color field
+            Constraint::Length(2),  // Default tags
+            Constraint::Length(2),  // Archive policy
+            Constraint::Length(2),  // Own file
+            Constraint::Min(0),
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    let fields = [
+        (ProjectField::Color, "Color (cyan, #ff8800): ", &app.project_color_input),
+        (ProjectField::Tags, "Default tags: ", &app.project_tags_input),
+        (ProjectField::ArchiveDays, "Archive closed tasks after (days): ", &app.project_archive_input),
+    ];
+    for (row, (field, label, value)) in fields.into_iter().enumerate() {
+        let style = if app.project_field == field { app.theme.input_active } else { Style::default() };
+        let mut spans = vec![Span::raw(format!("{}{}", label, value))];
+        // A swatch previews the color as it's typed
+        if field == ProjectField::Color && !value.trim().is_empty() {
+            spans.push(Span::styled(format!(" {}", app.theme.icons.project), app.theme.label(Some(value.trim()))));
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)).style(style), chunks[row]);
+        if app.project_field == field {
+            let cursor_x = chunks[row].x + (label.len() + value.len()) as u16;
+            if cursor_x < chunks[row].x + chunks[row].width {
+                frame.set_cursor_position((cursor_x, chunks[row].y));
+            }
+        }
+    }
+
+    let checkbox = if app.project_own_file { "[x]" } else { "[ ]" };
+    let style = if app.project_field == ProjectField::OwnFile { app.theme.input_active } else { Style::default() };
+    let own_file = Paragraph::new(format!("{} Keep its tasks in their own file (projects/)", checkbox)).style(style);
+    frame.render_widget(own_file, chunks[3]);
+
+    // Instructions
+    let instructions = Paragraph::new("Tab: Switch field | Space: Toggle | Enter: Save | Esc: Cancel")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[5]);
+}
+
+fn render_blackout_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(50, 30, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    // Create the main popup block
+    let title = if app.editing_blackout.is_some() { "Days Away" } else { "New Days Away" };
+    let popup_block = themed_block(&app.theme)
+        .title(title)
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(2),  // From
+            Constraint::Length(2),  // To
+            Constraint::Length(2),  // Label
+            Constraint::Min(0),
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    let fields = [
+        (BlackoutField::Start, "From: ", &app.blackout_start_input),
+        (BlackoutField::End, "To: ", &app.blackout_end_input),
+        (BlackoutField::Label, "What for: ", &app.blackout_label_input),
+    ];
+    for (row, (field, label, value)) in fields.into_iter().enumerate() {
+        let style = if app.blackout_field == field { app.theme.input_active } else { Style::default() };
+        frame.render_widget(Paragraph::new(format!("{}{}", label, value)).style(style), chunks[row]);
+        if app.blackout_field == field {
+            let cursor_x = chunks[row].x + (label.len() + value.len()) as u16;
+            if cursor_x < chunks[row].x + chunks[row].width {
+                frame.set_cursor_position((cursor_x, chunks[row].y));
+            }
+        }
+    }
+
+    // Instructions
+    let instructions = if app.editing_blackout.is_some() {
+        "Tab: Next | Enter: Save | Del: Remove | Esc: Cancel"
+    } else {
+        "Tab: Next | Enter: Save | Esc: Cancel"
+    };
+    let instructions = Paragraph::new(instructions)
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[4]);
+}
+
+fn render_waiting_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(40, 30, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    // Create the main popup block
+    let popup_block = themed_block(&app.theme)
+        .title("Waiting On")
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(2),  // Who
+            Constraint::Min(0),
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    let who = Paragraph::new(format!("Waiting on: {}", app.waiting_on_input)).style(app.theme.input_active);
+    frame.render_widget(who, chunks[0]);
+
+    // Instructions
+    let instructions = Paragraph::new("Enter: Save | Esc: Cancel")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[2]);
+
+    let cursor_x = chunks[0].x + 12 + app.waiting_on_input.len() as u16; // "Waiting on: " is 12 chars
+    if cursor_x < chunks[0].x + chunks[0].width {
+        frame.set_cursor_position((cursor_x, chunks[0].y));
+    }
+}
+
+fn render_recovery_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(70, 60, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    // Create the main popup block
+    let popup_block = themed_block(&app.theme)
+        .title("Damaged Task File")
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(2),  // Heading
+            Constraint::Min(0),     // Files
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    let heading = Paragraph::new("Some task files couldn't be read. Nothing has been changed yet.")
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    frame.render_widget(heading, chunks[0]);
+
+    let mut lines = Vec::new();
+    for file in app.damaged_files() {
+        lines.push(Line::from(Span::styled(file.path.display().to_string(), app.theme.overdue)));
+        lines.push(Line::from(Span::styled(format!("  {}", file.reason), app.theme.muted)));
+        let skipped = match file.skipped {
+            0 => String::new(),
+            1 => ", 1 broken entry left out".to_string(),
+            n => format!(", {} broken entries left out", n),
+        };
+        lines.push(Line::from(format!("  Recovered {} task{}{}", file.recovered, if file.recovered == 1 { "" } else { "s" }, skipped)));
+        lines.push(Line::from(format!("  Keeping them backs the original up as {}", file.backup_path.display())));
+        lines.push(Line::from(""));
+    }
+    frame.render_widget(Paragraph::new(lines).wrap(ratatui::widgets::Wrap { trim: false }), chunks[1]);
+
+    // Instructions
+    let instructions = Paragraph::new("Enter: Keep the recovered tasks | q/Esc: Quit and fix the file by hand")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[2]);
+}
+
+fn render_keymap_problems(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(60, 50, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    // Create the main popup block
+    let popup_block = themed_block(&app.theme)
+        .title("Keybinding Problems")
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(2),  // Heading
+            Constraint::Min(0),     // Problems
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    let heading = Paragraph::new("Some entries in the \"keys\" config couldn't be used:")
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    frame.render_widget(heading, chunks[0]);
+
+    let problems: Vec<ListItem> = app.keymap_problems.iter()
+        .map(|problem| ListItem::new(format!("- {}", problem)).style(app.theme.overdue))
+        .collect();
+    frame.render_widget(List::new(problems), chunks[1]);
+
+    // Instructions
+    let instructions = Paragraph::new("Any key: Continue with the defaults for these")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[2]);
+}
+
+fn render_plan_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(60, 60, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    // Create the main popup block
+    let popup_block = themed_block(&app.theme)
+        .title(format!("Schedule for me (up to {} per day)", app.config.planning.daily_capacity))
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(3),     // Proposed dates
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    let items: Vec<ListItem> = app.plan
+        .iter()
+        .map(|item| {
+            let checkbox = if item.include { "[x]" } else { "[ ]" };
+            let to = match item.to {
+                Some(to) => to.format("%a %Y-%m-%d").to_string(),
+                None => "no room       ".to_string(),
+            };
+            let from = match item.from {
+                Some(from) => format!("was due {}", from.format("%Y-%m-%d")),
+                None => "no due date".to_string(),
+            };
+            let style = if item.include { Style::default() } else { app.theme.muted };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{} {}  ", checkbox, to), style),
+                Span::styled(item.title.clone(), style),
+                Span::styled(format!("  ({})", from), app.theme.dim),
+            ]))
+        })
+        .collect();
+
+    let plan_list = List::new(items)
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(app.selected_plan_index));
+    frame.render_stateful_widget(plan_list, chunks[0], &mut list_state);
+
+    // Instructions
+    let instructions = Paragraph::new("Up/Down: Select | Left/Right: Move a day | Space: Skip | Enter: Apply | Esc: Cancel")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[1]);
+}
+
+fn render_batch_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(60, 70, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    let marked = app.marked_todos();
+    let popup_block = themed_block(&app.theme)
+        .title(format!("Batch edit ({} task{})", marked.len(), if marked.len() == 1 { "" } else { "s" }))
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(2),  // Tags field
+            Constraint::Length(2),  // Priority field
+            Constraint::Length(2),  // Project field
+            Constraint::Min(3),     // Preview
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    let fields = [
+        (BatchField::Tags, "Tags (+add -remove): ", &app.batch_tags),
+        (BatchField::Priority, "Priority (high, medium, low, none): ", &app.batch_priority),
+        (BatchField::Project, "Project (name, none): ", &app.batch_project),
+    ];
+    for (row, (field, label, value)) in fields.into_iter().enumerate() {
+        let style = if app.batch_field == field { app.theme.input_active } else { Style::default() };
+        frame.render_widget(Paragraph::new(format!("{}{}", label, value)).style(style), chunks[row]);
+        if app.batch_field == field {
+            let cursor_x = chunks[row].x + (label.len() + value.len()) as u16;
+            if cursor_x < chunks[row].x + chunks[row].width {
+                frame.set_cursor_position((cursor_x, chunks[row].y));
+            }
+        }
+    }
+
+    // Preview each task's changes, or why the input can't be applied
+    let preview: Vec<ListItem> = match app.batch_edit() {
+        Err(err) => vec![ListItem::new(err.to_string()).style(app.theme.overdue)],
+        Ok(edit) => marked.iter()
+            .map(|todo| {
+                let changes = edit.changes(todo);
+                let (text, style) = if changes.is_empty() {
+                    ("no change".to_string(), app.theme.dim)
+                } else {
+                    (changes.join("; "), app.theme.accent)
+                };
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{}: ", todo.title)),
+                    Span::styled(text, style),
+                ]))
+            })
+            .collect(),
+    };
+    let preview_list = List::new(preview)
+        .block(themed_block(&app.theme)
+            .title("Preview")
+            .borders(Borders::TOP));
+    frame.render_widget(preview_list, chunks[3]);
+
+    // Instructions
+    let instructions = Paragraph::new("Tab: Switch field | Enter: Apply to all | Esc: Cancel")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[4]);
+}
+
+fn render_transfer_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(60, 60, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    let count = app.marked_or_selected().len();
+    let popup_block = themed_block(&app.theme)
+        .title(format!("{} {} task{}", if app.transfer_copy { "Copy" } else { "Move" }, count, if count == 1 { "" } else { "s" }))
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(2),  // Destination filter
+            Constraint::Min(3),     // Destinations
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    let label = "To: ";
+    frame.render_widget(Paragraph::new(format!("{}{}", label, app.transfer_input)).style(app.theme.input_active), chunks[0]);
+    let cursor_x = chunks[0].x + (label.len() + app.transfer_input.chars().count()) as u16;
+    if cursor_x < chunks[0].x + chunks[0].width {
+        frame.set_cursor_position((cursor_x, chunks[0].y));
+    }
+
+    let choices = app.transfer_choices();
+    let items: Vec<ListItem> = if choices.is_empty() {
+        vec![ListItem::new("Nowhere matches").style(app.theme.dim)]
+    } else {
+        choices.iter().map(|choice| ListItem::new(choice.label(&app.config.stats.sources))).collect()
+    };
+    let destinations = List::new(items)
+        .block(themed_block(&app.theme)
+            .title("Destination")
+            .borders(Borders::TOP))
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+    let mut list_state = ListState::default();
+    list_state.select((!choices.is_empty()).then_some(app.selected_transfer_index));
+    frame.render_stateful_widget(destinations, chunks[1], &mut list_state);
+
+    // Instructions
+    let instructions = Paragraph::new("Type to filter | Tab: Move/Copy | Enter: Apply | Esc: Cancel")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[2]);
+}
+
+fn render_triage_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(60, 60, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    // Create the main popup block, titled for what's in the review
+    let (stale, someday) = (app.triage.iter().any(|item| !item.someday), app.triage.iter().any(|item| item.someday));
+    let title = match (stale, someday) {
+        (true, false) => format!("Stale tasks (untouched for {}+ days)", app.config.triage.stale_after_days),
+        (false, _) => format!("Someday tasks (not looked at for {}+ days)", app.config.triage.someday_after_days),
+        (true, true) => "Stale and someday tasks".to_string(),
+    };
+    let popup_block = themed_block(&app.theme)
+        .title(title)
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(3),     // Stale tasks
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    let loads = app.day_loads();
+    let items: Vec<ListItem> = app.triage
+        .iter()
+        .map(|item| {
+            let style = match item.action {
+                TriageAction::Keep => Style::default(),
+                TriageAction::Activate => app.theme.success,
+                TriageAction::Reschedule(_) => app.theme.accent,
+                TriageAction::Delete => app.theme.overdue,
+            };
+            let mut spans = vec![Span::styled(format!("{:<18}  ", item.action.label()), style)];
+            if item.someday {
+                spans.push(Span::styled("[Someday] ", app.theme.muted));
+            }
+            spans.push(Span::raw(item.title.clone()));
+            spans.push(Span::styled(format!("  ({} days)", item.idle_days), app.theme.dim));
+            // How full the day it's moving to already is
+            if let TriageAction::Reschedule(date) = item.action {
+                let load = loads.get(&date).copied().unwrap_or(0.0);
+                let style = if load >= app.day_capacity() { app.theme.overdue } else { app.theme.dim };
+                spans.push(Span::styled(format!("  [{} that day]", app.describe_load(load)), style));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let triage_list = List::new(items)
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(app.selected_triage_index));
+    frame.render_stateful_widget(triage_list, chunks[0], &mut list_state);
+
+    // Instructions
+    let activate = if someday { "a: Make active | " } else { "" };
+    let instructions = Paragraph::new(format!("k: Keep | {}r, Left/Right: Reschedule | x: Delete | Enter: Apply | Esc: Later", activate))
+        .style(app.theme.muted)
+        .alignment(Alignment::Center)
+        .wrap(ratatui::widgets::Wrap { trim: true });
+    frame.render_widget(instructions, chunks[1]);
+}
+
+fn render_history_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(80, 70, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    // Create the main popup block
+    let title = app.history.first().map_or(String::new(), |version| format!("History of #{} {}", version.todo.id, version.todo.title));
+    let popup_block = themed_block(&app.theme)
+        .title(title)
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(3),     // Versions and the diff
+            Constraint::Length(1),  // Instructions
+        ])
+        .split(inner_area);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(chunks[0]);
+
+    // One row per description change, newest first
+    let items: Vec<ListItem> = app.history
+        .iter()
+        .map(|version| {
+            ListItem::new(Line::from(vec![
+                Span::styled(app.clock.local(version.committed_at).format("%Y-%m-%d %H:%M  ").to_string(), app.theme.muted),
+                Span::raw(version.message.clone()),
+            ]))
+        })
+        .collect();
+    let version_list = List::new(items)
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+    let mut list_state = ListState::default();
+    list_state.select(Some(app.selected_history_index));
+    frame.render_stateful_widget(version_list, columns[0], &mut list_state);
+
+    // The selected version's description against the one before it (nothing, for the first)
+    let selected = app.history.get(app.selected_history_index).map_or("", |version| version.todo.description.as_str());
+    let previous = app.history.get(app.selected_history_index + 1).map_or("", |version| version.todo.description.as_str());
+    let diff_lines: Vec<Line> = diff::lines(previous, selected)
+        .into_iter()
+        .map(|line| match line {
+            DiffLine::Same(text) => Line::from(format!("  {}", text)),
+            DiffLine::Added(text) => Line::styled(format!("+ {}", text), app.theme.success),
+            DiffLine::Removed(text) => Line::styled(format!("- {}", text), app.theme.overdue),
+        })
+        .collect();
+    let diff_lines = if diff_lines.is_empty() { vec![Line::styled("(no description)", app.theme.muted)] } else { diff_lines };
+    let diff_widget = Paragraph::new(diff_lines)
+        .block(themed_block(&app.theme).borders(Borders::LEFT).border_style(app.theme.dim))
+        .wrap(ratatui::widgets::Wrap { trim: false });
+    frame.render_widget(diff_widget, columns[1]);
+
+    // Instructions
+    let instructions = Paragraph::new("Up/Down: Pick a version | Esc: Close")
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[1]);
+}
+
+fn render_attention_panel(frame: &mut Frame, app: &App) {
+    // Create a centered rectangle for the popup
+    let popup_area = centered_rect(60, 50, frame.area());
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    // Create the main popup block
+    let popup_block = themed_block(&app.theme)
+        .title(format!("Needs attention (overdue by more than {} days)", app.config.escalation.overdue_days))
+        .borders(Borders::ALL)
+        .style(app.theme.popup);
+
+    // Get the inner area before rendering
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(popup_block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(3),     // Escalated tasks
+            Constraint::Length(2),  // Instructions
+        ])
+        .split(inner_area);
+
+    let today = app.clock.today();
+    let items: Vec<ListItem> = app.attention
+        .iter()
+        .filter_map(|&id| app.todos.iter().find(|t| t.id == id))
+        .map(|todo| {
+            let days = todo.due_date.map_or(0, |due| (today - due).num_days());
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("#{:<5} ", todo.id), app.theme.dim),
+                Span::styled(todo.title.clone(), app.theme.overdue.add_modifier(Modifier::BOLD)),
+                Span::styled(format!("  ({} days overdue)", days), app.theme.dim),
+            ]))
+        })
+        .collect();
+
+    let attention_list = List::new(items)
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(app.selected_attention_index));
+    frame.render_stateful_widget(attention_list, chunks[0], &mut list_state);
+
+    // Instructions
+    let instructions = Paragraph::new(format!(
+        "d: Done | r: Reschedule to {} (next day with room) | Enter: Go to task | Esc: Later",
+        app.next_day_with_room(today).format("%a %Y-%m-%d"),
+    ))
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[1]);
+}
+
+fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
+    // The ':' jump-to-ID prompt takes the footer while it's typed
+    if app.input_mode == InputMode::JumpToId {
+        let prompt = Line::from(vec![
+            Span::styled(format!(" :{}", app.jump_input), app.theme.input_active),
+            Span::styled("  Enter: Jump to task ID | Esc: Cancel", app.theme.muted),
+        ]);
+        frame.render_widget(Paragraph::new(prompt), area);
+        frame.set_cursor_position((area.x + 2 + app.jump_input.len() as u16, area.y));
+        return;
+    }
+    // So does the link typed after 'U'
+    if app.input_mode == InputMode::AddUrl {
+        let prompt = Line::from(vec![
+            Span::styled(format!(" Link: {}", app.url_input), app.theme.input_active),
+            Span::styled("  Enter: Add to read later | Esc: Cancel", app.theme.muted),
+        ]);
+        frame.render_widget(Paragraph::new(prompt), area);
+        frame.set_cursor_position((area.x + 7 + app.url_input.len() as u16, area.y));
+        return;
+    }
+    if app.input_mode == InputMode::JumpHints {
+        let prompt = Paragraph::new(" Type a task's or day's label to jump there | Esc: Cancel").style(app.theme.muted);
+        frame.render_widget(prompt, area);
+        return;
+    }
+
+    // A pending notice (reminder, input error) replaces the key hints until the next key press
+    if let Some(notice) = &app.notice {
+        let notice_line = Line::from(Span::styled(format!(" {} ", notice), app.theme.due_today.add_modifier(Modifier::BOLD)));
+        frame.render_widget(Paragraph::new(notice_line), area);
+        return;
+    }
+
+    // Laid out by the "footer" config, with the right-hand part taking the room it needs
+    let right = Line::from(footer_spans(app, &app.config.footer.right));
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(right.width() as u16)])
+        .split(area);
+    let mut left = vec![Span::raw(" ")];
+    left.extend(footer_spans(app, &app.config.footer.left));
+    frame.render_widget(Paragraph::new(Line::from(left)), columns[0]);
+    frame.render_widget(Paragraph::new(right), columns[1]);
+}
+
+/// A footer template's text with each {placeholder} filled in; unknown ones are left as typed
+fn footer_spans<'a>(app: &App, template: &'a str) -> Vec<Span<'a>> {
+    let mut spans = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}').map(|close| open + close) else {
+            break;
+        };
+        spans.push(Span::raw(&rest[..open]));
+        let today = app.clock.today();
+        let open_tasks = || app.todos.iter().filter(|todo| !todo.completed && !todo.deleted && !todo.someday);
+        match &rest[open + 1..close] {
+            "hints" => spans.extend(key_hint_spans(app)),
+            "counts" => {
+                let overdue = open_tasks().filter(|todo| todo.is_overdue(today)).count();
+                let due_today = open_tasks().filter(|todo| todo.due_date == Some(today)).count();
+                spans.push(Span::raw(format!("{} open, ", open_tasks().count())));
+                spans.push(Span::styled(format!("{} overdue", overdue), if overdue > 0 { app.theme.overdue } else { Style::default() }));
+                spans.push(Span::raw(format!(", {} due today", due_today)));
+            }
+            "clock" => spans.push(Span::raw(app.clock.now().format("%H:%M").to_string())),
+            "sync" => spans.push(Span::styled(app.save_status(), app.theme.muted)),
+            "filter" => spans.push(Span::raw(app.filter_label())),
+            _ => spans.push(Span::raw(&rest[open..=close])),
+        }
+        rest = &rest[close + 1..];
+    }
+    spans.push(Span::raw(rest));
+    spans
+}
+
+/// The main screen's key hints, as keys and what they do
+fn key_hint_spans(app: &App) -> Vec<Span<'static>> {
+    let key_style = app.theme.accent.add_modifier(Modifier::BOLD);
+    // The keys come from the keymap, so custom ones show and turned-off actions drop out
+    let hints = [
+        ("new_task", "new"), ("done", "done"), ("delete", "delete"), ("search", "search"),
+        ("context", "context"), ("zoom", "zoom"), ("split", "split"), ("group", "group"),
+        ("workspace", "workspace"), ("plan", "plan"), ("today", "today"), ("note", "note"), ("blackout", "away"),
+        ("focus", "focus"), ("mark", "mark"), ("batch_edit", "batch edit"),
+        ("move_to_project", "move/copy"),
+    ];
+    let mut spans = Vec::new();
+    for (action, label) in hints {
+        if let Some(key) = app.keymap.label(action) {
+            spans.push(Span::styled(format!("{} ", key), key_style));
+            spans.push(Span::raw(format!(": {}  ", label)));
+        }
+        if action == "context" {
+            spans.push(Span::styled("tab ", key_style));
+            spans.push(Span::raw(": panels  "));
+        }
+    }
+    spans.push(Span::styled(if app.theme.ascii { "shift+left/right " } else { "shift+←/→ " }, key_style));
+    spans.push(Span::raw(": tabs"));
+    spans
+}
+
+/// Yes/No buttons for a confirmation popup, with the "don't ask again" checkbox below them
+fn render_confirm_buttons(frame: &mut Frame, app: &App, area: Rect, yes_selected: bool) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),  // Buttons
+            Constraint::Length(1),  // Checkbox
+        ])
+        .split(area);
+    let button_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(50),
+            Constraint::Percentage(50),
+        ])
+        .split(rows[0]);
+
+    // Yes button
+    let yes_style = if yes_selected {
+        app.theme.yes_button_selected
+    } else {
+        app.theme.yes_button
+    };
+    let yes_button = Paragraph::new(button_label(&app.theme, "Yes", yes_selected))
+        .style(yes_style)
+        .alignment(Alignment::Center);
+    frame.render_widget(yes_button, button_chunks[0]);
 
-    let footer = Paragraph::new(footer_text);
+    // No button
+    let no_style = if !yes_selected {
+        app.theme.no_button_selected
+    } else {
+        app.theme.no_button
+    };
+    let no_button = Paragraph::new(button_label(&app.theme, "No", !yes_selected))
+        .style(no_style)
+        .alignment(Alignment::Center);
+    frame.render_widget(no_button, button_chunks[1]);
+
+    let checkbox = if app.dont_ask_again { "[x]" } else { "[ ]" };
+    let checkbox = Paragraph::new(format!("{} Don't ask again", checkbox))
+        .style(app.theme.muted)
+        .alignment(Alignment::Center);
+    frame.render_widget(checkbox, rows[1]);
+}
 
-    frame.render_widget(footer, area);
+/// Helper function to label a popup button, marking the selected one with an icon as well as color
+fn button_label(theme: &Theme, label: &str, is_selected: bool) -> String {
+    if is_selected {
+        format!("{} [ {} ]", theme.icons.selected_button, label)
+    } else {
+        format!("[ {} ]", label)
+    }
 }
 
 /// Helper function to create a centered rectangle
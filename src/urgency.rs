@@ -0,0 +1,99 @@
+// Urgency module - A Taskwarrior-style score for ordering the list by what needs doing most: how
+// close the due date is, priority, age, tags, and whether finishing a task unblocks another. Each
+// part is a weight from config times a factor between 0 and 1, and the parts are kept so the
+// details panel can show how a score came about.
+
+use crate::clock::Clock;
+use crate::config::UrgencyConfig;
+use crate::models::{Priority, Todo};
+use chrono::NaiveDate;
+use std::collections::HashSet;
+
+/// A task's score and what it's made of, largest part first
+#[derive(Debug, Clone, Default)]
+pub struct Urgency {
+    pub total: f64,
+    pub parts: Vec<(&'static str, f64)>,
+}
+
+impl Urgency {
+    /// "due 9.6, priority 3.9, age 0.2"
+    pub fn describe(&self) -> String {
+        self.parts.iter()
+            .map(|(name, value)| format!("{} {:.1}", name, value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Scores tasks against the same set of tasks, worked out once for all of them
+pub struct Scorer<'a> {
+    config: &'a UrgencyConfig,
+    clock: &'a Clock,
+    today: NaiveDate,
+    /// Open tasks, by ID
+    open: HashSet<usize>,
+    /// Tasks with at least one open subtask, by ID
+    with_open_subtasks: HashSet<usize>,
+}
+
+impl<'a> Scorer<'a> {
+    /// `todos` is every stored task, so subtasks and parents outside the list still count
+    pub fn new(config: &'a UrgencyConfig, todos: &[Todo], clock: &'a Clock) -> Self {
+        let is_open = |todo: &&Todo| !todo.completed && !todo.deleted;
+        Self {
+            config,
+            clock,
+            today: clock.today(),
+            open: todos.iter().filter(is_open).map(|todo| todo.id).collect(),
+            with_open_subtasks: todos.iter().filter(is_open).filter_map(|todo| todo.parent).collect(),
+        }
+    }
+
+    pub fn score(&self, todo: &Todo) -> Urgency {
+        let config = self.config;
+        let mut parts = Vec::new();
+        if let Some(due_date) = todo.due_date {
+            parts.push(("due", config.due * due_factor(due_date, self.today)));
+        }
+        let priority = match todo.priority {
+            Some(Priority::High) => config.priority_high,
+            Some(Priority::Medium) => config.priority_medium,
+            Some(Priority::Low) => config.priority_low,
+            None => 0.0,
+        };
+        parts.push(("priority", priority));
+        let age_days = (self.today - self.clock.date_of(todo.created_at)).num_days().max(0);
+        parts.push(("age", config.age * (age_days as f64 / 365.0).min(1.0)));
+        let tags = match todo.tags.len() {
+            0 => 0.0,
+            1 => 0.8,
+            2 => 0.9,
+            _ => 1.0,
+        };
+        parts.push(("tags", config.tags * tags));
+        // An open subtask holds up its parent; a parent waits on its open subtasks
+        if todo.parent.is_some_and(|parent| self.open.contains(&parent)) {
+            parts.push(("blocking", config.blocking));
+        }
+        if self.with_open_subtasks.contains(&todo.id) {
+            parts.push(("blocked", config.blocked));
+        }
+
+        parts.retain(|(_, value)| *value != 0.0);
+        parts.sort_by(|a, b| b.1.abs().total_cmp(&a.1.abs()));
+        Urgency { total: parts.iter().map(|(_, value)| value).sum(), parts }
+    }
+}
+
+/// How pressing a due date is: a fifth 14 or more days out, rising to all of it a week overdue
+fn due_factor(due_date: NaiveDate, today: NaiveDate) -> f64 {
+    let overdue_days = (today - due_date).num_days() as f64;
+    if overdue_days >= 7.0 {
+        1.0
+    } else if overdue_days >= -14.0 {
+        (overdue_days + 14.0) * 0.8 / 21.0 + 0.2
+    } else {
+        0.2
+    }
+}
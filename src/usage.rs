@@ -0,0 +1,84 @@
+// Usage module - Opt-in counts of which main-screen actions get used, for the Stats tab
+// Only kept when "usage_metrics" is on in config. Counts are per day and per action name (the
+// names from the "keys" config) in usage.json next to todos.json; nothing else is recorded and
+// nothing is ever sent anywhere. Days older than a year are dropped when the file is saved.
+
+use crate::storage::FileStorage;
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// How many days of counts are kept
+const KEEP_DAYS: i64 = 365;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UsageLog {
+    #[serde(skip)]
+    file_path: PathBuf,
+    #[serde(skip)]
+    dirty: bool,
+    days: BTreeMap<NaiveDate, BTreeMap<String, usize>>,
+}
+
+impl UsageLog {
+    pub fn open(file_path: PathBuf) -> Self {
+        // A missing or unreadable file just means nothing recorded yet
+        let mut log = fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<UsageLog>(&contents).ok())
+            .unwrap_or_default();
+        log.file_path = file_path;
+        log
+    }
+
+    pub fn get_default_path() -> PathBuf {
+        // Keep usage alongside todos.json: ~/.local/share/tdui/usage.json
+        FileStorage::get_default_path().with_file_name("usage.json")
+    }
+
+    pub fn record(&mut self, action: &str, today: NaiveDate) {
+        *self.days.entry(today).or_default().entry(action.to_string()).or_default() += 1;
+        self.dirty = true;
+    }
+
+    /// Write the counts if anything was recorded since the last save
+    pub fn save(&mut self, today: NaiveDate) -> anyhow::Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let cutoff = today - Duration::days(KEEP_DAYS);
+        self.days.retain(|&date, _| date > cutoff);
+
+        if let Some(parent) = self.file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.file_path, serde_json::to_string(self)?)?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Actions per day from `start` through `today`, zero for days without any
+    pub fn daily_totals(&self, start: NaiveDate, today: NaiveDate) -> Vec<(NaiveDate, usize)> {
+        start.iter_days()
+            .take_while(|&date| date <= today)
+            .map(|date| (date, self.days.get(&date).map_or(0, |actions| actions.values().sum())))
+            .collect()
+    }
+
+    /// How often each action was used since `start`, most used first
+    pub fn action_totals(&self, start: NaiveDate) -> Vec<(String, usize)> {
+        let mut totals: BTreeMap<&str, usize> = BTreeMap::new();
+        for actions in self.days.range(start..).map(|(_, actions)| actions) {
+            for (action, count) in actions {
+                *totals.entry(action).or_default() += count;
+            }
+        }
+        let mut totals: Vec<(String, usize)> = totals.into_iter()
+            .map(|(action, count)| (action.to_string(), count))
+            .collect();
+        totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        totals
+    }
+}
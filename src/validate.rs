@@ -0,0 +1,175 @@
+// Validate module - A strict reading of the task files and config, for `tdui --validate`
+// Loading is lenient so files written by a newer tdui still open: unknown fields are kept as
+// they are, missing ones take their defaults and values this version doesn't know read as empty.
+// Validation reports every one of those spots (and anything that can't be read at all) without
+// changing a file.
+
+use crate::config::Config;
+use crate::models::{ParentCompletion, Priority, Recurrence, Todo};
+use crate::storage::FileStorage;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Task fields the lenient load fills in when they're missing
+const DEFAULTED_FIELDS: &[(&str, &str)] = &[("description", "empty"), ("completed", "not done")];
+
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub path: PathBuf,
+    /// The task or setting it's about, e.g. "task 12"; None for the file as a whole
+    pub location: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "{}: {}: {}", self.path.display(), location, self.message),
+            None => write!(f, "{}: {}", self.path.display(), self.message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    /// Each file checked, with the number of tasks in it (None for config)
+    pub checked: Vec<(PathBuf, Option<usize>)>,
+    pub problems: Vec<Problem>,
+}
+
+impl Report {
+    fn problem(&mut self, path: &Path, location: Option<String>, message: String) {
+        self.problems.push(Problem { path: path.to_path_buf(), location, message });
+    }
+}
+
+/// Check todos.json, every project file and config.json
+pub fn run() -> Report {
+    let mut report = Report::default();
+    let storage = FileStorage::open(FileStorage::get_default_path());
+    let projects_dir = FileStorage::get_default_path().with_file_name("projects");
+    let mut seen_ids: BTreeMap<u64, PathBuf> = BTreeMap::new();
+    for path in storage.file_paths() {
+        let count = check_task_file(&path, path.starts_with(&projects_dir), &mut seen_ids, &mut report);
+        report.checked.push((path, count));
+    }
+    check_config(&Config::get_default_path(), &mut report);
+    report
+}
+
+/// Check one task file, returning how many task entries it has (None if it couldn't be read)
+fn check_task_file(path: &Path, is_project_file: bool, seen_ids: &mut BTreeMap<u64, PathBuf>, report: &mut Report) -> Option<usize> {
+    let value: Value = match fs::read_to_string(path).map(|contents| serde_json::from_str(&contents)) {
+        Ok(Ok(value)) => value,
+        Ok(Err(err)) => {
+            report.problem(path, None, format!("isn't valid JSON: {}", err));
+            return None;
+        }
+        Err(err) => {
+            report.problem(path, None, format!("can't be read: {}", err));
+            return None;
+        }
+    };
+
+    let entries = if is_project_file {
+        if !value.get("project").is_some_and(Value::is_string) {
+            report.problem(path, None, "has no \"project\" name".to_string());
+        }
+        value.get("todos").and_then(Value::as_array)
+    } else {
+        value.as_array()
+    };
+    let Some(entries) = entries else {
+        let expected = if is_project_file { "an object with a \"todos\" list" } else { "a list of tasks" };
+        report.problem(path, None, format!("should be {}", expected));
+        return None;
+    };
+
+    let known_fields = known_fields();
+    for (index, entry) in entries.iter().enumerate() {
+        let location = match entry.get("id").and_then(Value::as_u64) {
+            Some(id) => format!("task {}", id),
+            None => format!("entry {}", index + 1),
+        };
+        let Some(fields) = entry.as_object() else {
+            report.problem(path, Some(location), "isn't a task".to_string());
+            continue;
+        };
+        if let Err(err) = serde_json::from_value::<Todo>(entry.clone()) {
+            report.problem(path, Some(location), format!("can't be read: {}", err));
+            continue;
+        }
+        check_task_fields(path, &location, fields, &known_fields, report);
+
+        if let Some(id) = entry.get("id").and_then(Value::as_u64) {
+            match seen_ids.get(&id) {
+                Some(first) if first == path => report.problem(path, Some(location), "the ID is used twice in this file".to_string()),
+                Some(first) => report.problem(path, Some(location), format!("the ID is also used in {}", first.display())),
+                None => {
+                    seen_ids.insert(id, path.to_path_buf());
+                }
+            }
+        }
+    }
+    Some(entries.len())
+}
+
+/// What the lenient load would quietly work around in a task that does read
+fn check_task_fields(path: &Path, location: &str, fields: &Map<String, Value>, known_fields: &BTreeSet<String>, report: &mut Report) {
+    for name in fields.keys().filter(|name| !known_fields.contains(*name)) {
+        report.problem(path, Some(location.to_string()), format!("unknown field \"{}\" (kept as it is)", name));
+    }
+    for (name, default) in DEFAULTED_FIELDS {
+        if !fields.contains_key(*name) {
+            report.problem(path, Some(location.to_string()), format!("missing \"{}\" (read as {})", name, default));
+        }
+    }
+    if let Some(priority) = fields.get("priority").filter(|value| !value.is_null())
+        && serde_json::from_value::<Priority>(priority.clone()).is_err()
+    {
+        report.problem(path, Some(location.to_string()), format!("unknown priority {} (read as none)", priority));
+    }
+    if let Some(choice) = fields.get("parent_completion").filter(|value| !value.is_null())
+        && serde_json::from_value::<ParentCompletion>(choice.clone()).is_err()
+    {
+        report.problem(path, Some(location.to_string()), format!("unknown parent_completion {} (read as the config default)", choice));
+    }
+    if let Some(rule) = fields.get("recurrence").filter(|value| !value.is_null())
+        && let Err(err) = serde_json::from_value::<Recurrence>(rule.clone())
+    {
+        report.problem(path, Some(location.to_string()), format!("repeat rule {} can't be used: {} (read as not repeating)", rule, err));
+    }
+}
+
+/// Every field a task is saved with
+fn known_fields() -> BTreeSet<String> {
+    match serde_json::to_value(Todo::new(0, String::new(), String::new(), None)) {
+        Ok(Value::Object(fields)) => fields.into_iter().map(|(name, _)| name).collect(),
+        _ => BTreeSet::new(),
+    }
+}
+
+/// Config that doesn't parse runs on defaults; settings this version doesn't know are ignored
+fn check_config(path: &Path, report: &mut Report) {
+    if !path.exists() {
+        return;
+    }
+    if let Err(err) = Config::read(path.to_path_buf()) {
+        report.problem(path, None, err.to_string());
+        report.checked.push((path.to_path_buf(), None));
+        return;
+    }
+    let raw: Option<Map<String, Value>> = fs::read_to_string(path).ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok());
+    let known: BTreeSet<String> = match serde_json::to_value(Config::default()) {
+        Ok(Value::Object(fields)) => fields.into_iter().map(|(name, _)| name).collect(),
+        _ => BTreeSet::new(),
+    };
+    for name in raw.iter().flat_map(|fields| fields.keys()).filter(|name| !known.contains(*name)) {
+        report.problem(path, Some(format!("\"{}\"", name)), "unknown setting (ignored)".to_string());
+    }
+    report.checked.push((path.to_path_buf(), None));
+}
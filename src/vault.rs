@@ -0,0 +1,387 @@
+// Vault module - Two-way sync with the Markdown checkboxes of an Obsidian vault
+// Checkbox lines in the notes that match the configured patterns are tasks in the Tasks plugin's
+// syntax: "- [ ] Call mom #family ⏫ 📅 2026-10-20", and "- [x] ... ✅ 2026-10-16" once done.
+// A line tdui hasn't seen becomes a task, and gets a block ID ("^tdui-12") at its end that
+// matches it to that task from then on. Each sync compares both sides with how they were left
+// by the last one (kept in vault.json): whichever side changed wins, tdui if both did, except
+// that a task done on either side ends up done on both. Tasks added in tdui stay out of the vault.
+
+use crate::clock::Clock;
+use crate::config::{Config, VaultConfig, expand_home};
+use crate::models::{Priority, Todo};
+use crate::storage::{Archive, FileStorage};
+use chrono::{NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DUE: &str = "📅";
+const DONE: &str = "✅";
+/// Tasks plugin fields kept on the line as they are: created, scheduled, start and cancelled dates
+const DATE_FIELDS: &[&str] = &["➕", "⏳", "🛫", "❌"];
+const RECURRENCE: &str = "🔁";
+const PRIORITIES: &[(&str, Priority)] = &[
+    ("🔺", Priority::High),
+    ("⏫", Priority::High),
+    ("🔼", Priority::Medium),
+    ("🔽", Priority::Low),
+    ("⏬", Priority::Low),
+];
+const BLOCK_ID: &str = "^tdui-";
+
+/// What's synced of a task, as both sides had it after the last sync
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+struct Synced {
+    title: String,
+    due_date: Option<NaiveDate>,
+    done: bool,
+    priority: Option<Priority>,
+    tags: Vec<String>,
+}
+
+impl Synced {
+    fn of_task(todo: &Todo) -> Self {
+        Self {
+            title: todo.title.clone(),
+            due_date: todo.due_date,
+            done: todo.completed,
+            priority: todo.priority,
+            tags: todo.tags.clone(),
+        }
+    }
+}
+
+/// A checkbox line, split into what's synced and what's kept as it is
+#[derive(Debug, Clone)]
+struct TaskLine {
+    /// Indentation and list marker, e.g. "  - "
+    prefix: String,
+    synced: Synced,
+    done_on: Option<NaiveDate>,
+    /// Plugin fields tdui doesn't use, in their original order
+    extras: Vec<String>,
+    id: Option<usize>,
+}
+
+impl TaskLine {
+    fn parse(line: &str) -> Option<Self> {
+        let body = line.trim_start();
+        let indent = &line[..line.len() - body.len()];
+        let (marker, rest) = body.split_at_checked(2)?;
+        if !matches!(marker, "- " | "* " | "+ ") {
+            return None;
+        }
+        let done = match rest.get(..4)? {
+            "[ ] " => false,
+            "[x] " | "[X] " => true,
+            // Cancelled ("[-]") and other custom states aren't tasks to sync
+            _ => return None,
+        };
+
+        let mut task = TaskLine {
+            prefix: format!("{}{}", indent, marker),
+            synced: Synced { done, ..Synced::default() },
+            done_on: None,
+            extras: Vec::new(),
+            id: None,
+        };
+        let mut title = Vec::new();
+        let mut words = rest[4..].split_whitespace().peekable();
+        while let Some(word) = words.next() {
+            let date = |value: Option<&&str>| value.and_then(|value| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok());
+            if word == DUE && let Some(due) = date(words.peek()) {
+                task.synced.due_date = Some(due);
+                words.next();
+            } else if word == DONE && let Some(done_on) = date(words.peek()) {
+                task.done_on = Some(done_on);
+                words.next();
+            } else if DATE_FIELDS.contains(&word) && date(words.peek()).is_some() {
+                task.extras.push(format!("{} {}", word, words.next().unwrap_or_default()));
+            } else if word == RECURRENCE {
+                // The rule runs up to the next field
+                let mut rule = vec![word];
+                while let Some(next) = words.next_if(|next| !is_field(next)) {
+                    rule.push(next);
+                }
+                task.extras.push(rule.join(" "));
+            } else if let Some(&(_, priority)) = PRIORITIES.iter().find(|(emoji, _)| *emoji == word) {
+                task.synced.priority = Some(priority);
+            } else if let Some(id) = word.strip_prefix(BLOCK_ID).and_then(|id| id.parse().ok()) {
+                task.id = Some(id);
+            } else if let Some(tag) = word.strip_prefix('#').filter(|tag| !tag.is_empty()).and_then(Todo::normalize_tag) {
+                task.synced.tags.push(tag);
+            } else {
+                title.push(word);
+            }
+        }
+        task.synced.title = title.join(" ");
+        Some(task)
+    }
+
+    fn render(&self) -> String {
+        let mut line = format!("{}[{}] {}", self.prefix, if self.synced.done { 'x' } else { ' ' }, self.synced.title);
+        for tag in &self.synced.tags {
+            line.push_str(&format!(" #{}", tag));
+        }
+        for extra in &self.extras {
+            line.push_str(&format!(" {}", extra));
+        }
+        if let Some(priority) = self.synced.priority
+            && let Some((emoji, _)) = PRIORITIES.iter().find(|&&(emoji, p)| p == priority && emoji != "🔺" && emoji != "⏬")
+        {
+            line.push_str(&format!(" {}", emoji));
+        }
+        if let Some(due) = self.synced.due_date {
+            line.push_str(&format!(" {} {}", DUE, due.format("%Y-%m-%d")));
+        }
+        if let Some(done_on) = self.done_on.filter(|_| self.synced.done) {
+            line.push_str(&format!(" {} {}", DONE, done_on.format("%Y-%m-%d")));
+        }
+        if let Some(id) = self.id {
+            line.push_str(&format!(" {}{}", BLOCK_ID, id));
+        }
+        line
+    }
+}
+
+fn is_field(word: &str) -> bool {
+    word == DUE || word == DONE || word == RECURRENCE || DATE_FIELDS.contains(&word)
+        || PRIORITIES.iter().any(|(emoji, _)| *emoji == word) || word.starts_with(BLOCK_ID)
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SyncReport {
+    /// Tasks added from new checkbox lines
+    pub created: usize,
+    /// Tasks changed to match their line
+    pub updated: usize,
+    /// Lines rewritten to match their task
+    pub written: usize,
+}
+
+impl SyncReport {
+    pub fn is_empty(&self) -> bool {
+        self.created == 0 && self.updated == 0 && self.written == 0
+    }
+
+    pub fn describe(&self) -> String {
+        format!("{} added, {} updated from notes, {} written back", self.created, self.updated, self.written)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncState {
+    tasks: BTreeMap<usize, Synced>,
+}
+
+pub struct VaultSync {
+    root: PathBuf,
+    patterns: Vec<String>,
+    state_path: PathBuf,
+}
+
+impl VaultSync {
+    /// Syncing as configured, or None if no vault is set
+    pub fn from_config(config: &VaultConfig) -> Option<Self> {
+        let path = config.path.as_ref()?;
+        let root = expand_home(path);
+        Some(Self { root, patterns: config.patterns.clone(), state_path: Self::get_default_state_path() })
+    }
+
+    pub fn get_default_state_path() -> PathBuf {
+        // Keep the sync state alongside todos.json: ~/.local/share/tdui/vault.json
+        FileStorage::get_default_path().with_file_name("vault.json")
+    }
+
+    /// Sync every matching note with `storage`, numbering new tasks from `next_id`
+    pub fn sync(&self, storage: &mut FileStorage, mut next_id: usize, context: Option<&str>, clock: &Clock) -> anyhow::Result<SyncReport> {
+        if !self.root.is_dir() {
+            anyhow::bail!("the vault folder {} doesn't exist", self.root.display());
+        }
+        let mut state: SyncState = fs::read_to_string(&self.state_path).ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        let mut report = SyncReport::default();
+        let mut seen = BTreeSet::new();
+
+        for path in self.note_paths() {
+            let relative = path.strip_prefix(&self.root).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+            let text = fs::read_to_string(&path)
+                .map_err(|err| anyhow::anyhow!("couldn't read {}: {}", path.display(), err))?;
+            let mut lines: Vec<String> = text.lines().map(String::from).collect();
+            let mut changed = false;
+
+            for line in &mut lines {
+                let Some(mut task) = TaskLine::parse(line) else {
+                    continue;
+                };
+                let Some(id) = task.id else {
+                    if task.synced.title.is_empty() {
+                        continue;
+                    }
+                    let todo = new_task(next_id, &task, &relative, context, clock);
+                    state.tasks.insert(next_id, Synced::of_task(&todo));
+                    seen.insert(next_id);
+                    storage.upsert(todo);
+                    *line = format!("{} {}{}", line.trim_end(), BLOCK_ID, next_id);
+                    next_id += 1;
+                    changed = true;
+                    report.created += 1;
+                    continue;
+                };
+                // A task since archived, purged or deleted leaves its line alone
+                let Some(todo) = storage.todos().iter().find(|todo| todo.id == id && !todo.deleted).cloned() else {
+                    continue;
+                };
+                seen.insert(id);
+                let in_task = Synced::of_task(&todo);
+                // Without a record of the last sync, the task is taken as unchanged since
+                let last = state.tasks.get(&id).cloned().unwrap_or_else(|| in_task.clone());
+                let merged = merge(&last, &in_task, &task.synced);
+
+                if merged != in_task {
+                    storage.update(id, |todo| apply(todo, &merged, task.done_on, clock));
+                    report.updated += 1;
+                }
+                if merged != task.synced {
+                    task.done_on = match merged.done {
+                        true => task.done_on.or(todo.completed_at.map(|at| clock.date_of(at))).or(Some(clock.today())),
+                        false => None,
+                    };
+                    task.synced = merged.clone();
+                    *line = task.render();
+                    changed = true;
+                    report.written += 1;
+                }
+                state.tasks.insert(id, merged);
+            }
+
+            if changed {
+                let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+                let mut contents = lines.join(newline);
+                if text.ends_with('\n') {
+                    contents.push_str(newline);
+                }
+                fs::write(&path, contents).map_err(|err| anyhow::anyhow!("couldn't write {}: {}", path.display(), err))?;
+            }
+        }
+
+        // Lines removed from the vault stop being tracked
+        state.tasks.retain(|id, _| seen.contains(id));
+        fs::write(&self.state_path, serde_json::to_string(&state)?)
+            .map_err(|err| anyhow::anyhow!("couldn't write {}: {}", self.state_path.display(), err))?;
+        Ok(report)
+    }
+
+    /// Notes under the vault matching a pattern, skipping hidden folders like .obsidian and .trash
+    fn note_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        let mut folders = vec![self.root.clone()];
+        while let Some(folder) = folders.pop() {
+            let Ok(entries) = fs::read_dir(&folder) else {
+                continue;
+            };
+            for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+                if path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.')) {
+                    continue;
+                }
+                if path.is_dir() {
+                    folders.push(path);
+                } else if self.matches(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+        paths.sort();
+        paths
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+        let segments: Vec<&str> = relative.split('/').collect();
+        self.patterns.iter().any(|pattern| {
+            let pattern: Vec<&str> = pattern.split('/').collect();
+            glob_segments(&pattern, &segments)
+        })
+    }
+}
+
+/// Sync the configured vault with the task files, for `tdui vault sync`
+pub fn sync_configured() -> anyhow::Result<SyncReport> {
+    let config = Config::load(Config::get_default_path());
+    let Some(vault) = VaultSync::from_config(&config.vault) else {
+        anyhow::bail!("no vault is set: add \"vault\": {{ \"path\": \"~/Notes\" }} to config");
+    };
+    let clock = Clock::from_config_or_warn(&config);
+    let mut storage = FileStorage::open(FileStorage::get_default_path());
+    // Refuse to overwrite a file we couldn't read
+    storage.load_todos()?;
+    let next_id = storage.next_free_id(&Archive::open(Archive::get_default_path()));
+    let report = vault.sync(&mut storage, next_id, config.active_context.as_deref(), &clock)?;
+    storage.flush()?;
+    Ok(report)
+}
+
+/// What both sides should have: the side that changed since `last` wins (the task if both did),
+/// but done on either side makes it done
+fn merge(last: &Synced, in_task: &Synced, in_line: &Synced) -> Synced {
+    let mut merged = if in_task != last { in_task.clone() } else { in_line.clone() };
+    let (task_moved, line_moved) = (in_task.done != last.done, in_line.done != last.done);
+    merged.done = if (task_moved && in_task.done) || (line_moved && in_line.done) {
+        true
+    } else if task_moved || line_moved {
+        false
+    } else {
+        last.done
+    };
+    merged
+}
+
+fn apply(todo: &mut Todo, synced: &Synced, done_on: Option<NaiveDate>, clock: &Clock) {
+    todo.title = synced.title.clone();
+    todo.due_date = synced.due_date;
+    todo.priority = synced.priority;
+    todo.tags = synced.tags.clone();
+    if todo.completed != synced.done {
+        todo.toggle_completed();
+        if todo.completed && let Some(at) = done_on.and_then(|date| completed_at(date, clock)) {
+            todo.completed_at = Some(at);
+        }
+    }
+}
+
+fn new_task(id: usize, line: &TaskLine, note: &str, context: Option<&str>, clock: &Clock) -> Todo {
+    let mut todo = Todo::new(id, String::new(), String::new(), None);
+    apply(&mut todo, &line.synced, line.done_on, clock);
+    todo.context = context.map(String::from);
+    todo.source_id = Some(format!("obsidian:{}", note));
+    todo
+}
+
+/// A task done on `date` counts as done at noon, unless that's today (then it's now)
+fn completed_at(date: NaiveDate, clock: &Clock) -> Option<chrono::DateTime<Utc>> {
+    if date == clock.today() {
+        return Some(Utc::now());
+    }
+    clock.at(date, NaiveTime::from_hms_opt(12, 0, 0)?).map(|at| at.with_timezone(&Utc))
+}
+
+/// Path segments against a glob's: "**" spans any number of folders, "*" and "?" stay in one name
+fn glob_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| glob_segments(rest, &path[skip..])),
+        Some((segment, rest)) => path.split_first()
+            .is_some_and(|(name, path_rest)| glob_name(segment.as_bytes(), name.as_bytes()) && glob_segments(rest, path_rest)),
+    }
+}
+
+fn glob_name(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|skip| glob_name(rest, &name[skip..])),
+        Some((b'?', rest)) => !name.is_empty() && glob_name(rest, &name[1..]),
+        Some((byte, rest)) => name.first() == Some(byte) && glob_name(rest, &name[1..]),
+    }
+}
@@ -0,0 +1,184 @@
+// Weekly plan module - A printable plan of the current week, for a paper copy
+// Used by `tdui plan --week`: open tasks grouped by due day with checkboxes and room for
+// notes, as Markdown on stdout or as an HTML page styled for printing (or saving as PDF).
+
+use crate::clock::Clock;
+use crate::config::{Config, WorkdayConfig};
+use crate::models::Todo;
+use crate::report::escape;
+use crate::storage::{FileStorage, NoteStorage};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Each day gets at least this many checkbox lines, padded with blank ones to write in
+const MIN_LINES: usize = 3;
+
+#[derive(Debug, Clone)]
+pub struct PlanDay {
+    pub date: NaiveDate,
+    /// False for weekends and configured holidays
+    pub working: bool,
+    /// (done, title) of the tasks due that day
+    pub tasks: Vec<(bool, String)>,
+    /// The day's note from the calendar, if any
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WeeklyPlan {
+    pub title: String,
+    /// Open tasks due before the week started
+    pub carried_over: Vec<String>,
+    /// Monday to Sunday
+    pub days: Vec<PlanDay>,
+    /// Open tasks without a due date
+    pub undated: Vec<String>,
+}
+
+impl WeeklyPlan {
+    /// Read the stored tasks and notes and plan the week containing today
+    pub fn load() -> anyhow::Result<Self> {
+        let config = Config::load(Config::get_default_path());
+        let clock = Clock::from_config_or_warn(&config);
+        let storage = FileStorage::open(FileStorage::get_default_path());
+        storage.load_todos()?;
+        let notes = NoteStorage::open(NoteStorage::get_default_path());
+
+        let context = config.active_context.as_deref();
+        let todos: Vec<Todo> = storage.todos().iter()
+            .filter(|t| t.in_context(context) && !t.deleted)
+            .cloned()
+            .collect();
+        Ok(Self::build(&todos, notes.notes(), &config.workdays, clock.today()))
+    }
+
+    pub fn build(todos: &[Todo], notes: &BTreeMap<NaiveDate, String>, workdays: &WorkdayConfig, today: NaiveDate) -> Self {
+        let monday = today.week(Weekday::Mon).first_day();
+        let sunday = monday + Duration::days(6);
+        let label = |todo: &Todo| match &todo.context {
+            Some(context) => format!("{} ({})", todo.title, context),
+            None => todo.title.clone(),
+        };
+
+        let mut sorted: Vec<&Todo> = todos.iter().filter(|t| !t.deleted && !t.someday).collect();
+        sorted.sort_by_key(|t| (t.due_date, t.id));
+        let open = || sorted.iter().filter(|t| !t.completed);
+
+        let days = (0..7)
+            .map(|offset| {
+                let date = monday + Duration::days(offset);
+                PlanDay {
+                    date,
+                    working: workdays.days.contains(&date.weekday()) && !workdays.holidays.contains(&date),
+                    tasks: sorted.iter()
+                        .filter(|t| t.due_date == Some(date))
+                        .map(|t| (t.completed, label(t)))
+                        .collect(),
+                    note: notes.get(&date).cloned(),
+                }
+            })
+            .collect();
+
+        Self {
+            title: format!("Week of {} to {}", monday.format("%a %Y-%m-%d"), sunday.format("%a %Y-%m-%d")),
+            carried_over: open().filter(|t| t.due_date.is_some_and(|d| d < monday)).map(|t| label(t)).collect(),
+            days,
+            undated: open().filter(|t| t.due_date.is_none()).map(|t| label(t)).collect(),
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut text = format!("# {}\n", self.title);
+        let checklist = |text: &mut String, titles: &[String]| {
+            for title in titles {
+                let _ = writeln!(text, "- [ ] {}", title);
+            }
+        };
+        if !self.carried_over.is_empty() {
+            text.push_str("\n## Carried over\n\n");
+            checklist(&mut text, &self.carried_over);
+        }
+        for day in &self.days {
+            let day_off = if day.working { "" } else { " (day off)" };
+            let _ = writeln!(text, "\n## {}{}\n", day.date.format("%A %Y-%m-%d"), day_off);
+            for (done, title) in &day.tasks {
+                let _ = writeln!(text, "- [{}] {}", if *done { "x" } else { " " }, title);
+            }
+            for _ in 0..MIN_LINES.saturating_sub(day.tasks.len()) {
+                text.push_str("- [ ] \n");
+            }
+            text.push_str("\nNotes:\n");
+            if let Some(note) = &day.note {
+                for line in note.lines() {
+                    let _ = writeln!(text, "> {}", line);
+                }
+            }
+        }
+        if !self.undated.is_empty() {
+            text.push_str("\n## No date\n\n");
+            checklist(&mut text, &self.undated);
+        }
+        text
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        let _ = write!(html, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+            escape(&self.title), STYLE, escape(&self.title));
+        let checklist = |html: &mut String, titles: &[String]| {
+            html.push_str("<ul>\n");
+            for title in titles {
+                let _ = writeln!(html, "<li>{}</li>", escape(title));
+            }
+            html.push_str("</ul>\n");
+        };
+        if !self.carried_over.is_empty() {
+            html.push_str("<section>\n<h2>Carried over</h2>\n");
+            checklist(&mut html, &self.carried_over);
+            html.push_str("</section>\n");
+        }
+        html.push_str("<div class=\"days\">\n");
+        for day in &self.days {
+            let class = if day.working { "day" } else { "day off" };
+            let _ = writeln!(html, "<section class=\"{}\">\n<h2>{}</h2>\n<ul>", class, day.date.format("%A %Y-%m-%d"));
+            for (done, title) in &day.tasks {
+                let _ = writeln!(html, "<li{}>{}</li>", if *done { " class=\"done\"" } else { "" }, escape(title));
+            }
+            for _ in 0..MIN_LINES.saturating_sub(day.tasks.len()) {
+                html.push_str("<li class=\"blank\"></li>\n");
+            }
+            html.push_str("</ul>\n<div class=\"notes\">");
+            if let Some(note) = &day.note {
+                html.push_str(&escape(note).replace('\n', "<br>"));
+            }
+            html.push_str("</div>\n</section>\n");
+        }
+        html.push_str("</div>\n");
+        if !self.undated.is_empty() {
+            html.push_str("<section>\n<h2>No date</h2>\n");
+            checklist(&mut html, &self.undated);
+            html.push_str("</section>\n");
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+const STYLE: &str = "
+body { font-family: system-ui, sans-serif; max-width: 860px; margin: 2em auto; padding: 0 1em; color: #222; }
+h1 { font-size: 1.4em; }
+h2 { font-size: 1.05em; margin: 0 0 0.4em; }
+.days { display: grid; grid-template-columns: 1fr 1fr; gap: 0.8em; }
+section { border: 1px solid #bbb; border-radius: 4px; padding: 0.6em; margin-bottom: 0.8em; break-inside: avoid; }
+.off { background: #f3f3f3; }
+ul { list-style: none; padding: 0; margin: 0; }
+li { padding: 0.15em 0; min-height: 1.2em; }
+li::before { content: '\\2610'; margin-right: 0.5em; }
+li.done::before { content: '\\2611'; }
+li.done { text-decoration: line-through; color: #777; }
+li.blank { border-bottom: 1px dotted #bbb; }
+.notes { min-height: 4em; margin-top: 0.5em; border-top: 1px solid #ddd; padding-top: 0.3em; font-size: 0.9em; }
+.notes::before { content: 'Notes'; display: block; color: #777; font-size: 0.85em; }
+@media print { body { margin: 0; max-width: none; } @page { margin: 1.2cm; } }
+";
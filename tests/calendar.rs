@@ -0,0 +1,43 @@
+// Calendar feed tests: repeating events land on the days their rule names, or only on their
+// first day when the rule asks for more than tdui expands.
+// Run with `cargo test --test calendar`.
+
+use chrono::{Datelike, Duration, Weekday};
+use tdui::clock::Clock;
+use tdui::ics;
+use tdui::models::Recurrence;
+
+/// A feed with one all-day event starting today and repeating by `rule`
+fn feed(rule: &str, clock: &Clock) -> String {
+    let start = clock.today().format("%Y%m%d");
+    format!("BEGIN:VCALENDAR\nBEGIN:VEVENT\nSUMMARY:Team sync\nDTSTART;VALUE=DATE:{}\nRRULE:{}\nEND:VEVENT\nEND:VCALENDAR\n", start, rule)
+}
+
+#[test]
+fn positional_byday_shows_first_occurrence_only() {
+    let clock = Clock::default();
+    assert_eq!(Recurrence::parse_lenient("FREQ=MONTHLY;BYDAY=2TU"), None);
+    let events = ics::parse(&feed("FREQ=MONTHLY;BYDAY=2TU", &clock), &clock);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].start, clock.today());
+}
+
+#[test]
+fn rules_narrowed_by_other_parts_show_first_occurrence_only() {
+    for rule in ["FREQ=YEARLY;BYMONTH=3", "FREQ=MONTHLY;BYMONTHDAY=15", "FREQ=MONTHLY;BYDAY=MO;BYSETPOS=-1", "FREQ=WEEKLY;BYDAY=-1FR", "FREQ=MONTHLY;BYDAY=TU"] {
+        assert_eq!(Recurrence::parse_lenient(rule), None, "{}", rule);
+    }
+}
+
+#[test]
+fn week_start_and_times_of_day_are_passed_over() {
+    let clock = Clock::default();
+    let rule = Recurrence::parse_lenient("FREQ=WEEKLY;WKST=SU;BYDAY=MO;BYHOUR=9;BYMINUTE=30").expect("rule");
+    assert_eq!(rule.by_day, vec![Weekday::Mon]);
+    let events = ics::parse(&feed("FREQ=WEEKLY;WKST=SU;BYDAY=MO", &clock), &clock);
+    assert!(events.len() > 1);
+    assert!(events.iter().all(|event| event.start.weekday() == Weekday::Mon));
+    assert!(events.windows(2).all(|pair| pair[1].start - pair[0].start == Duration::weeks(1)));
+    // Strict parsing, for rules typed into a task, still refuses them
+    assert!(Recurrence::parse("FREQ=WEEKLY;WKST=SU;BYDAY=MO").is_err());
+}
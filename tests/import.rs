@@ -0,0 +1,148 @@
+// Import tests: iCalendar, CSV, todo.txt and Taskwarrior files read into tasks that a second import merges into rather
+// than repeats.
+// Run with `cargo test --test import`.
+
+use chrono::{NaiveDate, NaiveTime};
+use tdui::clock::Clock;
+use tdui::import::{Action, parse_csv, parse_ics, parse_taskwarrior, parse_todo_txt, plan};
+use tdui::models::{Frequency, Priority};
+
+const ICS: &str = "BEGIN:VCALENDAR\r
+BEGIN:VTODO\r
+UID:todo-1@example.com\r
+SUMMARY:Renew passport\r
+DESCRIPTION:Photos first\\nthen the form\r
+DUE;VALUE=DATE:20261020\r
+PRIORITY:1\r
+CATEGORIES:Errands,Admin\r
+BEGIN:VALARM\r
+TRIGGER:-PT15M\r
+DESCRIPTION:Reminder\r
+END:VALARM\r
+END:VTODO\r
+BEGIN:VTODO\r
+UID:todo-2@example.com\r
+SUMMARY:File taxes\r
+STATUS:COMPLETED\r
+COMPLETED:20261002T120000Z\r
+END:VTODO\r
+BEGIN:VEVENT\r
+UID:event-1@example.com\r
+SUMMARY:Team sync\r
+DTSTART:20261019T093000\r
+RRULE:FREQ=WEEKLY;BYDAY=MO\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:event-2@example.com\r
+SUMMARY:Dropped meeting\r
+STATUS:CANCELLED\r
+DTSTART;VALUE=DATE:20261021\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+#[test]
+fn ics_todos_and_events_become_tasks() {
+    let todos = parse_ics(ICS, &Clock::default());
+    assert_eq!(todos.len(), 3);
+
+    let passport = &todos[0];
+    assert_eq!(passport.title, "Renew passport");
+    assert_eq!(passport.description, "Photos first\nthen the form");
+    assert_eq!(passport.due_date, NaiveDate::from_ymd_opt(2026, 10, 20));
+    assert_eq!(passport.priority, Some(Priority::High));
+    assert_eq!(passport.tags, vec!["errands".to_string(), "admin".to_string()]);
+    assert_eq!(passport.source_id.as_deref(), Some("ics:todo-1@example.com"));
+
+    assert!(todos[1].completed);
+    assert!(todos[1].completed_at.is_some());
+
+    let sync = &todos[2];
+    assert_eq!(sync.due_time, NaiveTime::from_hms_opt(9, 30, 0));
+    assert_eq!(sync.recurrence.as_ref().map(|rule| rule.frequency), Some(Frequency::Weekly));
+}
+
+#[test]
+fn csv_rows_become_tasks() {
+    let csv = "\u{feff}Title,Due Date,Priority,Project,Tags,Done,ID,Notes\n\
+        Renew passport,2026-10-20 14:00,high,Home,\"errands, admin\",,7,\"Photos first,\nthen the form\"\n\
+        \"Call \"\"Bob\"\"\",,B,,,x,8,\n\
+        ,2026-10-21,,,,,9,no title\n";
+    let todos = parse_csv(csv).expect("parse");
+    assert_eq!(todos.len(), 2);
+
+    let passport = &todos[0];
+    assert_eq!(passport.title, "Renew passport");
+    assert_eq!(passport.description, "Photos first,\nthen the form");
+    assert_eq!(passport.due_date, NaiveDate::from_ymd_opt(2026, 10, 20));
+    assert_eq!(passport.due_time, NaiveTime::from_hms_opt(14, 0, 0));
+    assert_eq!(passport.priority, Some(Priority::High));
+    assert_eq!(passport.project.as_deref(), Some("Home"));
+    assert_eq!(passport.tags, vec!["errands".to_string(), "admin".to_string()]);
+    assert_eq!(passport.source_id.as_deref(), Some("csv:7"));
+
+    assert_eq!(todos[1].title, "Call \"Bob\"");
+    assert_eq!(todos[1].priority, Some(Priority::Medium));
+    assert!(todos[1].completed);
+    assert!(todos[1].completed_at.is_some());
+
+    assert_eq!(parse_csv("Name\nBuy milk\n").expect("parse")[0].title, "Buy milk");
+    assert!(parse_csv("Due,Project\n2026-10-20,Home\n").is_err());
+}
+
+#[test]
+fn done_rows_keep_their_completion_date() {
+    let todos = parse_csv("Title,Done,Completed At,Tags\nFile taxes,yes,2026-10-02,\"admin, money, admin\"\nPay rent,yes,,\n").expect("parse");
+    assert_eq!(todos[0].completed_at.map(|at| at.date_naive()), NaiveDate::from_ymd_opt(2026, 10, 2));
+    assert_eq!(todos[0].tags, vec!["admin".to_string(), "money".to_string()]);
+    assert!(todos[1].completed_at.is_some());
+
+    let todos = parse_todo_txt("x 2026-10-02 File taxes +money @home +admin @desk +admin\nx Pay rent\n");
+    assert_eq!(todos[0].completed_at.map(|at| at.date_naive()), NaiveDate::from_ymd_opt(2026, 10, 2));
+    assert_eq!(todos[0].tags, vec!["admin".to_string(), "desk".to_string()]);
+    assert!(todos[1].completed_at.is_some());
+}
+
+#[test]
+fn taskwarrior_export_becomes_tasks() {
+    let export = r#"[
+{"id":1,"description":"Renew passport","due":"20261020T000000Z","entry":"20261001T080000Z","priority":"H","project":"Home","status":"pending","tags":["errands","admin","errands"],"uuid":"5b2d0b6e-1f7a-4c4e-9a53-0d7f3c1e2a11","annotations":[{"entry":"20261001T080500Z","description":"Photos first"}]},
+{"id":0,"description":"File taxes","end":"20261002T120000Z","entry":"20260920T080000Z","status":"completed","uuid":"8c1f4e2a-6b3d-4f0e-8d2c-1a9b7e5f3c22"},
+{"id":0,"description":"Dropped idea","status":"deleted","uuid":"0e7a9c3b-2d4f-4a1e-b6c8-5f2d1e9a7b33"}
+]"#;
+    let todos = parse_taskwarrior(export, &Clock::from_config(Some("UTC")).expect("clock")).expect("parse");
+    assert_eq!(todos.len(), 2);
+
+    let passport = &todos[0];
+    assert_eq!(passport.title, "Renew passport");
+    assert_eq!(passport.description, "Photos first");
+    assert_eq!(passport.due_date, NaiveDate::from_ymd_opt(2026, 10, 20));
+    assert_eq!(passport.due_time, None);
+    assert_eq!(passport.priority, Some(Priority::High));
+    assert_eq!(passport.project.as_deref(), Some("Home"));
+    assert_eq!(passport.tags, vec!["errands".to_string(), "admin".to_string()]);
+    assert_eq!(passport.source_id.as_deref(), Some("taskwarrior:5b2d0b6e-1f7a-4c4e-9a53-0d7f3c1e2a11"));
+
+    assert!(todos[1].completed);
+    assert_eq!(todos[1].completed_at.map(|at| at.date_naive()), NaiveDate::from_ymd_opt(2026, 10, 2));
+
+    // Older versions write one task per line
+    let lines = r#"{"description":"Buy milk","status":"pending","uuid":"1"},
+{"description":"Call mom","status":"waiting","uuid":"2"}"#;
+    assert_eq!(parse_taskwarrior(lines, &Clock::default()).expect("parse").len(), 2);
+    assert!(parse_taskwarrior("not json", &Clock::default()).is_err());
+}
+
+#[test]
+fn importing_again_merges_instead_of_repeating() {
+    let clock = Clock::default();
+    let mut stored = parse_ics(ICS, &clock);
+    for (id, todo) in stored.iter_mut().enumerate() {
+        todo.id = id + 1;
+    }
+    stored[0].tags.clear();
+
+    let planned = plan(parse_ics(ICS, &clock), &stored, &[]);
+    assert!(matches!(&planned[0].action, Action::Merge(1, changes) if changes.len() == 1));
+    assert!(planned[1..].iter().all(|task| matches!(task.action, Action::Skip(Some(_), _))));
+}
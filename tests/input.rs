@@ -0,0 +1,56 @@
+// Input tests: what's typed into tdui (or read back from its files) is turned down with an error
+// when it's out of range, never a panic.
+// Run with `cargo test --test input`.
+
+use chrono::NaiveDate;
+use tdui::clock::Clock;
+use tdui::config::ReminderConfig;
+use tdui::models::{ReminderOffset, Todo};
+use tdui::notify::reminders_for;
+use tdui::quick_add::{QuickAdd, parse_due_date};
+
+#[test]
+fn reminder_offset_too_many_weeks_is_an_error() {
+    assert!(ReminderOffset::parse("99999999999999999w").is_err());
+    assert!(ReminderOffset::parse_list("1d, 99999999999999999w").is_err());
+}
+
+#[test]
+fn reminder_offset_too_many_minutes_is_an_error() {
+    assert!(ReminderOffset::parse("999999999999999m").is_err());
+}
+
+#[test]
+fn reminder_offset_beyond_five_years_is_an_error() {
+    assert!(ReminderOffset::parse("99999999w").is_err());
+    assert!(ReminderOffset::parse("261w").is_err());
+    assert!(ReminderOffset::parse("260w").is_ok());
+}
+
+#[test]
+fn huge_reminder_offset_fires_no_reminder() {
+    let mut todo = Todo::new(1, "Renew passport".to_string(), String::new(), NaiveDate::from_ymd_opt(2026, 10, 20));
+    todo.reminders = Some(vec![ReminderOffset::from_minutes(i64::MAX), ReminderOffset::parse("1d").expect("parse")]);
+    let reminders = reminders_for(&todo, &ReminderConfig::default(), &Clock::default());
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].offset.to_string(), "1d");
+}
+
+#[test]
+fn reminder_offset_out_of_range_in_a_file_fails_to_load() {
+    assert!(serde_json::from_str::<ReminderOffset>("\"999999999999999m\"").is_err());
+    let offset: ReminderOffset = serde_json::from_str("\"2w\"").expect("parse");
+    assert_eq!(offset.duration(), chrono::Duration::weeks(2));
+}
+
+#[test]
+fn quick_add_count_past_the_calendar_is_no_date() {
+    let today = NaiveDate::from_ymd_opt(2026, 10, 16).expect("date");
+    for input in ["buy milk in 4000000000 days", "buy milk in 4000000000 weeks", "buy milk in 4000000000 months"] {
+        let parsed = QuickAdd::parse(input, today);
+        assert_eq!(parsed.due_date, None, "{}", input);
+        assert_eq!(parsed.title, input);
+    }
+    assert_eq!(parse_due_date("in 4000000000 days", today), None);
+    assert_eq!(parse_due_date("in 3 weeks", today), NaiveDate::from_ymd_opt(2026, 11, 6));
+}
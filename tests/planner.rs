@@ -0,0 +1,52 @@
+// Planner tests: "schedule for me" fills days by the same measure the calendar shows their
+// load in, a count of tasks or hours of estimated work.
+// Run with `cargo test --test planner`.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use tdui::models::Todo;
+use tdui::planner::{Capacity, plan};
+
+/// Monday, so the week's working days follow on from it
+fn today() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, 12).expect("date")
+}
+
+fn weekday(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+fn task(id: usize, due_date: Option<NaiveDate>, estimate_minutes: Option<u32>) -> Todo {
+    let mut todo = Todo::new(id, format!("Task {}", id), String::new(), due_date);
+    todo.created_at += Duration::seconds(id as i64);
+    todo.estimate_minutes = estimate_minutes;
+    todo
+}
+
+fn dates(todos: &[Todo], capacity: Capacity) -> Vec<(usize, Option<NaiveDate>)> {
+    plan(todos, today(), capacity, weekday).into_iter().map(|planned| (planned.id, planned.to)).collect()
+}
+
+#[test]
+fn capacity_in_tasks_counts_tasks_already_due() {
+    let day = |offset| Some(today() + Duration::days(offset));
+    let todos = vec![task(1, day(0), None), task(2, None, None), task(3, None, None), task(4, None, None)];
+    assert_eq!(dates(&todos, Capacity::Tasks(2)), vec![(2, day(0)), (3, day(1)), (4, day(1))]);
+}
+
+#[test]
+fn capacity_in_hours_sums_estimates() {
+    let day = |offset| Some(today() + Duration::days(offset));
+    let todos = vec![
+        // Four hours already due today
+        task(1, day(0), Some(240)),
+        task(2, None, Some(180)),
+        task(3, None, Some(60)),
+        // Longer than a whole day, so it gets one to itself
+        task(4, None, Some(600)),
+        task(5, None, None),
+    ];
+    // A smaller task still fits the room left earlier, and one without an estimate takes none
+    assert_eq!(dates(&todos, Capacity::Hours(6.0)), vec![(2, day(1)), (3, day(0)), (4, day(2)), (5, day(0))]);
+    // The same tasks counted as tasks ignore the estimates
+    assert_eq!(dates(&todos, Capacity::Tasks(2)), vec![(2, day(0)), (3, day(1)), (4, day(1)), (5, day(2))]);
+}
@@ -0,0 +1,238 @@
+// Property tests for the date logic that's easy to get wrong at month and year
+// boundaries: task ordering, due-date buckets, the calendar's month stepping and the
+// expansion of repeat rules.
+// Run with `cargo test --test properties`; set PROPTEST_CASES for a longer run.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+use proptest::prelude::*;
+use tdui::app::{DueBucket, calendar_view_for};
+use tdui::models::{Frequency, Recurrence, Todo};
+use tdui::ui::{get_next_month, get_previous_month};
+
+/// Any valid date from 1900 to 2100, with month ends (and Feb 29 in leap years) as likely as any other day
+fn date() -> impl Strategy<Value = NaiveDate> {
+    (1900i32..=2100, 1u32..=12, 1u32..=31).prop_filter_map("not a calendar date", |(year, month, day)| {
+        NaiveDate::from_ymd_opt(year, month, day)
+    })
+}
+
+fn timestamp() -> impl Strategy<Value = DateTime<Utc>> {
+    // A handful of distinct seconds, so ties on created_at actually happen
+    (0i64..20).prop_map(|seconds| DateTime::UNIX_EPOCH + Duration::seconds(seconds))
+}
+
+fn todo() -> impl Strategy<Value = Todo> {
+    (proptest::option::of(date()), timestamp()).prop_map(|(due_date, created_at)| {
+        let mut todo = Todo::new(0, String::new(), String::new(), due_date);
+        todo.created_at = created_at;
+        todo
+    })
+}
+
+/// Tasks with unique IDs, so the sorted output can be checked against the input
+fn todos() -> impl Strategy<Value = Vec<Todo>> {
+    proptest::collection::vec(todo(), 0..40).prop_map(|mut todos| {
+        for (id, todo) in todos.iter_mut().enumerate() {
+            todo.id = id;
+        }
+        todos
+    })
+}
+
+/// A start date with a rule that repeats from it, ending by COUNT, UNTIL (up to two years on) or never
+fn recurrence() -> impl Strategy<Value = (NaiveDate, Recurrence)> {
+    let frequency = prop_oneof![Just(Frequency::Daily), Just(Frequency::Weekly), Just(Frequency::Monthly), Just(Frequency::Yearly)];
+    let weekdays = vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun];
+    (date(), frequency, 1u32..4, proptest::sample::subsequence(weekdays, 0..=3), 0..3, 1u32..10, 0i64..730)
+        .prop_map(|(start, frequency, interval, by_day, end, count, until_days)| {
+            let by_day = if matches!(frequency, Frequency::Daily | Frequency::Weekly) { by_day } else { Vec::new() };
+            let rule = Recurrence {
+                frequency,
+                interval,
+                count: (end == 1).then_some(count),
+                until: (end == 2).then(|| start + Duration::days(until_days)),
+                by_day,
+            };
+            (start, rule)
+        })
+}
+
+/// Months since year 0, so consecutive months differ by exactly one
+fn month_number(date: NaiveDate) -> i32 {
+    date.year() * 12 + date.month0() as i32
+}
+
+/// Whether `date` falls in the three months the calendar shows around `current_date`
+fn visible(current_date: NaiveDate, date: NaiveDate) -> bool {
+    (month_number(date) - month_number(current_date)).abs() <= 1
+}
+
+proptest! {
+    #[test]
+    fn sort_keeps_every_task(todos in todos()) {
+        let mut sorted = todos.clone();
+        Todo::sort_by_due(&mut sorted);
+
+        let mut ids: Vec<usize> = sorted.iter().map(|t| t.id).collect();
+        ids.sort_unstable();
+        prop_assert_eq!(ids, (0..todos.len()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn sort_orders_by_due_date_then_created(todos in todos()) {
+        let mut sorted = todos;
+        Todo::sort_by_due(&mut sorted);
+
+        for pair in sorted.windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            match (a.due_date, b.due_date) {
+                (Some(due_a), Some(due_b)) => {
+                    prop_assert!(due_a <= due_b);
+                    if due_a == due_b {
+                        prop_assert!(a.created_at <= b.created_at);
+                    }
+                }
+                // Undated tasks only ever follow dated ones
+                (None, Some(_)) => prop_assert!(false, "task {} without a due date sorted before task {}", a.id, b.id),
+                (Some(_), None) => {}
+                (None, None) => prop_assert!(a.created_at <= b.created_at),
+            }
+        }
+    }
+
+    #[test]
+    fn sort_ignores_input_order(todos in todos()) {
+        let mut forward = todos.clone();
+        let mut reversed: Vec<Todo> = todos.into_iter().rev().collect();
+        Todo::sort_by_due(&mut forward);
+        Todo::sort_by_due(&mut reversed);
+
+        // Ties may land either way round, but the sort keys must line up
+        let keys = |todos: &[Todo]| todos.iter().map(|t| (t.due_date.is_none(), t.due_date, t.created_at)).collect::<Vec<_>>();
+        prop_assert_eq!(keys(&forward), keys(&reversed));
+    }
+
+    #[test]
+    fn overdue_means_due_before_today(due_date in proptest::option::of(date()), today in date()) {
+        let todo = Todo::new(1, String::new(), String::new(), due_date);
+        let bucket = DueBucket::of(&todo, today);
+
+        prop_assert_eq!(bucket == DueBucket::Overdue, due_date.is_some_and(|due| due < today));
+        prop_assert_eq!(bucket == DueBucket::NoDate, due_date.is_none());
+    }
+
+    #[test]
+    fn buckets_follow_the_week(today in date(), offset in -30i64..60) {
+        let due_date = today + Duration::days(offset);
+        let todo = Todo::new(1, String::new(), String::new(), Some(due_date));
+        // The week ends on Sunday, however the month or year falls
+        let sunday = today.week(Weekday::Mon).last_day();
+
+        let expected = match offset {
+            ..0 => DueBucket::Overdue,
+            0 => DueBucket::Today,
+            1 => DueBucket::Tomorrow,
+            _ if due_date <= sunday => DueBucket::ThisWeek,
+            _ => DueBucket::Later,
+        };
+        prop_assert_eq!(DueBucket::of(&todo, today), expected);
+    }
+
+    #[test]
+    fn previous_month_is_first_of_the_month_before(date in date()) {
+        let previous = get_previous_month(date);
+
+        prop_assert_eq!(previous.day(), 1);
+        prop_assert_eq!(month_number(previous), month_number(date) - 1);
+        prop_assert_eq!(get_next_month(previous), date.with_day(1).unwrap());
+    }
+
+    #[test]
+    fn next_month_is_first_of_the_month_after(date in date()) {
+        let next = get_next_month(date);
+
+        prop_assert_eq!(next.day(), 1);
+        prop_assert_eq!(month_number(next), month_number(date) + 1);
+        prop_assert_eq!(get_previous_month(next), date.with_day(1).unwrap());
+    }
+
+    #[test]
+    fn calendar_view_stays_put_while_selection_is_visible(current_date in date(), offset in -1i32..=1, day in 1u32..=31) {
+        let month = current_date.with_day(1).unwrap();
+        let month = match offset {
+            -1 => get_previous_month(month),
+            1 => get_next_month(month),
+            _ => month,
+        };
+        let selected = month.with_day(day).unwrap_or(month);
+
+        prop_assert_eq!(calendar_view_for(current_date, selected), current_date);
+    }
+
+    #[test]
+    fn calendar_view_follows_a_moving_selection(
+        current_date in date(),
+        start in -1i32..=1,
+        day in 1u32..=31,
+        steps in proptest::collection::vec(prop_oneof![Just(-7i64), Just(-1), Just(1), Just(7)], 1..120),
+    ) {
+        // Start from a selection the calendar is showing, then move it with h/j/k/l-sized steps
+        let month = current_date.with_day(1).unwrap();
+        let month = match start {
+            -1 => get_previous_month(month),
+            1 => get_next_month(month),
+            _ => month,
+        };
+        let mut selected = month.with_day(day).unwrap_or(month);
+        let mut view = current_date;
+
+        for step in steps {
+            selected += Duration::days(step);
+            let next_view = calendar_view_for(view, selected);
+            // The view only ever moves a month at a time, and only when it has to
+            prop_assert!((month_number(next_view) - month_number(view)).abs() <= 1);
+            prop_assert_eq!(next_view == view, visible(view, selected));
+            prop_assert!(visible(next_view, selected));
+            view = next_view;
+        }
+    }
+
+    #[test]
+    fn recurrence_round_trips_through_rrule((_, rule) in recurrence()) {
+        let text = rule.to_string();
+        prop_assert_eq!(Recurrence::parse(&text).ok(), Some(rule.clone()));
+        prop_assert_eq!(Recurrence::parse(&format!("RRULE:{}", text.to_lowercase())).ok(), Some(rule));
+    }
+
+    #[test]
+    fn next_occurrence_is_later_and_fits_the_rule((start, rule) in recurrence()) {
+        if let Some((next, rest)) = rule.next_after(start) {
+            prop_assert!(next > start);
+            prop_assert!(rule.until.is_none_or(|until| next <= until));
+            prop_assert!(rule.by_day.is_empty() || rule.by_day.contains(&next.weekday()));
+            prop_assert_eq!(rest.count, rule.count.map(|count| count - 1));
+            if matches!(rule.frequency, Frequency::Monthly | Frequency::Yearly) {
+                prop_assert_eq!(next.day(), start.day());
+            }
+        }
+    }
+
+    #[test]
+    fn completing_each_occurrence_walks_the_expansion((start, rule) in recurrence()) {
+        let horizon = start + Duration::days(800);
+        let dates = rule.dates(start, horizon);
+        // The first occurrence is the start itself unless BYDAY leaves its weekday out
+        let Some(&first) = dates.first() else {
+            return Ok(());
+        };
+
+        let (mut date, mut current) = (first, rule);
+        for &expected in &dates[1..] {
+            let next = current.next_after(date);
+            prop_assert_eq!(next.as_ref().map(|(next, _)| *next), Some(expected));
+            (date, current) = next.unwrap();
+        }
+        // After the last one in range the series is over or carries on past the horizon
+        prop_assert!(current.next_after(date).is_none_or(|(next, _)| next > horizon));
+    }
+}
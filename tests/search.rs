@@ -0,0 +1,56 @@
+// Search tests: tasks stay findable once they've left todos.json for the archive.
+// Run with `cargo test --test search`.
+
+use chrono::{Duration, Utc};
+use std::path::PathBuf;
+use tdui::clock::Clock;
+use tdui::models::Todo;
+use tdui::search::{SearchIndex, TaskSource};
+use tdui::storage::{Archive, FileStorage};
+
+/// An empty data folder of its own for each test
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("tdui-tests").join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("create test directory");
+    dir
+}
+
+#[test]
+fn archived_task_is_found() {
+    let dir = scratch_dir("search-archived");
+    let clock = Clock::default();
+    let mut storage = FileStorage::open(dir.join("todos.json"));
+    let mut done = Todo::new(1, "Renew passport".to_string(), "Photos from the booth downtown".to_string(), None);
+    done.completed = true;
+    done.completed_at = Some(Utc::now() - Duration::days(90));
+    storage.upsert(done);
+    storage.upsert(Todo::new(2, "Water plants".to_string(), String::new(), None));
+
+    let mut archive = Archive::open(dir.join("archive"));
+    let archived = archive.archive_closed(&mut storage, |_| Some(clock.today()), &clock).expect("archive");
+    assert_eq!(archived, 1);
+    assert!(storage.todos().iter().all(|todo| todo.id != 1));
+
+    let mut index = SearchIndex::open(dir.join("search_index.json"));
+    index.sync(storage.todos(), &archive.load_all());
+    let hits = index.search("passport");
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].id, 1);
+    assert_eq!(hits[0].source, TaskSource::Archived);
+    // The description is searched too
+    assert_eq!(index.search("booth").len(), 1);
+}
+
+#[test]
+fn restored_task_counts_as_stored() {
+    let dir = scratch_dir("search-restored");
+    let mut todo = Todo::new(7, "Call the bank".to_string(), String::new(), None);
+    todo.completed = true;
+
+    let mut index = SearchIndex::open(dir.join("search_index.json"));
+    index.sync(std::slice::from_ref(&todo), std::slice::from_ref(&todo));
+    let hits = index.search("bank");
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].source, TaskSource::Completed);
+}
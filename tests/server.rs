@@ -0,0 +1,93 @@
+// Server tests: the dashboard keeps to this machine unless there's a token, takes the token as a
+// header too, and answers a WebSocket client's ping and close.
+// Run with `cargo test --test server`.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::sync::mpsc;
+use tdui::config::ServerConfig;
+use tdui::server::Server;
+
+fn config(token: Option<&str>, tools: bool) -> ServerConfig {
+    ServerConfig { listen: None, token: token.map(str::to_string), tools }
+}
+
+/// Start a server on a port nobody else is using
+fn start(config: &ServerConfig) -> (Server, u16) {
+    for port in 41700..41800 {
+        let (_, events) = mpsc::channel();
+        if let Ok(server) = Server::start(&format!("127.0.0.1:{}", port), config, events) {
+            return (server, port);
+        }
+    }
+    panic!("no free port");
+}
+
+/// Send a request and read back the status line
+fn status(port: u16, request: &str) -> String {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).expect("connect");
+    stream.write_all(request.as_bytes()).expect("send");
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).expect("read");
+    line.trim().to_string()
+}
+
+/// A masked client frame, as browsers send them
+fn client_frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mask = [1, 2, 3, 4];
+    let mut frame = vec![0x80 | opcode, 0x80 | payload.len() as u8];
+    frame.extend_from_slice(&mask);
+    frame.extend(payload.iter().enumerate().map(|(index, byte)| byte ^ mask[index % 4]));
+    frame
+}
+
+fn read_frame(stream: &mut impl Read) -> (u8, Vec<u8>) {
+    let mut head = [0; 2];
+    stream.read_exact(&mut head).expect("frame");
+    let mut payload = vec![0; (head[1] & 0x7f) as usize];
+    stream.read_exact(&mut payload).expect("payload");
+    (head[0] & 0x0f, payload)
+}
+
+#[test]
+fn needs_a_token_beyond_loopback_or_with_tools() {
+    let (_, events) = mpsc::channel();
+    assert!(Server::start("0.0.0.0:0", &config(None, false), events).is_err());
+    let (_, events) = mpsc::channel();
+    assert!(Server::start("127.0.0.1:0", &config(None, true), events).is_err());
+    let (_, events) = mpsc::channel();
+    assert!(Server::start("127.0.0.1:0", &config(None, false), events).is_ok());
+    let (_, events) = mpsc::channel();
+    assert!(Server::start("0.0.0.0:0", &config(Some("secret"), true), events).is_ok());
+}
+
+#[test]
+fn token_in_query_or_bearer_header() {
+    let (_server, port) = start(&config(Some("secret"), false));
+    assert!(status(port, "GET /api/today HTTP/1.1\r\n\r\n").contains("403"));
+    assert!(status(port, "GET /api/today HTTP/1.1\r\nAuthorization: Bearer wrong\r\n\r\n").contains("403"));
+    assert!(status(port, "GET /api/today?token=secret HTTP/1.1\r\n\r\n").contains("200"));
+    assert!(status(port, "GET /api/today HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n").contains("200"));
+}
+
+#[test]
+fn websocket_ping_and_close_are_answered() {
+    let (_server, port) = start(&config(None, false));
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).expect("connect");
+    stream.write_all(b"GET /api/events HTTP/1.1\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n").expect("handshake");
+    let mut reader = BufReader::new(stream.try_clone().expect("clone"));
+    let mut line = String::new();
+    reader.read_line(&mut line).expect("status");
+    assert!(line.contains("101"));
+    while line != "\r\n" {
+        line.clear();
+        reader.read_line(&mut line).expect("header");
+    }
+
+    stream.write_all(&client_frame(0x9, b"hello")).expect("ping");
+    assert_eq!(read_frame(&mut reader), (0xA, b"hello".to_vec()));
+    stream.write_all(&client_frame(0x8, &1000u16.to_be_bytes())).expect("close");
+    assert_eq!(read_frame(&mut reader), (0x8, 1000u16.to_be_bytes().to_vec()));
+    // The server hangs up once it has answered the close
+    assert_eq!(reader.read(&mut [0; 1]).expect("end"), 0);
+}
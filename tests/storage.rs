@@ -0,0 +1,119 @@
+// Storage tests: a save from one tdui doesn't write over tasks another saved in the meantime.
+// Run with `cargo test --test storage`.
+
+use std::path::PathBuf;
+use tdui::models::Todo;
+use tdui::storage::{Archive, Entry, FileStorage};
+
+/// An empty data folder of its own for each test
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("tdui-tests").join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("create test directory");
+    dir
+}
+
+fn task(id: usize, title: &str) -> Todo {
+    Todo::new(id, title.to_string(), String::new(), None)
+}
+
+fn titles(storage: &FileStorage) -> Vec<(usize, String)> {
+    let mut titles: Vec<(usize, String)> = storage.todos().iter().map(|t| (t.id, t.title.clone())).collect();
+    titles.sort();
+    titles
+}
+
+#[test]
+fn save_keeps_tasks_added_elsewhere() {
+    let dir = scratch_dir("storage-added-elsewhere");
+    let path = dir.join("todos.json");
+    let mut first = FileStorage::open(path.clone());
+    first.upsert(task(1, "Water plants"));
+    first.flush().expect("save");
+
+    // The TUI has an unsaved change while `tdui <text>` adds a task from another shell
+    let mut tui = FileStorage::open(path.clone()).with_background_writer();
+    tui.update(1, |todo| todo.title = "Water the plants".to_string());
+    let mut cli = FileStorage::open(path.clone());
+    cli.upsert(task(cli.next_id(), "Buy milk"));
+    cli.flush().expect("save");
+
+    tui.flush().expect("save");
+    assert!(tui.take_changed_elsewhere());
+    let expected = vec![(1, "Water the plants".to_string()), (2, "Buy milk".to_string())];
+    assert_eq!(titles(&tui), expected);
+    assert_eq!(titles(&FileStorage::open(path)), expected);
+}
+
+#[test]
+fn save_takes_changes_elsewhere_to_untouched_tasks() {
+    let dir = scratch_dir("storage-changed-elsewhere");
+    let path = dir.join("todos.json");
+    let mut first = FileStorage::open(path.clone());
+    first.append(vec![task(1, "Water plants"), task(2, "Call the bank"), task(3, "Pay rent")]);
+    first.flush().expect("save");
+
+    let mut tui = FileStorage::open(path.clone());
+    let mut other = FileStorage::open(path.clone());
+    other.update(1, |todo| todo.title = "Water plants (theirs)".to_string());
+    other.update(2, |todo| todo.title = "Call the bank (theirs)".to_string());
+    other.remove_where(|todo| todo.id == 3);
+    other.flush().expect("save");
+
+    // Where both changed a task, the copy being saved wins
+    tui.update(2, |todo| todo.title = "Call the bank (ours)".to_string());
+    tui.flush().expect("save");
+    let expected = vec![(1, "Water plants (theirs)".to_string()), (2, "Call the bank (ours)".to_string())];
+    assert_eq!(titles(&tui), expected);
+    assert_eq!(titles(&FileStorage::open(path)), expected);
+}
+
+#[test]
+fn task_added_under_the_same_id_elsewhere_gets_a_new_one() {
+    let dir = scratch_dir("storage-same-id");
+    let path = dir.join("todos.json");
+    let mut tui = FileStorage::open(path.clone());
+    let mut cli = FileStorage::open(path.clone());
+    cli.upsert(task(cli.next_id(), "Buy milk"));
+    cli.flush().expect("save");
+
+    tui.upsert(task(tui.next_id(), "Book dentist"));
+    tui.flush().expect("save");
+    let expected = vec![(1, "Book dentist".to_string()), (2, "Buy milk".to_string())];
+    assert_eq!(titles(&tui), expected);
+    assert_eq!(tui.next_id(), 3);
+}
+
+#[test]
+fn subscription_carries_on_after_a_reload() {
+    let dir = scratch_dir("storage-subscription-reload");
+    let path = dir.join("todos.json");
+    let mut tui = FileStorage::open(path.clone());
+    let events = tui.subscribe();
+
+    // A git pull brings in a task saved on another machine
+    let mut other = FileStorage::open(path.clone());
+    other.upsert(task(1, "Water plants"));
+    other.flush().expect("save");
+    assert!(tui.reload().expect("reload"));
+
+    tui.upsert(task(tui.next_id(), "Buy milk"));
+    let Ok(Entry::Upsert(todo)) = events.try_recv() else {
+        panic!("the new task wasn't sent to the subscriber");
+    };
+    assert_eq!((todo.id, todo.title.as_str()), (2, "Buy milk"));
+}
+
+#[test]
+fn new_ids_skip_those_in_an_older_archive() {
+    let dir = scratch_dir("storage-archive-ids");
+    let mut storage = FileStorage::open(dir.join("todos.json"));
+    storage.upsert(task(1, "Water plants"));
+    // Archived before storage kept track of the highest ID itself
+    std::fs::create_dir_all(dir.join("archive")).expect("create archive");
+    std::fs::write(dir.join("archive").join("summary.json"), r#"{"max_id":40,"months":{}}"#).expect("write summary");
+
+    assert_eq!(storage.next_id(), 2);
+    assert_eq!(storage.next_free_id(&Archive::open(dir.join("archive"))), 41);
+    assert_eq!(storage.next_free_id(&Archive::open(dir.join("no-archive"))), 2);
+}
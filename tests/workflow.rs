@@ -0,0 +1,46 @@
+// Workflow tests: a task's status and its done/deleted flags can't disagree.
+// Run with `cargo test --test workflow`.
+
+use tdui::config::WorkflowConfig;
+use tdui::models::Todo;
+
+fn task() -> Todo {
+    Todo::new(1, "Write report".to_string(), String::new(), None)
+}
+
+#[test]
+fn done_task_has_no_open_status() {
+    let workflow = WorkflowConfig::default();
+    let mut todo = task();
+    todo.set_status(Some("In Progress".to_string()));
+    assert_eq!(todo.status(), Some("In Progress"));
+
+    todo.toggle_completed();
+    assert_eq!(todo.status(), None);
+    assert_eq!(workflow.status_of(&todo).name, "Done");
+    // Done is past the workflow, so a status can't be put back on it
+    todo.set_status(Some("Waiting".to_string()));
+    assert_eq!(todo.status(), None);
+
+    // Reopening starts the workflow over
+    todo.toggle_completed();
+    assert_eq!(workflow.status_of(&todo).name, "Todo");
+}
+
+#[test]
+fn deleted_task_has_no_open_status() {
+    let mut todo = task();
+    todo.set_status(Some("Waiting".to_string()));
+    todo.mark_deleted();
+    assert_eq!(todo.status(), None);
+}
+
+#[test]
+fn status_saved_on_a_done_task_is_ignored() {
+    // Written by a version that kept the status when a task was finished
+    let json = r#"{"id":1,"title":"Write report","completed":true,"created_at":"2026-10-01T08:00:00Z",
+        "due_date":null,"completed_at":"2026-10-02T08:00:00Z","status":"In Progress"}"#;
+    let todo: Todo = serde_json::from_str(json).expect("parse");
+    assert_eq!(todo.status(), None);
+    assert_eq!(WorkflowConfig::default().status_of(&todo).name, "Done");
+}